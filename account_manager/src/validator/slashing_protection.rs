@@ -1,4 +1,5 @@
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use db_encryption::KeySource;
 use environment::Environment;
 use slashing_protection::{
     interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
@@ -7,6 +8,7 @@ use slashing_protection::{
 use std::fs::File;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 
 pub const CMD: &str = "slashing-protection";
@@ -17,11 +19,38 @@ pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
 
 pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const DB_ENCRYPTION_KEY_FILE_FLAG: &str = "db-encryption-key-file";
+pub const DB_ENCRYPTION_KEY_ENV_FLAG: &str = "db-encryption-key-env";
 
 pub fn cli_app() -> Command {
     Command::new(CMD)
         .about("Import or export slashing protection data to or from another client")
         .display_order(0)
+        .arg(
+            Arg::new(DB_ENCRYPTION_KEY_FILE_FLAG)
+                .long(DB_ENCRYPTION_KEY_FILE_FLAG)
+                .action(ArgAction::Set)
+                .value_name("PATH")
+                .help(
+                    "File containing the hex-encoded key of an encrypted slashing protection \
+                     database. A new database is created encrypted.",
+                )
+                .conflicts_with(DB_ENCRYPTION_KEY_ENV_FLAG)
+                .global(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(DB_ENCRYPTION_KEY_ENV_FLAG)
+                .long(DB_ENCRYPTION_KEY_ENV_FLAG)
+                .action(ArgAction::Set)
+                .value_name("VAR")
+                .help(
+                    "Environment variable containing the hex-encoded key of an encrypted \
+                     slashing protection database. A new database is created encrypted.",
+                )
+                .global(true)
+                .display_order(0),
+        )
         .subcommand(
             Command::new(IMPORT_CMD)
                 .about("Import an interchange file")
@@ -71,6 +100,17 @@ pub fn cli_run<E: EthSpec>(
         .genesis_validators_root::<E>()?
         .ok_or_else(|| "Unable to get genesis state, has genesis occurred?".to_string())?;
 
+    let key_source =
+        if let Some(path) = clap_utils::parse_optional(matches, DB_ENCRYPTION_KEY_FILE_FLAG)? {
+            Some(KeySource::File(path))
+        } else {
+            clap_utils::parse_optional(matches, DB_ENCRYPTION_KEY_ENV_FLAG)?.map(KeySource::Env)
+        };
+    let cipher = key_source
+        .map(|source| source.load_cipher().map(Arc::new))
+        .transpose()
+        .map_err(|e| format!("Unable to load database encryption key: {:?}", e))?;
+
     match matches.subcommand() {
         Some((IMPORT_CMD, matches)) => {
             let import_filename: PathBuf = clap_utils::parse_required(matches, IMPORT_FILE_ARG)?;
@@ -88,13 +128,14 @@ pub fn cli_run<E: EthSpec>(
             eprintln!(" [done].");
 
             let slashing_protection_database =
-                SlashingDatabase::open_or_create(&slashing_protection_db_path).map_err(|e| {
-                    format!(
-                        "Unable to open database at {}: {:?}",
-                        slashing_protection_db_path.display(),
-                        e
-                    )
-                })?;
+                SlashingDatabase::open_or_create_with_cipher(&slashing_protection_db_path, cipher)
+                    .map_err(|e| {
+                        format!(
+                            "Unable to open database at {}: {:?}",
+                            slashing_protection_db_path.display(),
+                            e
+                        )
+                    })?;
 
             let display_slot = |slot: Option<Slot>| {
                 slot.map_or("none".to_string(), |slot| format!("slot {}", slot.as_u64()))
@@ -195,14 +236,16 @@ pub fn cli_run<E: EthSpec>(
                 ));
             }
 
-            let slashing_protection_database = SlashingDatabase::open(&slashing_protection_db_path)
-                .map_err(|e| {
-                    format!(
-                        "Unable to open database at {}: {:?}",
-                        slashing_protection_db_path.display(),
-                        e
-                    )
-                })?;
+            let slashing_protection_database =
+                SlashingDatabase::open_with_cipher(&slashing_protection_db_path, cipher).map_err(
+                    |e| {
+                        format!(
+                            "Unable to open database at {}: {:?}",
+                            slashing_protection_db_path.display(),
+                            e
+                        )
+                    },
+                )?;
 
             let interchange = slashing_protection_database
                 .export_interchange_info(genesis_validators_root, selected_pubkeys.as_deref())
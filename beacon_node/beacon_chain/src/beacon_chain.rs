@@ -34,6 +34,7 @@ use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, Prep
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::graffiti_calculator::GraffitiCalculator;
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
+use crate::historical_block_provider::HistoricalBlockProvider;
 use crate::light_client_finality_update_verification::{
     Error as LightClientFinalityUpdateError, VerifiedLightClientFinalityUpdate,
 };
@@ -72,6 +73,7 @@ use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
+use eth2::lighthouse::ReOrgDecision;
 use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
 use execution_layer::{
     BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
@@ -86,7 +88,8 @@ use itertools::process_results;
 use itertools::Itertools;
 use kzg::Kzg;
 use operation_pool::{
-    CompactAttestationRef, OperationPool, PersistedOperationPool, ReceivedPreCapella,
+    CompactAttestationRef, OperationOverrides, OperationPool, PersistedOperationPool,
+    ReceivedPreCapella,
 };
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use proto_array::{DoNotReOrg, ProposerHeadError};
@@ -111,6 +114,7 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::io::prelude::*;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -157,6 +161,9 @@ const PREPARE_PROPOSER_HISTORIC_EPOCHS: u64 = 4;
 /// impact whilst having 8 epochs without a block is a comfortable grace period.
 const MAX_PER_SLOT_FORK_CHOICE_DISTANCE: u64 = 256;
 
+/// The number of recent proposer re-org decisions to keep for the HTTP API.
+const MAX_RE_ORG_DECISIONS: usize = 64;
+
 /// Reported to the user when the justified block has an invalid execution payload.
 pub const INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON: &str =
     "Justified block has an invalid execution payload.";
@@ -330,6 +337,8 @@ struct PartialBeaconBlock<E: EthSpec> {
     sync_aggregate: Option<SyncAggregate<E>>,
     prepare_payload_handle: Option<PreparePayloadHandle<E>>,
     bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+    /// The operation pool overrides applied to the block, cleared once it has been produced.
+    overrides: OperationOverrides,
 }
 
 pub enum BlockProcessStatus<E: EthSpec> {
@@ -474,6 +483,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub reqresp_pre_import_cache: Arc<RwLock<ReqRespPreImportCache<T::EthSpec>>>,
     /// A cache used to keep track of various block timings.
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
+    /// The most recent decisions on whether to re-org the head when proposing, oldest first.
+    pub re_org_decisions: Mutex<VecDeque<ReOrgDecision>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
     /// A cache used to produce light_client server messages
@@ -489,6 +500,9 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) graffiti_calculator: GraffitiCalculator<T>,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    /// Optional source of blocks from before the oldest block in the database, used to serve
+    /// block requests from peers.
+    pub historical_block_provider: Option<Arc<dyn HistoricalBlockProvider<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
     pub validator_monitor: RwLock<ValidatorMonitor<T::EthSpec>>,
     /// The slot at which blocks are downloaded back to.
@@ -1397,7 +1411,6 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.store,
             sync_committee_period,
             count,
-            &self.spec,
         )
     }
 
@@ -4656,15 +4669,49 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Fetch the beacon state to use for producing a block if a 1-slot proposer re-org is viable.
     ///
-    /// This function will return `None` if proposer re-orgs are disabled.
+    /// This function will return `None` if proposer re-orgs are disabled. The decision is recorded
+    /// in `re_org_decisions` either way.
     fn get_state_for_re_org(
         &self,
         slot: Slot,
         head_slot: Slot,
         canonical_head: Hash256,
     ) -> Option<(BeaconState<T::EthSpec>, Hash256)> {
-        let re_org_head_threshold = self.config.re_org_head_threshold?;
-        let re_org_parent_threshold = self.config.re_org_parent_threshold?;
+        let (state, reason) = match self.try_get_state_for_re_org(slot, head_slot, canonical_head) {
+            Ok((state, state_root, reason)) => (Some((state, state_root)), reason),
+            Err(reason) => (None, reason),
+        };
+
+        let mut decisions = self.re_org_decisions.lock();
+        if decisions.len() >= MAX_RE_ORG_DECISIONS {
+            decisions.pop_front();
+        }
+        decisions.push_back(ReOrgDecision {
+            slot,
+            head_block_root: canonical_head,
+            head_slot,
+            re_org: state.is_some(),
+            reason,
+        });
+        drop(decisions);
+
+        state
+    }
+
+    /// Returns the state and state root to re-org the head, along with the reason for the
+    /// re-org, or the reason for not re-orging it.
+    fn try_get_state_for_re_org(
+        &self,
+        slot: Slot,
+        head_slot: Slot,
+        canonical_head: Hash256,
+    ) -> Result<(BeaconState<T::EthSpec>, Hash256, String), String> {
+        let (Some(re_org_head_threshold), Some(re_org_parent_threshold)) = (
+            self.config.re_org_head_threshold,
+            self.config.re_org_parent_threshold,
+        ) else {
+            return Err("re-orgs disabled".into());
+        };
 
         if self.spec.proposer_score_boost.is_none() {
             warn!(
@@ -4672,19 +4719,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "Ignoring proposer re-org configuration";
                 "reason" => "this network does not have proposer boost enabled"
             );
-            return None;
+            return Err("proposer boost disabled".into());
         }
 
         let slot_delay = self
             .slot_clock
             .seconds_from_current_slot_start()
-            .or_else(|| {
+            .ok_or_else(|| {
                 warn!(
                     self.log,
                     "Not attempting re-org";
                     "error" => "unable to read slot clock"
                 );
-                None
+                "unable to read slot clock".to_string()
             })?;
 
         // Attempt a proposer re-org if:
@@ -4699,7 +4746,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "Not attempting re-org";
                 "reason" => "not proposing on time",
             );
-            return None;
+            return Err("not proposing on time".into());
         }
 
         let head_late = self.block_observed_after_attestation_deadline(canonical_head, head_slot);
@@ -4709,7 +4756,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "Not attempting re-org";
                 "reason" => "head not late"
             );
-            return None;
+            return Err("head not late".into());
         }
 
         // Is the current head weak and appropriate for re-orging?
@@ -4733,6 +4780,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         "Not attempting re-org";
                         "reason" => %reason,
                     );
+                    reason.to_string()
                 }
                 ProposerHeadError::Error(e) => {
                     warn!(
@@ -4740,22 +4788,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         "Not attempting re-org";
                         "error" => ?e,
                     );
+                    format!("error: {e:?}")
                 }
-            })
-            .ok()?;
+            })?;
         drop(proposer_head_timer);
         let re_org_parent_block = proposer_head.parent_node.root;
 
         let (state_root, state) = self
             .store
             .get_advanced_hot_state_from_cache(re_org_parent_block, slot)
-            .or_else(|| {
+            .ok_or_else(|| {
                 warn!(
                     self.log,
                     "Not attempting re-org";
                     "reason" => "no state in cache"
                 );
-                None
+                "no state in cache".to_string()
             })?;
 
         info!(
@@ -4767,7 +4815,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "threshold_weight" => proposer_head.re_org_head_weight_threshold
         );
 
-        Some((state, state_root))
+        let reason = format!(
+            "head weak ({}/{})",
+            proposer_head.head_node.weight, proposer_head.re_org_head_weight_threshold
+        );
+        Ok((state, state_root, reason))
     }
 
     /// Get the proposer index and `prev_randao` value for a proposal at slot `proposal_slot`.
@@ -5385,9 +5437,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.filter_op_pool_attestation(&mut curr_filter_cache, att, &state)
         };
 
-        let mut attestations = self
+        let (mut attestations, packing_outcome) = self
             .op_pool
-            .get_attestations(
+            .pack_attestations(
                 &state,
                 prev_attestation_filter,
                 curr_attestation_filter,
@@ -5396,6 +5448,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(BlockProductionError::OpPoolError)?;
         drop(attestation_packing_timer);
 
+        debug!(
+            self.log,
+            "Packed attestations";
+            "slot" => state.slot(),
+            "strategy" => packing_outcome.strategy,
+            "candidates" => packing_outcome.candidates,
+            "packed" => packing_outcome.packed,
+            "attesters" => packing_outcome.attesters,
+            "reward" => packing_outcome.reward,
+        );
+        self.op_pool
+            .record_packing_outcome(state.slot(), parent_root, packing_outcome);
+
+        // Overrides only apply to the next block produced, they're cleared once it's complete.
+        let overrides = self.op_pool.overrides();
+        if !overrides.is_empty() {
+            info!(
+                self.log,
+                "Applied operation pool overrides";
+                "slot" => state.slot(),
+                "include" => overrides.include.len(),
+                "exclude" => overrides.exclude.len(),
+            );
+        }
+
         // If paranoid mode is enabled re-check the signatures of every included message.
         // This will be a lot slower but guards against bugs in block production and can be
         // quickly rolled out without a release.
@@ -5505,6 +5582,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             sync_aggregate,
             prepare_payload_handle,
             bls_to_execution_changes,
+            overrides,
         })
     }
 
@@ -5533,6 +5611,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // produce said `execution_payload`.
             prepare_payload_handle: _,
             bls_to_execution_changes,
+            overrides,
         } = partial_beacon_block;
 
         let (attester_slashings_base, attester_slashings_electra) =
@@ -5842,6 +5921,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         drop(blobs_verification_timer);
 
+        self.op_pool.clear_overrides(&overrides);
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
         trace!(
@@ -5,10 +5,12 @@ use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::data_availability_checker::DataAvailabilityChecker;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
+use crate::fork_choice_journal::ForkChoiceJournalWriter;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::graffiti_calculator::{GraffitiCalculator, GraffitiOrigin};
 use crate::head_tracker::HeadTracker;
+use crate::historical_block_provider::HistoricalBlockProvider;
 use crate::light_client_server_cache::LightClientServerCache;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
 use crate::observed_data_sidecars::ObservedDataSidecars;
@@ -26,16 +28,17 @@ use execution_layer::ExecutionLayer;
 use fork_choice::{ForkChoice, ResetPayloadStatuses};
 use futures::channel::mpsc::Sender;
 use kzg::Kzg;
-use operation_pool::{OperationPool, PersistedOperationPool};
+use operation_pool::{DiscardedOperations, OperationPool, PersistedOperationPool};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, o, Logger};
+use slog::{crit, debug, error, info, o, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::{per_slot_processing, AllCaches};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
+use store::retention::RetentionPolicy;
 use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
 use task_executor::{ShutdownReason, TaskExecutor};
 use types::{
@@ -98,6 +101,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     log: Option<Logger>,
     beacon_graffiti: GraffitiOrigin,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    historical_block_provider: Option<Arc<dyn HistoricalBlockProvider<T::EthSpec>>>,
     // Pending I/O batch that is constructed during building and should be executed atomically
     // alongside `PersistedBeaconChain` storage when `BeaconChainBuilder::build` is called.
     pending_io_batch: Vec<KeyValueStoreOp>,
@@ -142,6 +146,7 @@ where
             log: None,
             beacon_graffiti: GraffitiOrigin::default(),
             slasher: None,
+            historical_block_provider: None,
             pending_io_batch: vec![],
             kzg,
             task_executor: None,
@@ -304,20 +309,27 @@ where
 
         self.genesis_time = Some(genesis_state.genesis_time());
 
-        self.op_pool = Some(
-            store
-                .get_item::<PersistedOperationPool<E>>(&OP_POOL_DB_KEY)
-                .map_err(|e| format!("DB error whilst reading persisted op pool: {:?}", e))?
-                .map(PersistedOperationPool::into_operation_pool)
-                .transpose()
-                .map_err(|e| {
-                    format!(
-                        "Error while creating the op pool from the persisted op pool: {:?}",
-                        e
-                    )
-                })?
-                .unwrap_or_else(OperationPool::new),
-        );
+        let (op_pool, discarded_ops) = store
+            .get_item::<PersistedOperationPool<E>>(&OP_POOL_DB_KEY)
+            .map_err(|e| format!("DB error whilst reading persisted op pool: {:?}", e))?
+            .map(PersistedOperationPool::into_operation_pool_with_discarded)
+            .transpose()
+            .map_err(|e| {
+                format!(
+                    "Error while creating the op pool from the persisted op pool: {:?}",
+                    e
+                )
+            })?
+            .unwrap_or_else(|| (OperationPool::new(), DiscardedOperations::default()));
+        if !discarded_ops.is_empty() {
+            warn!(
+                log,
+                "Discarded undecodable operations from op pool";
+                "total" => discarded_ops.total(),
+                "discarded" => %discarded_ops,
+            );
+        }
+        self.op_pool = Some(op_pool);
 
         let pubkey_cache = ValidatorPubkeyCache::load_from_store(store)
             .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?;
@@ -678,6 +690,15 @@ where
         self
     }
 
+    /// Sets the source of blocks older than the oldest block in the database.
+    pub fn historical_block_provider(
+        mut self,
+        provider: Arc<dyn HistoricalBlockProvider<E>>,
+    ) -> Self {
+        self.historical_block_provider = Some(provider);
+        self
+    }
+
     /// Sets the `beacon_graffiti` field.
     pub fn beacon_graffiti(mut self, beacon_graffiti: GraffitiOrigin) -> Self {
         self.beacon_graffiti = beacon_graffiti;
@@ -875,6 +896,10 @@ where
 
         let genesis_validators_root = head_snapshot.beacon_state.genesis_validators_root();
         let genesis_time = head_snapshot.beacon_state.genesis_time();
+        if let Some(config) = &self.chain_config.fork_choice_journal {
+            let journal = ForkChoiceJournalWriter::new(config.clone(), log.clone())?;
+            fork_choice.set_journal(Arc::new(journal));
+        }
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
 
@@ -905,6 +930,9 @@ where
             }
         };
 
+        let op_pool = self.op_pool.ok_or("Cannot build without op pool")?;
+        op_pool.set_attestation_packing(self.chain_config.attestation_packing);
+
         let beacon_chain = BeaconChain {
             spec: self.spec.clone(),
             config: self.chain_config,
@@ -914,7 +942,7 @@ where
                 .ok_or("Cannot build without task executor")?,
             store_migrator,
             slot_clock: slot_clock.clone(),
-            op_pool: self.op_pool.ok_or("Cannot build without op pool")?,
+            op_pool,
             // TODO: allow for persisting and loading the pool from disk.
             naive_aggregation_pool: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
@@ -961,6 +989,7 @@ where
             eth1_finalization_cache: RwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache,
             block_times_cache: <_>::default(),
+            re_org_decisions: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
             validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
@@ -979,6 +1008,7 @@ where
                 log.clone(),
             ),
             slasher: self.slasher.clone(),
+            historical_block_provider: self.historical_block_provider,
             validator_monitor: RwLock::new(validator_monitor),
             genesis_backfill_slot,
             data_availability_checker: Arc::new(
@@ -1044,7 +1074,12 @@ where
         }
 
         // Prune finalized execution payloads in the background.
-        if beacon_chain.store.get_config().prune_payloads {
+        if beacon_chain
+            .store
+            .get_config()
+            .effective_payload_retention()
+            != RetentionPolicy::KeepAll
+        {
             let store = beacon_chain.store.clone();
             let log = log.clone();
             beacon_chain.task_executor.spawn_blocking(
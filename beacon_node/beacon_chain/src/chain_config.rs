@@ -1,3 +1,7 @@
+use crate::fork_choice_journal::ForkChoiceJournalConfig;
+use crate::historical_block_provider::HistoricalBlockSource;
+use crate::http_backfill::BackfillSource;
+pub use operation_pool::AttestationPacking;
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -62,6 +66,8 @@ pub struct ChainConfig {
     pub always_reset_payload_statuses: bool,
     /// Whether to apply paranoid checks to blocks proposed by this beacon node.
     pub paranoid_block_proposal: bool,
+    /// How to choose the attestations packed into blocks proposed by this beacon node.
+    pub attestation_packing: AttestationPacking,
     /// Optionally set timeout for calls to checkpoint sync endpoint.
     pub checkpoint_sync_url_timeout: u64,
     /// The offset before the start of a proposal slot at which payload attributes should be sent.
@@ -84,6 +90,9 @@ pub struct ChainConfig {
     pub epochs_per_migration: u64,
     /// When set to true Light client server computes and caches state proofs for serving updates
     pub enable_light_client_server: bool,
+    /// Whether the light client server generates updates for past sync committee periods from
+    /// the freezer database.
+    pub light_client_historical_updates: bool,
     /// The number of data columns to withhold / exclude from publishing when proposing a block.
     pub malicious_withhold_count: usize,
     /// Enable peer sampling on blocks.
@@ -94,6 +103,12 @@ pub struct ChainConfig {
     /// The delay in milliseconds applied by the node between sending each blob or data column batch.
     /// This doesn't apply if the node is the block proposer.
     pub blob_publication_batch_interval: Duration,
+    /// Where to load blocks from before the oldest block in the database, to serve them to peers.
+    pub historical_block_source: Option<HistoricalBlockSource>,
+    /// Backfill blocks from a trusted HTTP source rather than from peers.
+    pub backfill_source: Option<BackfillSource>,
+    /// Journal every input to fork choice to disk, so that head selection can be replayed.
+    pub fork_choice_journal: Option<ForkChoiceJournalConfig>,
 }
 
 impl Default for ChainConfig {
@@ -116,6 +131,7 @@ impl Default for ChainConfig {
             builder_fallback_disable_checks: false,
             always_reset_payload_statuses: false,
             paranoid_block_proposal: false,
+            attestation_packing: AttestationPacking::default(),
             checkpoint_sync_url_timeout: 60,
             prepare_payload_lookahead: Duration::from_secs(4),
             // This value isn't actually read except in tests.
@@ -125,10 +141,14 @@ impl Default for ChainConfig {
             always_prepare_payload: false,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
             enable_light_client_server: false,
+            light_client_historical_updates: false,
             malicious_withhold_count: 0,
             enable_sampling: false,
             blob_publication_batches: 4,
             blob_publication_batch_interval: Duration::from_millis(300),
+            historical_block_source: None,
+            backfill_source: None,
+            fork_choice_journal: None,
         }
     }
 }
@@ -0,0 +1,369 @@
+//! Writes every input to fork choice to a rotating set of files on disk.
+//!
+//! The current file is `fork_choice.journal` and rotated files are `fork_choice.journal.1` (the
+//! newest) to `fork_choice.journal.<max_files - 1>` (the oldest). Every file starts with a
+//! snapshot of fork choice, so head selection can be reproduced offline from any file onwards
+//! using `lcli fork-choice-replay`. See `fork_choice::journal` for the encoding.
+use fork_choice::journal::{encode_frame, ForkChoiceJournal, JOURNAL_MAGIC};
+use fork_choice::replay::ForkChoiceEvent;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use slog::{error, info, Logger};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+
+/// Name of the file currently being written.
+const JOURNAL_FILE: &str = "fork_choice.journal";
+
+pub const DEFAULT_FORK_CHOICE_JOURNAL_MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+pub const DEFAULT_FORK_CHOICE_JOURNAL_MAX_FILES: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkChoiceJournalConfig {
+    /// Directory containing the journal files.
+    pub dir: PathBuf,
+    /// Size in bytes after which the current file is rotated.
+    pub max_file_size: u64,
+    /// Number of files to keep, including the current one.
+    pub max_files: usize,
+}
+
+impl ForkChoiceJournalConfig {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_file_size: DEFAULT_FORK_CHOICE_JOURNAL_MAX_FILE_SIZE,
+            max_files: DEFAULT_FORK_CHOICE_JOURNAL_MAX_FILES,
+        }
+    }
+}
+
+/// A frame for the writer thread to append to the journal.
+struct WriteJob {
+    frame: Vec<u8>,
+    /// Rotate the files and start a new one before writing `frame`.
+    new_file: bool,
+    /// Flush the file after writing `frame`.
+    flush: bool,
+    /// Close the file after writing `frame`, because it's full.
+    close: bool,
+}
+
+struct WriterState {
+    /// Whether a file is open, i.e. the next frame doesn't start a new file.
+    file_open: bool,
+    /// Bytes written to the open file.
+    written: u64,
+}
+
+/// A `ForkChoiceJournal` which writes to disk.
+///
+/// Events are recorded while fork choice is locked, so they're only encoded there. The frames are
+/// written by a separate thread, which flushes them after each `GetHead` event.
+pub struct ForkChoiceJournalWriter {
+    config: ForkChoiceJournalConfig,
+    state: Mutex<WriterState>,
+    /// Set by the writer thread after an I/O error, after which nothing more is written.
+    disabled: Arc<AtomicBool>,
+    tx: Option<Mutex<mpsc::Sender<WriteJob>>>,
+    thread: Option<JoinHandle<()>>,
+    log: Logger,
+}
+
+impl ForkChoiceJournalWriter {
+    pub fn new(config: ForkChoiceJournalConfig, log: Logger) -> Result<Self, String> {
+        if config.max_files == 0 {
+            return Err("Fork choice journal must keep at least one file".into());
+        }
+        fs::create_dir_all(&config.dir).map_err(|e| {
+            format!(
+                "Unable to create fork choice journal dir {}: {e}",
+                config.dir.display()
+            )
+        })?;
+        info!(
+            log,
+            "Journaling fork choice";
+            "dir" => %config.dir.display(),
+            "max_file_size" => config.max_file_size,
+            "max_files" => config.max_files,
+        );
+
+        let disabled = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+        let thread = {
+            let config = config.clone();
+            let disabled = disabled.clone();
+            let log = log.clone();
+            thread::Builder::new()
+                .name("fork_choice_journal".into())
+                .spawn(move || {
+                    if let Err(e) = write_jobs(&config, rx) {
+                        error!(
+                            log,
+                            "Fork choice journal disabled";
+                            "error" => e,
+                            "dir" => %config.dir.display(),
+                        );
+                        disabled.store(true, Ordering::Relaxed);
+                    }
+                })
+                .map_err(|e| format!("Unable to spawn fork choice journal thread: {e}"))?
+        };
+
+        Ok(Self {
+            config,
+            state: Mutex::new(WriterState {
+                file_open: false,
+                written: 0,
+            }),
+            disabled,
+            tx: Some(Mutex::new(tx)),
+            thread: Some(thread),
+            log,
+        })
+    }
+
+    fn write(&self, event: &ForkChoiceEvent) -> Result<(), String> {
+        let frame = encode_frame(event)?;
+
+        let mut state = self.state.lock();
+        let new_file = !state.file_open;
+        if new_file {
+            state.file_open = true;
+            state.written = JOURNAL_MAGIC.len() as u64;
+        }
+        state.written += frame.len() as u64;
+        // The next event starts a new file, preceded by a snapshot.
+        let close = state.written >= self.config.max_file_size;
+        if close {
+            state.file_open = false;
+        }
+
+        let job = WriteJob {
+            frame,
+            new_file,
+            flush: close || matches!(event, ForkChoiceEvent::GetHead { .. }),
+            close,
+        };
+        self.tx
+            .as_ref()
+            .ok_or("Journal writer has stopped")?
+            .lock()
+            .send(job)
+            .map_err(|_| "Journal writer thread has stopped".to_string())
+    }
+}
+
+impl ForkChoiceJournal for ForkChoiceJournalWriter {
+    fn needs_snapshot(&self) -> bool {
+        !self.state.lock().file_open && !self.disabled.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, event: ForkChoiceEvent) {
+        if self.disabled.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Err(e) = self.write(&event) {
+            error!(
+                self.log,
+                "Fork choice journal disabled";
+                "error" => e,
+                "dir" => %self.config.dir.display(),
+            );
+            self.disabled.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ForkChoiceJournalWriter {
+    /// Write out the remaining frames before returning.
+    fn drop(&mut self) {
+        drop(self.tx.take());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!(self.log, "Fork choice journal thread panicked");
+            }
+        }
+    }
+}
+
+/// Write the frames received on `rx` until the channel is closed or an I/O error occurs.
+fn write_jobs(
+    config: &ForkChoiceJournalConfig,
+    rx: mpsc::Receiver<WriteJob>,
+) -> Result<(), String> {
+    let mut file: Option<BufWriter<File>> = None;
+    while let Ok(job) = rx.recv() {
+        if job.new_file {
+            rotate(&config.dir, config.max_files)
+                .map_err(|e| format!("Unable to rotate journal files: {e}"))?;
+            let path = config.dir.join(JOURNAL_FILE);
+            let mut new_file = BufWriter::new(
+                File::create(&path)
+                    .map_err(|e| format!("Unable to create {}: {e}", path.display()))?,
+            );
+            new_file
+                .write_all(JOURNAL_MAGIC)
+                .map_err(|e| format!("Unable to write journal: {e}"))?;
+            file = Some(new_file);
+        }
+
+        let current = file
+            .as_mut()
+            .ok_or("Journal frame received without a file")?;
+        current
+            .write_all(&job.frame)
+            .map_err(|e| format!("Unable to write journal: {e}"))?;
+        if job.flush {
+            current
+                .flush()
+                .map_err(|e| format!("Unable to flush journal: {e}"))?;
+        }
+        if job.close {
+            file = None;
+        }
+    }
+    if let Some(mut file) = file {
+        file.flush()
+            .map_err(|e| format!("Unable to flush journal: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Shift each journal file in `dir` to the next suffix, deleting the oldest so that at most
+/// `max_files - 1` remain.
+fn rotate(dir: &Path, max_files: usize) -> io::Result<()> {
+    for index in (0..max_files).rev() {
+        let from = journal_file_path(dir, index);
+        if !from.exists() {
+            continue;
+        }
+        if index + 1 >= max_files {
+            fs::remove_file(from)?;
+        } else {
+            fs::rename(from, journal_file_path(dir, index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+fn journal_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(JOURNAL_FILE)
+    } else {
+        dir.join(format!("{JOURNAL_FILE}.{index}"))
+    }
+}
+
+/// Return the journal files in `dir`, oldest first.
+pub fn journal_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .filter_map(|name| {
+            name.map(|name| {
+                let name = name.to_str()?;
+                let index = match name.strip_prefix(JOURNAL_FILE)? {
+                    "" => 0,
+                    suffix => suffix.strip_prefix('.')?.parse::<usize>().ok()?,
+                };
+                Some((index, dir.join(name)))
+            })
+            .transpose()
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fork_choice::journal::JournalReader;
+    use logging::test_logger;
+    use types::{Hash256, Slot};
+
+    fn read_all(dir: &Path) -> Vec<Vec<ForkChoiceEvent>> {
+        journal_files(dir)
+            .unwrap()
+            .into_iter()
+            .map(|path| {
+                JournalReader::new(File::open(path).unwrap())
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotates_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ForkChoiceJournalConfig {
+            dir: dir.path().to_path_buf(),
+            max_file_size: 64,
+            max_files: 3,
+        };
+        let journal = ForkChoiceJournalWriter::new(config, test_logger()).unwrap();
+
+        for slot in 0..20 {
+            journal.record(ForkChoiceEvent::UpdateTime {
+                current_slot: Slot::new(slot),
+            });
+            journal.record(ForkChoiceEvent::GetHead {
+                head_root: Hash256::repeat_byte(slot as u8),
+            });
+        }
+        drop(journal);
+
+        let files = read_all(dir.path());
+        assert_eq!(files.len(), 3);
+        let events = files.concat();
+        // Only the most recent events are kept, in order.
+        assert_eq!(
+            events.last(),
+            Some(&ForkChoiceEvent::GetHead {
+                head_root: Hash256::repeat_byte(19)
+            })
+        );
+        assert!(events.len() < 40);
+        assert!(events.windows(2).all(|pair| match (&pair[0], &pair[1]) {
+            (
+                ForkChoiceEvent::UpdateTime { current_slot },
+                ForkChoiceEvent::GetHead { head_root },
+            ) => *head_root == Hash256::repeat_byte(current_slot.as_u64() as u8),
+            (ForkChoiceEvent::GetHead { .. }, ForkChoiceEvent::UpdateTime { .. }) => true,
+            _ => false,
+        }));
+    }
+
+    #[test]
+    fn restart_starts_new_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for slot in 0..2 {
+            let config = ForkChoiceJournalConfig::new(dir.path().to_path_buf());
+            let journal = ForkChoiceJournalWriter::new(config, test_logger()).unwrap();
+            assert!(journal.needs_snapshot());
+            journal.record(ForkChoiceEvent::UpdateTime {
+                current_slot: Slot::new(slot),
+            });
+        }
+
+        let files = read_all(dir.path());
+        assert_eq!(
+            files,
+            vec![
+                vec![ForkChoiceEvent::UpdateTime {
+                    current_slot: Slot::new(0)
+                }],
+                vec![ForkChoiceEvent::UpdateTime {
+                    current_slot: Slot::new(1)
+                }],
+            ]
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Sources of blocks older than the oldest block in the database.
+//!
+//! Nodes which checkpoint synced without backfilling, or which pruned their history, can still
+//! serve `BlocksByRange` and `BlocksByRoot` requests for old slots by falling back to a
+//! `HistoricalBlockProvider`. Blocks from a provider are trusted by the operator and are not
+//! verified before being served, peers verify them as part of their own backfill.
+use itertools::process_results;
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use store::era::{era_of_block, find_era_files, read_era_blocks};
+use store::{DatabaseBlock, Error as StoreError, HotColdDB, LevelDB, StoreConfig};
+use types::{ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// Directory names of the databases inside a beacon node data directory.
+const HOT_DB_DIR: &str = "chain_db";
+const FREEZER_DB_DIR: &str = "freezer_db";
+const BLOBS_DB_DIR: &str = "blobs_db";
+
+/// Where historical blocks are loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HistoricalBlockSource {
+    /// A directory of era files, such as those written by `lighthouse db export-era`.
+    EraDir(PathBuf),
+    /// The `beacon` directory of another Lighthouse node, whose database is only read from. The
+    /// node must not be running.
+    Datadir(PathBuf),
+}
+
+/// Provides canonical blocks from outside the database.
+pub trait HistoricalBlockProvider<E: EthSpec>: Send + Sync {
+    /// Return the blocks with slots in `[start_slot, start_slot + count)` in slot-ascending order.
+    ///
+    /// If only part of the range is available, the blocks from `start_slot` up to the first gap
+    /// in the provider's history are returned.
+    fn blocks_by_range(
+        &self,
+        start_slot: Slot,
+        count: u64,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String>;
+
+    /// Return the block with `block_root`, if the provider has it.
+    fn block_by_root(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<E>>>, String>;
+}
+
+/// Open the provider for `source`.
+pub fn open_historical_block_provider<E: EthSpec>(
+    source: &HistoricalBlockSource,
+    store_config: StoreConfig,
+    spec: Arc<ChainSpec>,
+    log: &Logger,
+) -> Result<Arc<dyn HistoricalBlockProvider<E>>, String> {
+    match source {
+        HistoricalBlockSource::EraDir(dir) => {
+            let provider = EraBlockProvider::new(dir, spec)?;
+            info!(
+                log,
+                "Serving historical blocks from era files";
+                "dir" => ?dir,
+                "num_eras" => provider.era_files.len(),
+            );
+            Ok(Arc::new(provider))
+        }
+        HistoricalBlockSource::Datadir(dir) => {
+            let provider = StoreBlockProvider::open(dir, store_config, spec, log.clone())?;
+            info!(
+                log,
+                "Serving historical blocks from secondary database";
+                "dir" => ?dir,
+                "oldest_block_slot" => provider.store.get_oldest_block_slot(),
+                "split_slot" => provider.store.get_split_slot(),
+            );
+            Ok(Arc::new(provider))
+        }
+    }
+}
+
+/// Serves blocks from a directory of era files.
+///
+/// The directory is scanned once on startup. Era files are only indexed by slot, so blocks can't
+/// be looked up by root.
+pub struct EraBlockProvider {
+    era_files: BTreeMap<u64, PathBuf>,
+    spec: Arc<ChainSpec>,
+}
+
+impl EraBlockProvider {
+    pub fn new(dir: &Path, spec: Arc<ChainSpec>) -> Result<Self, String> {
+        Ok(Self {
+            era_files: find_era_files(dir)?,
+            spec,
+        })
+    }
+}
+
+impl<E: EthSpec> HistoricalBlockProvider<E> for EraBlockProvider {
+    fn blocks_by_range(
+        &self,
+        start_slot: Slot,
+        count: u64,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+        let end_slot = start_slot.saturating_add(count);
+        if count == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut blocks = vec![];
+        for era in era_of_block::<E>(start_slot)..=era_of_block::<E>(end_slot - 1) {
+            let Some(path) = self.era_files.get(&era) else {
+                break;
+            };
+            blocks.extend(
+                read_era_blocks::<E>(path, start_slot, end_slot, &self.spec)?
+                    .into_iter()
+                    .map(Arc::new),
+            );
+        }
+        Ok(blocks)
+    }
+
+    fn block_by_root(
+        &self,
+        _block_root: &Hash256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<E>>>, String> {
+        Ok(None)
+    }
+}
+
+/// Serves finalized blocks from the database of another node.
+pub struct StoreBlockProvider<E: EthSpec> {
+    store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+}
+
+impl<E: EthSpec> StoreBlockProvider<E> {
+    /// Open the databases in the `beacon` directory `dir` without writing to them.
+    pub fn open(
+        dir: &Path,
+        config: StoreConfig,
+        spec: Arc<ChainSpec>,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let store = HotColdDB::open_read_only(
+            &dir.join(HOT_DB_DIR),
+            &dir.join(FREEZER_DB_DIR),
+            &dir.join(BLOBS_DB_DIR),
+            config,
+            spec,
+            log,
+        )
+        .map_err(|e| format!("Unable to open historical block database {dir:?}: {e:?}"))?;
+        Ok(Self { store })
+    }
+
+    fn full_block(&self, block_root: &Hash256) -> Result<Option<SignedBeaconBlock<E>>, String> {
+        // Blocks whose payloads have been pruned can't be served.
+        match self.store.try_get_full_block(block_root) {
+            Ok(Some(DatabaseBlock::Full(block))) => Ok(Some(block)),
+            Ok(Some(DatabaseBlock::Blinded(_))) | Ok(None) => Ok(None),
+            Err(e) => Err(format!("Unable to load block {block_root:?}: {e:?}")),
+        }
+    }
+}
+
+impl<E: EthSpec> HistoricalBlockProvider<E> for StoreBlockProvider<E> {
+    fn blocks_by_range(
+        &self,
+        start_slot: Slot,
+        count: u64,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+        // Only the frozen part of the database is served, which doesn't require any state to
+        // iterate.
+        let end_slot = std::cmp::min(
+            start_slot.saturating_add(count),
+            self.store.get_split_slot(),
+        );
+        if start_slot < self.store.get_oldest_block_slot() || start_slot >= end_slot {
+            return Ok(vec![]);
+        }
+
+        let root_iter = self
+            .store
+            .forwards_block_roots_iterator_until(start_slot, end_slot - 1, || {
+                Err(StoreError::StateShouldNotBeRequired(end_slot - 1))
+            })
+            .map_err(|e| format!("Unable to iterate block roots: {e:?}"))?;
+        let block_roots = process_results(root_iter, |iter| {
+            iter.map(|(block_root, _)| block_root).collect::<Vec<_>>()
+        })
+        .map_err(|e| format!("Unable to iterate block roots: {e:?}"))?;
+
+        let mut blocks = vec![];
+        let mut prev_block_root = None;
+        for block_root in block_roots {
+            // Skipped slots repeat the root of the previous block.
+            if prev_block_root == Some(block_root) {
+                continue;
+            }
+            prev_block_root = Some(block_root);
+
+            let Some(block) = self.full_block(&block_root)? else {
+                break;
+            };
+            // The first slot of the range may be a skipped slot pointing at an earlier block.
+            if block.slot() >= start_slot {
+                blocks.push(Arc::new(block));
+            }
+        }
+        Ok(blocks)
+    }
+
+    fn block_by_root(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<E>>>, String> {
+        Ok(self.full_block(block_root)?.map(Arc::new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Signature;
+    use ssz::Encode;
+    use store::era::{
+        era_file_name, E2Writer, E2_COMPRESSED_BEACON_STATE, E2_COMPRESSED_SIGNED_BEACON_BLOCK,
+        E2_VERSION,
+    };
+    use types::{BeaconBlock, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn block_at(slot: u64, spec: &ChainSpec) -> SignedBeaconBlock<E> {
+        let mut block = BeaconBlock::<E>::empty(spec);
+        *block.slot_mut() = Slot::new(slot);
+        SignedBeaconBlock::from_block(block, Signature::empty())
+    }
+
+    /// Write an era file holding `blocks`, with a placeholder state which the provider never reads.
+    fn write_era(dir: &Path, era: u64, blocks: &[SignedBeaconBlock<E>]) {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let start_slot = Slot::new((era - 1) * slots_per_era);
+        let mut writer = E2Writer::new(vec![]);
+        writer.write_record(E2_VERSION, &[]).unwrap();
+        let mut offsets = vec![None; slots_per_era as usize];
+        for block in blocks {
+            let offset = writer
+                .write_compressed(E2_COMPRESSED_SIGNED_BEACON_BLOCK, &block.as_ssz_bytes())
+                .unwrap();
+            offsets[(block.slot() - start_slot).as_usize()] = Some(offset);
+        }
+        let state_offset = writer
+            .write_compressed(E2_COMPRESSED_BEACON_STATE, &[0])
+            .unwrap();
+        writer.write_slot_index(start_slot, &offsets).unwrap();
+        writer
+            .write_slot_index(Slot::new(era * slots_per_era), &[Some(state_offset)])
+            .unwrap();
+
+        let file_name = era_file_name(Some("minimal"), era, Hash256::zero());
+        std::fs::write(dir.join(file_name), writer.into_inner()).unwrap();
+    }
+
+    #[test]
+    fn era_blocks_by_range() {
+        let spec = Arc::new(E::default_spec());
+        let dir = tempfile::tempdir().unwrap();
+
+        // Eras 1 and 2 cover slots 0..128 with the minimal spec, era 3 is missing.
+        let era_1_blocks = vec![block_at(1, &spec), block_at(63, &spec)];
+        let era_2_blocks = vec![block_at(64, &spec), block_at(100, &spec)];
+        write_era(dir.path(), 1, &era_1_blocks);
+        write_era(dir.path(), 2, &era_2_blocks);
+        let provider = EraBlockProvider::new(dir.path(), spec.clone()).unwrap();
+
+        let slots = |start_slot: u64, count: u64| {
+            HistoricalBlockProvider::<E>::blocks_by_range(&provider, Slot::new(start_slot), count)
+                .unwrap()
+                .iter()
+                .map(|block| block.slot().as_u64())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(slots(0, 64), vec![1, 63]);
+        assert_eq!(slots(2, 62), vec![63]);
+        assert_eq!(slots(60, 10), vec![63, 64]);
+        assert_eq!(slots(0, 128), vec![1, 63, 64, 100]);
+        assert_eq!(slots(101, 27), Vec::<u64>::new());
+        assert_eq!(slots(1, 0), Vec::<u64>::new());
+
+        // The range stops at the first missing era.
+        assert_eq!(slots(90, 100), vec![100]);
+        assert_eq!(slots(128, 64), Vec::<u64>::new());
+
+        let block = HistoricalBlockProvider::<E>::blocks_by_range(&provider, Slot::new(64), 1)
+            .unwrap()
+            .remove(0);
+        assert_eq!(*block, era_2_blocks[0]);
+    }
+
+    #[test]
+    fn era_block_by_root_is_unavailable() {
+        let spec = Arc::new(E::default_spec());
+        let dir = tempfile::tempdir().unwrap();
+        let block = block_at(5, &spec);
+        write_era(dir.path(), 1, &[block.clone()]);
+        let provider = EraBlockProvider::new(dir.path(), spec).unwrap();
+
+        let result =
+            HistoricalBlockProvider::<E>::block_by_root(&provider, &block.canonical_root());
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn era_provider_rejects_duplicate_eras() {
+        let spec = Arc::new(E::default_spec());
+        let dir = tempfile::tempdir().unwrap();
+        write_era(dir.path(), 1, &[]);
+        let other_name = era_file_name(Some("minimal"), 1, Hash256::repeat_byte(1));
+        std::fs::write(dir.path().join(other_name), []).unwrap();
+
+        assert!(EraBlockProvider::new(dir.path(), spec).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+//! Light client updates for sync committee periods the node didn't follow itself.
+//!
+//! The light client server only produces updates for blocks as they are imported, so a node that
+//! checkpoint-synced has no updates for earlier periods. Once such a period is finalized and its
+//! blocks have been backfilled, its best update is derived from the stored blocks and freezer
+//! states, and written to `DBColumn::LightClientHistoricalUpdate`.
+use crate::light_client_server_cache::LightClientCachedData;
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use slog::{debug, info, warn};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{EthSpec, Hash256, LightClientUpdate, SignedBlindedBeaconBlock, Slot};
+
+/// Number of blocks per period, taken in order of sync committee participation, for which a full
+/// update is computed. Each one requires loading a state from the freezer.
+const CANDIDATES_PER_PERIOD: usize = 4;
+/// Delay between checks for periods that have become available.
+const POLL_INTERVAL: Duration = Duration::from_secs(600);
+
+/// A block whose sync aggregate signs its parent block, both within the same period.
+struct Candidate<E: EthSpec> {
+    signature_block: SignedBlindedBeaconBlock<E>,
+    attested_block_root: Hash256,
+    participation: usize,
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    fn sync_committee_period_start_slot(&self, sync_committee_period: u64) -> Slot {
+        (self.spec.epochs_per_sync_committee_period * sync_committee_period)
+            .start_slot(T::EthSpec::slots_per_epoch())
+    }
+
+    /// Return the past sync committee periods without a light client update for which one can be
+    /// generated.
+    ///
+    /// A period qualifies once it is entirely below the split slot, all of its blocks are stored
+    /// and all of its states are available in the freezer.
+    pub fn missing_historical_light_client_update_periods(
+        &self,
+    ) -> Result<Vec<u64>, BeaconChainError> {
+        let Some(altair_fork_epoch) = self.spec.altair_fork_epoch else {
+            return Ok(vec![]);
+        };
+        let first_period = altair_fork_epoch.sync_committee_period(&self.spec)?;
+        let split_period = self
+            .store
+            .get_split_slot()
+            .epoch(T::EthSpec::slots_per_epoch())
+            .sync_committee_period(&self.spec)?;
+        let oldest_block_slot = self.store.get_oldest_block_slot();
+        let (state_lower_limit, state_upper_limit) = self.store.get_historic_state_limits();
+
+        let mut periods = vec![];
+        for period in first_period..split_period {
+            let start_slot = self.sync_committee_period_start_slot(period);
+            let last_slot = self.sync_committee_period_start_slot(period + 1) - 1;
+
+            let blocks_available = start_slot >= oldest_block_slot;
+            let states_available = last_slot <= state_lower_limit
+                || start_slot >= state_upper_limit
+                || state_lower_limit >= state_upper_limit;
+            if !blocks_available || !states_available {
+                continue;
+            }
+
+            if self.store.get_light_client_update(period)?.is_none()
+                && self
+                    .store
+                    .get_historical_light_client_update(period)?
+                    .is_none()
+            {
+                periods.push(period);
+            }
+        }
+        Ok(periods)
+    }
+
+    /// Derive the best light client update for a past `sync_committee_period` from stored blocks
+    /// and freezer states.
+    ///
+    /// Only the `CANDIDATES_PER_PERIOD` blocks with the highest sync committee participation are
+    /// compared with `is_better_light_client_update`. Returns `None` if no block in the period
+    /// signs a parent from the same period.
+    pub fn compute_historical_light_client_update(
+        &self,
+        sync_committee_period: u64,
+    ) -> Result<Option<LightClientUpdate<T::EthSpec>>, BeaconChainError> {
+        let start_slot = self.sync_committee_period_start_slot(sync_committee_period);
+        let last_slot = self.sync_committee_period_start_slot(sync_committee_period + 1) - 1;
+
+        let mut candidates = vec![];
+        let mut parent: Option<(Hash256, Slot)> = None;
+        for res in self.forwards_iter_block_roots_until(start_slot, last_slot)? {
+            let (block_root, _) = res?;
+            if parent.is_some_and(|(parent_root, _)| parent_root == block_root) {
+                // Skipped slot.
+                continue;
+            }
+            let block = self
+                .get_blinded_block(&block_root)?
+                .ok_or(BeaconChainError::MissingBeaconBlock(block_root))?;
+            let block_slot = block.slot();
+
+            if let Some((attested_block_root, attested_slot)) = parent {
+                let participation = block
+                    .message()
+                    .body()
+                    .sync_aggregate()
+                    .map_or(0, |sync_aggregate| {
+                        sync_aggregate.sync_committee_bits.num_set_bits()
+                    });
+                if attested_slot >= start_slot && participation > 0 {
+                    candidates.push(Candidate {
+                        signature_block: block,
+                        attested_block_root,
+                        participation,
+                    });
+                }
+            }
+            parent = Some((block_root, block_slot));
+        }
+
+        candidates.sort_by_key(|candidate| Reverse(candidate.participation));
+
+        let mut best_update: Option<LightClientUpdate<T::EthSpec>> = None;
+        for candidate in candidates.into_iter().take(CANDIDATES_PER_PERIOD) {
+            let update = self.light_client_update_for_candidate(candidate)?;
+            let is_better = match &best_update {
+                Some(best_update) => {
+                    best_update.is_better_light_client_update(&update, &self.spec)?
+                }
+                None => true,
+            };
+            if is_better {
+                best_update = Some(update);
+            }
+        }
+        Ok(best_update)
+    }
+
+    fn light_client_update_for_candidate(
+        &self,
+        candidate: Candidate<T::EthSpec>,
+    ) -> Result<LightClientUpdate<T::EthSpec>, BeaconChainError> {
+        let attested_block = self
+            .get_blinded_block(&candidate.attested_block_root)?
+            .ok_or(BeaconChainError::MissingBeaconBlock(
+                candidate.attested_block_root,
+            ))?;
+        let mut attested_state = self
+            .store
+            .get_state(&attested_block.state_root(), Some(attested_block.slot()))?
+            .ok_or(BeaconChainError::MissingBeaconState(
+                attested_block.state_root(),
+            ))?;
+        let cached_parts = LightClientCachedData::from_state(&mut attested_state)?;
+
+        // The genesis block is also stored under the zero root, which isn't a finalized block.
+        // Any other finalized block must be stored, or the update would be persisted without
+        // finality and never regenerated. It may be missing if it's older than the oldest block,
+        // in which case the period is retried once backfill has stored it.
+        let finalized_block = if cached_parts.finalized_block_root.is_zero() {
+            None
+        } else {
+            Some(
+                self.get_blinded_block(&cached_parts.finalized_block_root)?
+                    .ok_or(BeaconChainError::MissingBeaconBlock(
+                        cached_parts.finalized_block_root,
+                    ))?,
+            )
+        };
+
+        let sync_aggregate = candidate
+            .signature_block
+            .message()
+            .body()
+            .sync_aggregate()
+            .map_err(|_| {
+                BeaconChainError::DBInconsistent(format!(
+                    "Block {:?} has no sync aggregate",
+                    candidate.signature_block.canonical_root()
+                ))
+            })?;
+
+        Ok(LightClientUpdate::new(
+            sync_aggregate,
+            candidate.signature_block.slot(),
+            cached_parts.next_sync_committee,
+            cached_parts.next_sync_committee_branch,
+            cached_parts.finality_branch,
+            &attested_block,
+            finalized_block.as_ref(),
+            &self.spec,
+        )?)
+    }
+
+    /// Compute and store the update for `sync_committee_period`, returning whether one was found.
+    pub fn store_historical_light_client_update(
+        &self,
+        sync_committee_period: u64,
+    ) -> Result<bool, BeaconChainError> {
+        let Some(update) = self.compute_historical_light_client_update(sync_committee_period)?
+        else {
+            return Ok(false);
+        };
+        self.store
+            .store_historical_light_client_update(sync_committee_period, &update)?;
+        Ok(true)
+    }
+}
+
+/// Spawns a routine which generates light client updates for past sync committee periods, if
+/// `chain.config.light_client_historical_updates` is set.
+pub fn start_historical_light_client_updates_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    if !chain.config.enable_light_client_server || !chain.config.light_client_historical_updates {
+        return;
+    }
+
+    executor.spawn(
+        async move { historical_light_client_updates_service(chain).await },
+        "historical_light_client_updates",
+    );
+}
+
+/// Periodically generate updates for the periods that have become available, one at a time.
+async fn historical_light_client_updates_service<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) {
+    // Periods for which no update could be derived, which aren't retried.
+    let mut periods_without_update = HashSet::new();
+
+    loop {
+        let inner_chain = chain.clone();
+        let periods = match chain
+            .spawn_blocking_handle(
+                move || inner_chain.missing_historical_light_client_update_periods(),
+                "historical_light_client_update_periods",
+            )
+            .await
+        {
+            Ok(Ok(periods)) => periods
+                .into_iter()
+                .filter(|period| !periods_without_update.contains(period))
+                .collect::<Vec<_>>(),
+            Ok(Err(e)) | Err(e) => {
+                warn!(
+                    chain.log,
+                    "Unable to find missing light client update periods";
+                    "error" => ?e,
+                );
+                vec![]
+            }
+        };
+
+        if !periods.is_empty() {
+            info!(
+                chain.log,
+                "Generating historical light client updates";
+                "num_periods" => periods.len(),
+                "first_period" => periods[0],
+            );
+        }
+
+        let mut num_stored = 0;
+        for period in periods {
+            let inner_chain = chain.clone();
+            match chain
+                .spawn_blocking_handle(
+                    move || inner_chain.store_historical_light_client_update(period),
+                    "historical_light_client_update",
+                )
+                .await
+            {
+                Ok(Ok(stored)) => {
+                    debug!(
+                        chain.log,
+                        "Generated historical light client update";
+                        "period" => period,
+                        "stored" => stored,
+                    );
+                    if stored {
+                        num_stored += 1;
+                    } else {
+                        periods_without_update.insert(period);
+                    }
+                }
+                Ok(Err(e)) | Err(e) => {
+                    warn!(
+                        chain.log,
+                        "Failed to generate historical light client update";
+                        "period" => period,
+                        "error" => ?e,
+                    );
+                }
+            }
+        }
+
+        if num_stored > 0 {
+            info!(
+                chain.log,
+                "Stored historical light client updates";
+                "count" => num_stored,
+            );
+        }
+
+        sleep(POLL_INTERVAL).await;
+    }
+}
@@ -1,13 +1,9 @@
 use crate::errors::BeaconChainError;
 use crate::{metrics, BeaconChainTypes, BeaconStore};
 use parking_lot::{Mutex, RwLock};
-use safe_arith::SafeArith;
 use slog::{debug, Logger};
-use ssz::Decode;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use store::DBColumn;
-use store::KeyValueStore;
 use tree_hash::TreeHash;
 use types::non_zero_usize::new_non_zero_usize;
 use types::{
@@ -266,37 +262,23 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
         Ok(None)
     }
 
+    /// Fetch the light client updates for up to `count` periods from `start_period`.
+    ///
+    /// Updates produced while following the chain take precedence over those generated for past
+    /// periods from the freezer. Periods without either are skipped.
     pub fn get_light_client_updates(
         &self,
         store: &BeaconStore<T>,
         start_period: u64,
         count: u64,
-        chain_spec: &ChainSpec,
     ) -> Result<Vec<LightClientUpdate<T::EthSpec>>, BeaconChainError> {
-        let column = DBColumn::LightClientUpdate;
         let mut light_client_updates = vec![];
-        for res in store
-            .hot_db
-            .iter_column_from::<Vec<u8>>(column, &start_period.to_le_bytes())
-        {
-            let (sync_committee_bytes, light_client_update_bytes) = res?;
-            let sync_committee_period = u64::from_ssz_bytes(&sync_committee_bytes)
-                .map_err(store::errors::Error::SszDecodeError)?;
-
-            if sync_committee_period >= start_period + count {
-                break;
-            }
-
-            let epoch = sync_committee_period
-                .safe_mul(chain_spec.epochs_per_sync_committee_period.into())?;
-
-            let fork_name = chain_spec.fork_name_at_epoch(epoch.into());
-
-            let light_client_update =
-                LightClientUpdate::from_ssz_bytes(&light_client_update_bytes, &fork_name)
-                    .map_err(store::errors::Error::SszDecodeError)?;
-
-            light_client_updates.push(light_client_update);
+        for sync_committee_period in start_period..start_period.saturating_add(count) {
+            let light_client_update = match store.get_light_client_update(sync_committee_period)? {
+                Some(light_client_update) => Some(light_client_update),
+                None => store.get_historical_light_client_update(sync_committee_period)?,
+            };
+            light_client_updates.extend(light_client_update);
         }
         Ok(light_client_updates)
     }
@@ -408,18 +390,18 @@ impl<T: BeaconChainTypes> Default for LightClientServerCache<T> {
 }
 
 #[derive(Clone)]
-struct LightClientCachedData<E: EthSpec> {
-    finalized_checkpoint: Checkpoint,
-    finality_branch: MerkleProof,
-    next_sync_committee_branch: MerkleProof,
-    current_sync_committee_branch: MerkleProof,
-    next_sync_committee: Arc<SyncCommittee<E>>,
-    current_sync_committee: Arc<SyncCommittee<E>>,
-    finalized_block_root: Hash256,
+pub(crate) struct LightClientCachedData<E: EthSpec> {
+    pub(crate) finalized_checkpoint: Checkpoint,
+    pub(crate) finality_branch: MerkleProof,
+    pub(crate) next_sync_committee_branch: MerkleProof,
+    pub(crate) current_sync_committee_branch: MerkleProof,
+    pub(crate) next_sync_committee: Arc<SyncCommittee<E>>,
+    pub(crate) current_sync_committee: Arc<SyncCommittee<E>>,
+    pub(crate) finalized_block_root: Hash256,
 }
 
 impl<E: EthSpec> LightClientCachedData<E> {
-    fn from_state(state: &mut BeaconState<E>) -> Result<Self, BeaconChainError> {
+    pub(crate) fn from_state(state: &mut BeaconState<E>) -> Result<Self, BeaconChainError> {
         let (finality_branch, next_sync_committee_branch, current_sync_committee_branch) = (
             state.compute_finalized_root_proof()?,
             state.compute_current_sync_committee_proof()?,
@@ -11,6 +11,7 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::{migrate_database, HotColdDBError};
 use store::iter::RootsIterator;
+use store::retention::RetentionPolicy;
 use store::{Error, ItemStore, StoreItem, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
@@ -42,6 +43,8 @@ pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>
     prev_migration: Arc<Mutex<PrevMigration>>,
     #[allow(clippy::type_complexity)]
     tx_thread: Option<Mutex<(mpsc::Sender<Notification>, thread::JoinHandle<()>)>>,
+    /// Channel to the thread which computes column statistics, if not running in the foreground.
+    column_stats_tx: Option<Mutex<mpsc::Sender<Epoch>>>,
     /// Genesis block root, for persisting the `PersistedBeaconChain`.
     genesis_block_root: Hash256,
     log: Logger,
@@ -132,6 +135,7 @@ pub struct FinalizationNotification {
     head_tracker: Arc<HeadTracker>,
     prev_migration: Arc<Mutex<PrevMigration>>,
     genesis_block_root: Hash256,
+    column_stats_tx: Option<mpsc::Sender<Epoch>>,
 }
 
 impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Hot, Cold> {
@@ -147,14 +151,21 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             epoch: db.get_split_slot().epoch(E::slots_per_epoch()),
             epochs_per_migration: config.epochs_per_migration,
         }));
-        let tx_thread = if config.blocking {
-            None
+        let (tx_thread, column_stats_tx) = if config.blocking {
+            (None, None)
         } else {
-            Some(Mutex::new(Self::spawn_thread(db.clone(), log.clone())))
+            (
+                Some(Mutex::new(Self::spawn_thread(db.clone(), log.clone()))),
+                Some(Mutex::new(Self::spawn_column_stats_thread(
+                    db.clone(),
+                    log.clone(),
+                ))),
+            )
         };
         Self {
             db,
             tx_thread,
+            column_stats_tx,
             prev_migration,
             genesis_block_root,
             log,
@@ -178,6 +189,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker,
             prev_migration: self.prev_migration.clone(),
             genesis_block_root: self.genesis_block_root,
+            column_stats_tx: self.column_stats_tx.as_ref().map(|tx| tx.lock().clone()),
         };
 
         // Send to background thread if configured, otherwise run in foreground.
@@ -396,9 +408,14 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         };
 
-        // Finally, compact the database so that new free space is properly reclaimed.
+        // Enforce the retention policies for data which isn't pruned during the migration.
+        if let Err(e) = Self::run_retention(db.clone(), log) {
+            warn!(log, "Database retention pruning failed"; "error" => ?e);
+        }
+
+        // Compact the database so that new free space is properly reclaimed.
         if let Err(e) = Self::run_compaction(
-            db,
+            db.clone(),
             old_finalized_checkpoint.epoch,
             notif.finalized_checkpoint.epoch,
             log,
@@ -406,6 +423,18 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             warn!(log, "Database compaction failed"; "error" => format!("{:?}", e));
         }
 
+        // Finally, refresh the column statistics so that they reflect any reclaimed space. This
+        // iterates the whole database, so it runs on its own thread to avoid delaying the next
+        // migration.
+        let finalized_epoch = notif.finalized_checkpoint.epoch;
+        if let Some(tx) = notif.column_stats_tx {
+            if tx.send(finalized_epoch).is_err() {
+                warn!(log, "Database column statistics thread has stopped");
+            }
+        } else if let Err(e) = Self::run_column_stats(db, finalized_epoch, log) {
+            warn!(log, "Database column statistics failed"; "error" => ?e);
+        }
+
         debug!(log, "Database consolidation complete");
     }
 
@@ -465,6 +494,26 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         (tx, thread)
     }
 
+    /// Spawn a child thread to compute column statistics.
+    ///
+    /// Return a channel for sending it the finalized epoch after each migration.
+    fn spawn_column_stats_thread(
+        db: Arc<HotColdDB<E, Hot, Cold>>,
+        log: Logger,
+    ) -> mpsc::Sender<Epoch> {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            while let Ok(epoch) = rx.recv() {
+                // Skip any epochs which finalized while the previous statistics were computed.
+                let finalized_epoch = rx.try_iter().fold(epoch, std::cmp::max);
+                if let Err(e) = Self::run_column_stats(db.clone(), finalized_epoch, &log) {
+                    warn!(log, "Database column statistics failed"; "error" => ?e);
+                }
+            }
+        });
+        tx
+    }
+
     /// Traverses live heads and prunes blocks and states of chains that we know can't be built
     /// upon because finalization would prohibit it. This is an optimisation intended to save disk
     /// space.
@@ -747,6 +796,47 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         })
     }
 
+    /// Prune payloads retained for a number of epochs after finalization, and historic states,
+    /// according to the store's retention policies.
+    ///
+    /// Blobs are pruned separately by `run_prune_blobs`, and payloads which aren't retained after
+    /// finalization are deleted by `migrate_database`. Reconstructed historic states are never
+    /// pruned here, that requires `lighthouse db prune --confirm`.
+    pub fn run_retention(db: Arc<HotColdDB<E, Hot, Cold>>, log: &Logger) -> Result<(), Error> {
+        if let RetentionPolicy::KeepLastNEpochs(n @ 1..) =
+            db.get_config().effective_payload_retention()
+        {
+            debug!(log, "Pruning retained execution payloads"; "epochs" => n);
+            db.try_prune_execution_payloads(false)?;
+        }
+        db.try_prune_historic_states(false)?;
+        Ok(())
+    }
+
+    /// Recompute the per-column key counts and sizes if `epochs_per_column_stats` epochs have
+    /// passed since they were last computed.
+    pub fn run_column_stats(
+        db: Arc<HotColdDB<E, Hot, Cold>>,
+        finalized_epoch: Epoch,
+        log: &Logger,
+    ) -> Result<(), Error> {
+        let epochs_per_column_stats = db.get_config().epochs_per_column_stats;
+        if epochs_per_column_stats == 0 {
+            return Ok(());
+        }
+        if db
+            .get_column_stats()
+            .is_some_and(|stats| stats.epoch + epochs_per_column_stats > finalized_epoch)
+        {
+            return Ok(());
+        }
+
+        debug!(log, "Computing database column statistics"; "finalized_epoch" => finalized_epoch);
+        db.update_column_stats(finalized_epoch)?;
+        debug!(log, "Database column statistics complete");
+        Ok(())
+    }
+
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
     /// was last compacted.
     pub fn run_compaction(
@@ -0,0 +1,51 @@
+use crate::beacon_chain::{BeaconChainTypes, OP_POOL_DB_KEY};
+use operation_pool::{
+    PersistedOperationPool, PersistedOperationPoolV20, PersistedOperationPoolV23,
+};
+use slog::{debug, info, warn, Logger};
+use std::sync::Arc;
+use store::{Error, HotColdDB, KeyValueStoreOp, StoreItem};
+
+pub fn upgrade_to_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v22 to v23");
+
+    // Load a V20 op pool and encode each of its operations individually.
+    let Some(pool_v20) = db.get_item::<PersistedOperationPoolV20<T::EthSpec>>(&OP_POOL_DB_KEY)?
+    else {
+        debug!(log, "Nothing to do, no operation pool stored");
+        return Ok(vec![]);
+    };
+
+    let v23 = PersistedOperationPool::V23(PersistedOperationPoolV23::from(pool_v20));
+    Ok(vec![v23.as_kv_store_op(OP_POOL_DB_KEY)])
+}
+
+pub fn downgrade_from_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v23 to v22");
+
+    // Load a V23 op pool and decode its operations into a V20 op pool.
+    let Some(pool_v23) = db.get_item::<PersistedOperationPoolV23<T::EthSpec>>(&OP_POOL_DB_KEY)?
+    else {
+        debug!(log, "Nothing to do, no operation pool stored");
+        return Ok(vec![]);
+    };
+
+    let (pool_v20, discarded) = pool_v23.decode_operations();
+    if !discarded.is_empty() {
+        warn!(
+            log,
+            "Discarded undecodable operations during downgrade";
+            "total" => discarded.total(),
+            "discarded" => %discarded,
+        );
+    }
+
+    let v20 = PersistedOperationPool::V20(pool_v20);
+    Ok(vec![v20.as_kv_store_op(OP_POOL_DB_KEY)])
+}
@@ -8,7 +8,7 @@ use beacon_chain::schema_change::migrate_schema;
 use beacon_chain::test_utils::SyncCommitteeStrategy;
 use beacon_chain::test_utils::{
     get_kzg, mock_execution_layer_from_parts, test_spec, AttestationStrategy, BeaconChainHarness,
-    BlockStrategy, DiskHarnessType,
+    BlockStrategy, DiskHarnessType, LightClientStrategy,
 };
 use beacon_chain::{
     data_availability_checker::MaybeAvailableBlock, historical_blocks::HistoricalBlockError,
@@ -25,10 +25,13 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
+use store::hdiff::HierarchyConfig;
+use store::hierarchy_migration::swap_reencoded_freezer_states;
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
 use store::{
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, LevelDB, StoreConfig,
+    retention::RetentionPolicy,
+    AnchorInfo, BlobInfo, DBColumn, HotColdDB, KeyValueStore, LevelDB, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -229,6 +232,76 @@ async fn light_client_updates_test() {
     assert_eq!(lc_updates.len(), 2);
 }
 
+#[tokio::test]
+async fn historical_light_client_updates_test() {
+    let spec = test_spec::<E>();
+    let Some(_) = spec.altair_fork_epoch else {
+        // No-op prior to Altair.
+        return;
+    };
+
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(&db_path, StoreConfig::default(), spec.clone());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let slots_per_period = E::slots_per_epoch() * spec.epochs_per_sync_committee_period.as_u64();
+
+    // Produce three periods of blocks without computing light client updates as they're imported.
+    harness
+        .extend_chain_with_sync(
+            (3 * slots_per_period) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+            LightClientStrategy::Disabled,
+        )
+        .await;
+
+    let periods = harness
+        .chain
+        .missing_historical_light_client_update_periods()
+        .unwrap();
+    assert!(!periods.is_empty());
+    let count = periods.len() as u64;
+    assert!(harness
+        .chain
+        .get_light_client_updates(periods[0], count)
+        .unwrap()
+        .is_empty());
+
+    for &period in &periods {
+        assert!(harness
+            .chain
+            .store_historical_light_client_update(period)
+            .unwrap());
+    }
+    assert!(harness
+        .chain
+        .missing_historical_light_client_update_periods()
+        .unwrap()
+        .is_empty());
+
+    let lc_updates = harness
+        .chain
+        .get_light_client_updates(periods[0], count)
+        .unwrap();
+    assert_eq!(lc_updates.len(), periods.len());
+    for (lc_update, period) in lc_updates.iter().zip(&periods) {
+        let signature_period = lc_update
+            .signature_slot()
+            .epoch(E::slots_per_epoch())
+            .sync_committee_period(&spec)
+            .unwrap();
+        assert_eq!(signature_period, *period);
+        assert_eq!(
+            lc_update
+                .sync_aggregate()
+                .sync_committee_bits
+                .num_set_bits(),
+            E::sync_committee_size()
+        );
+    }
+}
+
 #[tokio::test]
 async fn full_participation_no_skips() {
     let num_blocks_produced = E::slots_per_epoch() * 5;
@@ -250,6 +323,54 @@ async fn full_participation_no_skips() {
     check_iterators(&harness);
 }
 
+#[tokio::test]
+async fn column_stats() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The migrator computes the statistics on the first finalization.
+    let first_stats = store.get_column_stats().expect("column stats computed");
+    assert!(first_stats.epoch > 0);
+
+    let finalized_epoch = harness
+        .chain
+        .canonical_head
+        .cached_head()
+        .finalized_checkpoint()
+        .epoch;
+    store.update_column_stats(finalized_epoch).unwrap();
+    let stats = store.get_column_stats().unwrap();
+    assert_eq!(stats.epoch, finalized_epoch);
+
+    // All blocks including genesis are stored in the hot DB.
+    let blocks = stats.hot_db[DBColumn::BeaconBlock.as_str()];
+    assert_eq!(blocks.num_keys, num_blocks_produced + 1);
+    assert!(blocks.total_bytes > 0);
+
+    // The freezer holds one block root per finalized slot.
+    let split_slot = store.get_split_slot();
+    let block_roots = stats.freezer_db[DBColumn::BeaconBlockRoots.as_str()];
+    assert_eq!(block_roots.num_keys, split_slot.as_u64());
+
+    // Empty columns are omitted.
+    assert!(stats
+        .hot_db
+        .values()
+        .chain(stats.freezer_db.values())
+        .chain(stats.blobs_db.values())
+        .all(|stats| stats.num_keys > 0));
+}
+
 #[tokio::test]
 async fn randomised_skips() {
     let num_slots = E::slots_per_epoch() * 5;
@@ -3294,6 +3415,82 @@ async fn deneb_prune_blobs_margin_test(margin: u64) {
     check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
 }
 
+/// Check that blobs kept by the retention policy below the oldest blob slot are recorded as sparse.
+#[tokio::test]
+async fn deneb_prune_blobs_keep_finalized_checkpoints_only() {
+    let config = StoreConfig {
+        blob_retention: Some(RetentionPolicy::KeepFinalizedCheckpointsOnly),
+        data_column_retention: Some(RetentionPolicy::KeepFinalizedCheckpointsOnly),
+        ..StoreConfig::default()
+    };
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+
+    let spec = store.get_chain_spec();
+    if spec.deneb_fork_epoch.is_none() || spec.eip7594_fork_epoch.is_some() {
+        // No blobs prior to Deneb, and only data columns with PeerDAS.
+        return;
+    }
+
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Not every block has blobs, so note the slots of those that do before pruning.
+    let data_availability_boundary = Epoch::new(6);
+    let end_slot = data_availability_boundary.start_slot(E::slots_per_epoch());
+    let blob_slots = harness
+        .chain
+        .forwards_iter_block_roots_until(Slot::new(0), end_slot - 1)
+        .unwrap()
+        .map(Result::unwrap)
+        .filter(|(block_root, _)| store.get_blobs(block_root).unwrap().is_some())
+        .map(|(block_root, slot)| (block_root, slot, slot % E::slots_per_epoch() == 0))
+        .collect::<Vec<_>>();
+
+    store
+        .try_prune_blobs(true, data_availability_boundary)
+        .unwrap();
+
+    // Only the blobs of checkpoint blocks before the boundary remain.
+    for (block_root, slot, is_checkpoint) in &blob_slots {
+        let blobs = store.get_blobs(block_root).unwrap();
+        assert_eq!(blobs.is_some(), *is_checkpoint, "blobs at slot {slot}");
+    }
+
+    // Blobs are contiguous after the last pruned block, those kept before it are sparse.
+    let last_pruned = blob_slots
+        .iter()
+        .rposition(|(_, _, is_checkpoint)| !is_checkpoint)
+        .unwrap_or(0);
+    let (sparse, contiguous) = blob_slots.split_at(last_pruned);
+    let blob_info = store.get_blob_info();
+    assert_eq!(
+        blob_info.oldest_blob_slot,
+        Some(
+            contiguous
+                .iter()
+                .find(|(_, _, is_checkpoint)| *is_checkpoint)
+                .map_or(end_slot, |(_, slot, _)| *slot)
+        )
+    );
+    assert_eq!(
+        blob_info.oldest_sparse_blob_slot,
+        sparse
+            .iter()
+            .find(|(_, _, is_checkpoint)| *is_checkpoint)
+            .map(|(_, slot, _)| *slot)
+    );
+    check_blob_existence(&harness, end_slot, harness.head_slot(), true);
+}
+
 /// Check that a database with `blobs_db=false` can be upgraded to `blobs_db=true` before Deneb.
 #[tokio::test]
 async fn change_to_separate_blobs_db_before_deneb() {
@@ -3415,6 +3612,191 @@ async fn prune_historic_states() {
     check_split_slot(&harness, store);
 }
 
+#[tokio::test]
+async fn retention_policies_keep_last_n_epochs() {
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let retained_epochs = 2;
+    let db_path = tempdir().unwrap();
+    let spec = test_spec::<E>();
+    let config = StoreConfig {
+        // Store a snapshot at the start of every epoch.
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![1, 3],
+        },
+        payload_retention: Some(RetentionPolicy::KeepLastNEpochs(retained_epochs)),
+        historic_state_retention: Some(RetentionPolicy::KeepLastNEpochs(retained_epochs)),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, spec.clone());
+    let harness = get_harness_generic(store.clone(), LOW_VALIDATOR_COUNT, ChainConfig::default());
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The migrator enforces the policies after each migration.
+    let split_slot = store.get_split_slot();
+    let cutoff_slot =
+        (split_slot.epoch(E::slots_per_epoch()) - retained_epochs).start_slot(E::slots_per_epoch());
+    assert!(cutoff_slot > 0);
+
+    let anchor = store.get_anchor_info();
+    assert_eq!(anchor.state_upper_limit, cutoff_slot);
+    assert_eq!(anchor.state_lower_limit, 0);
+
+    // States older than the cutoff are deleted, except for genesis.
+    for slot in 0..split_slot.as_u64() {
+        let state_root = store
+            .cold_db
+            .get_bytes(DBColumn::BeaconStateRoots.into(), &slot.to_be_bytes())
+            .unwrap();
+        assert_eq!(
+            state_root.is_some(),
+            slot == 0 || slot >= cutoff_slot,
+            "incorrect state retention at slot {slot}"
+        );
+    }
+    store.load_cold_state_by_slot(cutoff_slot).unwrap();
+
+    // Payloads are kept for the last `retained_epochs` finalized epochs.
+    if spec.bellatrix_fork_epoch.is_some() {
+        for snapshot in harness.chain.chain_dump().unwrap() {
+            let slot = snapshot.beacon_block.slot();
+            if slot == 0 {
+                continue;
+            }
+            assert_eq!(
+                store
+                    .execution_payload_exists(&snapshot.beacon_block_root)
+                    .unwrap(),
+                slot >= cutoff_slot,
+                "incorrect payload retention at slot {slot}"
+            );
+        }
+    }
+}
+
+/// Check that reconstructed states are only pruned with confirmation.
+#[tokio::test]
+async fn retention_policies_keep_reconstructed_states_without_confirm() {
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        historic_state_retention: Some(RetentionPolicy::KeepLastNEpochs(2)),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness_generic(store.clone(), LOW_VALIDATOR_COUNT, ChainConfig::default());
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Emulate a partially complete state reconstruction.
+    let anchor = store.get_anchor_info();
+    let reconstructed_anchor = AnchorInfo {
+        state_lower_limit: Slot::new(E::slots_per_epoch()),
+        ..anchor.clone()
+    };
+    store
+        .compare_and_set_anchor_info_with_write(anchor, reconstructed_anchor.clone())
+        .unwrap();
+
+    store.try_prune_historic_states(false).unwrap();
+    assert_eq!(store.get_anchor_info(), reconstructed_anchor);
+
+    store.try_prune_historic_states(true).unwrap();
+    assert_eq!(store.get_anchor_info().state_lower_limit, 0);
+}
+
+#[tokio::test]
+async fn reencode_freezer_hierarchy() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let spec = test_spec::<E>();
+    let old_config = StoreConfig {
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![1, 3, 4],
+        },
+        ..StoreConfig::default()
+    };
+    let new_config = StoreConfig {
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![2, 3, 5],
+        },
+        ..StoreConfig::default()
+    };
+
+    let store = get_store_generic(&db_path, old_config.clone(), spec.clone());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    let frozen_state_roots = harness
+        .chain
+        .forwards_iter_state_roots(Slot::new(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .take_while(|(_, slot)| *slot < split_slot)
+        .collect::<Vec<_>>();
+    assert!(split_slot > 2 * E::slots_per_epoch());
+
+    // Close the database to ensure everything is written to disk.
+    drop(store);
+    drop(harness);
+
+    // Re-encode using the old config, with a batch size small enough to require several batches.
+    let store = get_store_generic(&db_path, old_config.clone(), spec.clone());
+    store
+        .reencode_freezer_states(&new_config.hierarchy_config, 3)
+        .unwrap();
+    drop(store);
+
+    // The freezer is not readable until the re-encoded states have been moved into place.
+    let hot_path = db_path.path().join("chain_db");
+    let cold_path = db_path.path().join("freezer_db");
+    let blobs_path = db_path.path().join("blobs_db");
+    assert!(HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        old_config,
+        spec.clone().into(),
+        test_logger(),
+    )
+    .is_err());
+
+    let hot_db = LevelDB::<E>::open(&hot_path).unwrap();
+    let cold_db = LevelDB::<E>::open(&cold_path).unwrap();
+    swap_reencoded_freezer_states(&hot_db, &cold_db, 3, &test_logger()).unwrap();
+    drop(hot_db);
+    drop(cold_db);
+
+    // All historic states should be loadable using the new config.
+    let store = get_store_generic(&db_path, new_config, spec);
+    for (state_root, slot) in frozen_state_roots {
+        let mut state = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.canonical_root().unwrap(), state_root);
+    }
+}
+
 /// Checks that two chains are the same, for the purpose of these tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
@@ -50,14 +50,14 @@ use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp;
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
 use std::time::Duration;
-use strum::IntoStaticStr;
+use strum::{EnumIter, EnumString, IntoStaticStr};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
@@ -65,6 +65,7 @@ use types::{
     Attestation, BeaconState, ChainSpec, Hash256, RelativeEpoch, SignedAggregateAndProof, SubnetId,
 };
 use types::{EthSpec, Slot};
+use work_queues::WorkQueues;
 use work_reprocessing_queue::{
     spawn_reprocess_scheduler, QueuedAggregate, QueuedLightClientUpdate, QueuedRpcBlock,
     QueuedUnaggregate, ReadyWork,
@@ -72,6 +73,9 @@ use work_reprocessing_queue::{
 use work_reprocessing_queue::{IgnoredRpcBlock, QueuedSamplingRequest};
 
 mod metrics;
+pub mod work_queues;
+
+pub use work_queues::{FlowWeights, SchedulerMode};
 pub mod work_reprocessing_queue;
 
 /// The maximum size of the channel for work events to the `BeaconProcessor`.
@@ -242,6 +246,11 @@ pub struct BeaconProcessorConfig {
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
     pub enable_backfill_rate_limiting: bool,
+    pub scheduler_mode: SchedulerMode,
+    /// The weights of peers sharing a queue with `SchedulerMode::FairQueuing`.
+    pub flow_weights: FlowWeights,
+    /// Priority lanes of work types, see `work_queues::priority_order`.
+    pub work_type_priorities: Vec<(WorkType, u8)>,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -253,6 +262,9 @@ impl Default for BeaconProcessorConfig {
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
             enable_backfill_rate_limiting: true,
+            scheduler_mode: SchedulerMode::default(),
+            flow_weights: FlowWeights::default(),
+            work_type_priorities: vec![],
         }
     }
 }
@@ -287,90 +299,6 @@ impl<E: EthSpec> Default for BeaconProcessorChannels<E> {
     }
 }
 
-/// A simple first-in-first-out queue with a maximum length.
-struct FifoQueue<T> {
-    queue: VecDeque<T>,
-    max_length: usize,
-}
-
-impl<T> FifoQueue<T> {
-    /// Create a new, empty queue with the given length.
-    pub fn new(max_length: usize) -> Self {
-        Self {
-            queue: VecDeque::default(),
-            max_length,
-        }
-    }
-
-    /// Add a new item to the queue.
-    ///
-    /// Drops `item` if the queue is full.
-    pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
-        if self.queue.len() == self.max_length {
-            error!(
-                log,
-                "Work queue is full";
-                "msg" => "the system has insufficient resources for load",
-                "queue_len" => self.max_length,
-                "queue" => item_desc,
-            )
-        } else {
-            self.queue.push_back(item);
-        }
-    }
-
-    /// Remove the next item from the queue.
-    pub fn pop(&mut self) -> Option<T> {
-        self.queue.pop_front()
-    }
-
-    /// Returns the current length of the queue.
-    pub fn len(&self) -> usize {
-        self.queue.len()
-    }
-}
-
-/// A simple last-in-first-out queue with a maximum length.
-struct LifoQueue<T> {
-    queue: VecDeque<T>,
-    max_length: usize,
-}
-
-impl<T> LifoQueue<T> {
-    /// Create a new, empty queue with the given length.
-    pub fn new(max_length: usize) -> Self {
-        Self {
-            queue: VecDeque::default(),
-            max_length,
-        }
-    }
-
-    /// Add a new item to the front of the queue.
-    ///
-    /// If the queue is full, the item at the back of the queue is dropped.
-    pub fn push(&mut self, item: T) {
-        if self.queue.len() == self.max_length {
-            self.queue.pop_back();
-        }
-        self.queue.push_front(item);
-    }
-
-    /// Remove the next item from the queue.
-    pub fn pop(&mut self) -> Option<T> {
-        self.queue.pop_front()
-    }
-
-    /// Returns `true` if the queue is full.
-    pub fn is_full(&self) -> bool {
-        self.queue.len() >= self.max_length
-    }
-
-    /// Returns the current length of the queue.
-    pub fn len(&self) -> usize {
-        self.queue.len()
-    }
-}
-
 /// A handle that sends a message on the provided channel to a receiver when it gets dropped.
 ///
 /// The receiver task is responsible for removing the provided `entry` from the `DuplicateCache`
@@ -424,6 +352,8 @@ impl DuplicateCache {
 #[derive(Debug)]
 pub struct WorkEvent<E: EthSpec> {
     pub drop_during_sync: bool,
+    /// The peer which caused this work, used to share queues fairly between peers.
+    pub peer_id: Option<PeerId>,
     pub work: Work<E>,
 }
 
@@ -448,6 +378,7 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 process_fn,
             }) => Self {
                 drop_during_sync: false,
+                peer_id: None,
                 work: Work::DelayedImportBlock {
                     beacon_block_slot,
                     beacon_block_root,
@@ -460,10 +391,12 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 ignore_fn: _,
             }) => Self {
                 drop_during_sync: false,
+                peer_id: None,
                 work: Work::RpcBlock { process_fn },
             },
             ReadyWork::IgnoredRpcBlock(IgnoredRpcBlock { process_fn }) => Self {
                 drop_during_sync: false,
+                peer_id: None,
                 work: Work::IgnoredRpcBlock { process_fn },
             },
             ReadyWork::Unaggregate(QueuedUnaggregate {
@@ -471,6 +404,7 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 process_fn,
             }) => Self {
                 drop_during_sync: true,
+                peer_id: None,
                 work: Work::UnknownBlockAttestation { process_fn },
             },
             ReadyWork::Aggregate(QueuedAggregate {
@@ -478,6 +412,7 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 beacon_block_root: _,
             }) => Self {
                 drop_during_sync: true,
+                peer_id: None,
                 work: Work::UnknownBlockAggregate { process_fn },
             },
             ReadyWork::LightClientUpdate(QueuedLightClientUpdate {
@@ -485,6 +420,7 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 process_fn,
             }) => Self {
                 drop_during_sync: true,
+                peer_id: None,
                 work: Work::UnknownLightClientOptimisticUpdate {
                     parent_root,
                     process_fn,
@@ -492,10 +428,12 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
             },
             ReadyWork::SamplingRequest(QueuedSamplingRequest { process_fn, .. }) => Self {
                 drop_during_sync: true,
+                peer_id: None,
                 work: Work::UnknownBlockSamplingRequest { process_fn },
             },
             ReadyWork::BackfillSync(QueuedBackfillBatch(process_fn)) => Self {
                 drop_during_sync: false,
+                peer_id: None,
                 work: Work::ChainSegmentBackfill(process_fn),
             },
         }
@@ -635,8 +573,21 @@ impl<E: EthSpec> fmt::Debug for Work<E> {
     }
 }
 
-#[derive(IntoStaticStr, PartialEq, Eq, Debug)]
+#[derive(
+    IntoStaticStr,
+    EnumString,
+    EnumIter,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum WorkType {
     GossipAttestation,
     UnknownBlockAttestation,
@@ -843,74 +794,14 @@ impl<E: EthSpec> BeaconProcessor<E> {
         // Used by workers to communicate that they are finished a task.
         let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
 
-        // Using LIFO queues for attestations since validator profits rely upon getting fresh
-        // attestations into blocks. Additionally, later attestations contain more information than
-        // earlier ones, so we consider them more valuable.
-        let mut aggregate_queue = LifoQueue::new(queue_lengths.aggregate_queue);
+        let mut work_queues = WorkQueues::new(
+            &queue_lengths,
+            self.config.scheduler_mode,
+            &self.config.flow_weights,
+        );
+        let priority_order = work_queues::priority_order(&self.config.work_type_priorities);
         let mut aggregate_debounce = TimeLatch::default();
-        let mut attestation_queue = LifoQueue::new(queue_lengths.attestation_queue);
         let mut attestation_debounce = TimeLatch::default();
-        let mut unknown_block_aggregate_queue =
-            LifoQueue::new(queue_lengths.unknown_block_aggregate_queue);
-        let mut unknown_block_attestation_queue =
-            LifoQueue::new(queue_lengths.unknown_block_attestation_queue);
-
-        let mut sync_message_queue = LifoQueue::new(queue_lengths.sync_message_queue);
-        let mut sync_contribution_queue = LifoQueue::new(queue_lengths.sync_contribution_queue);
-
-        // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't have
-        // a strong feeling about queue type for exits.
-        let mut gossip_voluntary_exit_queue =
-            FifoQueue::new(queue_lengths.gossip_voluntary_exit_queue);
-
-        // Using a FIFO queue for slashing to prevent people from flushing their slashings from the
-        // queues with lots of junk messages.
-        let mut gossip_proposer_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_proposer_slashing_queue);
-        let mut gossip_attester_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_attester_slashing_queue);
-
-        // Using a FIFO queue for light client updates to maintain sequence order.
-        let mut finality_update_queue = FifoQueue::new(queue_lengths.finality_update_queue);
-        let mut optimistic_update_queue = FifoQueue::new(queue_lengths.optimistic_update_queue);
-        let mut unknown_light_client_update_queue =
-            FifoQueue::new(queue_lengths.unknown_light_client_update_queue);
-        let mut unknown_block_sampling_request_queue =
-            FifoQueue::new(queue_lengths.unknown_block_sampling_request_queue);
-
-        // Using a FIFO queue since blocks need to be imported sequentially.
-        let mut rpc_block_queue = FifoQueue::new(queue_lengths.rpc_block_queue);
-        let mut rpc_blob_queue = FifoQueue::new(queue_lengths.rpc_blob_queue);
-        let mut rpc_custody_column_queue = FifoQueue::new(queue_lengths.rpc_custody_column_queue);
-        let mut rpc_verify_data_column_queue =
-            FifoQueue::new(queue_lengths.rpc_verify_data_column_queue);
-        let mut sampling_result_queue = FifoQueue::new(queue_lengths.sampling_result_queue);
-        let mut chain_segment_queue = FifoQueue::new(queue_lengths.chain_segment_queue);
-        let mut backfill_chain_segment = FifoQueue::new(queue_lengths.backfill_chain_segment);
-        let mut gossip_block_queue = FifoQueue::new(queue_lengths.gossip_block_queue);
-        let mut gossip_blob_queue = FifoQueue::new(queue_lengths.gossip_blob_queue);
-        let mut gossip_data_column_queue = FifoQueue::new(queue_lengths.gossip_data_column_queue);
-        let mut delayed_block_queue = FifoQueue::new(queue_lengths.delayed_block_queue);
-
-        let mut status_queue = FifoQueue::new(queue_lengths.status_queue);
-        let mut bbrange_queue = FifoQueue::new(queue_lengths.bbrange_queue);
-        let mut bbroots_queue = FifoQueue::new(queue_lengths.bbroots_queue);
-        let mut blbroots_queue = FifoQueue::new(queue_lengths.blbroots_queue);
-        let mut blbrange_queue = FifoQueue::new(queue_lengths.blbrange_queue);
-        let mut dcbroots_queue = FifoQueue::new(queue_lengths.dcbroots_queue);
-        let mut dcbrange_queue = FifoQueue::new(queue_lengths.dcbrange_queue);
-
-        let mut gossip_bls_to_execution_change_queue =
-            FifoQueue::new(queue_lengths.gossip_bls_to_execution_change_queue);
-
-        let mut lc_bootstrap_queue = FifoQueue::new(queue_lengths.lc_bootstrap_queue);
-        let mut lc_optimistic_update_queue =
-            FifoQueue::new(queue_lengths.lc_optimistic_update_queue);
-        let mut lc_finality_update_queue = FifoQueue::new(queue_lengths.lc_finality_update_queue);
-        let mut lc_update_range_queue = FifoQueue::new(queue_lengths.lc_update_range_queue);
-
-        let mut api_request_p0_queue = FifoQueue::new(queue_lengths.api_request_p0_queue);
-        let mut api_request_p1_queue = FifoQueue::new(queue_lengths.api_request_p1_queue);
 
         // Channels for sending work to the re-process scheduler (`work_reprocessing_tx`) and to
         // receive them back once they are ready (`ready_work_rx`).
@@ -1031,236 +922,25 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
-                        // Check for chain segments first, they're the most efficient way to get
-                        // blocks into the system.
-                        let work_event: Option<Work<E>> = if let Some(item) =
-                            chain_segment_queue.pop()
-                        {
-                            Some(item)
-                        // Check sync blocks before gossip blocks, since we've already explicitly
-                        // requested these blocks.
-                        } else if let Some(item) = rpc_block_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = rpc_blob_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = rpc_custody_column_queue.pop() {
-                            Some(item)
-                        // TODO(das): decide proper prioritization for sampling columns
-                        } else if let Some(item) = rpc_custody_column_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = rpc_verify_data_column_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = sampling_result_queue.pop() {
-                            Some(item)
-                        // Check delayed blocks before gossip blocks, the gossip blocks might rely
-                        // on the delayed ones.
-                        } else if let Some(item) = delayed_block_queue.pop() {
-                            Some(item)
-                        // Check gossip blocks before gossip attestations, since a block might be
-                        // required to verify some attestations.
-                        } else if let Some(item) = gossip_block_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = gossip_blob_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = gossip_data_column_queue.pop() {
-                            Some(item)
-                        // Check the priority 0 API requests after blocks and blobs, but before attestations.
-                        } else if let Some(item) = api_request_p0_queue.pop() {
-                            Some(item)
-                        // Check the aggregates, *then* the unaggregates since we assume that
-                        // aggregates are more valuable to local validators and effectively give us
-                        // more information with less signature verification time.
-                        } else if aggregate_queue.len() > 0 {
-                            let batch_size = cmp::min(
-                                aggregate_queue.len(),
-                                self.config.max_gossip_aggregate_batch_size,
-                            );
-
-                            if batch_size < 2 {
-                                // One single aggregate is in the queue, process it individually.
-                                aggregate_queue.pop()
-                            } else {
-                                // Collect two or more aggregates into a batch, so they can take
-                                // advantage of batch signature verification.
-                                //
-                                // Note: this will convert the `Work::GossipAggregate` item into a
-                                // `Work::GossipAggregateBatch` item.
-                                let mut aggregates = Vec::with_capacity(batch_size);
-                                let mut process_batch_opt = None;
-                                for _ in 0..batch_size {
-                                    if let Some(item) = aggregate_queue.pop() {
-                                        match item {
-                                            Work::GossipAggregate {
-                                                aggregate,
-                                                process_individual: _,
-                                                process_batch,
-                                            } => {
-                                                aggregates.push(*aggregate);
-                                                if process_batch_opt.is_none() {
-                                                    process_batch_opt = Some(process_batch);
-                                                }
-                                            }
-                                            _ => {
-                                                error!(self.log, "Invalid item in aggregate queue");
-                                            }
-                                        }
-                                    }
-                                }
-
-                                if let Some(process_batch) = process_batch_opt {
-                                    // Process all aggregates with a single worker.
-                                    Some(Work::GossipAggregateBatch {
-                                        aggregates,
-                                        process_batch,
-                                    })
-                                } else {
-                                    // There is no good reason for this to
-                                    // happen, it is a serious logic error.
-                                    // Since we only form batches when multiple
-                                    // work items exist, we should always have a
-                                    // work closure at this point.
-                                    crit!(self.log, "Missing aggregate work");
-                                    None
-                                }
+                        let work_event = priority_order.iter().find_map(|work_type| {
+                            work_queues.pop(*work_type, &self.config, &self.log)
+                        });
+                        match work_event {
+                            Some(work_event) => {
+                                let work_type = work_event.to_type();
+                                self.spawn_worker(work_event, idle_tx);
+                                Some(work_type)
                             }
-                        // Check the unaggregated attestation queue.
-                        //
-                        // Potentially use batching.
-                        } else if attestation_queue.len() > 0 {
-                            let batch_size = cmp::min(
-                                attestation_queue.len(),
-                                self.config.max_gossip_attestation_batch_size,
-                            );
-
-                            if batch_size < 2 {
-                                // One single attestation is in the queue, process it individually.
-                                attestation_queue.pop()
-                            } else {
-                                // Collect two or more attestations into a batch, so they can take
-                                // advantage of batch signature verification.
-                                //
-                                // Note: this will convert the `Work::GossipAttestation` item into a
-                                // `Work::GossipAttestationBatch` item.
-                                let mut attestations = Vec::with_capacity(batch_size);
-                                let mut process_batch_opt = None;
-                                for _ in 0..batch_size {
-                                    if let Some(item) = attestation_queue.pop() {
-                                        match item {
-                                            Work::GossipAttestation {
-                                                attestation,
-                                                process_individual: _,
-                                                process_batch,
-                                            } => {
-                                                attestations.push(*attestation);
-                                                if process_batch_opt.is_none() {
-                                                    process_batch_opt = Some(process_batch);
-                                                }
-                                            }
-                                            _ => error!(
-                                                self.log,
-                                                "Invalid item in attestation queue"
-                                            ),
-                                        }
-                                    }
-                                }
-
-                                if let Some(process_batch) = process_batch_opt {
-                                    // Process all attestations with a single worker.
-                                    Some(Work::GossipAttestationBatch {
-                                        attestations,
-                                        process_batch,
-                                    })
-                                } else {
-                                    // There is no good reason for this to
-                                    // happen, it is a serious logic error.
-                                    // Since we only form batches when multiple
-                                    // work items exist, we should always have a
-                                    // work closure at this point.
-                                    crit!(self.log, "Missing attestations work");
-                                    None
+                            None => {
+                                // Let the journal know that a worker is freed and there's nothing
+                                // else for it to do.
+                                if let Some(work_journal_tx) = &work_journal_tx {
+                                    // We don't care if this message was successfully sent, we only
+                                    // use the journal during testing.
+                                    let _ = work_journal_tx.try_send(NOTHING_TO_DO);
                                 }
+                                None
                             }
-                        // Check sync committee messages after attestations as their rewards are lesser
-                        // and they don't influence fork choice.
-                        } else if let Some(item) = sync_contribution_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = sync_message_queue.pop() {
-                            Some(item)
-                        // Aggregates and unaggregates queued for re-processing are older and we
-                        // care about fresher ones, so check those first.
-                        } else if let Some(item) = unknown_block_aggregate_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = unknown_block_attestation_queue.pop() {
-                            Some(item)
-                        // Check RPC methods next. Status messages are needed for sync so
-                        // prioritize them over syncing requests from other peers (BlocksByRange
-                        // and BlocksByRoot)
-                        } else if let Some(item) = status_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = bbrange_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = bbroots_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = blbrange_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = blbroots_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = dcbroots_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = dcbrange_queue.pop() {
-                            Some(item)
-                        // Prioritize sampling requests after block syncing requests
-                        } else if let Some(item) = unknown_block_sampling_request_queue.pop() {
-                            Some(item)
-                        // Check slashings after all other consensus messages so we prioritize
-                        // following head.
-                        //
-                        // Check attester slashings before proposer slashings since they have the
-                        // potential to slash multiple validators at once.
-                        } else if let Some(item) = gossip_attester_slashing_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = gossip_proposer_slashing_queue.pop() {
-                            Some(item)
-                        // Check exits and address changes late since our validators don't get
-                        // rewards from them.
-                        } else if let Some(item) = gossip_voluntary_exit_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = gossip_bls_to_execution_change_queue.pop() {
-                            Some(item)
-                        // Check the priority 1 API requests after we've
-                        // processed all the interesting things from the network
-                        // and things required for us to stay in good repute
-                        // with our P2P peers.
-                        } else if let Some(item) = api_request_p1_queue.pop() {
-                            Some(item)
-                        // Handle backfill sync chain segments.
-                        } else if let Some(item) = backfill_chain_segment.pop() {
-                            Some(item)
-                        // Handle light client requests.
-                        } else if let Some(item) = lc_bootstrap_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = lc_optimistic_update_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = lc_finality_update_queue.pop() {
-                            Some(item)
-                            // This statement should always be the final else statement.
-                        } else {
-                            // Let the journal know that a worker is freed and there's nothing else
-                            // for it to do.
-                            if let Some(work_journal_tx) = &work_journal_tx {
-                                // We don't care if this message was successfully sent, we only use the journal
-                                // during testing.
-                                let _ = work_journal_tx.try_send(NOTHING_TO_DO);
-                            }
-                            None
-                        };
-
-                        if let Some(work_event) = work_event {
-                            let work_type = work_event.to_type();
-                            self.spawn_worker(work_event, idle_tx);
-                            Some(work_type)
-                        } else {
-                            None
                         }
                     }
                     // There is no new work event and we are unable to spawn a new worker.
@@ -1294,131 +974,13 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     }
                     // There is a new work event and the chain is not syncing. Process it or queue
                     // it.
-                    Some(WorkEvent { work, .. }) => {
-                        let work_id = work.str_id();
+                    Some(WorkEvent { work, peer_id, .. }) => {
                         let work_type = work.to_type();
-
-                        match work {
-                            _ if can_spawn => self.spawn_worker(work, idle_tx),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
-                            // Attestation batches are formed internally within the
-                            // `BeaconProcessor`, they are not sent from external services.
-                            Work::GossipAttestationBatch { .. } => crit!(
-                                    self.log,
-                                    "Unsupported inbound event";
-                                    "type" => "GossipAttestationBatch"
-                            ),
-                            Work::GossipAggregate { .. } => aggregate_queue.push(work),
-                            // Aggregate batches are formed internally within the `BeaconProcessor`,
-                            // they are not sent from external services.
-                            Work::GossipAggregateBatch { .. } => crit!(
-                                    self.log,
-                                    "Unsupported inbound event";
-                                    "type" => "GossipAggregateBatch"
-                            ),
-                            Work::GossipBlock { .. } => {
-                                gossip_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipBlobSidecar { .. } => {
-                                gossip_blob_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipDataColumnSidecar { .. } => {
-                                gossip_data_column_queue.push(work, work_id, &self.log)
-                            }
-                            Work::DelayedImportBlock { .. } => {
-                                delayed_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipVoluntaryExit { .. } => {
-                                gossip_voluntary_exit_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipProposerSlashing { .. } => {
-                                gossip_proposer_slashing_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipAttesterSlashing { .. } => {
-                                gossip_attester_slashing_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
-                            Work::GossipSyncContribution { .. } => {
-                                sync_contribution_queue.push(work)
-                            }
-                            Work::GossipLightClientFinalityUpdate { .. } => {
-                                finality_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipLightClientOptimisticUpdate { .. } => {
-                                optimistic_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::RpcBlock { .. } | Work::IgnoredRpcBlock { .. } => {
-                                rpc_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::RpcBlobs { .. } => rpc_blob_queue.push(work, work_id, &self.log),
-                            Work::RpcCustodyColumn { .. } => {
-                                rpc_custody_column_queue.push(work, work_id, &self.log)
-                            }
-                            Work::RpcVerifyDataColumn(_) => {
-                                rpc_verify_data_column_queue.push(work, work_id, &self.log)
-                            }
-                            Work::SamplingResult(_) => {
-                                sampling_result_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ChainSegment { .. } => {
-                                chain_segment_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ChainSegmentBackfill { .. } => {
-                                backfill_chain_segment.push(work, work_id, &self.log)
-                            }
-                            Work::Status { .. } => status_queue.push(work, work_id, &self.log),
-                            Work::BlocksByRangeRequest { .. } => {
-                                bbrange_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlocksByRootsRequest { .. } => {
-                                bbroots_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlobsByRangeRequest { .. } => {
-                                blbrange_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientBootstrapRequest { .. } => {
-                                lc_bootstrap_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientOptimisticUpdateRequest { .. } => {
-                                lc_optimistic_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientFinalityUpdateRequest { .. } => {
-                                lc_finality_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientUpdatesByRangeRequest { .. } => {
-                                lc_update_range_queue.push(work, work_id, &self.log)
-                            }
-                            Work::UnknownBlockAttestation { .. } => {
-                                unknown_block_attestation_queue.push(work)
-                            }
-                            Work::UnknownBlockAggregate { .. } => {
-                                unknown_block_aggregate_queue.push(work)
-                            }
-                            Work::GossipBlsToExecutionChange { .. } => {
-                                gossip_bls_to_execution_change_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlobsByRootsRequest { .. } => {
-                                blbroots_queue.push(work, work_id, &self.log)
-                            }
-                            Work::DataColumnsByRootsRequest { .. } => {
-                                dcbroots_queue.push(work, work_id, &self.log)
-                            }
-                            Work::DataColumnsByRangeRequest { .. } => {
-                                dcbrange_queue.push(work, work_id, &self.log)
-                            }
-                            Work::UnknownLightClientOptimisticUpdate { .. } => {
-                                unknown_light_client_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::UnknownBlockSamplingRequest { .. } => {
-                                unknown_block_sampling_request_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ApiRequestP0 { .. } => {
-                                api_request_p0_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ApiRequestP1 { .. } => {
-                                api_request_p1_queue.push(work, work_id, &self.log)
-                            }
-                        };
+                        if can_spawn {
+                            self.spawn_worker(work, idle_tx)
+                        } else {
+                            work_queues.push(work, peer_id, &self.log)
+                        }
                         Some(work_type)
                     }
                 };
@@ -1429,60 +991,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 );
 
                 if let Some(modified_queue_id) = modified_queue_id {
-                    let queue_len = match modified_queue_id {
-                        WorkType::GossipAttestation => aggregate_queue.len(),
-                        WorkType::UnknownBlockAttestation => unknown_block_attestation_queue.len(),
-                        WorkType::GossipAttestationBatch => 0, // No queue
-                        WorkType::GossipAggregate => aggregate_queue.len(),
-                        WorkType::UnknownBlockAggregate => unknown_block_aggregate_queue.len(),
-                        WorkType::UnknownLightClientOptimisticUpdate => {
-                            unknown_light_client_update_queue.len()
-                        }
-                        WorkType::UnknownBlockSamplingRequest => {
-                            unknown_block_sampling_request_queue.len()
-                        }
-                        WorkType::GossipAggregateBatch => 0, // No queue
-                        WorkType::GossipBlock => gossip_block_queue.len(),
-                        WorkType::GossipBlobSidecar => gossip_blob_queue.len(),
-                        WorkType::GossipDataColumnSidecar => gossip_data_column_queue.len(),
-                        WorkType::DelayedImportBlock => delayed_block_queue.len(),
-                        WorkType::GossipVoluntaryExit => gossip_voluntary_exit_queue.len(),
-                        WorkType::GossipProposerSlashing => gossip_proposer_slashing_queue.len(),
-                        WorkType::GossipAttesterSlashing => gossip_attester_slashing_queue.len(),
-                        WorkType::GossipSyncSignature => sync_message_queue.len(),
-                        WorkType::GossipSyncContribution => sync_contribution_queue.len(),
-                        WorkType::GossipLightClientFinalityUpdate => finality_update_queue.len(),
-                        WorkType::GossipLightClientOptimisticUpdate => {
-                            optimistic_update_queue.len()
-                        }
-                        WorkType::RpcBlock => rpc_block_queue.len(),
-                        WorkType::RpcBlobs | WorkType::IgnoredRpcBlock => rpc_blob_queue.len(),
-                        WorkType::RpcCustodyColumn => rpc_custody_column_queue.len(),
-                        WorkType::RpcVerifyDataColumn => rpc_verify_data_column_queue.len(),
-                        WorkType::SamplingResult => sampling_result_queue.len(),
-                        WorkType::ChainSegment => chain_segment_queue.len(),
-                        WorkType::ChainSegmentBackfill => backfill_chain_segment.len(),
-                        WorkType::Status => status_queue.len(),
-                        WorkType::BlocksByRangeRequest => blbrange_queue.len(),
-                        WorkType::BlocksByRootsRequest => blbroots_queue.len(),
-                        WorkType::BlobsByRangeRequest => bbrange_queue.len(),
-                        WorkType::BlobsByRootsRequest => bbroots_queue.len(),
-                        WorkType::DataColumnsByRootsRequest => dcbroots_queue.len(),
-                        WorkType::DataColumnsByRangeRequest => dcbrange_queue.len(),
-                        WorkType::GossipBlsToExecutionChange => {
-                            gossip_bls_to_execution_change_queue.len()
-                        }
-                        WorkType::LightClientBootstrapRequest => lc_bootstrap_queue.len(),
-                        WorkType::LightClientOptimisticUpdateRequest => {
-                            lc_optimistic_update_queue.len()
-                        }
-                        WorkType::LightClientFinalityUpdateRequest => {
-                            lc_finality_update_queue.len()
-                        }
-                        WorkType::LightClientUpdatesByRangeRequest => lc_update_range_queue.len(),
-                        WorkType::ApiRequestP0 => api_request_p0_queue.len(),
-                        WorkType::ApiRequestP1 => api_request_p1_queue.len(),
-                    };
+                    let queue_len = work_queues.len(modified_queue_id);
                     metrics::observe_vec(
                         &metrics::BEACON_PROCESSOR_QUEUE_LENGTH,
                         &[modified_queue_id.into()],
@@ -1490,22 +999,26 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     );
                 }
 
-                if aggregate_queue.is_full() && aggregate_debounce.elapsed() {
-                    error!(
-                        self.log,
-                        "Aggregate attestation queue full";
-                        "msg" => "the system has insufficient resources for load",
-                        "queue_len" => aggregate_queue.max_length,
-                    )
+                if let Some(queue) = work_queues.queue(WorkType::GossipAggregate) {
+                    if queue.is_full() && aggregate_debounce.elapsed() {
+                        error!(
+                            self.log,
+                            "Aggregate attestation queue full";
+                            "msg" => "the system has insufficient resources for load",
+                            "queue_len" => queue.max_length(),
+                        )
+                    }
                 }
 
-                if attestation_queue.is_full() && attestation_debounce.elapsed() {
-                    error!(
-                        self.log,
-                        "Attestation queue full";
-                        "msg" => "the system has insufficient resources for load",
-                        "queue_len" => attestation_queue.max_length,
-                    )
+                if let Some(queue) = work_queues.queue(WorkType::GossipAttestation) {
+                    if queue.is_full() && attestation_debounce.elapsed() {
+                        error!(
+                            self.log,
+                            "Attestation queue full";
+                            "msg" => "the system has insufficient resources for load",
+                            "queue_len" => queue.max_length(),
+                        )
+                    }
                 }
             }
         };
@@ -72,6 +72,22 @@ pub static BEACON_PROCESSOR_QUEUE_LENGTH: LazyLock<Result<HistogramVec>> = LazyL
         &["type"],
     )
 });
+pub static BEACON_PROCESSOR_QUEUE_TIME: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec_with_buckets(
+        "beacon_processor_queue_time_seconds",
+        "Time work events spent queued before being given to a worker.",
+        decimal_buckets(-3, 1),
+        &["type"],
+    )
+});
+pub static BEACON_PROCESSOR_WORK_EVENTS_EVICTED_COUNT: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_processor_work_events_evicted_count",
+            "Count of queued work events dropped to make room for work from a less busy peer",
+            &["type"],
+        )
+    });
 
 /*
  * Attestation reprocessing queue metrics.
@@ -0,0 +1,842 @@
+//! Queues for work which is waiting for a worker, and the order in which they are drained.
+//!
+//! There is one queue per `WorkType`. When a worker becomes free the queues are checked in
+//! priority order, which defaults to `DEFAULT_PRIORITY_ORDER` and can be adjusted by assigning
+//! work types to priority lanes.
+//!
+//! With `SchedulerMode::FairQueuing` each queue is further split into one flow per peer, and the
+//! flows are served in turn, each taking as many items as its weight (see `FlowWeights`). A single
+//! peer flooding a queue then only delays its own work, and when a queue is full the work of the
+//! peer with the most queued items is dropped first.
+use crate::{metrics, BeaconProcessorConfig, BeaconProcessorQueueLengths, Work, WorkType};
+use lighthouse_network::{PeerId, PeerIdSerialized};
+use serde::{Deserialize, Serialize};
+use slog::{crit, debug, error, Logger};
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+use types::EthSpec;
+
+/// The priority lane of work types which haven't been assigned one. Lower lanes are served first.
+pub const DEFAULT_PRIORITY_LANE: u8 = 1;
+
+/// The default weight of the flow of locally-originated work.
+///
+/// Work without a peer includes re-processed gossip and our own sync requests, which would
+/// otherwise get a vanishingly small share of a queue that many peers are sending to.
+pub const DEFAULT_LOCAL_FLOW_WEIGHT: usize = 8;
+
+/// The weight of peers which haven't been assigned one.
+pub const DEFAULT_PEER_FLOW_WEIGHT: usize = 1;
+
+/// The order in which queues are checked for work, before any priority lanes are applied.
+///
+/// `IgnoredRpcBlock` shares the queue of `RpcBlock`, and the batch work types are formed from the
+/// `GossipAttestation` and `GossipAggregate` queues, so they don't appear here.
+pub const DEFAULT_PRIORITY_ORDER: &[WorkType] = &[
+    // Chain segments are the most efficient way to get blocks into the system.
+    WorkType::ChainSegment,
+    // Sync blocks come before gossip blocks, since we've explicitly requested them.
+    WorkType::RpcBlock,
+    WorkType::RpcBlobs,
+    WorkType::RpcCustodyColumn,
+    WorkType::RpcVerifyDataColumn,
+    WorkType::SamplingResult,
+    // Delayed blocks come before gossip blocks, which might rely on them.
+    WorkType::DelayedImportBlock,
+    // Gossip blocks come before attestations, which might require them.
+    WorkType::GossipBlock,
+    WorkType::GossipBlobSidecar,
+    WorkType::GossipDataColumnSidecar,
+    WorkType::ApiRequestP0,
+    // Aggregates give us more information for less signature verification than unaggregates.
+    WorkType::GossipAggregate,
+    WorkType::GossipAttestation,
+    // Sync committee messages have lower rewards and don't influence fork choice.
+    WorkType::GossipSyncContribution,
+    WorkType::GossipSyncSignature,
+    // Re-processed attestations are older than fresh ones.
+    WorkType::UnknownBlockAggregate,
+    WorkType::UnknownBlockAttestation,
+    // Status messages are needed for sync, so come before other peers' sync requests.
+    WorkType::Status,
+    WorkType::BlocksByRangeRequest,
+    WorkType::BlocksByRootsRequest,
+    WorkType::BlobsByRangeRequest,
+    WorkType::BlobsByRootsRequest,
+    WorkType::DataColumnsByRootsRequest,
+    WorkType::DataColumnsByRangeRequest,
+    WorkType::UnknownBlockSamplingRequest,
+    // Slashings come after other consensus messages to prioritise following the head. Attester
+    // slashings can slash several validators at once.
+    WorkType::GossipAttesterSlashing,
+    WorkType::GossipProposerSlashing,
+    // Our validators don't get rewards from exits and address changes.
+    WorkType::GossipVoluntaryExit,
+    WorkType::GossipBlsToExecutionChange,
+    WorkType::ApiRequestP1,
+    WorkType::ChainSegmentBackfill,
+    WorkType::LightClientBootstrapRequest,
+    WorkType::LightClientOptimisticUpdateRequest,
+    WorkType::LightClientFinalityUpdateRequest,
+    WorkType::LightClientUpdatesByRangeRequest,
+    WorkType::GossipLightClientFinalityUpdate,
+    WorkType::GossipLightClientOptimisticUpdate,
+    WorkType::UnknownLightClientOptimisticUpdate,
+];
+
+/// How work is taken from each queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerMode {
+    /// Work is taken in arrival order (or reverse arrival order), regardless of its peer.
+    #[default]
+    Strict,
+    /// Work from different peers is interleaved, and peers with the most queued work are the
+    /// first to have it dropped.
+    FairQueuing,
+}
+
+/// The number of items each flow of a queue takes in turn with `SchedulerMode::FairQueuing`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowWeights {
+    /// The weight of locally-originated work.
+    pub local: usize,
+    /// The weights of individual peers. Other peers have `DEFAULT_PEER_FLOW_WEIGHT`.
+    pub peers: Vec<(PeerIdSerialized, usize)>,
+}
+
+impl Default for FlowWeights {
+    fn default() -> Self {
+        Self {
+            local: DEFAULT_LOCAL_FLOW_WEIGHT,
+            peers: vec![],
+        }
+    }
+}
+
+impl FlowWeights {
+    /// Return the weights keyed by flow, with `None` for locally-originated work.
+    ///
+    /// Weights are at least 1, so that every flow is served.
+    fn by_flow(&self) -> HashMap<Option<PeerId>, usize> {
+        self.peers
+            .iter()
+            .map(|(peer_id, weight)| (Some(peer_id.clone().into()), *weight))
+            .chain([(None, self.local)])
+            .map(|(flow, weight)| (flow, cmp::max(weight, 1)))
+            .collect()
+    }
+}
+
+/// Return the queues in the order they should be checked for work, applying `priority_lanes`
+/// to `DEFAULT_PRIORITY_ORDER`.
+///
+/// Work types in lower lanes are checked first. Within a lane the default order is kept.
+pub fn priority_order(priority_lanes: &[(WorkType, u8)]) -> Vec<WorkType> {
+    let lane = |work_type: &WorkType| {
+        priority_lanes
+            .iter()
+            .rev()
+            .find(|(other, _)| other == work_type)
+            .map_or(DEFAULT_PRIORITY_LANE, |(_, lane)| *lane)
+    };
+    let mut order = DEFAULT_PRIORITY_ORDER.to_vec();
+    order.sort_by_key(lane);
+    order
+}
+
+/// Whether a queue is first-in-first-out or last-in-first-out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueueOrder {
+    Fifo,
+    Lifo,
+}
+
+/// An item in a `WorkQueue`.
+struct Queued<T> {
+    item: T,
+    queued_at: Instant,
+}
+
+/// A queue with a maximum length, split into one flow per peer when fair queuing is enabled.
+///
+/// Items are pushed to the back of a flow for FIFO queues and the front for LIFO queues, so that
+/// they're always popped from the front and the back of a flow is always the least valuable item.
+pub struct WorkQueue<T> {
+    order: QueueOrder,
+    /// The weights of the flows if fair queuing is enabled, see `FlowWeights::by_flow`.
+    weights: Option<Arc<HashMap<Option<PeerId>, usize>>>,
+    max_length: usize,
+    len: usize,
+    flows: HashMap<Option<PeerId>, VecDeque<Queued<T>>>,
+    /// Flows with queued items, in the order they are served.
+    active: VecDeque<Option<PeerId>>,
+    /// The number of items the flow at the front of `active` may still take before the next flow
+    /// is served.
+    credit: usize,
+}
+
+impl<T> WorkQueue<T> {
+    /// Create a new, empty first-in-first-out queue, which is shared between peers if `weights`
+    /// are given.
+    ///
+    /// New items are dropped if the queue is full.
+    pub fn fifo(max_length: usize, weights: Option<Arc<HashMap<Option<PeerId>, usize>>>) -> Self {
+        Self::new(QueueOrder::Fifo, max_length, weights)
+    }
+
+    /// Create a new, empty last-in-first-out queue, which is shared between peers if `weights`
+    /// are given.
+    ///
+    /// The oldest item is dropped to make room for a new one if the queue is full.
+    pub fn lifo(max_length: usize, weights: Option<Arc<HashMap<Option<PeerId>, usize>>>) -> Self {
+        Self::new(QueueOrder::Lifo, max_length, weights)
+    }
+
+    fn new(
+        order: QueueOrder,
+        max_length: usize,
+        weights: Option<Arc<HashMap<Option<PeerId>, usize>>>,
+    ) -> Self {
+        Self {
+            order,
+            weights,
+            max_length,
+            len: 0,
+            flows: HashMap::new(),
+            active: VecDeque::new(),
+            credit: 0,
+        }
+    }
+
+    /// Add a new item to the queue, on behalf of `peer_id`.
+    pub fn push(&mut self, item: T, peer_id: Option<PeerId>, item_desc: &str, log: &Logger) {
+        let flow = if self.weights.is_some() {
+            peer_id
+        } else {
+            None
+        };
+
+        if self.len >= self.max_length {
+            let own_len = self.flows.get(&flow).map_or(0, VecDeque::len);
+            let largest = self
+                .flows
+                .iter()
+                .max_by_key(|(_, queue)| queue.len())
+                .map(|(flow, queue)| (*flow, queue.len()));
+
+            match (self.order, largest) {
+                (QueueOrder::Lifo, Some((victim, _))) => self.evict(victim),
+                (QueueOrder::Fifo, Some((victim, victim_len))) if victim_len > own_len => {
+                    debug!(
+                        log,
+                        "Dropped queued work from busiest peer";
+                        "queue" => item_desc,
+                        "peer" => ?victim,
+                    );
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_WORK_EVENTS_EVICTED_COUNT,
+                        &[item_desc],
+                    );
+                    self.evict(victim)
+                }
+                _ => {
+                    error!(
+                        log,
+                        "Work queue is full";
+                        "msg" => "the system has insufficient resources for load",
+                        "queue_len" => self.max_length,
+                        "queue" => item_desc,
+                    );
+                    return;
+                }
+            }
+        }
+
+        let queued = Queued {
+            item,
+            queued_at: Instant::now(),
+        };
+        let queue = self.flows.entry(flow).or_insert_with(|| {
+            self.active.push_back(flow);
+            VecDeque::new()
+        });
+        match self.order {
+            QueueOrder::Fifo => queue.push_back(queued),
+            QueueOrder::Lifo => queue.push_front(queued),
+        }
+        self.len += 1;
+    }
+
+    /// Remove the next item from the queue, returning it with the time it was queued.
+    fn pop_queued(&mut self) -> Option<Queued<T>> {
+        let flow = *self.active.front()?;
+        let queue = self.flows.get_mut(&flow)?;
+        let queued = queue.pop_front()?;
+        self.len -= 1;
+
+        if self.credit == 0 {
+            self.credit = self
+                .weights
+                .as_ref()
+                .and_then(|weights| weights.get(&flow).copied())
+                .unwrap_or(DEFAULT_PEER_FLOW_WEIGHT);
+        }
+        self.credit -= 1;
+
+        if queue.is_empty() {
+            self.flows.remove(&flow);
+            self.active.pop_front();
+            self.credit = 0;
+        } else if self.credit == 0 {
+            self.active.rotate_left(1);
+        }
+        Some(queued)
+    }
+
+    /// Remove the next item from the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        self.pop_queued().map(|queued| queued.item)
+    }
+
+    /// Drop the least valuable item of `flow`.
+    fn evict(&mut self, flow: Option<PeerId>) {
+        let Some(queue) = self.flows.get_mut(&flow) else {
+            return;
+        };
+        if queue.pop_back().is_some() {
+            self.len -= 1;
+        }
+        if queue.is_empty() {
+            self.flows.remove(&flow);
+            if self.active.front() == Some(&flow) {
+                self.credit = 0;
+            }
+            self.active.retain(|active| *active != flow);
+        }
+    }
+
+    /// Returns `true` if the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.len >= self.max_length
+    }
+
+    /// Returns the current length of the queue.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum length of the queue.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+/// The queues of every `WorkType`.
+pub struct WorkQueues<E: EthSpec> {
+    aggregate_queue: WorkQueue<Work<E>>,
+    attestation_queue: WorkQueue<Work<E>>,
+    unknown_block_aggregate_queue: WorkQueue<Work<E>>,
+    unknown_block_attestation_queue: WorkQueue<Work<E>>,
+    sync_message_queue: WorkQueue<Work<E>>,
+    sync_contribution_queue: WorkQueue<Work<E>>,
+    gossip_voluntary_exit_queue: WorkQueue<Work<E>>,
+    gossip_proposer_slashing_queue: WorkQueue<Work<E>>,
+    gossip_attester_slashing_queue: WorkQueue<Work<E>>,
+    finality_update_queue: WorkQueue<Work<E>>,
+    optimistic_update_queue: WorkQueue<Work<E>>,
+    unknown_light_client_update_queue: WorkQueue<Work<E>>,
+    unknown_block_sampling_request_queue: WorkQueue<Work<E>>,
+    rpc_block_queue: WorkQueue<Work<E>>,
+    rpc_blob_queue: WorkQueue<Work<E>>,
+    rpc_custody_column_queue: WorkQueue<Work<E>>,
+    rpc_verify_data_column_queue: WorkQueue<Work<E>>,
+    sampling_result_queue: WorkQueue<Work<E>>,
+    chain_segment_queue: WorkQueue<Work<E>>,
+    backfill_chain_segment: WorkQueue<Work<E>>,
+    gossip_block_queue: WorkQueue<Work<E>>,
+    gossip_blob_queue: WorkQueue<Work<E>>,
+    gossip_data_column_queue: WorkQueue<Work<E>>,
+    delayed_block_queue: WorkQueue<Work<E>>,
+    status_queue: WorkQueue<Work<E>>,
+    bbrange_queue: WorkQueue<Work<E>>,
+    bbroots_queue: WorkQueue<Work<E>>,
+    blbroots_queue: WorkQueue<Work<E>>,
+    blbrange_queue: WorkQueue<Work<E>>,
+    dcbroots_queue: WorkQueue<Work<E>>,
+    dcbrange_queue: WorkQueue<Work<E>>,
+    gossip_bls_to_execution_change_queue: WorkQueue<Work<E>>,
+    lc_bootstrap_queue: WorkQueue<Work<E>>,
+    lc_optimistic_update_queue: WorkQueue<Work<E>>,
+    lc_finality_update_queue: WorkQueue<Work<E>>,
+    lc_update_range_queue: WorkQueue<Work<E>>,
+    api_request_p0_queue: WorkQueue<Work<E>>,
+    api_request_p1_queue: WorkQueue<Work<E>>,
+}
+
+impl<E: EthSpec> WorkQueues<E> {
+    pub fn new(
+        queue_lengths: &BeaconProcessorQueueLengths,
+        mode: SchedulerMode,
+        flow_weights: &FlowWeights,
+    ) -> Self {
+        let weights =
+            (mode == SchedulerMode::FairQueuing).then(|| Arc::new(flow_weights.by_flow()));
+        let fifo = |max_length| WorkQueue::fifo(max_length, weights.clone());
+        let lifo = |max_length| WorkQueue::lifo(max_length, weights.clone());
+
+        Self {
+            // Using LIFO queues for attestations since validator profits rely upon getting fresh
+            // attestations into blocks. Additionally, later attestations contain more information
+            // than earlier ones, so we consider them more valuable.
+            aggregate_queue: lifo(queue_lengths.aggregate_queue),
+            attestation_queue: lifo(queue_lengths.attestation_queue),
+            unknown_block_aggregate_queue: lifo(queue_lengths.unknown_block_aggregate_queue),
+            unknown_block_attestation_queue: lifo(queue_lengths.unknown_block_attestation_queue),
+            sync_message_queue: lifo(queue_lengths.sync_message_queue),
+            sync_contribution_queue: lifo(queue_lengths.sync_contribution_queue),
+            // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't
+            // have a strong feeling about queue type for exits.
+            gossip_voluntary_exit_queue: fifo(queue_lengths.gossip_voluntary_exit_queue),
+            // Using a FIFO queue for slashing to prevent people from flushing their slashings
+            // from the queues with lots of junk messages.
+            gossip_proposer_slashing_queue: fifo(queue_lengths.gossip_proposer_slashing_queue),
+            gossip_attester_slashing_queue: fifo(queue_lengths.gossip_attester_slashing_queue),
+            // Using a FIFO queue for light client updates to maintain sequence order.
+            finality_update_queue: fifo(queue_lengths.finality_update_queue),
+            optimistic_update_queue: fifo(queue_lengths.optimistic_update_queue),
+            unknown_light_client_update_queue: fifo(
+                queue_lengths.unknown_light_client_update_queue,
+            ),
+            unknown_block_sampling_request_queue: fifo(
+                queue_lengths.unknown_block_sampling_request_queue,
+            ),
+            // Using a FIFO queue since blocks need to be imported sequentially.
+            rpc_block_queue: fifo(queue_lengths.rpc_block_queue),
+            rpc_blob_queue: fifo(queue_lengths.rpc_blob_queue),
+            rpc_custody_column_queue: fifo(queue_lengths.rpc_custody_column_queue),
+            rpc_verify_data_column_queue: fifo(queue_lengths.rpc_verify_data_column_queue),
+            sampling_result_queue: fifo(queue_lengths.sampling_result_queue),
+            chain_segment_queue: fifo(queue_lengths.chain_segment_queue),
+            backfill_chain_segment: fifo(queue_lengths.backfill_chain_segment),
+            gossip_block_queue: fifo(queue_lengths.gossip_block_queue),
+            gossip_blob_queue: fifo(queue_lengths.gossip_blob_queue),
+            gossip_data_column_queue: fifo(queue_lengths.gossip_data_column_queue),
+            delayed_block_queue: fifo(queue_lengths.delayed_block_queue),
+            status_queue: fifo(queue_lengths.status_queue),
+            bbrange_queue: fifo(queue_lengths.bbrange_queue),
+            bbroots_queue: fifo(queue_lengths.bbroots_queue),
+            blbroots_queue: fifo(queue_lengths.blbroots_queue),
+            blbrange_queue: fifo(queue_lengths.blbrange_queue),
+            dcbroots_queue: fifo(queue_lengths.dcbroots_queue),
+            dcbrange_queue: fifo(queue_lengths.dcbrange_queue),
+            gossip_bls_to_execution_change_queue: fifo(
+                queue_lengths.gossip_bls_to_execution_change_queue,
+            ),
+            lc_bootstrap_queue: fifo(queue_lengths.lc_bootstrap_queue),
+            lc_optimistic_update_queue: fifo(queue_lengths.lc_optimistic_update_queue),
+            lc_finality_update_queue: fifo(queue_lengths.lc_finality_update_queue),
+            lc_update_range_queue: fifo(queue_lengths.lc_update_range_queue),
+            api_request_p0_queue: fifo(queue_lengths.api_request_p0_queue),
+            api_request_p1_queue: fifo(queue_lengths.api_request_p1_queue),
+        }
+    }
+
+    /// Return the queue for `work_type`, or `None` for the batch work types, which are formed
+    /// within the `BeaconProcessor` and never queued.
+    pub fn queue(&self, work_type: WorkType) -> Option<&WorkQueue<Work<E>>> {
+        let queue = match work_type {
+            WorkType::GossipAttestation => &self.attestation_queue,
+            WorkType::UnknownBlockAttestation => &self.unknown_block_attestation_queue,
+            WorkType::GossipAttestationBatch => return None,
+            WorkType::GossipAggregate => &self.aggregate_queue,
+            WorkType::UnknownBlockAggregate => &self.unknown_block_aggregate_queue,
+            WorkType::UnknownLightClientOptimisticUpdate => &self.unknown_light_client_update_queue,
+            WorkType::UnknownBlockSamplingRequest => &self.unknown_block_sampling_request_queue,
+            WorkType::GossipAggregateBatch => return None,
+            WorkType::GossipBlock => &self.gossip_block_queue,
+            WorkType::GossipBlobSidecar => &self.gossip_blob_queue,
+            WorkType::GossipDataColumnSidecar => &self.gossip_data_column_queue,
+            WorkType::DelayedImportBlock => &self.delayed_block_queue,
+            WorkType::GossipVoluntaryExit => &self.gossip_voluntary_exit_queue,
+            WorkType::GossipProposerSlashing => &self.gossip_proposer_slashing_queue,
+            WorkType::GossipAttesterSlashing => &self.gossip_attester_slashing_queue,
+            WorkType::GossipSyncSignature => &self.sync_message_queue,
+            WorkType::GossipSyncContribution => &self.sync_contribution_queue,
+            WorkType::GossipLightClientFinalityUpdate => &self.finality_update_queue,
+            WorkType::GossipLightClientOptimisticUpdate => &self.optimistic_update_queue,
+            WorkType::RpcBlock | WorkType::IgnoredRpcBlock => &self.rpc_block_queue,
+            WorkType::RpcBlobs => &self.rpc_blob_queue,
+            WorkType::RpcCustodyColumn => &self.rpc_custody_column_queue,
+            WorkType::RpcVerifyDataColumn => &self.rpc_verify_data_column_queue,
+            WorkType::SamplingResult => &self.sampling_result_queue,
+            WorkType::ChainSegment => &self.chain_segment_queue,
+            WorkType::ChainSegmentBackfill => &self.backfill_chain_segment,
+            WorkType::Status => &self.status_queue,
+            WorkType::BlocksByRangeRequest => &self.bbrange_queue,
+            WorkType::BlocksByRootsRequest => &self.bbroots_queue,
+            WorkType::BlobsByRangeRequest => &self.blbrange_queue,
+            WorkType::BlobsByRootsRequest => &self.blbroots_queue,
+            WorkType::DataColumnsByRootsRequest => &self.dcbroots_queue,
+            WorkType::DataColumnsByRangeRequest => &self.dcbrange_queue,
+            WorkType::GossipBlsToExecutionChange => &self.gossip_bls_to_execution_change_queue,
+            WorkType::LightClientBootstrapRequest => &self.lc_bootstrap_queue,
+            WorkType::LightClientOptimisticUpdateRequest => &self.lc_optimistic_update_queue,
+            WorkType::LightClientFinalityUpdateRequest => &self.lc_finality_update_queue,
+            WorkType::LightClientUpdatesByRangeRequest => &self.lc_update_range_queue,
+            WorkType::ApiRequestP0 => &self.api_request_p0_queue,
+            WorkType::ApiRequestP1 => &self.api_request_p1_queue,
+        };
+        Some(queue)
+    }
+
+    /// Mutable version of `Self::queue`.
+    fn queue_mut(&mut self, work_type: WorkType) -> Option<&mut WorkQueue<Work<E>>> {
+        let queue = match work_type {
+            WorkType::GossipAttestation => &mut self.attestation_queue,
+            WorkType::UnknownBlockAttestation => &mut self.unknown_block_attestation_queue,
+            WorkType::GossipAttestationBatch => return None,
+            WorkType::GossipAggregate => &mut self.aggregate_queue,
+            WorkType::UnknownBlockAggregate => &mut self.unknown_block_aggregate_queue,
+            WorkType::UnknownLightClientOptimisticUpdate => {
+                &mut self.unknown_light_client_update_queue
+            }
+            WorkType::UnknownBlockSamplingRequest => &mut self.unknown_block_sampling_request_queue,
+            WorkType::GossipAggregateBatch => return None,
+            WorkType::GossipBlock => &mut self.gossip_block_queue,
+            WorkType::GossipBlobSidecar => &mut self.gossip_blob_queue,
+            WorkType::GossipDataColumnSidecar => &mut self.gossip_data_column_queue,
+            WorkType::DelayedImportBlock => &mut self.delayed_block_queue,
+            WorkType::GossipVoluntaryExit => &mut self.gossip_voluntary_exit_queue,
+            WorkType::GossipProposerSlashing => &mut self.gossip_proposer_slashing_queue,
+            WorkType::GossipAttesterSlashing => &mut self.gossip_attester_slashing_queue,
+            WorkType::GossipSyncSignature => &mut self.sync_message_queue,
+            WorkType::GossipSyncContribution => &mut self.sync_contribution_queue,
+            WorkType::GossipLightClientFinalityUpdate => &mut self.finality_update_queue,
+            WorkType::GossipLightClientOptimisticUpdate => &mut self.optimistic_update_queue,
+            WorkType::RpcBlock | WorkType::IgnoredRpcBlock => &mut self.rpc_block_queue,
+            WorkType::RpcBlobs => &mut self.rpc_blob_queue,
+            WorkType::RpcCustodyColumn => &mut self.rpc_custody_column_queue,
+            WorkType::RpcVerifyDataColumn => &mut self.rpc_verify_data_column_queue,
+            WorkType::SamplingResult => &mut self.sampling_result_queue,
+            WorkType::ChainSegment => &mut self.chain_segment_queue,
+            WorkType::ChainSegmentBackfill => &mut self.backfill_chain_segment,
+            WorkType::Status => &mut self.status_queue,
+            WorkType::BlocksByRangeRequest => &mut self.bbrange_queue,
+            WorkType::BlocksByRootsRequest => &mut self.bbroots_queue,
+            WorkType::BlobsByRangeRequest => &mut self.blbrange_queue,
+            WorkType::BlobsByRootsRequest => &mut self.blbroots_queue,
+            WorkType::DataColumnsByRootsRequest => &mut self.dcbroots_queue,
+            WorkType::DataColumnsByRangeRequest => &mut self.dcbrange_queue,
+            WorkType::GossipBlsToExecutionChange => &mut self.gossip_bls_to_execution_change_queue,
+            WorkType::LightClientBootstrapRequest => &mut self.lc_bootstrap_queue,
+            WorkType::LightClientOptimisticUpdateRequest => &mut self.lc_optimistic_update_queue,
+            WorkType::LightClientFinalityUpdateRequest => &mut self.lc_finality_update_queue,
+            WorkType::LightClientUpdatesByRangeRequest => &mut self.lc_update_range_queue,
+            WorkType::ApiRequestP0 => &mut self.api_request_p0_queue,
+            WorkType::ApiRequestP1 => &mut self.api_request_p1_queue,
+        };
+        Some(queue)
+    }
+
+    /// Queue `work` on behalf of `peer_id`.
+    pub fn push(&mut self, work: Work<E>, peer_id: Option<PeerId>, log: &Logger) {
+        let work_type = work.to_type();
+        let work_id = work.str_id();
+        match self.queue_mut(work_type) {
+            Some(queue) => queue.push(work, peer_id, work_id, log),
+            // Batches are formed internally within the `BeaconProcessor`, they are not sent from
+            // external services.
+            None => crit!(
+                log,
+                "Unsupported inbound event";
+                "type" => work_id,
+            ),
+        }
+    }
+
+    /// Returns the number of items queued for `work_type`.
+    pub fn len(&self, work_type: WorkType) -> usize {
+        self.queue(work_type).map_or(0, WorkQueue::len)
+    }
+
+    /// Take the next work from the queue of `work_type`.
+    ///
+    /// Attestations and aggregates are collected into batches when more than one is queued, so
+    /// they can take advantage of batch signature verification.
+    pub fn pop(
+        &mut self,
+        work_type: WorkType,
+        config: &BeaconProcessorConfig,
+        log: &Logger,
+    ) -> Option<Work<E>> {
+        let work_id: &'static str = work_type.into();
+        let queue = self.queue_mut(work_type)?;
+        let batch_size = match work_type {
+            WorkType::GossipAggregate => {
+                cmp::min(queue.len(), config.max_gossip_aggregate_batch_size)
+            }
+            WorkType::GossipAttestation => {
+                cmp::min(queue.len(), config.max_gossip_attestation_batch_size)
+            }
+            _ => 1,
+        };
+
+        let mut items = Vec::with_capacity(batch_size);
+        for _ in 0..cmp::max(batch_size, 1) {
+            if let Some(queued) = queue.pop_queued() {
+                metrics::observe_timer_vec(
+                    &metrics::BEACON_PROCESSOR_QUEUE_TIME,
+                    &[work_id],
+                    queued.queued_at.elapsed(),
+                );
+                items.push(queued.item);
+            }
+        }
+
+        if items.len() < 2 {
+            return items.pop();
+        }
+
+        // Note: this converts the `Work::GossipAggregate` or `Work::GossipAttestation` items into
+        // a `Work::GossipAggregateBatch` or `Work::GossipAttestationBatch` item.
+        match work_type {
+            WorkType::GossipAggregate => {
+                let mut aggregates = Vec::with_capacity(items.len());
+                let mut process_batch_opt = None;
+                for item in items {
+                    match item {
+                        Work::GossipAggregate {
+                            aggregate,
+                            process_individual: _,
+                            process_batch,
+                        } => {
+                            aggregates.push(*aggregate);
+                            if process_batch_opt.is_none() {
+                                process_batch_opt = Some(process_batch);
+                            }
+                        }
+                        _ => error!(log, "Invalid item in aggregate queue"),
+                    }
+                }
+
+                if let Some(process_batch) = process_batch_opt {
+                    // Process all aggregates with a single worker.
+                    Some(Work::GossipAggregateBatch {
+                        aggregates,
+                        process_batch,
+                    })
+                } else {
+                    // There is no good reason for this to happen, it is a serious logic error.
+                    // Since we only form batches when multiple work items exist, we should always
+                    // have a work closure at this point.
+                    crit!(log, "Missing aggregate work");
+                    None
+                }
+            }
+            WorkType::GossipAttestation => {
+                let mut attestations = Vec::with_capacity(items.len());
+                let mut process_batch_opt = None;
+                for item in items {
+                    match item {
+                        Work::GossipAttestation {
+                            attestation,
+                            process_individual: _,
+                            process_batch,
+                        } => {
+                            attestations.push(*attestation);
+                            if process_batch_opt.is_none() {
+                                process_batch_opt = Some(process_batch);
+                            }
+                        }
+                        _ => error!(log, "Invalid item in attestation queue"),
+                    }
+                }
+
+                if let Some(process_batch) = process_batch_opt {
+                    // Process all attestations with a single worker.
+                    Some(Work::GossipAttestationBatch {
+                        attestations,
+                        process_batch,
+                    })
+                } else {
+                    // There is no good reason for this to happen, it is a serious logic error.
+                    // Since we only form batches when multiple work items exist, we should always
+                    // have a work closure at this point.
+                    crit!(log, "Missing attestations work");
+                    None
+                }
+            }
+            _ => {
+                crit!(log, "Batch formed for unbatched work"; "type" => work_id);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::test_logger;
+
+    fn drain(queue: &mut WorkQueue<(usize, u64)>) -> Vec<(usize, u64)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    fn fair(flow_weights: &FlowWeights) -> Option<Arc<HashMap<Option<PeerId>, usize>>> {
+        Some(Arc::new(flow_weights.by_flow()))
+    }
+
+    #[test]
+    fn strict_fifo_ignores_peers() {
+        let log = test_logger();
+        let peers = [PeerId::random(), PeerId::random()];
+        let mut queue = WorkQueue::fifo(8, None);
+        for i in 0..4 {
+            queue.push((0, i), Some(peers[0]), "test", &log);
+        }
+        queue.push((1, 0), Some(peers[1]), "test", &log);
+
+        assert_eq!(
+            drain(&mut queue),
+            vec![(0, 0), (0, 1), (0, 2), (0, 3), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn fair_fifo_interleaves_peers() {
+        let log = test_logger();
+        let peers = [PeerId::random(), PeerId::random()];
+        let mut queue = WorkQueue::fifo(8, fair(&FlowWeights::default()));
+        for i in 0..4 {
+            queue.push((0, i), Some(peers[0]), "test", &log);
+        }
+        for i in 0..2 {
+            queue.push((1, i), Some(peers[1]), "test", &log);
+        }
+
+        assert_eq!(
+            drain(&mut queue),
+            vec![(0, 0), (1, 0), (0, 1), (1, 1), (0, 2), (0, 3)]
+        );
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn fair_fifo_drops_busiest_peer_when_full() {
+        let log = test_logger();
+        let peers = [PeerId::random(), PeerId::random()];
+        let mut queue = WorkQueue::fifo(4, fair(&FlowWeights::default()));
+        for i in 0..4 {
+            queue.push((0, i), Some(peers[0]), "test", &log);
+        }
+        // The flooding peer's newest item makes room.
+        queue.push((1, 0), Some(peers[1]), "test", &log);
+        assert_eq!(queue.len(), 4);
+        // The flooding peer can't push any more work.
+        queue.push((0, 4), Some(peers[0]), "test", &log);
+        assert_eq!(queue.len(), 4);
+
+        assert_eq!(drain(&mut queue), vec![(0, 0), (1, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn fair_lifo_drops_oldest_of_busiest_peer() {
+        let log = test_logger();
+        let peers = [PeerId::random(), PeerId::random()];
+        let mut queue = WorkQueue::lifo(3, fair(&FlowWeights::default()));
+        queue.push((1, 0), Some(peers[1]), "test", &log);
+        for i in 0..3 {
+            queue.push((0, i), Some(peers[0]), "test", &log);
+        }
+
+        assert_eq!(drain(&mut queue), vec![(1, 0), (0, 2), (0, 1)]);
+    }
+
+    #[test]
+    fn local_work_is_weighted() {
+        let log = test_logger();
+        let peer = PeerId::random();
+        let mut queue = WorkQueue::fifo(64, fair(&FlowWeights::default()));
+        for i in 0..DEFAULT_LOCAL_FLOW_WEIGHT as u64 + 1 {
+            queue.push((0, i), None, "test", &log);
+        }
+        queue.push((1, 0), Some(peer), "test", &log);
+
+        let order = drain(&mut queue);
+        assert_eq!(order[DEFAULT_LOCAL_FLOW_WEIGHT], (1, 0));
+        assert_eq!(order.len(), DEFAULT_LOCAL_FLOW_WEIGHT + 2);
+    }
+
+    #[test]
+    fn peer_weights() {
+        let log = test_logger();
+        let peers = [PeerId::random(), PeerId::random()];
+        let flow_weights = FlowWeights {
+            local: 1,
+            peers: vec![(peers[0].to_string().parse().unwrap(), 3)],
+        };
+        let mut queue = WorkQueue::fifo(16, fair(&flow_weights));
+        for i in 0..4 {
+            queue.push((0, i), Some(peers[0]), "test", &log);
+            queue.push((1, i), Some(peers[1]), "test", &log);
+        }
+        queue.push((2, 0), None, "test", &log);
+
+        assert_eq!(
+            drain(&mut queue),
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (2, 0),
+                (0, 3),
+                (1, 1),
+                (1, 2),
+                (1, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn priority_lanes() {
+        assert_eq!(priority_order(&[]), DEFAULT_PRIORITY_ORDER);
+
+        let order = priority_order(&[
+            (WorkType::BlocksByRangeRequest, 0),
+            (WorkType::ChainSegment, 2),
+        ]);
+        assert_eq!(order.first(), Some(&WorkType::BlocksByRangeRequest));
+        assert_eq!(order.last(), Some(&WorkType::ChainSegment));
+        assert_eq!(order[1], WorkType::RpcBlock);
+        assert_eq!(order.len(), DEFAULT_PRIORITY_ORDER.len());
+    }
+
+    #[test]
+    fn default_order_covers_all_queues() {
+        use strum::IntoEnumIterator;
+
+        for work_type in WorkType::iter() {
+            let queued = !matches!(
+                work_type,
+                WorkType::GossipAttestationBatch
+                    | WorkType::GossipAggregateBatch
+                    | WorkType::IgnoredRpcBlock
+            );
+            assert_eq!(
+                DEFAULT_PRIORITY_ORDER.contains(&work_type),
+                queued,
+                "{work_type:?}"
+            );
+        }
+    }
+}
@@ -193,6 +193,7 @@ impl<E: EthSpec> From<QueuedBackfillBatch> for WorkEvent<E> {
     fn from(queued_backfill_batch: QueuedBackfillBatch) -> WorkEvent<E> {
         WorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::ChainSegmentBackfill(queued_backfill_batch.0),
         }
     }
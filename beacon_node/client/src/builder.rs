@@ -1,3 +1,4 @@
+use crate::checkpoint_sync::{download_checkpoint, RemoteCheckpoint};
 use crate::compute_light_client_updates::{
     compute_light_client_updates, LIGHT_CLIENT_SERVER_CHANNEL_CAPACITY,
 };
@@ -7,6 +8,9 @@ use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
 use beacon_chain::graffiti_calculator::start_engine_version_cache_refresh_service;
+use beacon_chain::historical_block_provider::open_historical_block_provider;
+use beacon_chain::historical_light_client_updates::start_historical_light_client_updates_service;
+use beacon_chain::http_backfill::start_http_backfill_service;
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
@@ -23,10 +27,7 @@ use beacon_processor::{BeaconProcessor, BeaconProcessorChannels};
 use beacon_processor::{BeaconProcessorConfig, BeaconProcessorQueueLengths};
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
-use eth2::{
-    types::{BlockId, StateId},
-    BeaconNodeHttpClient, Error as ApiError, Timeouts,
-};
+use eth2::{BeaconNodeHttpClient, Error as ApiError, Timeouts};
 use execution_layer::test_utils::generate_genesis_header;
 use execution_layer::ExecutionLayer;
 use futures::channel::mpsc::Receiver;
@@ -226,6 +227,18 @@ where
             builder
         };
 
+        let builder = if let Some(source) = &config.chain.historical_block_source {
+            let provider = open_historical_block_provider(
+                source,
+                config.store.clone(),
+                spec.clone(),
+                context.log(),
+            )?;
+            builder.historical_block_provider(provider)
+        } else {
+            builder
+        };
+
         let builder = if config.network.enable_light_client_server {
             let (tx, rv) = futures::channel::mpsc::channel::<LightClientProducerEvent<E>>(
                 LIGHT_CLIENT_SERVER_CHANNEL_CAPACITY,
@@ -382,11 +395,12 @@ where
                     )
                     .map(|v| (v, None))?
             }
-            ClientGenesis::CheckpointSyncUrl { url } => {
+            ClientGenesis::CheckpointSyncUrl { urls, quorum } => {
                 info!(
                     context.log(),
                     "Starting checkpoint sync";
-                    "remote_url" => %url,
+                    "remote_urls" => ?urls,
+                    "quorum" => quorum,
                 );
                 if config.chain.genesis_backfill {
                     info!(
@@ -395,12 +409,12 @@ where
                     );
                 }
 
-                let remote = BeaconNodeHttpClient::new(
-                    url,
-                    Timeouts::set_all(Duration::from_secs(
-                        config.chain.checkpoint_sync_url_timeout,
-                    )),
-                );
+                let timeout = Duration::from_secs(config.chain.checkpoint_sync_url_timeout);
+                let url = urls
+                    .first()
+                    .cloned()
+                    .ok_or("At least one checkpoint sync URL is required")?;
+                let remote = BeaconNodeHttpClient::new(url, Timeouts::set_all(timeout));
 
                 let deposit_snapshot = if config.sync_eth1_chain {
                     // We want to fetch deposit snapshot before fetching the finalized beacon state to
@@ -447,65 +461,28 @@ where
                     None
                 };
 
-                debug!(
+                let RemoteCheckpoint {
+                    state,
+                    block,
+                    blobs,
+                    url: source_url,
+                } = download_checkpoint::<E>(
+                    &urls,
+                    quorum,
+                    timeout,
+                    config.chain.weak_subjectivity_checkpoint,
+                    &spec,
                     context.log(),
-                    "Downloading finalized state";
-                );
-                let state = remote
-                    .get_debug_beacon_states_ssz::<E>(StateId::Finalized, &spec)
-                    .await
-                    .map_err(|e| format!("Error loading checkpoint state from remote: {:?}", e))?
-                    .ok_or_else(|| "Checkpoint state missing from remote".to_string())?;
-
-                debug!(context.log(), "Downloaded finalized state"; "slot" => ?state.slot());
-
-                let finalized_block_slot = state.latest_block_header().slot;
-
-                debug!(context.log(), "Downloading finalized block"; "block_slot" => ?finalized_block_slot);
-                let block = remote
-                    .get_beacon_blocks_ssz::<E>(BlockId::Slot(finalized_block_slot), &spec)
-                    .await
-                    .map_err(|e| match e {
-                        ApiError::InvalidSsz(e) => format!(
-                            "Unable to parse SSZ: {:?}. Ensure the checkpoint-sync-url refers to a \
-                            node for the correct network",
-                            e
-                        ),
-                        e => format!("Error fetching finalized block from remote: {:?}", e),
-                    })?
-                    .ok_or("Finalized block missing from remote, it returned 404")?;
+                )
+                .await?;
                 let block_root = block.canonical_root();
 
-                debug!(context.log(), "Downloaded finalized block");
-
-                let blobs = if block.message().body().has_blobs() {
-                    debug!(context.log(), "Downloading finalized blobs");
-                    if let Some(response) = remote
-                        .get_blobs::<E>(BlockId::Root(block_root), None)
-                        .await
-                        .map_err(|e| format!("Error fetching finalized blobs from remote: {e:?}"))?
-                    {
-                        debug!(context.log(), "Downloaded finalized blobs");
-                        Some(response.data)
-                    } else {
-                        warn!(
-                            context.log(),
-                            "Checkpoint server is missing blobs";
-                            "block_root" => %block_root,
-                            "hint" => "use a different URL or ask the provider to update",
-                            "impact" => "db will be slightly corrupt until these blobs are pruned",
-                        );
-                        None
-                    }
-                } else {
-                    None
-                };
-
                 let genesis_state = genesis_state(&runtime_context, &config, log).await?;
 
                 info!(
                     context.log(),
                     "Loaded checkpoint block and state";
+                    "source" => %source_url,
                     "block_slot" => block.slot(),
                     "state_slot" => state.slot(),
                     "block_root" => ?block_root,
@@ -983,6 +960,11 @@ where
                 beacon_chain.task_executor.clone(),
                 beacon_chain.clone(),
             );
+            start_http_backfill_service(runtime_context.executor.clone(), beacon_chain.clone());
+            start_historical_light_client_updates_service(
+                runtime_context.executor.clone(),
+                beacon_chain.clone(),
+            );
         }
 
         Ok(Client {
@@ -0,0 +1,374 @@
+//! Download of the checkpoint state and block from one or more remote beacon nodes.
+//!
+//! Every source is asked for its finalized block header and the root of the finalized state it
+//! serves, and a quorum of sources must agree on them before the block and state are downloaded
+//! from one of the agreeing sources.
+use eth2::types::{BlockId, StateId};
+use eth2::{BeaconNodeHttpClient, Error as ApiError, Timeouts};
+use futures::future::join_all;
+use sensitive_url::SensitiveUrl;
+use slog::{debug, info, warn, Logger};
+use std::collections::HashMap;
+use std::time::Duration;
+use types::{
+    BeaconState, BlobSidecarList, ChainSpec, Checkpoint, EthSpec, Hash256, SignedBeaconBlock,
+};
+
+/// The checkpoint block and state, along with the source they were downloaded from.
+pub struct RemoteCheckpoint<E: EthSpec> {
+    pub state: BeaconState<E>,
+    pub block: SignedBeaconBlock<E>,
+    pub blobs: Option<BlobSidecarList<E>>,
+    pub url: SensitiveUrl,
+}
+
+/// The finalized block root and state root reported by a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FinalizedRoots {
+    block_root: Hash256,
+    state_root: Hash256,
+    /// The root of the finalized state served by the source, which differs from `state_root` if
+    /// the state was advanced through empty slots after the block.
+    served_state_root: Hash256,
+}
+
+/// Download the checkpoint from `urls`, requiring `quorum` of them to agree on it.
+///
+/// If a `wss_checkpoint` is given it is checked against the checkpoint when the state contains
+/// enough history to do so, and otherwise left to the checks on startup and during sync.
+pub async fn download_checkpoint<E: EthSpec>(
+    urls: &[SensitiveUrl],
+    quorum: usize,
+    timeout: Duration,
+    wss_checkpoint: Option<Checkpoint>,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<RemoteCheckpoint<E>, String> {
+    let remotes = urls
+        .iter()
+        .map(|url| BeaconNodeHttpClient::new(url.clone(), Timeouts::set_all(timeout)))
+        .collect::<Vec<_>>();
+
+    debug!(log, "Downloading finalized block headers"; "sources" => urls.len());
+    let results = join_all(remotes.iter().map(finalized_roots)).await;
+    let (roots, agreeing) = select_quorum(urls, &results, quorum)?;
+
+    for (url, result) in urls.iter().zip(&results) {
+        match result {
+            Ok(other) if *other == roots => {}
+            Ok(other) => warn!(
+                log,
+                "Checkpoint source diverged from quorum";
+                "source" => %url,
+                "block_root" => ?other.block_root,
+                "state_root" => ?other.state_root,
+                "served_state_root" => ?other.served_state_root,
+            ),
+            Err(e) => warn!(
+                log,
+                "Checkpoint source failed";
+                "source" => %url,
+                "error" => e,
+            ),
+        }
+    }
+    info!(
+        log,
+        "Checkpoint sources agree";
+        "block_root" => ?roots.block_root,
+        "state_root" => ?roots.state_root,
+        "served_state_root" => ?roots.served_state_root,
+        "agreeing" => agreeing.len(),
+        "sources" => urls.len(),
+    );
+
+    // Any of the agreeing sources can serve the checkpoint, try them in order.
+    let mut errors = vec![];
+    for index in agreeing {
+        match download_from(&remotes[index], roots, spec, log).await {
+            Ok((state, block, blobs)) => {
+                if let Some(wss_checkpoint) = wss_checkpoint {
+                    verify_wss_checkpoint(&state, &block, wss_checkpoint, log)?;
+                }
+                return Ok(RemoteCheckpoint {
+                    state,
+                    block,
+                    blobs,
+                    url: urls[index].clone(),
+                });
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to download checkpoint";
+                    "source" => %urls[index],
+                    "error" => &e,
+                );
+                errors.push(format!("{}: {e}", urls[index]));
+            }
+        }
+    }
+    Err(format!(
+        "Unable to download checkpoint from any source: {}",
+        errors.join(", ")
+    ))
+}
+
+/// Fetch the finalized block root, the block's state root and the root of the finalized state
+/// from `remote`.
+async fn finalized_roots(remote: &BeaconNodeHttpClient) -> Result<FinalizedRoots, String> {
+    let header = remote
+        .get_beacon_headers_block_id(BlockId::Finalized)
+        .await
+        .map_err(|e| format!("Error fetching finalized block header: {e:?}"))?
+        .ok_or("Finalized block header missing from remote, it returned 404")?
+        .data;
+    let served_state_root = remote
+        .get_beacon_states_root(StateId::Finalized)
+        .await
+        .map_err(|e| format!("Error fetching finalized state root: {e:?}"))?
+        .ok_or("Finalized state root missing from remote, it returned 404")?
+        .data
+        .root;
+    Ok(FinalizedRoots {
+        block_root: header.root,
+        state_root: header.header.message.state_root,
+        served_state_root,
+    })
+}
+
+/// Find the roots that at least `quorum` sources agree on.
+///
+/// Return the roots and the indices of the sources that reported them, or an error describing
+/// what each source reported. It's an error for more than one set of roots to reach the quorum.
+fn select_quorum(
+    urls: &[SensitiveUrl],
+    results: &[Result<FinalizedRoots, String>],
+    quorum: usize,
+) -> Result<(FinalizedRoots, Vec<usize>), String> {
+    let mut votes: HashMap<FinalizedRoots, Vec<usize>> = HashMap::new();
+    for (index, result) in results.iter().enumerate() {
+        if let Ok(roots) = result {
+            votes.entry(*roots).or_default().push(index);
+        }
+    }
+
+    let mut reached = votes
+        .into_iter()
+        .filter(|(_, agreeing)| agreeing.len() >= quorum)
+        .collect::<Vec<_>>();
+    let problem = match reached.len() {
+        1 => return Ok(reached.remove(0)),
+        0 => format!(
+            "Fewer than {quorum} of {} checkpoint sources agree on the finalized checkpoint",
+            urls.len()
+        ),
+        _ => format!(
+            "More than one finalized checkpoint is reported by {quorum} of {} checkpoint sources, \
+             raise the quorum",
+            urls.len()
+        ),
+    };
+
+    let reports = urls
+        .iter()
+        .zip(results)
+        .map(|(url, result)| match result {
+            Ok(roots) => format!(
+                "{url}: block_root {:?}, state_root {:?}, served_state_root {:?}",
+                roots.block_root, roots.state_root, roots.served_state_root
+            ),
+            Err(e) => format!("{url}: {e}"),
+        })
+        .collect::<Vec<_>>();
+    Err(format!(
+        "{problem}. Sources may briefly disagree while finality advances, if this persists check \
+         the sources. Reported: {}",
+        reports.join("; ")
+    ))
+}
+
+/// Download the checkpoint state, block and blobs and check them against `roots`.
+async fn download_from<E: EthSpec>(
+    remote: &BeaconNodeHttpClient,
+    roots: FinalizedRoots,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<
+    (
+        BeaconState<E>,
+        SignedBeaconBlock<E>,
+        Option<BlobSidecarList<E>>,
+    ),
+    String,
+> {
+    let block_root = roots.block_root;
+
+    debug!(log, "Downloading finalized block"; "block_root" => ?block_root);
+    let block = remote
+        .get_beacon_blocks_ssz::<E>(BlockId::Root(block_root), spec)
+        .await
+        .map_err(|e| match e {
+            ApiError::InvalidSsz(e) => format!(
+                "Unable to parse SSZ: {:?}. Ensure the checkpoint-sync-url refers to a \
+                node for the correct network",
+                e
+            ),
+            e => format!("Error fetching finalized block from remote: {:?}", e),
+        })?
+        .ok_or("Finalized block missing from remote, it returned 404")?;
+    if block.canonical_root() != block_root || block.state_root() != roots.state_root {
+        return Err(format!("Remote served the wrong block for {block_root:?}"));
+    }
+
+    debug!(log, "Downloading finalized state");
+    let mut state = remote
+        .get_debug_beacon_states_ssz::<E>(StateId::Finalized, spec)
+        .await
+        .map_err(|e| format!("Error loading checkpoint state from remote: {:?}", e))?
+        .ok_or_else(|| "Checkpoint state missing from remote".to_string())?;
+    debug!(log, "Downloaded finalized state"; "slot" => ?state.slot());
+
+    // The state may have been advanced through empty slots after the block, in which case it must
+    // also record the block's state root.
+    let state_root = state
+        .canonical_root()
+        .map_err(|e| format!("Error computing checkpoint state root: {e:?}"))?;
+    let links_to_block = if state.slot() == block.slot() {
+        state_root == roots.state_root
+    } else {
+        state.latest_block_header().slot == block.slot()
+            && state.get_state_root(block.slot()).ok() == Some(&roots.state_root)
+    };
+    if state_root != roots.served_state_root || !links_to_block {
+        return Err(format!(
+            "Remote served a state which doesn't match the finalized block {block_root:?}, \
+             finality may have advanced during the download"
+        ));
+    }
+
+    let blobs = if block.message().body().has_blobs() {
+        debug!(log, "Downloading finalized blobs");
+        if let Some(response) = remote
+            .get_blobs::<E>(BlockId::Root(block_root), None)
+            .await
+            .map_err(|e| format!("Error fetching finalized blobs from remote: {e:?}"))?
+        {
+            debug!(log, "Downloaded finalized blobs");
+            Some(response.data)
+        } else {
+            warn!(
+                log,
+                "Checkpoint server is missing blobs";
+                "block_root" => %block_root,
+                "hint" => "use a different URL or ask the provider to update",
+                "impact" => "db will be slightly corrupt until these blobs are pruned",
+            );
+            None
+        }
+    } else {
+        None
+    };
+
+    Ok((state, block, blobs))
+}
+
+/// Check that the checkpoint is descended from `wss_checkpoint`, if the state still has the block
+/// root at the start of its epoch.
+fn verify_wss_checkpoint<E: EthSpec>(
+    state: &BeaconState<E>,
+    block: &SignedBeaconBlock<E>,
+    wss_checkpoint: Checkpoint,
+    log: &Logger,
+) -> Result<(), String> {
+    let wss_slot = wss_checkpoint.epoch.start_slot(E::slots_per_epoch());
+    let root = if wss_slot > state.slot() {
+        None
+    } else if wss_slot >= block.slot() {
+        Some(block.canonical_root())
+    } else {
+        state.get_block_root(wss_slot).ok().copied()
+    };
+
+    match root {
+        Some(root) if root == wss_checkpoint.root => {
+            info!(
+                log,
+                "Checkpoint matches weak subjectivity checkpoint";
+                "wss_epoch" => wss_checkpoint.epoch,
+            );
+            Ok(())
+        }
+        Some(root) => Err(format!(
+            "Checkpoint conflicts with the weak subjectivity checkpoint: block root at epoch {} \
+             is {root:?}, expected {:?}",
+            wss_checkpoint.epoch, wss_checkpoint.root
+        )),
+        None => {
+            warn!(
+                log,
+                "Unable to check weak subjectivity checkpoint before checkpoint sync";
+                "wss_epoch" => wss_checkpoint.epoch,
+                "checkpoint_slot" => state.slot(),
+                "info" => "it will be checked once the chain reaches it",
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::FixedBytesExtended;
+
+    fn urls(n: usize) -> Vec<SensitiveUrl> {
+        (0..n)
+            .map(|i| SensitiveUrl::parse(&format!("http://node-{i}:5052")).unwrap())
+            .collect()
+    }
+
+    fn roots(n: u64) -> FinalizedRoots {
+        FinalizedRoots {
+            block_root: Hash256::from_low_u64_be(n),
+            state_root: Hash256::from_low_u64_be(n + 100),
+            served_state_root: Hash256::from_low_u64_be(n + 200),
+        }
+    }
+
+    #[test]
+    fn quorum_reached_despite_divergent_source() {
+        let results = vec![Ok(roots(1)), Ok(roots(2)), Ok(roots(1))];
+        let (agreed, agreeing) = select_quorum(&urls(3), &results, 2).unwrap();
+        assert_eq!(agreed, roots(1));
+        assert_eq!(agreeing, vec![0, 2]);
+    }
+
+    #[test]
+    fn quorum_not_reached() {
+        let results = vec![Ok(roots(1)), Ok(roots(2)), Err("timeout".to_string())];
+        let error = select_quorum(&urls(3), &results, 2).unwrap_err();
+        assert!(error.contains("http://node-1:5052/"), "{error}");
+        assert!(error.contains("timeout"), "{error}");
+    }
+
+    #[test]
+    fn several_quorums_rejected() {
+        let results = vec![Ok(roots(1)), Ok(roots(2)), Ok(roots(1)), Ok(roots(2))];
+        let error = select_quorum(&urls(4), &results, 2).unwrap_err();
+        assert!(
+            error.contains("More than one finalized checkpoint"),
+            "{error}"
+        );
+
+        let results = vec![Ok(roots(1)), Ok(roots(2)), Ok(roots(1))];
+        assert!(select_quorum(&urls(3), &results, 1).is_err());
+    }
+
+    #[test]
+    fn failed_sources_do_not_count() {
+        let results = vec![Ok(roots(1)), Err("timeout".to_string())];
+        assert!(select_quorum(&urls(2), &results, 2).is_err());
+        assert!(select_quorum(&urls(2), &results, 1).is_ok());
+    }
+}
@@ -43,8 +43,11 @@ pub enum ClientGenesis {
         anchor_block_bytes: Vec<u8>,
         anchor_blobs_bytes: Option<Vec<u8>>,
     },
+    /// Downloads the checkpoint from the beacon nodes at `urls`, at least `quorum` of which must
+    /// agree on it.
     CheckpointSyncUrl {
-        url: SensitiveUrl,
+        urls: Vec<SensitiveUrl>,
+        quorum: usize,
     },
 }
 
@@ -1,3 +1,4 @@
+mod checkpoint_sync;
 mod compute_light_client_updates;
 pub mod config;
 mod metrics;
@@ -1,6 +1,7 @@
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2::lighthouse::{
-    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
+    AttestationPackingEfficiency, BlockPackingEfficiency, BlockPackingEfficiencyQuery,
+    ProposerInfo, UniqueAttestation,
 };
 use parking_lot::Mutex;
 use state_processing::{
@@ -353,6 +354,19 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
         // Get all attestations included in the block.
         let included = handler.lock().apply_block(block)?;
 
+        // If this node produced the block, include how it packed the attestations.
+        let attestation_packing = chain
+            .op_pool
+            .packing_outcome(slot, block.parent_root())
+            .filter(|outcome| outcome.packed == block_message.body().attestations_len())
+            .map(|outcome| AttestationPackingEfficiency {
+                strategy: outcome.strategy.to_string(),
+                candidate_attestations: outcome.candidates,
+                packed_attestations: outcome.packed,
+                attesters: outcome.attesters,
+                proposer_reward: outcome.reward,
+            });
+
         let efficiency = BlockPackingEfficiency {
             slot,
             block_hash: block.canonical_root(),
@@ -360,6 +374,7 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
             available_attestations: available_count,
             included_attestations: included,
             prior_skip_slots: handler.lock().prior_skip_slots,
+            attestation_packing,
         };
 
         // Write to response.
@@ -11,6 +11,7 @@ pub fn info<T: BeaconChainTypes>(
     let config = store.get_config().clone();
     let anchor = store.get_anchor_info();
     let blob_info = store.get_blob_info();
+    let column_stats = store.get_column_stats();
 
     Ok(DatabaseInfo {
         schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
@@ -18,5 +19,6 @@ pub fn info<T: BeaconChainTypes>(
         split,
         anchor,
         blob_info,
+        column_stats,
     })
 }
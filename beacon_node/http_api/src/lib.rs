@@ -15,7 +15,9 @@ mod builder_states;
 mod database;
 mod light_client;
 mod metrics;
+mod op_pool;
 mod produce_block;
+mod proofs;
 mod proposer_duties;
 mod publish_attestations;
 mod publish_blocks;
@@ -50,7 +52,10 @@ use eth2::types::{
     ValidatorStatus, ValidatorsRequestBody,
 };
 use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use lighthouse_network::{
+    rpc::config::RateLimitsFile, types::SyncState, EnrExt, NetworkGlobals, PeerId,
+    PeerIdSerialized, PubsubMessage,
+};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
@@ -3594,7 +3599,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp_utils::json::json())
-        .and(network_tx_filter)
+        .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             |not_synced_filter: Result<(), Rejection>,
@@ -4185,7 +4190,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("connected"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(network_globals)
+        .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
@@ -4203,6 +4208,136 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/peers/{peer_id}/stats
+    let get_lighthouse_peer_stats = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("stats"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |requested_peer_id: String,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peer_id = requested_peer_id.parse::<PeerId>().map_err(|e| {
+                        warp_utils::reject::custom_bad_request(format!("invalid peer id: {}", e))
+                    })?;
+                    network_globals
+                        .peers
+                        .read()
+                        .peer_info(&peer_id)
+                        .map(|peer_info| {
+                            api_types::GenericResponse::from(peer_info.traffic().clone())
+                        })
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "unknown peer: {}",
+                                peer_id
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/peers/allowlist
+    let get_lighthouse_peers_allowlist = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("allowlist"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peers = network_globals.peer_allowlist().ok_or_else(|| {
+                        warp_utils::reject::custom_bad_request(
+                            "private network mode is not enabled".to_string(),
+                        )
+                    })?;
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::PeerAllowlist {
+                            peers: peers.iter().map(ToString::to_string).collect(),
+                        },
+                    ))
+                })
+            },
+        );
+
+    // POST lighthouse/peers/allowlist
+    let post_lighthouse_peers_allowlist = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("allowlist"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(network_globals)
+        .then(
+            |update: eth2::lighthouse::PeerAllowlistUpdate,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let parse = |peers: &[String]| {
+                        peers
+                            .iter()
+                            .map(|peer| {
+                                PeerIdSerialized::from_peer_id_or_enr(peer)
+                                    .map(PeerId::from)
+                                    .map_err(warp_utils::reject::custom_bad_request)
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    };
+                    let add = parse(&update.add)?;
+                    let remove = parse(&update.remove)?;
+                    let peers = network_globals
+                        .update_peer_allowlist(add, remove)
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::PeerAllowlist {
+                            peers: peers.iter().map(ToString::to_string).collect(),
+                        },
+                    ))
+                })
+            },
+        );
+
+    // POST lighthouse/rate_limits/reload
+    let post_lighthouse_rate_limits_reload = warp::path("lighthouse")
+        .and(warp::path("rate_limits"))
+        .and(warp::path("reload"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .and(network_tx_filter)
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let path = network_globals
+                        .config
+                        .rate_limits_file
+                        .as_ref()
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_bad_request(
+                                "no rate limits file is configured".to_string(),
+                            )
+                        })?;
+                    let rate_limits = RateLimitsFile::load(path)
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::UpdateRateLimits {
+                            rate_limits: rate_limits.clone(),
+                        },
+                    )?;
+                    Ok(api_types::GenericResponse::from(rate_limits))
+                })
+            },
+        );
+
     // GET lighthouse/proto_array
     let get_lighthouse_proto_array = warp::path("lighthouse")
         .and(warp::path("proto_array"))
@@ -4225,6 +4360,27 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_choice/reorg_decisions
+    let get_lighthouse_fork_choice_reorg_decisions = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path("reorg_decisions"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let decisions = chain
+                        .re_org_decisions
+                        .lock()
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    Ok(api_types::GenericResponse::from(decisions))
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4362,6 +4518,117 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    let op_pool_path = warp::path("lighthouse").and(warp::path("op_pool"));
+
+    // GET lighthouse/op_pool/attestations
+    let get_lighthouse_op_pool_attestations = op_pool_path
+        .clone()
+        .and(warp::path("attestations"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::attestations(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/attester_slashings
+    let get_lighthouse_op_pool_attester_slashings = op_pool_path
+        .clone()
+        .and(warp::path("attester_slashings"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::attester_slashings(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/proposer_slashings
+    let get_lighthouse_op_pool_proposer_slashings = op_pool_path
+        .clone()
+        .and(warp::path("proposer_slashings"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::proposer_slashings(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/voluntary_exits
+    let get_lighthouse_op_pool_voluntary_exits = op_pool_path
+        .clone()
+        .and(warp::path("voluntary_exits"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::voluntary_exits(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/bls_to_execution_changes
+    let get_lighthouse_op_pool_bls_to_execution_changes = op_pool_path
+        .clone()
+        .and(warp::path("bls_to_execution_changes"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::bls_to_execution_changes(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/overrides
+    let get_lighthouse_op_pool_overrides = op_pool_path
+        .clone()
+        .and(warp::path("overrides"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(op_pool::get_overrides(
+                        chain,
+                    )))
+                })
+            },
+        );
+
+    // POST lighthouse/op_pool/overrides
+    let post_lighthouse_op_pool_overrides = op_pool_path
+        .and(warp::path("overrides"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |overrides: eth2::lighthouse::OpPoolOverrides,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::set_overrides(chain, overrides).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     let database_path = warp::path("lighthouse").and(warp::path("database"));
 
     // GET lighthouse/database/info
@@ -4479,6 +4746,48 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/proofs/execution_payload/{block_id}
+    let get_lighthouse_proofs_execution_payload = warp::path("lighthouse")
+        .and(warp::path("proofs"))
+        .and(warp::path("execution_payload"))
+        .and(block_id_or_err)
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |block_id: BlockId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    proofs::execution_payload_proof(block_id, &chain)
+                })
+            },
+        );
+
+    // GET lighthouse/proofs/state/{state_id}?path
+    let get_lighthouse_proofs_state = warp::path("lighthouse")
+        .and(warp::path("proofs"))
+        .and(warp::path("state"))
+        .and(warp::path::param::<StateId>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid state ID".to_string(),
+            ))
+        }))
+        .and(warp::query::<eth2::lighthouse::StateProofQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |state_id: StateId,
+             query,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    proofs::state_proof(state_id, query, &chain)
+                })
+            },
+        );
+
     let get_events = eth_v1
         .and(warp::path("events"))
         .and(warp::path::end())
@@ -4683,7 +4992,10 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_peers_allowlist)
+                .uor(get_lighthouse_peer_stats)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_fork_choice_reorg_decisions)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
@@ -4691,8 +5003,16 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_op_pool_attestations)
+                .uor(get_lighthouse_op_pool_attester_slashings)
+                .uor(get_lighthouse_op_pool_proposer_slashings)
+                .uor(get_lighthouse_op_pool_voluntary_exits)
+                .uor(get_lighthouse_op_pool_bls_to_execution_changes)
+                .uor(get_lighthouse_op_pool_overrides)
                 .uor(get_lighthouse_block_rewards)
                 .uor(get_lighthouse_attestation_performance)
+                .uor(get_lighthouse_proofs_execution_payload)
+                .uor(get_lighthouse_proofs_state)
                 .uor(
                     enable(ctx.config.enable_light_client_server)
                         .and(get_beacon_light_client_optimistic_update),
@@ -4752,6 +5072,9 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_peers_allowlist)
+                    .uor(post_lighthouse_op_pool_overrides)
+                    .uor(post_lighthouse_rate_limits_reload)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
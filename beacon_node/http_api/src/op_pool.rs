@@ -0,0 +1,187 @@
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{OpPoolInclusionStatus, OpPoolOperation, OpPoolOverrides};
+use operation_pool::{CompactAttestationRef, OperationOverrides};
+use state_processing::epoch_cache::initialize_epoch_cache;
+use state_processing::state_advance::complete_state_advance;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    Attestation, AttesterSlashing, BeaconState, ProposerSlashing, SignedBlsToExecutionChange,
+    SignedVoluntaryExit,
+};
+use warp_utils::reject::{beacon_chain_error, beacon_state_error, custom_bad_request};
+
+/// Load the state that the next block would be packed against: the head state advanced to the
+/// next slot, or the current slot if the head is skipping.
+fn proposal_state<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<BeaconState<T::EthSpec>, warp::Rejection> {
+    let head = chain.canonical_head.cached_head();
+    let current_slot = chain.slot().map_err(beacon_chain_error)?;
+    let slot = std::cmp::max(current_slot, head.head_slot() + 1);
+
+    let (state_root, mut state) = chain
+        .store
+        .get_advanced_hot_state(head.head_block_root(), slot, head.head_state_root())
+        .map_err(|e| beacon_chain_error(BeaconChainError::DBError(e)))?
+        .ok_or_else(|| {
+            beacon_chain_error(BeaconChainError::MissingBeaconState(head.head_state_root()))
+        })?;
+    if state.slot() < slot {
+        complete_state_advance(&mut state, Some(state_root), slot, &chain.spec)
+            .map_err(|e| beacon_chain_error(e.into()))?;
+    }
+
+    // Epoch cache and total balance cache are required for op pool packing.
+    state
+        .build_total_active_balance_cache(&chain.spec)
+        .map_err(beacon_state_error)?;
+    initialize_epoch_cache(&mut state, &chain.spec).map_err(|e| beacon_chain_error(e.into()))?;
+
+    Ok(state)
+}
+
+/// Label each operation with whether it would be packed into the next block.
+fn with_status<Op: TreeHash + PartialEq>(
+    operations: Vec<(Op, Option<u64>)>,
+    packed: &[Op],
+    overrides: &OperationOverrides,
+) -> Vec<OpPoolOperation<Op>> {
+    operations
+        .into_iter()
+        .map(|(operation, score)| {
+            let root = operation.tree_hash_root();
+            let status = if packed.contains(&operation) {
+                OpPoolInclusionStatus::Included
+            } else if overrides.exclude.contains(&root) {
+                OpPoolInclusionStatus::Excluded
+            } else {
+                OpPoolInclusionStatus::NotIncluded
+            };
+            OpPoolOperation {
+                root,
+                status,
+                forced: overrides.include.contains(&root),
+                score,
+                operation,
+            }
+        })
+        .collect()
+}
+
+fn unscored<Op>(operations: Vec<Op>) -> Vec<(Op, Option<u64>)> {
+    operations.into_iter().map(|op| (op, None)).collect()
+}
+
+pub fn attestations<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OpPoolOperation<Attestation<T::EthSpec>>>, warp::Rejection> {
+    let state = proposal_state(&chain)?;
+    let overrides = chain.op_pool.overrides();
+
+    let mut prev_filter_cache = HashMap::new();
+    let mut curr_filter_cache = HashMap::new();
+    let packed = chain
+        .op_pool
+        .get_attestations(
+            &state,
+            |att: &CompactAttestationRef<T::EthSpec>| {
+                chain.filter_op_pool_attestation(&mut prev_filter_cache, att, &state)
+            },
+            |att: &CompactAttestationRef<T::EthSpec>| {
+                chain.filter_op_pool_attestation(&mut curr_filter_cache, att, &state)
+            },
+            &chain.spec,
+        )
+        .map_err(|e| beacon_chain_error(e.into()))?;
+
+    let mut prev_filter_cache = HashMap::new();
+    let mut curr_filter_cache = HashMap::new();
+    let scored = chain
+        .op_pool
+        .get_attestation_scores(
+            &state,
+            |att: &CompactAttestationRef<T::EthSpec>| {
+                chain.filter_op_pool_attestation(&mut prev_filter_cache, att, &state)
+            },
+            |att: &CompactAttestationRef<T::EthSpec>| {
+                chain.filter_op_pool_attestation(&mut curr_filter_cache, att, &state)
+            },
+            &chain.spec,
+        )
+        .map_err(|e| beacon_chain_error(e.into()))?;
+
+    Ok(with_status(scored, &packed, &overrides))
+}
+
+pub fn attester_slashings<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OpPoolOperation<AttesterSlashing<T::EthSpec>>>, warp::Rejection> {
+    let state = proposal_state(&chain)?;
+    let overrides = chain.op_pool.overrides();
+    let (_, packed, _) = chain.op_pool.get_slashings_and_exits(&state, &chain.spec);
+    let all = unscored(chain.op_pool.get_all_attester_slashings());
+    Ok(with_status(all, &packed, &overrides))
+}
+
+pub fn proposer_slashings<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OpPoolOperation<ProposerSlashing>>, warp::Rejection> {
+    let state = proposal_state(&chain)?;
+    let overrides = chain.op_pool.overrides();
+    let (packed, _, _) = chain.op_pool.get_slashings_and_exits(&state, &chain.spec);
+    let all = unscored(chain.op_pool.get_all_proposer_slashings());
+    Ok(with_status(all, &packed, &overrides))
+}
+
+pub fn voluntary_exits<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OpPoolOperation<SignedVoluntaryExit>>, warp::Rejection> {
+    let state = proposal_state(&chain)?;
+    let overrides = chain.op_pool.overrides();
+    let (_, _, packed) = chain.op_pool.get_slashings_and_exits(&state, &chain.spec);
+    let all = unscored(chain.op_pool.get_all_voluntary_exits());
+    Ok(with_status(all, &packed, &overrides))
+}
+
+pub fn bls_to_execution_changes<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OpPoolOperation<SignedBlsToExecutionChange>>, warp::Rejection> {
+    let state = proposal_state(&chain)?;
+    let overrides = chain.op_pool.overrides();
+    let packed = chain
+        .op_pool
+        .get_bls_to_execution_changes(&state, &chain.spec);
+    let all = unscored(chain.op_pool.get_all_bls_to_execution_changes());
+    Ok(with_status(all, &packed, &overrides))
+}
+
+pub fn get_overrides<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) -> OpPoolOverrides {
+    to_api_overrides(chain.op_pool.overrides())
+}
+
+pub fn set_overrides<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    overrides: OpPoolOverrides,
+) -> Result<OpPoolOverrides, warp::Rejection> {
+    let overrides = OperationOverrides {
+        include: overrides.include.into_iter().collect(),
+        exclude: overrides.exclude.into_iter().collect(),
+    };
+    if let Some(root) = overrides.include.intersection(&overrides.exclude).next() {
+        return Err(custom_bad_request(format!(
+            "operation {root:?} is both included and excluded"
+        )));
+    }
+    chain.op_pool.set_overrides(overrides.clone());
+    Ok(to_api_overrides(overrides))
+}
+
+fn to_api_overrides(overrides: OperationOverrides) -> OpPoolOverrides {
+    let mut include = overrides.include.into_iter().collect::<Vec<_>>();
+    let mut exclude = overrides.exclude.into_iter().collect::<Vec<_>>();
+    include.sort();
+    exclude.sort();
+    OpPoolOverrides { include, exclude }
+}
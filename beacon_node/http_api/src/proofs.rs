@@ -0,0 +1,71 @@
+//! Merkle proofs of execution payloads and beacon state fields.
+use crate::{BlockId, StateId};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ExecutionPayloadProof, StateProof, StateProofQuery};
+use eth2::types::{ExecutionOptimisticFinalizedResponse, GenericResponse};
+use types::{BeaconStateError, ExecPayload};
+use warp_utils::reject::{beacon_state_error, custom_bad_request, custom_server_error};
+
+/// Prove the execution block hash of the block identified by `block_id` against its root.
+pub fn execution_payload_proof<T: BeaconChainTypes>(
+    block_id: BlockId,
+    chain: &BeaconChain<T>,
+) -> Result<ExecutionOptimisticFinalizedResponse<ExecutionPayloadProof>, warp::Rejection> {
+    let (block, execution_optimistic, finalized) = block_id.blinded_block(chain)?;
+    let block_root = block.canonical_root();
+    let payload = block.message().execution_payload().map_err(|_| {
+        custom_bad_request(format!("block {block_root:?} has no execution payload"))
+    })?;
+    let proof = block
+        .message()
+        .compute_execution_block_hash_proof()
+        .map_err(beacon_state_error)?;
+
+    // Guard against serving an invalid proof if the block and proof code ever disagree.
+    if !proof.verify(block_root) {
+        return Err(custom_server_error(
+            "computed an invalid execution payload proof".to_string(),
+        ));
+    }
+
+    Ok(GenericResponse::from(ExecutionPayloadProof {
+        slot: block.slot(),
+        block_root,
+        execution_block_hash: payload.block_hash(),
+        execution_block_number: payload.block_number(),
+        proof,
+    })
+    .add_execution_optimistic_finalized(execution_optimistic, finalized))
+}
+
+/// Prove the node at `query.path` in the state identified by `state_id` against its root.
+pub fn state_proof<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: StateProofQuery,
+    chain: &BeaconChain<T>,
+) -> Result<ExecutionOptimisticFinalizedResponse<StateProof>, warp::Rejection> {
+    let (mut state, execution_optimistic, finalized) = state_id.state(chain)?;
+    let state_root = state.update_tree_hash_cache().map_err(beacon_state_error)?;
+    let proof = state
+        .compute_merkle_proof_for_path(&query.path)
+        .map_err(|e| match e {
+            BeaconStateError::InvalidMerkleProofPath(elem) => {
+                custom_bad_request(format!("invalid path {:?} at {elem:?}", query.path))
+            }
+            e => beacon_state_error(e),
+        })?;
+
+    if !proof.verify(state_root) {
+        return Err(custom_server_error(format!(
+            "computed an invalid proof for {:?}",
+            query.path
+        )));
+    }
+
+    Ok(GenericResponse::from(StateProof {
+        state_root,
+        path: query.path,
+        proof,
+    })
+    .add_execution_optimistic_finalized(execution_optimistic, finalized))
+}
@@ -24,6 +24,7 @@ impl Priority {
         };
         WorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work,
         }
     }
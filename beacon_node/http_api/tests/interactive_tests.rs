@@ -649,6 +649,24 @@ pub async fn proposer_boost_re_org_test(
         assert_eq!(block_c.parent_root(), block_b_root);
     }
 
+    // The decision should be available from the HTTP API.
+    let re_org_decisions = tester
+        .client
+        .get_lighthouse_fork_choice_reorg_decisions()
+        .await
+        .unwrap()
+        .data;
+    let re_org_decision = re_org_decisions
+        .iter()
+        .rfind(|decision| decision.slot == slot_c)
+        .expect("should record re-org decision for block C");
+    assert_eq!(re_org_decision.head_block_root, block_b_root);
+    assert_eq!(
+        re_org_decision.re_org, should_re_org,
+        "{}",
+        re_org_decision.reason
+    );
+
     // Applying block C should cause it to become head regardless (re-org or continuation).
     let block_root_c = Hash256::from(
         harness
@@ -3,6 +3,7 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
+use eth2::lighthouse::{OpPoolInclusionStatus, OpPoolOverrides};
 use eth2::{
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
@@ -5527,6 +5528,210 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_rate_limits_reload_unconfigured(self) -> Self {
+        let err = self
+            .client
+            .post_lighthouse_rate_limits_reload()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_peer_stats(self) -> Self {
+        let stats = self
+            .client
+            .get_lighthouse_peer_stats(&self.external_peer_id.to_string())
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(stats.first_seen_blocks, 0);
+
+        let err = self
+            .client
+            .get_lighthouse_peer_stats(&PeerId::random().to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        let err = self
+            .client
+            .get_lighthouse_peer_stats("not_a_peer_id")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_lighthouse_peers_allowlist_disabled(self) -> Self {
+        let err = self
+            .client
+            .get_lighthouse_peers_allowlist()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        let update = eth2::lighthouse::PeerAllowlistUpdate {
+            add: vec![PeerId::random().to_string()],
+            remove: vec![],
+        };
+        let err = self
+            .client
+            .post_lighthouse_peers_allowlist(&update)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_lighthouse_op_pool(mut self) -> Self {
+        self.client
+            .post_beacon_pool_proposer_slashings(&self.proposer_slashing)
+            .await
+            .unwrap();
+        self.network_rx.network_recv.recv().await.unwrap();
+
+        let root = self.proposer_slashing.tree_hash_root();
+        let slashings = self
+            .client
+            .get_lighthouse_op_pool_proposer_slashings()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(slashings.len(), 1);
+        assert_eq!(slashings[0].root, root);
+        assert_eq!(slashings[0].status, OpPoolInclusionStatus::Included);
+        assert!(!slashings[0].forced);
+
+        let overrides = OpPoolOverrides {
+            include: vec![],
+            exclude: vec![root],
+        };
+        let response = self
+            .client
+            .post_lighthouse_op_pool_overrides(&overrides)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(response, overrides);
+        assert_eq!(
+            self.client
+                .get_lighthouse_op_pool_overrides()
+                .await
+                .unwrap()
+                .data,
+            overrides
+        );
+
+        let slashings = self
+            .client
+            .get_lighthouse_op_pool_proposer_slashings()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(slashings[0].status, OpPoolInclusionStatus::Excluded);
+
+        // A root can't be both included and excluded.
+        let conflicting = OpPoolOverrides {
+            include: vec![root],
+            exclude: vec![root],
+        };
+        let err = self
+            .client
+            .post_lighthouse_op_pool_overrides(&conflicting)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self.client
+            .post_lighthouse_op_pool_overrides(&OpPoolOverrides::default())
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proofs_execution_payload(self) -> Self {
+        for block_id in self.interesting_block_ids() {
+            let block_opt = block_id
+                .blinded_block(&self.chain)
+                .ok()
+                .map(|(block, _execution_optimistic, _finalized)| block);
+            let result = self
+                .client
+                .get_lighthouse_proofs_execution_payload(block_id.0)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+
+            if result.is_none() && block_opt.is_none() {
+                continue;
+            }
+
+            let block = block_opt.expect("result should be none");
+            let result = result.unwrap();
+            let payload = block.message().execution_payload().unwrap();
+
+            assert_eq!(result.slot, block.slot());
+            assert_eq!(result.block_root, block.canonical_root());
+            assert_eq!(result.execution_block_hash, payload.block_hash());
+            assert_eq!(result.execution_block_number, payload.block_number());
+            assert_eq!(result.proof.leaf, payload.block_hash().tree_hash_root());
+            assert!(result.proof.verify(result.block_root));
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proofs_state(self) -> Self {
+        let paths = [
+            "balances/1",
+            "validators/2/effective_balance",
+            "latest_execution_payload_header/block_hash",
+            "finalized_checkpoint/root",
+        ];
+        for state_id in self.interesting_state_ids() {
+            let state_opt = state_id
+                .state(&self.chain)
+                .ok()
+                .map(|(state, _execution_optimistic, _finalized)| state);
+
+            for path in paths {
+                let result = self
+                    .client
+                    .get_lighthouse_proofs_state(state_id.0, path)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+
+                if result.is_none() && state_opt.is_none() {
+                    continue;
+                }
+
+                let mut state = state_opt.clone().expect("result should be none");
+                let result = result.unwrap();
+
+                assert_eq!(result.state_root, state.update_tree_hash_cache().unwrap());
+                assert_eq!(result.path, path);
+                assert!(result.proof.verify(result.state_root));
+            }
+        }
+
+        for path in ["", "unknown", "balances/foo", "validators/1000000"] {
+            let err = self
+                .client
+                .get_lighthouse_proofs_state(CoreStateId::Head, path)
+                .await
+                .unwrap_err();
+            assert_eq!(err.status().unwrap(), 400);
+        }
+
+        self
+    }
+
     pub async fn test_get_lighthouse_database_info(self) -> Self {
         let info = self.client.get_lighthouse_database_info().await.unwrap();
 
@@ -7001,6 +7206,14 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_staking()
         .await
+        .test_lighthouse_peers_allowlist_disabled()
+        .await
+        .test_get_lighthouse_peer_stats()
+        .await
+        .test_post_lighthouse_rate_limits_reload_unconfigured()
+        .await
+        .test_lighthouse_op_pool()
+        .await
         .test_get_lighthouse_database_info()
         .await
         .test_post_lighthouse_database_reconstruct()
@@ -7009,6 +7222,16 @@ async fn lighthouse_endpoints() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_proofs() {
+    ApiTester::new_with_hard_forks(true, true)
+        .await
+        .test_get_lighthouse_proofs_execution_payload()
+        .await
+        .test_get_lighthouse_proofs_state()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn optimistic_responses() {
     ApiTester::new_with_hard_forks(true, true)
@@ -62,12 +62,13 @@ use super::rpc_proto::proto;
 use super::subscription_filter::{AllowAllSubscriptionFilter, TopicSubscriptionFilter};
 use super::time_cache::DuplicateCache;
 use super::topic::{Hasher, Topic, TopicHash};
+use super::trace::{TraceEvent, TraceSink};
 use super::transform::{DataTransform, IdentityTransform};
 use super::types::{
     ControlAction, FailedMessages, Message, MessageAcceptance, MessageId, PeerInfo, RawMessage,
-    Subscription, SubscriptionAction,
+    Subscription, SubscriptionAction, TopicTraffic,
 };
-use super::types::{Graft, IHave, IWant, PeerConnections, PeerKind, Prune};
+use super::types::{Graft, IHave, IWant, PeerConnections, PeerKind, Prune, Rpc};
 use super::{backoff::BackoffStorage, types::RpcSender};
 use super::{
     config::{Config, ValidationMode},
@@ -281,6 +282,17 @@ pub struct Behaviour<D = IdentityTransform, F = AllowAllSubscriptionFilter> {
     /// forward messages to, outside of the scoring system.
     explicit_peers: HashSet<PeerId>,
 
+    /// A set of peers that are exempt from score based filtering and to which forwarded messages
+    /// are sent through the priority queue. Used to relay gossip to private nodes behind a sentry.
+    priority_peers: HashSet<PeerId>,
+
+    /// A set of peers which may only use the topics accepted by `restricted_topic_filter`. Any
+    /// subscriptions, messages and control messages for other topics they send are dropped.
+    restricted_peers: HashSet<PeerId>,
+
+    /// The topics restricted peers may use. If unset, restricted peers may not use any topic.
+    restricted_topic_filter: Option<Box<dyn Fn(&TopicHash) -> bool + Send>>,
+
     /// A list of peers that have been blacklisted by the user.
     /// Messages are not sent to and are rejected from these peers.
     blacklisted_peers: HashSet<PeerId>,
@@ -320,6 +332,9 @@ pub struct Behaviour<D = IdentityTransform, F = AllowAllSubscriptionFilter> {
     /// Stores optional peer score data together with thresholds and decay interval.
     peer_score: Option<(PeerScore, PeerScoreThresholds, Ticker)>,
 
+    /// An optional sink recording message propagation events.
+    trace_sink: Option<Box<dyn TraceSink>>,
+
     /// Counts the number of `IHAVE` received from each peer since the last heartbeat.
     count_received_ihave: HashMap<PeerId, usize>,
 
@@ -456,6 +471,9 @@ where
             publish_config: privacy.into(),
             duplicate_cache: DuplicateCache::new(config.duplicate_cache_time()),
             explicit_peers: HashSet::new(),
+            priority_peers: HashSet::new(),
+            restricted_peers: HashSet::new(),
+            restricted_topic_filter: None,
             blacklisted_peers: HashSet::new(),
             mesh: HashMap::new(),
             fanout: HashMap::new(),
@@ -474,6 +492,7 @@ where
             px_peers: HashSet::new(),
             outbound_peers: HashSet::new(),
             peer_score: None,
+            trace_sink: None,
             count_received_ihave: HashMap::new(),
             count_sent_iwant: HashMap::new(),
             connected_peers: HashMap::new(),
@@ -529,6 +548,19 @@ where
             .map(|(score, ..)| score.score(peer_id))
     }
 
+    /// Returns the message bytes received from and sent to each connected peer on each topic
+    /// since the last call, resetting the counts.
+    pub fn take_peer_traffic(&mut self) -> Vec<(PeerId, TopicHash, TopicTraffic)> {
+        self.connected_peers
+            .iter_mut()
+            .flat_map(|(peer_id, peer)| {
+                peer.traffic
+                    .drain()
+                    .map(move |(topic, traffic)| (*peer_id, topic, traffic))
+            })
+            .collect()
+    }
+
     /// Subscribe to a topic.
     ///
     /// Returns [`Ok(true)`] if the subscription worked. Returns [`Ok(false)`] if we were already
@@ -751,7 +783,13 @@ where
                     self.config.publish_queue_duration(),
                     self.metrics.as_mut(),
                 ) {
-                    Ok(_) => publish_failed = false,
+                    Ok(_) => {
+                        publish_failed = false;
+                        peer.traffic
+                            .entry(topic_hash.clone())
+                            .or_default()
+                            .bytes_sent += raw_message.raw_protobuf_len() as u64;
+                    }
                     Err(_) => {
                         self.failed_messages.entry(*peer_id).or_default().priority += 1;
 
@@ -787,6 +825,11 @@ where
             metrics.register_published_message(&topic_hash);
         }
 
+        self.trace(|| TraceEvent::PublishMessage {
+            message_id: msg_id.clone(),
+            topic: topic_hash,
+        });
+
         Ok(msg_id)
     }
 
@@ -854,6 +897,13 @@ where
                 metrics.register_msg_validation(&raw_message.topic, &acceptance);
             }
 
+            self.trace(|| TraceEvent::RejectMessage {
+                message_id: Some(msg_id.clone()),
+                topic: raw_message.topic.clone(),
+                received_from: *propagation_source,
+                reason: reject_reason.to_string(),
+            });
+
             // Tell peer_score about reject
             // Reject the original source, and any duplicates we've seen from other peers.
             if let Some((peer_score, ..)) = &mut self.peer_score {
@@ -897,6 +947,65 @@ where
         self.explicit_peers.remove(peer_id);
     }
 
+    /// Adds a peer to the set of priority peers. Priority peers are never filtered by their score
+    /// and messages forwarded to them use the priority queue. Note that this does not make the
+    /// peer explicit.
+    pub fn add_priority_peer(&mut self, peer_id: &PeerId) {
+        tracing::debug!(peer=%peer_id, "Adding priority peer");
+        self.priority_peers.insert(*peer_id);
+    }
+
+    /// Removes a peer from the set of priority peers.
+    pub fn remove_priority_peer(&mut self, peer_id: &PeerId) {
+        tracing::debug!(peer=%peer_id, "Removing priority peer");
+        self.priority_peers.remove(peer_id);
+    }
+
+    /// Sets the topics that restricted peers may use.
+    pub fn set_restricted_topic_filter(
+        &mut self,
+        filter: impl Fn(&TopicHash) -> bool + Send + 'static,
+    ) {
+        self.restricted_topic_filter = Some(Box::new(filter));
+    }
+
+    /// Restricts a peer to the topics accepted by the restricted topic filter, for as long as it
+    /// remains connected.
+    pub fn restrict_peer(&mut self, peer_id: &PeerId) {
+        tracing::debug!(peer=%peer_id, "Restricting peer topics");
+        self.restricted_peers.insert(*peer_id);
+    }
+
+    /// Drops everything in an RPC from a restricted peer which refers to a topic the peer may not
+    /// use.
+    ///
+    /// IWANTs only carry message ids, so the topic of each id is looked up in the message cache.
+    /// Ids which aren't cached are dropped too, they wouldn't be served anyway.
+    fn filter_restricted_rpc(&self, rpc: &mut Rpc) {
+        let allowed = |topic: &TopicHash| {
+            self.restricted_topic_filter
+                .as_ref()
+                .is_some_and(|filter| filter(topic))
+        };
+        rpc.subscriptions
+            .retain(|subscription| allowed(&subscription.topic_hash));
+        rpc.messages.retain(|message| allowed(&message.topic));
+        rpc.control_msgs.retain_mut(|control| match control {
+            ControlAction::IHave(IHave { topic_hash, .. })
+            | ControlAction::Graft(Graft { topic_hash })
+            | ControlAction::Prune(Prune { topic_hash, .. }) => allowed(topic_hash),
+            ControlAction::IWant(IWant { message_ids }) => {
+                message_ids.retain(|id| {
+                    self.mcache
+                        .get(id)
+                        .is_some_and(|message| allowed(&message.topic))
+                });
+                !message_ids.is_empty()
+            }
+            ControlAction::IDontWant(_) => true,
+        });
+    }
+
     /// Blacklists a peer. All messages from this peer will be rejected and any message that was
     /// created by this peer will be rejected.
     pub fn blacklist_peer(&mut self, peer_id: &PeerId) {
@@ -944,6 +1053,19 @@ where
         Ok(())
     }
 
+    /// Records message propagation events to the given sink. Replaces any previously set sink.
+    pub fn with_trace_sink(&mut self, sink: Box<dyn TraceSink>) {
+        self.trace_sink = Some(sink);
+    }
+
+    /// Records an event with the trace sink, if one is set. The event is only constructed if it
+    /// will be recorded.
+    fn trace(&mut self, event: impl FnOnce() -> TraceEvent) {
+        if let Some(sink) = self.trace_sink.as_mut() {
+            sink.trace(SystemTime::now(), event());
+        }
+    }
+
     /// Sets scoring parameters for a topic.
     ///
     /// The [`Self::with_peer_score()`] must first be called to initialise peer scoring.
@@ -1204,6 +1326,10 @@ where
         peer_id: &PeerId,
         threshold: impl Fn(&PeerScoreThresholds) -> f64,
     ) -> (bool, f64) {
+        // Priority peers are never filtered by their score.
+        if self.priority_peers.contains(peer_id) {
+            return (false, 0.0);
+        }
         Self::score_below_threshold_from_scores(&self.peer_score, peer_id, threshold)
     }
 
@@ -1226,6 +1352,14 @@ where
     /// Handles an IHAVE control message. Checks our cache of messages. If the message is unknown,
     /// requests it with an IWANT control message.
     fn handle_ihave(&mut self, peer_id: &PeerId, ihave_msgs: Vec<(TopicHash, Vec<MessageId>)>) {
+        for (topic, message_ids) in &ihave_msgs {
+            self.trace(|| TraceEvent::RecvIHave {
+                peer_id: *peer_id,
+                topic: topic.clone(),
+                message_ids: message_ids.clone(),
+            });
+        }
+
         // We ignore IHAVE gossip from any peer whose score is below the gossip threshold
         if let (true, score) = self.score_below_threshold(peer_id, |pst| pst.gossip_threshold) {
             tracing::debug!(
@@ -1322,6 +1456,11 @@ where
                 Instant::now() + self.config.iwant_followup_time(),
             );
 
+            self.trace(|| TraceEvent::SendIWant {
+                peer_id: *peer_id,
+                message_ids: iwant_ids_vec.clone(),
+            });
+
             if let Some(peer) = &mut self.connected_peers.get_mut(peer_id) {
                 tracing::trace!(
                     peer=%peer_id,
@@ -1358,6 +1497,11 @@ where
     /// Handles an IWANT control message. Checks our cache of messages. If the message exists it is
     /// forwarded to the requesting peer.
     fn handle_iwant(&mut self, peer_id: &PeerId, iwant_msgs: Vec<MessageId>) {
+        self.trace(|| TraceEvent::RecvIWant {
+            peer_id: *peer_id,
+            message_ids: iwant_msgs.clone(),
+        });
+
         // We ignore IWANT gossip from any peer whose score is below the gossip threshold
         if let (true, score) = self.score_below_threshold(peer_id, |pst| pst.gossip_threshold) {
             tracing::debug!(
@@ -1798,6 +1942,12 @@ where
         if let Some(metrics) = self.metrics.as_mut() {
             metrics.msg_recvd_unfiltered(&raw_message.topic, raw_message.raw_protobuf_len());
         }
+        if let Some(peer) = self.connected_peers.get_mut(propagation_source) {
+            peer.traffic
+                .entry(raw_message.topic.clone())
+                .or_default()
+                .bytes_received += raw_message.raw_protobuf_len() as u64;
+        }
 
         // Try and perform the data transform to the message. If it fails, consider it invalid.
         let message = match self.data_transform.inbound_transform(raw_message.clone()) {
@@ -1835,6 +1985,11 @@ where
 
         if !self.duplicate_cache.insert(msg_id.clone()) {
             tracing::debug!(message=%msg_id, "Message already received, ignoring");
+            self.trace(|| TraceEvent::DuplicateMessage {
+                message_id: msg_id.clone(),
+                topic: message.topic.clone(),
+                received_from: *propagation_source,
+            });
             if let Some((peer_score, ..)) = &mut self.peer_score {
                 peer_score.duplicated_message(propagation_source, &msg_id, &message.topic);
             }
@@ -1871,6 +2026,11 @@ where
         // Dispatch the message to the user if we are subscribed to any of the topics
         if self.mesh.contains_key(&message.topic) {
             tracing::debug!("Sending received message to user");
+            self.trace(|| TraceEvent::DeliverMessage {
+                message_id: msg_id.clone(),
+                topic: message.topic.clone(),
+                received_from: *propagation_source,
+            });
             self.events
                 .push_back(ToSwarm::GenerateEvent(Event::Message {
                     propagation_source: *propagation_source,
@@ -1909,6 +2069,20 @@ where
         raw_message: &RawMessage,
         reject_reason: RejectReason,
     ) {
+        if self.trace_sink.is_some() {
+            let message_id = self
+                .data_transform
+                .inbound_transform(raw_message.clone())
+                .ok()
+                .map(|message| self.config.message_id(&message));
+            self.trace(|| TraceEvent::RejectMessage {
+                message_id,
+                topic: raw_message.topic.clone(),
+                received_from: *propagation_source,
+                reason: reject_reason.to_string(),
+            });
+        }
+
         if let Some((peer_score, ..)) = &mut self.peer_score {
             if let Some(metrics) = self.metrics.as_mut() {
                 metrics.register_invalid_message(&raw_message.topic);
@@ -2538,6 +2712,10 @@ where
             }
         }
 
+        if let Some(sink) = self.trace_sink.as_mut() {
+            sink.flush();
+        }
+
         tracing::debug!("Completed Heartbeat");
         if let Some(metrics) = self.metrics.as_mut() {
             let duration = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
@@ -2595,6 +2773,17 @@ where
                     peer_message_ids.truncate(self.config.max_ihave_length());
                 }
 
+                if let Some(sink) = self.trace_sink.as_mut() {
+                    sink.trace(
+                        SystemTime::now(),
+                        TraceEvent::SendIHave {
+                            peer_id,
+                            topic: topic_hash.clone(),
+                            message_ids: peer_message_ids.clone(),
+                        },
+                    );
+                }
+
                 // send an IHAVE message
                 if let Some(peer) = &mut self.connected_peers.get_mut(&peer_id) {
                     if peer
@@ -2841,24 +3030,36 @@ where
                     }
 
                     tracing::debug!(%peer_id, message=%msg_id, "Sending message to peer");
-                    if peer
-                        .sender
-                        .forward(
+                    // Messages to priority peers skip the bounded forward queue.
+                    let is_priority = self.priority_peers.contains(peer_id);
+                    let sent = if is_priority {
+                        peer.sender.publish(
+                            message.clone(),
+                            self.config.publish_queue_duration(),
+                            self.metrics.as_mut(),
+                        )
+                    } else {
+                        peer.sender.forward(
                             message.clone(),
                             self.config.forward_queue_duration(),
                             self.metrics.as_mut(),
                         )
-                        .is_err()
-                    {
+                    };
+                    if sent.is_ok() {
+                        peer.traffic.entry(topic.clone()).or_default().bytes_sent +=
+                            message.raw_protobuf_len() as u64;
+                    } else {
                         // Downscore the peer
                         if let Some((peer_score, ..)) = &mut self.peer_score {
                             peer_score.failed_message_slow_peer(peer_id);
                         }
                         // Increment the failed message count
-                        self.failed_messages
-                            .entry(*peer_id)
-                            .or_default()
-                            .non_priority += 1;
+                        let failed = self.failed_messages.entry(*peer_id).or_default();
+                        if is_priority {
+                            failed.priority += 1;
+                        } else {
+                            failed.non_priority += 1;
+                        }
                     }
                 } else {
                     tracing::error!(peer = %peer_id,
@@ -3103,9 +3304,10 @@ where
                     .map(|peers| peers.remove(&peer_id));
             }
 
-            // Forget px and outbound status for this peer
+            // Forget px, outbound status and topic restrictions for this peer
             self.px_peers.remove(&peer_id);
             self.outbound_peers.remove(&peer_id);
+            self.restricted_peers.remove(&peer_id);
 
             // If metrics are enabled, register the disconnection of a peer based on its protocol.
             if let Some(metrics) = self.metrics.as_mut() {
@@ -3191,6 +3393,7 @@ where
                 topics: Default::default(),
                 dont_send_received: LinkedHashMap::new(),
                 dont_send_sent: LinkedHashMap::new(),
+                traffic: HashMap::new(),
             });
         // Add the new connection
         connected_peer.connections.push(connection_id);
@@ -3224,6 +3427,7 @@ where
                 topics: Default::default(),
                 dont_send_received: LinkedHashMap::new(),
                 dont_send_sent: LinkedHashMap::new(),
+                traffic: HashMap::new(),
             });
         // Add the new connection
         connected_peer.connections.push(connection_id);
@@ -3308,11 +3512,16 @@ where
                 }
             }
             HandlerEvent::Message {
-                rpc,
+                mut rpc,
                 invalid_messages,
             } => {
                 // Handle the gossipsub RPC
 
+                // Restricted peers may only use a subset of topics
+                if self.restricted_peers.contains(&propagation_source) {
+                    self.filter_restricted_rpc(&mut rpc);
+                }
+
                 // Handle subscriptions
                 // Update connected peers topics
                 if !rpc.subscriptions.is_empty() {
@@ -240,6 +240,7 @@ where
             topics: Default::default(),
             dont_send_received: LinkedHashMap::new(),
             dont_send_sent: LinkedHashMap::new(),
+            traffic: HashMap::new(),
             sender,
         },
     );
@@ -629,6 +630,7 @@ fn test_join() {
                 topics: Default::default(),
                 dont_send_received: LinkedHashMap::new(),
                 dont_send_sent: LinkedHashMap::new(),
+                traffic: HashMap::new(),
                 sender,
             },
         );
@@ -677,6 +679,57 @@ fn test_join() {
     );
 }
 
+/// Test that message bytes are accounted per peer and topic.
+#[test]
+fn test_peer_traffic_accounting() {
+    let (mut gs, peers, _receivers, topic_hashes) = inject_nodes1()
+        .peer_no(5)
+        .topics(vec![String::from("test_traffic")])
+        .to_subscribe(true)
+        .create_network();
+
+    // publish on topic
+    gs.publish(topic_hashes[0].clone(), vec![0; 42]).unwrap();
+
+    let traffic = gs.take_peer_traffic();
+    assert_eq!(
+        traffic.len(),
+        peers.len(),
+        "Every peer should be sent the message"
+    );
+    let sent_len = traffic[0].2.bytes_sent;
+    assert!(sent_len >= 42);
+    for (peer_id, topic, peer_traffic) in &traffic {
+        assert!(peers.contains(peer_id));
+        assert_eq!(topic, &topic_hashes[0]);
+        assert_eq!(peer_traffic.bytes_sent, sent_len);
+        assert_eq!(peer_traffic.bytes_received, 0);
+    }
+
+    // The counts are reset once taken.
+    assert!(gs.take_peer_traffic().is_empty());
+
+    // receive a message from the first peer
+    let raw_message = RawMessage {
+        source: Some(PeerId::random()),
+        data: vec![1; 10],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    let received_len = raw_message.raw_protobuf_len() as u64;
+    gs.handle_received_message(raw_message, &peers[0]);
+
+    let received = gs
+        .take_peer_traffic()
+        .into_iter()
+        .find(|(peer_id, _, _)| peer_id == &peers[0])
+        .expect("Should account the received message");
+    assert_eq!(received.2.bytes_received, received_len);
+}
+
 /// Test local node publish to subscribed topic
 #[test]
 fn test_publish_without_flood_publishing() {
@@ -1026,6 +1079,7 @@ fn test_get_random_peers() {
                 topics: topics.clone(),
                 sender: RpcSender::new(gs.config.connection_handler_queue_len()),
                 dont_send_sent: LinkedHashMap::new(),
+                traffic: HashMap::new(),
                 dont_send_received: LinkedHashMap::new(),
             },
         );
@@ -1662,6 +1716,142 @@ fn do_forward_messages_to_explicit_peers() {
     );
 }
 
+#[test]
+fn priority_peers_receive_forwards_on_priority_queue() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(2)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .scoring(Some((
+            PeerScoreParams::default(),
+            PeerScoreThresholds::default(),
+        )))
+        .create_network();
+
+    gs.add_priority_peer(&peers[0]);
+
+    // A priority peer is never graylisted, regardless of its score. Three behaviour penalties
+    // take the score below the default graylist threshold.
+    for peer in &peers {
+        gs.peer_score.as_mut().unwrap().0.add_penalty(peer, 3);
+    }
+    assert!(
+        !gs.score_below_threshold(&peers[0], |pst| pst.graylist_threshold)
+            .0
+    );
+    assert!(
+        gs.score_below_threshold(&peers[1], |pst| pst.graylist_threshold)
+            .0
+    );
+
+    let message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    gs.handle_received_message(message.clone(), &PeerId::random());
+
+    let (priority_fwds, non_priority_fwds) =
+        receivers
+            .into_iter()
+            .fold((0, 0), |(mut prio, mut non_prio), (peer_id, c)| {
+                if peer_id != peers[0] {
+                    return (prio, non_prio);
+                }
+                let priority = c.priority.into_inner();
+                while !priority.is_empty() {
+                    if matches!(priority.try_recv(), Ok(RpcOut::Publish { message: m, .. }) if m.data == message.data)
+                    {
+                        prio += 1;
+                    }
+                }
+                let non_priority = c.non_priority.into_inner();
+                while !non_priority.is_empty() {
+                    if matches!(non_priority.try_recv(), Ok(RpcOut::Forward { .. })) {
+                        non_prio += 1;
+                    }
+                }
+                (prio, non_prio)
+            });
+    assert_eq!(
+        priority_fwds, 1,
+        "The message was not forwarded to the priority peer on the priority queue"
+    );
+    assert_eq!(
+        non_priority_fwds, 0,
+        "The message was forwarded to the priority peer on the non-priority queue"
+    );
+}
+
+/// A `TraceSink` collecting events in memory.
+#[derive(Clone, Default)]
+struct CollectingTraceSink(std::sync::Arc<std::sync::Mutex<Vec<TraceEvent>>>);
+
+impl TraceSink for CollectingTraceSink {
+    fn trace(&mut self, _timestamp: SystemTime, event: TraceEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+fn test_trace_sink_records_message_events() {
+    let (mut gs, peers, _receivers, topic_hashes) = inject_nodes1()
+        .peer_no(2)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .create_network();
+
+    let sink = CollectingTraceSink::default();
+    gs.with_trace_sink(Box::new(sink.clone()));
+
+    let message = RawMessage {
+        source: Some(PeerId::random()),
+        data: vec![1, 2, 3],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    let msg_id = gs.config.message_id(
+        &gs.data_transform
+            .inbound_transform(message.clone())
+            .unwrap(),
+    );
+
+    // The first copy is delivered, the second one is a duplicate.
+    gs.handle_received_message(message.clone(), &peers[0]);
+    gs.handle_received_message(message, &peers[1]);
+    gs.handle_iwant(&peers[1], vec![msg_id.clone()]);
+
+    let events = sink.0.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            TraceEvent::DeliverMessage {
+                message_id: msg_id.clone(),
+                topic: topic_hashes[0].clone(),
+                received_from: peers[0],
+            },
+            TraceEvent::DuplicateMessage {
+                message_id: msg_id.clone(),
+                topic: topic_hashes[0].clone(),
+                received_from: peers[1],
+            },
+            TraceEvent::RecvIWant {
+                peer_id: peers[1],
+                message_ids: vec![msg_id],
+            },
+        ]
+    );
+}
+
 #[test]
 fn explicit_peers_not_added_to_mesh_on_subscribe() {
     let (mut gs, peers, receivers, _) = inject_nodes1()
@@ -3109,6 +3299,127 @@ fn test_ignore_rpc_from_peers_below_graylist_threshold() {
     assert!(gs.events.len() > 1);
 }
 
+#[test]
+fn test_restricted_peer_only_uses_allowed_topics() {
+    let (mut gs, _, _, topic_hashes) = inject_nodes1()
+        .topics(vec!["allowed".into(), "denied".into()])
+        .to_subscribe(true)
+        .create_network();
+
+    let allowed_topic = topic_hashes[0].clone();
+    gs.set_restricted_topic_filter(move |topic| *topic == allowed_topic);
+
+    let (peer, _receiver) = add_peer(&mut gs, &[], false, false);
+    gs.restrict_peer(&peer);
+
+    let subscriptions = topic_hashes
+        .iter()
+        .map(|topic_hash| Subscription {
+            action: SubscriptionAction::Subscribe,
+            topic_hash: topic_hash.clone(),
+        })
+        .collect::<Vec<_>>();
+    let denied_message = RawMessage {
+        source: Some(PeerId::random()),
+        data: vec![1, 2, 3],
+        sequence_number: Some(1u64),
+        topic: topic_hashes[1].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+
+    gs.on_connection_handler_event(
+        peer,
+        ConnectionId::new_unchecked(0),
+        HandlerEvent::Message {
+            rpc: Rpc {
+                messages: vec![denied_message],
+                subscriptions,
+                control_msgs: vec![ControlAction::Graft(Graft {
+                    topic_hash: topic_hashes[1].clone(),
+                })],
+            },
+            invalid_messages: Vec::new(),
+        },
+    );
+
+    // Only the subscription to the allowed topic is recorded.
+    assert_eq!(
+        gs.connected_peers[&peer].topics,
+        BTreeSet::from([topic_hashes[0].clone()])
+    );
+    // The message and graft on the denied topic are dropped.
+    assert!(!gs.mesh[&topic_hashes[1]].contains(&peer));
+    assert!(!gs
+        .events
+        .iter()
+        .any(|event| matches!(event, ToSwarm::GenerateEvent(Event::Message { .. }))));
+}
+
+#[test]
+fn test_restricted_peer_only_receives_allowed_iwant_messages() {
+    let (mut gs, _, mut receivers, topic_hashes) = inject_nodes1()
+        .topics(vec!["allowed".into(), "denied".into()])
+        .to_subscribe(true)
+        .create_network();
+
+    let allowed_topic = topic_hashes[0].clone();
+    gs.set_restricted_topic_filter(move |topic| *topic == allowed_topic);
+
+    let (peer, receiver) = add_peer(&mut gs, &[], false, false);
+    receivers.insert(peer, receiver);
+    gs.restrict_peer(&peer);
+
+    // Cache a message on each topic.
+    let msg_ids = topic_hashes
+        .iter()
+        .map(|topic_hash| {
+            let raw_message = RawMessage {
+                source: Some(PeerId::random()),
+                data: vec![1, 2, 3],
+                sequence_number: Some(1u64),
+                topic: topic_hash.clone(),
+                signature: None,
+                key: None,
+                validated: true,
+            };
+            let message = gs
+                .data_transform
+                .inbound_transform(raw_message.clone())
+                .unwrap();
+            let msg_id = gs.config.message_id(&message);
+            gs.mcache.put(&msg_id, raw_message);
+            msg_id
+        })
+        .collect::<Vec<_>>();
+
+    gs.on_connection_handler_event(
+        peer,
+        ConnectionId::new_unchecked(0),
+        HandlerEvent::Message {
+            rpc: Rpc {
+                messages: vec![],
+                subscriptions: vec![],
+                control_msgs: vec![ControlAction::IWant(IWant {
+                    message_ids: msg_ids.clone(),
+                })],
+            },
+            invalid_messages: Vec::new(),
+        },
+    );
+
+    // Only the message on the allowed topic is sent to the peer.
+    let non_priority = receivers.remove(&peer).unwrap().non_priority.into_inner();
+    let mut sent_topics = vec![];
+    while let Ok(rpc) = non_priority.try_recv() {
+        if let RpcOut::Forward { message, .. } = rpc {
+            sent_topics.push(message.topic);
+        }
+    }
+    assert_eq!(sent_topics, vec![topic_hashes[0].clone()]);
+}
+
 #[test]
 fn test_ignore_px_from_peers_below_accept_px_threshold() {
     let config = ConfigBuilder::default().prune_peers(16).build().unwrap();
@@ -935,3 +935,16 @@ pub(crate) enum RejectReason {
     /// The validation failed.
     ValidationFailed,
 }
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RejectReason::ValidationError(e) => write!(f, "validation error: {e}"),
+            RejectReason::SelfOrigin => write!(f, "self originated message"),
+            RejectReason::BlackListedPeer => write!(f, "blacklisted peer"),
+            RejectReason::BlackListedSource => write!(f, "blacklisted source"),
+            RejectReason::ValidationIgnored => write!(f, "validation ignored"),
+            RejectReason::ValidationFailed => write!(f, "validation failed"),
+        }
+    }
+}
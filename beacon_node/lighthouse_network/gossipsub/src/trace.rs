@@ -0,0 +1,92 @@
+// Copyright 2024 Sigma Prime Pty Ltd.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Tracing of the events involved in the propagation of gossipsub messages, modelled on the
+//! libp2p pubsub tracer. Events are handed to a user supplied [`TraceSink`] which is responsible
+//! for persisting them.
+
+use libp2p::identity::PeerId;
+use web_time::SystemTime;
+
+use crate::{MessageId, TopicHash};
+
+/// An event in the life of a gossipsub message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// We published a message.
+    PublishMessage {
+        message_id: MessageId,
+        topic: TopicHash,
+    },
+    /// A message was received for the first time and delivered to the application.
+    DeliverMessage {
+        message_id: MessageId,
+        topic: TopicHash,
+        received_from: PeerId,
+    },
+    /// A message we have already seen was received again.
+    DuplicateMessage {
+        message_id: MessageId,
+        topic: TopicHash,
+        received_from: PeerId,
+    },
+    /// A message was rejected, either during decoding or by the application. The message id is
+    /// unknown if the message could not be decoded.
+    RejectMessage {
+        message_id: Option<MessageId>,
+        topic: TopicHash,
+        received_from: PeerId,
+        reason: String,
+    },
+    /// A peer advertised messages to us via IHAVE.
+    RecvIHave {
+        peer_id: PeerId,
+        topic: TopicHash,
+        message_ids: Vec<MessageId>,
+    },
+    /// We advertised messages to a peer via IHAVE.
+    SendIHave {
+        peer_id: PeerId,
+        topic: TopicHash,
+        message_ids: Vec<MessageId>,
+    },
+    /// A peer requested messages from us via IWANT.
+    RecvIWant {
+        peer_id: PeerId,
+        message_ids: Vec<MessageId>,
+    },
+    /// We requested messages from a peer via IWANT.
+    SendIWant {
+        peer_id: PeerId,
+        message_ids: Vec<MessageId>,
+    },
+}
+
+/// A destination for [`TraceEvent`]s.
+///
+/// The sink is called synchronously from the behaviour, so implementations should avoid blocking
+/// for long periods (i.e. by buffering writes).
+pub trait TraceSink: Send {
+    /// Records an event which happened at `timestamp`.
+    fn trace(&mut self, timestamp: SystemTime, event: TraceEvent);
+
+    /// Flushes any buffered events. Called on every heartbeat.
+    fn flush(&mut self) {}
+}
@@ -30,7 +30,7 @@ use libp2p::identity::PeerId;
 use libp2p::swarm::ConnectionId;
 use prometheus_client::encoding::EncodeLabelValue;
 use quick_protobuf::MessageWrite;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -127,6 +127,15 @@ pub(crate) struct PeerConnections {
     pub(crate) dont_send_received: LinkedHashMap<MessageId, Instant>,
     /// IDONTWANT messages we sent to the peer.
     pub(crate) dont_send_sent: LinkedHashMap<MessageId, Instant>,
+    /// Message bytes exchanged with the peer per topic since they were last taken.
+    pub(crate) traffic: HashMap<TopicHash, TopicTraffic>,
+}
+
+/// The number of message bytes received from and sent to a peer on a topic.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TopicTraffic {
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
 }
 
 /// Describes the types of peers that can exist in the gossipsub context.
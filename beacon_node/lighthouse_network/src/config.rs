@@ -1,5 +1,8 @@
 use crate::listen_addr::{ListenAddr, ListenAddress};
-use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use crate::rpc::config::{
+    InboundRateLimiterConfig, OutboundRateLimiterConfig, PeerClassRateLimiterConfig, RateLimitsFile,
+};
+use crate::service::gossip_trace::DEFAULT_GOSSIP_TRACE_MAX_FILE_SIZE;
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -86,6 +89,21 @@ pub struct Config {
     /// List of trusted libp2p nodes which are not scored and marked as explicit.
     pub trusted_peers: Vec<PeerIdSerialized>,
 
+    /// Only allow connections to and from the peers in `peer_allowlist` and `trusted_peers`.
+    pub private_network: bool,
+
+    /// Peers which may connect in private network mode. Can be changed at runtime via the HTTP
+    /// API.
+    pub peer_allowlist: Vec<PeerIdSerialized>,
+
+    /// Run as a private node which only peers with its sentries (the `trusted_peers`) and is never
+    /// advertised via discovery.
+    pub private_peer: bool,
+
+    /// Private nodes this node is a sentry for. These are kept connected, are never scored out of
+    /// gossip and have gossip relayed to them with priority.
+    pub sentry_of: Vec<PeerIdSerialized>,
+
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
@@ -134,6 +152,10 @@ pub struct Config {
     /// Whether light client protocols should be enabled.
     pub enable_light_client_server: bool,
 
+    /// The UDP port to accept WebRTC-direct connections from browser light clients on. These
+    /// connections are limited to the light client RPC protocols and gossip topics.
+    pub webrtc_port: Option<u16>,
+
     /// Configuration for the outbound rate limiter (requests made by this node).
     pub outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
 
@@ -143,6 +165,16 @@ pub struct Config {
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 
+    /// A YAML file of rate limiter quotas for regular and trusted peers, which overrides the
+    /// inbound and outbound rate limiter configurations and can be reloaded at runtime.
+    pub rate_limits_file: Option<PathBuf>,
+
+    /// If set, gossipsub message propagation events are written to this file.
+    pub gossip_trace_file: Option<PathBuf>,
+
+    /// The size in bytes at which the gossip trace file is rotated.
+    pub gossip_trace_max_file_size: u64,
+
     /// Configuration for the minimum message size for which IDONTWANT messages are send in the mesh.
     /// Lower the value reduces the optimization effect of the IDONTWANT messages.
     pub idontwant_message_size_threshold: usize,
@@ -268,6 +300,36 @@ impl Config {
     pub fn listen_addrs(&self) -> &ListenAddress {
         &self.listen_addresses
     }
+
+    /// Returns the inbound and outbound rate limiter configurations, taking the quotas from
+    /// `rate_limits` where it gives them. A limiter which is disabled is `None`.
+    pub fn rate_limiter_configs(
+        &self,
+        rate_limits: Option<&RateLimitsFile>,
+    ) -> Result<
+        (
+            Option<PeerClassRateLimiterConfig>,
+            Option<PeerClassRateLimiterConfig>,
+        ),
+        String,
+    > {
+        let (file_inbound, file_outbound) = match rate_limits {
+            Some(rate_limits) => (
+                rate_limits.inbound_config()?,
+                rate_limits.outbound_config()?,
+            ),
+            None => (None, None),
+        };
+        let inbound = self
+            .inbound_rate_limiter_config
+            .as_ref()
+            .map(|config| file_inbound.unwrap_or_else(|| config.0.clone().into()));
+        let outbound = self
+            .outbound_rate_limiter_config
+            .as_ref()
+            .map(|config| file_outbound.unwrap_or_else(|| config.0.clone().into()));
+        Ok((inbound, outbound))
+    }
 }
 
 impl Default for Config {
@@ -339,6 +401,10 @@ impl Default for Config {
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
+            private_network: false,
+            peer_allowlist: vec![],
+            private_peer: false,
+            sentry_of: vec![],
             disable_peer_scoring: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
@@ -354,9 +420,13 @@ impl Default for Config {
             proposer_only: false,
             metrics_enabled: false,
             enable_light_client_server: false,
+            webrtc_port: None,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
+            rate_limits_file: None,
+            gossip_trace_file: None,
+            gossip_trace_max_file_size: DEFAULT_GOSSIP_TRACE_MAX_FILE_SIZE,
             idontwant_message_size_threshold: DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD,
         }
     }
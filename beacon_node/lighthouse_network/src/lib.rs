@@ -20,7 +20,7 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::str::FromStr;
 
 /// Wrapper over a libp2p `PeerId` which implements `Serialize` and `Deserialize`
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PeerIdSerialized(libp2p::PeerId);
 
 impl From<PeerIdSerialized> for PeerId {
@@ -39,6 +39,18 @@ impl FromStr for PeerIdSerialized {
     }
 }
 
+impl PeerIdSerialized {
+    /// Parse a peer id, or derive it from the public key of an `enr:` prefixed ENR.
+    pub fn from_peer_id_or_enr(s: &str) -> Result<Self, String> {
+        if s.starts_with("enr:") {
+            let enr = Enr::from_str(s).map_err(|e| format!("Invalid ENR: {}", e))?;
+            Ok(Self(enr.peer_id()))
+        } else {
+            s.parse()
+        }
+    }
+}
+
 impl Serialize for PeerIdSerialized {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -118,10 +130,15 @@ pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
     peerdb::client::Client,
     peerdb::score::{PeerAction, ReportSource},
+    peerdb::traffic::{FirstSeenMessage, PeerTrafficStats},
     peerdb::PeerDB,
-    ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
+    ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, PersistedPeerDB, SyncInfo,
+    SyncStatus,
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Response};
+pub use service::gossip_trace::{
+    read_gossip_trace, GossipTraceEvent, GossipTraceRecord, DEFAULT_GOSSIP_TRACE_MAX_FILE_SIZE,
+};
 pub use service::utils::*;
 pub use service::{Gossipsub, NetworkEvent};
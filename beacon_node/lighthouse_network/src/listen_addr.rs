@@ -84,6 +84,24 @@ impl ListenAddress {
             .chain(v6_tcp_multiaddr)
     }
 
+    /// Returns the WebRTC-direct addresses to listen on for browser light clients, using the given
+    /// UDP port on each listening IP.
+    pub fn webrtc_addresses(&self, webrtc_port: u16) -> impl Iterator<Item = Multiaddr> {
+        let v4_multiaddr = self.v4().map(|v4_addr| {
+            Multiaddr::from(v4_addr.addr)
+                .with(Protocol::Udp(webrtc_port))
+                .with(Protocol::WebRTCDirect)
+        });
+
+        let v6_multiaddr = self.v6().map(|v6_addr| {
+            Multiaddr::from(v6_addr.addr)
+                .with(Protocol::Udp(webrtc_port))
+                .with(Protocol::WebRTCDirect)
+        });
+
+        v4_multiaddr.into_iter().chain(v6_multiaddr)
+    }
+
     #[cfg(test)]
     pub fn unused_v4_ports() -> Self {
         ListenAddress::V4(ListenAddr {
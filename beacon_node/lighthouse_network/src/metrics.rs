@@ -165,6 +165,35 @@ pub static GOSSIP_FAILED_LATE_PUBLISH_PER_TOPIC_KIND: LazyLock<Result<IntCounter
             &["topic_kind"],
         )
     });
+pub static PEER_GOSSIP_BYTES_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_peer_gossip_bytes_total",
+        "Gossip message bytes exchanged with peers per client, topic kind and direction.",
+        &["client", "topic_kind", "direction"],
+    )
+});
+pub static PEER_RPC_REQUESTS_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_peer_rpc_requests_per_client_total",
+        "RPC requests exchanged with peers per client, protocol and direction.",
+        &["client", "protocol", "direction"],
+    )
+});
+pub static PEER_FIRST_SEEN_MESSAGES_PER_CLIENT: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "libp2p_peer_first_seen_messages_total",
+            "Valid gossip messages first delivered to us by peers, per client and message kind.",
+            &["client", "kind"],
+        )
+    });
+pub static RPC_RATE_LIMITED_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_rpc_rate_limited_requests_total",
+        "RPC requests held back by the inbound or self rate limiter, per protocol and direction.",
+        &["protocol", "direction"],
+    )
+});
 pub static PEER_SCORE_DISTRIBUTION: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
         "peer_score_distribution",
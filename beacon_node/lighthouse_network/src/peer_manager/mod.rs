@@ -3,6 +3,7 @@
 use crate::discovery::enr_ext::EnrExt;
 use crate::discovery::peer_id_to_node_id;
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RpcErrorResponse};
+use crate::service::utils::is_webrtc_address;
 use crate::service::TARGET_SUBNET_PEERS;
 use crate::{metrics, Gossipsub, NetworkGlobals, PeerId, Subnet, SubnetDiscovery};
 use delay_map::HashSetDelay;
@@ -29,6 +30,7 @@ use libp2p::multiaddr;
 pub use peerdb::peer_info::{
     ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
 };
+pub use peerdb::persisted::PersistedPeerDB;
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
@@ -178,6 +180,35 @@ impl<E: EthSpec> PeerManager<E> {
 
     /* Public accessible functions */
 
+    /// Restores the peer reputations persisted by a previous run of the node.
+    ///
+    /// Peers that are still banned are banned at the swarm level again and the restored peers
+    /// with the best scores are dialed first.
+    pub fn restore_persisted_peers(&mut self, persisted: PersistedPeerDB) {
+        let ban_operations = self
+            .network_globals
+            .peers
+            .write()
+            .restore_persisted_peers(&persisted);
+        for (peer_id, ban_operation) in ban_operations {
+            self.handle_ban_operation(&peer_id, ban_operation, None);
+        }
+
+        let mut peers_by_score = persisted
+            .peers
+            .iter()
+            .filter_map(|persisted_peer| Some((persisted_peer.score(), persisted_peer.enr()?)))
+            .collect::<Vec<_>>();
+        peers_by_score.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let target_peers = self.target_peers;
+        let dialed_peers = peers_by_score
+            .into_iter()
+            .filter(|(_, enr)| self.dial_peer(enr.clone()))
+            .take(target_peers)
+            .count();
+        debug!(self.log, "Dialing restored peers"; "peers" => dialed_peers);
+    }
+
     /// The application layer wants to disconnect from a peer for a particular reason.
     ///
     /// All instant disconnections are fatal and we ban the associated peer.
@@ -423,11 +454,12 @@ impl<E: EthSpec> PeerManager<E> {
     /// A peer is being dialed.
     /// Returns true, if this peer will be dialed.
     pub fn dial_peer(&mut self, peer: Enr) -> bool {
-        if self
-            .network_globals
-            .peers
-            .read()
-            .should_dial(&peer.peer_id())
+        if self.network_globals.is_peer_allowed(&peer.peer_id())
+            && self
+                .network_globals
+                .peers
+                .read()
+                .should_dial(&peer.peer_id())
         {
             self.peers_to_dial.push(peer);
             true
@@ -743,6 +775,17 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Adds the gossip message bytes exchanged with each peer, as counted by gossipsub.
+    pub(crate) fn record_gossip_traffic(
+        &mut self,
+        traffic: Vec<(PeerId, gossipsub::TopicHash, gossipsub::TopicTraffic)>,
+    ) {
+        self.network_globals
+            .peers
+            .write()
+            .record_gossip_traffic(traffic);
+    }
+
     /// Updates the gossipsub scores for all known peers in gossipsub.
     pub(crate) fn update_gossipsub_scores(&mut self, gossipsub: &Gossipsub) {
         let actions = self
@@ -835,6 +878,12 @@ impl<E: EthSpec> PeerManager<E> {
                     return true;
                 }
                 ConnectingType::IngoingConnected { multiaddr } => {
+                    // Browser light clients connected over WebRTC only speak the light client
+                    // protocols, so they are neither pinged nor sent a status.
+                    if is_webrtc_address(&multiaddr) {
+                        peerdb.connect_ingoing(peer_id, multiaddr, enr);
+                        return true;
+                    }
                     peerdb.connect_ingoing(peer_id, multiaddr, enr);
                     // start a timer to ping inbound peers.
                     self.inbound_ping_peers.insert(*peer_id);
@@ -1214,6 +1263,9 @@ impl<E: EthSpec> PeerManager<E> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Disconnect any peers that have been removed from the private network allow-list.
+        self.disconnect_disallowed_peers();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
@@ -1225,6 +1277,25 @@ impl<E: EthSpec> PeerManager<E> {
         self.shrink_mappings();
     }
 
+    /// Disconnect connected peers which are not in the private network allow-list.
+    fn disconnect_disallowed_peers(&mut self) {
+        if self.network_globals.peer_allowlist.is_none() {
+            return;
+        }
+        let disallowed_peers = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peer_ids()
+            .filter(|peer_id| !self.network_globals.is_peer_allowed(peer_id))
+            .copied()
+            .collect::<Vec<_>>();
+        for peer_id in disallowed_peers {
+            debug!(self.log, "Disconnecting peer not in allow-list"; "peer_id" => %peer_id);
+            self.disconnect_peer(peer_id, GoodbyeReason::IrrelevantNetwork);
+        }
+    }
+
     // Reduce memory footprint by routinely shrinking associating mappings.
     fn shrink_mappings(&mut self) {
         self.inbound_ping_peers.shrink_to(5);
@@ -1480,6 +1551,91 @@ mod tests {
         PeerManager::new(config, Arc::new(globals), &log).unwrap()
     }
 
+    async fn build_peer_manager_with_allowlist(
+        peer_allowlist: Vec<PeerId>,
+        target_peer_count: usize,
+    ) -> PeerManager<E> {
+        let config = config::Config {
+            target_peer_count,
+            discovery_enabled: false,
+            ..Default::default()
+        };
+        let network_config = Arc::new(NetworkConfig {
+            target_peers: target_peer_count,
+            private_network: true,
+            peer_allowlist: peer_allowlist
+                .into_iter()
+                .map(|peer_id| peer_id.to_string().parse().unwrap())
+                .collect(),
+            ..Default::default()
+        });
+        let log = build_log(slog::Level::Debug, false);
+        let spec = Arc::new(E::default_spec());
+        let globals = NetworkGlobals::new_test_globals(vec![], &log, network_config, spec);
+        PeerManager::new(config, Arc::new(globals), &log).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_peer_manager_disconnects_peers_outside_allowlist() {
+        let allowed_peer = PeerId::random();
+        let unknown_peer = PeerId::random();
+
+        let mut peer_manager = build_peer_manager_with_allowlist(vec![allowed_peer], 10).await;
+        assert!(peer_manager.network_globals.is_peer_allowed(&allowed_peer));
+        assert!(!peer_manager.network_globals.is_peer_allowed(&unknown_peer));
+
+        peer_manager.inject_connect_ingoing(&allowed_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.inject_connect_ingoing(&unknown_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 2);
+
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 1);
+        assert!(peer_manager.is_connected(&allowed_peer));
+
+        // Removing a peer from the allow-list disconnects it at the next heartbeat.
+        peer_manager
+            .network_globals
+            .update_peer_allowlist(vec![], vec![allowed_peer])
+            .unwrap();
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_private_peer_only_allows_sentries() {
+        let sentry = PeerId::random();
+        let allowed_peer = PeerId::random();
+
+        let config = config::Config {
+            discovery_enabled: false,
+            ..Default::default()
+        };
+        let network_config = Arc::new(NetworkConfig {
+            private_network: true,
+            private_peer: true,
+            peer_allowlist: vec![allowed_peer.to_string().parse().unwrap()],
+            ..Default::default()
+        });
+        let log = build_log(slog::Level::Debug, false);
+        let spec = Arc::new(E::default_spec());
+        let globals = NetworkGlobals::new_test_globals(vec![sentry], &log, network_config, spec);
+        let mut peer_manager: PeerManager<E> =
+            PeerManager::new(config, Arc::new(globals), &log).unwrap();
+
+        assert!(peer_manager.network_globals.is_peer_allowed(&sentry));
+        assert!(!peer_manager.network_globals.is_peer_allowed(&allowed_peer));
+        assert!(peer_manager
+            .network_globals
+            .update_peer_allowlist(vec![allowed_peer], vec![])
+            .is_err());
+
+        peer_manager.inject_connect_ingoing(&sentry, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.inject_connect_ingoing(&allowed_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 1);
+        assert!(peer_manager.is_connected(&sentry));
+    }
+
     #[tokio::test]
     async fn test_peer_manager_disconnects_correctly_during_heartbeat() {
         // Create 6 peers to connect to with a target of 3.
@@ -5,7 +5,7 @@ use std::task::{Context, Poll};
 
 use futures::StreamExt;
 use libp2p::core::transport::PortUse;
-use libp2p::core::ConnectedPoint;
+use libp2p::core::{ConnectedPoint, Endpoint};
 use libp2p::identity::PeerId;
 use libp2p::multiaddr::Protocol;
 use libp2p::swarm::behaviour::{ConnectionClosed, ConnectionEstablished, DialFailure, FromSwarm};
@@ -176,6 +176,35 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         Ok(())
     }
 
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        addresses: &[libp2p::Multiaddr],
+        _effective_role: Endpoint,
+    ) -> Result<Vec<libp2p::Multiaddr>, ConnectionDenied> {
+        if let Some(peer_id) = maybe_peer {
+            if !self.network_globals.is_peer_allowed(&peer_id) {
+                return Err(ConnectionDenied::new(format!(
+                    "Dial to peer {peer_id} rejected: peer is not in the allow-list"
+                )));
+            }
+
+            // Trusted peers are re-dialed by their peer id alone (i.e. gossipsub explicit peers,
+            // which include sentries and their private nodes). Provide the addresses the peer
+            // advertised via identify so that these dials can succeed without discovery.
+            if addresses.is_empty() {
+                if let Some(info) = self.network_globals.peers.read().peer_info(&peer_id) {
+                    if info.is_trusted() {
+                        return Ok(info.listening_addresses().clone());
+                    }
+                }
+            }
+        }
+
+        Ok(vec![])
+    }
+
     fn handle_established_inbound_connection(
         &mut self,
         _connection_id: ConnectionId,
@@ -184,6 +213,15 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         remote_addr: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, ConnectionDenied> {
         trace!(self.log, "Inbound connection"; "peer_id" => %peer_id, "multiaddr" => %remote_addr);
+        // The peer id has been authenticated by the transport's security upgrade, so reject
+        // peers outside the private network before any protocol, including the status
+        // handshake, is negotiated.
+        if !self.network_globals.is_peer_allowed(&peer_id) {
+            return Err(ConnectionDenied::new(
+                "Connection to peer rejected: peer is not in the allow-list",
+            ));
+        }
+
         // We already checked if the peer was banned on `handle_pending_inbound_connection`.
         if self.ban_status(&peer_id).is_some() {
             return Err(ConnectionDenied::new(
@@ -225,6 +263,13 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         _port_use: PortUse,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
         trace!(self.log, "Outbound connection"; "peer_id" => %peer_id, "multiaddr" => %addr);
+        // Dials to addresses without a peer id are only checked once the peer id is known.
+        if !self.network_globals.is_peer_allowed(&peer_id) {
+            return Err(ConnectionDenied::new(
+                "Connection to peer rejected: peer is not in the allow-list",
+            ));
+        }
+
         if let Some(cause) = self.ban_status(&peer_id) {
             error!(self.log, "Connected a banned peer. Rejecting connection"; "peer_id" => %peer_id);
             return Err(ConnectionDenied::new(cause));
@@ -1,8 +1,11 @@
 use crate::discovery::enr::PEERDAS_CUSTODY_SUBNET_COUNT_ENR_KEY;
 use crate::discovery::{peer_id_to_node_id, CombinedKey};
+use crate::rpc::Protocol;
+use crate::types::GossipTopic;
 use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, EnrExt, Gossipsub, PeerId};
 use itertools::Itertools;
 use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use persisted::{PersistedPeer, PersistedPeerDB};
 use score::{PeerAction, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::net::IpAddr;
@@ -13,12 +16,15 @@ use std::{
     fmt::Formatter,
 };
 use sync_status::SyncStatus;
+use traffic::{FirstSeenMessage, TrafficDirection};
 use types::{ChainSpec, DataColumnSubnetId, EthSpec};
 
 pub mod client;
 pub mod peer_info;
+pub mod persisted;
 pub mod score;
 pub mod sync_status;
+pub mod traffic;
 
 /// Max number of disconnected nodes to remember.
 const MAX_DC_PEERS: usize = 500;
@@ -550,6 +556,7 @@ impl<E: EthSpec> PeerDB<E> {
                 match result {
                     ScoreTransitionResult::Banned => {
                         // The peer was banned as a result of this action.
+                        info.set_ban_reason(Some(msg.to_string()));
                         self.update_connection_state(peer_id, NewConnectionState::Banned)
                             .into()
                     }
@@ -587,6 +594,151 @@ impl<E: EthSpec> PeerDB<E> {
         }
     }
 
+    /// Returns the reputations of all known, untrusted peers so that they can be persisted across
+    /// restarts.
+    pub fn persisted_peers(&self) -> PersistedPeerDB {
+        PersistedPeerDB {
+            saved_at: persisted::unix_timestamp(),
+            peers: self
+                .peers
+                .iter()
+                .filter_map(|(peer_id, info)| PersistedPeer::new(peer_id, info))
+                .collect(),
+        }
+    }
+
+    /// Restores the peers persisted by a previous run of the node as disconnected peers, decaying
+    /// their scores for the time the node was offline. Peers that are already known, such as
+    /// trusted peers, are left untouched.
+    ///
+    /// Returns the ban operations for peers that are still banned.
+    #[must_use = "Banned peers need to be handled in libp2p"]
+    pub(super) fn restore_persisted_peers(
+        &mut self,
+        persisted: &PersistedPeerDB,
+    ) -> Vec<(PeerId, BanOperation)> {
+        if self.disable_peer_scoring {
+            return vec![];
+        }
+
+        let offline = persisted.offline_duration();
+        let mut ban_operations = vec![];
+        for persisted_peer in &persisted.peers {
+            let Some(peer_id) = persisted_peer.peer_id() else {
+                continue;
+            };
+            if self.peers.contains_key(&peer_id) {
+                continue;
+            }
+
+            let score = Score::from_persisted(
+                persisted_peer.lighthouse_score(),
+                persisted_peer.ban_remaining(),
+                offline,
+            );
+            let is_banned = score.state() == ScoreState::Banned;
+            let info = PeerInfo::from_persisted(
+                score,
+                persisted_peer.seen_multiaddrs(),
+                persisted_peer.enr(),
+                persisted_peer.ban_reason().filter(|_| is_banned),
+            );
+            self.peers.insert(peer_id, info);
+            self.disconnected_peers += 1;
+
+            if is_banned {
+                if let Some(ban_operation) =
+                    self.update_connection_state(&peer_id, NewConnectionState::Banned)
+                {
+                    ban_operations.push((peer_id, ban_operation));
+                }
+            }
+        }
+
+        // Respect the bounds on disconnected and banned peers.
+        let purged_peers = self.shrink_to_fit();
+        ban_operations.retain(|(peer_id, _)| {
+            !purged_peers
+                .iter()
+                .any(|(purged_peer_id, _)| purged_peer_id == peer_id)
+        });
+
+        debug!(
+            self.log,
+            "Restored persisted peers";
+            "peers" => persisted.peers.len(),
+            "banned" => ban_operations.len(),
+            "offline_secs" => offline.as_secs(),
+        );
+        ban_operations
+    }
+
+    /// Adds the gossip message bytes exchanged with peers since they were last taken from
+    /// gossipsub.
+    // VISIBILITY: Only the peer manager can forward gossipsub's traffic counts.
+    pub(super) fn record_gossip_traffic(
+        &mut self,
+        traffic: Vec<(PeerId, gossipsub::TopicHash, gossipsub::TopicTraffic)>,
+    ) {
+        for (peer_id, topic, topic_traffic) in traffic {
+            let Some(info) = self.peers.get_mut(&peer_id) else {
+                continue;
+            };
+            let Ok(topic) = GossipTopic::decode(topic.as_str()) else {
+                continue;
+            };
+            let client = info.client().kind.as_ref();
+            let topic_kind = topic.kind().as_ref();
+            for (direction, bytes) in [
+                (TrafficDirection::Inbound, topic_traffic.bytes_received),
+                (TrafficDirection::Outbound, topic_traffic.bytes_sent),
+            ] {
+                metrics::inc_counter_vec_by(
+                    &metrics::PEER_GOSSIP_BYTES_PER_CLIENT,
+                    &[client, topic_kind, direction.as_ref()],
+                    bytes,
+                );
+            }
+            info.traffic_mut().add_gossip_bytes(
+                topic.kind().to_string(),
+                topic_traffic.bytes_received,
+                topic_traffic.bytes_sent,
+            );
+        }
+    }
+
+    /// Counts an RPC request received from or sent to a peer.
+    pub(crate) fn record_rpc_request(
+        &mut self,
+        peer_id: &PeerId,
+        protocol: Protocol,
+        direction: TrafficDirection,
+    ) {
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            metrics::inc_counter_vec(
+                &metrics::PEER_RPC_REQUESTS_PER_CLIENT,
+                &[
+                    info.client().kind.as_ref(),
+                    protocol.as_ref(),
+                    direction.as_ref(),
+                ],
+            );
+            info.traffic_mut()
+                .add_rpc_request(protocol.as_ref(), direction);
+        }
+    }
+
+    /// Counts a valid gossip message which the peer was the first to deliver to us.
+    pub fn record_first_seen(&mut self, peer_id: &PeerId, message: FirstSeenMessage) {
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            metrics::inc_counter_vec(
+                &metrics::PEER_FIRST_SEEN_MESSAGES_PER_CLIENT,
+                &[info.client().kind.as_ref(), message.as_ref()],
+            );
+            info.traffic_mut().add_first_seen(message);
+        }
+    }
+
     /// Update min ttl of a peer.
     // VISIBILITY: Only the peer manager can update the min_ttl
     pub(super) fn update_min_ttl(&mut self, peer_id: &PeerId, min_ttl: Instant) {
@@ -1013,6 +1165,7 @@ impl<E: EthSpec> PeerDB<E> {
                     PeerConnectionStatus::Dialing { .. } => {} // Also odd but acceptable
                     PeerConnectionStatus::Banned { since } => {
                         info.set_connection_status(PeerConnectionStatus::Disconnected { since });
+                        info.set_ban_reason(None);
 
                         // Increment the disconnected count and reduce the banned count
                         self.banned_peers_count
@@ -1314,6 +1467,7 @@ mod tests {
     use super::*;
     use libp2p::core::multiaddr::Protocol;
     use slog::{o, Drain};
+    use ssz::{Decode, Encode};
     use std::net::{Ipv4Addr, Ipv6Addr};
     use types::MinimalEthSpec;
 
@@ -1375,6 +1529,55 @@ mod tests {
         assert_eq!(peer_info.unwrap().connections(), (n_in, n_out));
     }
 
+    #[test]
+    fn test_persisted_peers_are_restored() {
+        let mut pdb = get_db();
+        let multiaddr: Multiaddr = "/ip4/10.0.0.1/tcp/9000".parse().unwrap();
+        let banned_peer = PeerId::random();
+        let bad_peer = PeerId::random();
+        let trusted_peer = PeerId::random();
+
+        pdb.connect_ingoing(&banned_peer, multiaddr.clone(), None);
+        pdb.connect_ingoing(&bad_peer, multiaddr.clone(), None);
+        let _ = pdb.report_peer(
+            &banned_peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            "test_ban",
+        );
+        pdb.inject_disconnect(&banned_peer);
+        add_score(&mut pdb, &bad_peer, -10.0);
+        pdb.inject_disconnect(&bad_peer);
+        assert!(pdb.peer_info(&banned_peer).unwrap().is_banned());
+
+        // Persist the peers and round-trip them through SSZ.
+        let persisted = pdb.persisted_peers();
+        assert_eq!(persisted.peers.len(), 2);
+        let persisted = PersistedPeerDB::from_ssz_bytes(&persisted.as_ssz_bytes()).unwrap();
+
+        // Restore them into a fresh database with a trusted peer, as on a restart.
+        let log = build_log(slog::Level::Debug, false);
+        let mut pdb: PeerDB<M> = PeerDB::new(vec![trusted_peer], false, &log);
+        let ban_operations = pdb.restore_persisted_peers(&persisted);
+        assert_eq!(ban_operations.len(), 1);
+        assert_eq!(ban_operations[0].0, banned_peer);
+
+        let banned_info = pdb.peer_info(&banned_peer).unwrap();
+        assert!(banned_info.is_banned());
+        assert_eq!(banned_info.ban_reason(), Some("test_ban"));
+        assert!(pdb.ban_status(&banned_peer).is_some());
+        assert_eq!(
+            banned_info.seen_multiaddrs().collect::<Vec<_>>(),
+            vec![&multiaddr]
+        );
+
+        let bad_info = pdb.peer_info(&bad_peer).unwrap();
+        assert!(bad_info.is_disconnected());
+        assert!((pdb.score(&bad_peer) - -10.0).abs() < 0.1);
+        assert_eq!(pdb.disconnected_peers, pdb.disconnected_peers().count());
+        assert!(pdb.peer_info(&trusted_peer).unwrap().is_trusted());
+    }
+
     #[test]
     fn test_outbound_only_peers_counted_correctly() {
         let mut pdb = get_db();
@@ -1,6 +1,7 @@
 use super::client::Client;
 use super::score::{PeerAction, Score, ScoreState};
 use super::sync_status::SyncStatus;
+use super::traffic::PeerTrafficStats;
 use crate::discovery::Eth2Enr;
 use crate::{rpc::MetaData, types::Subnet};
 use discv5::Enr;
@@ -56,6 +57,11 @@ pub struct PeerInfo<E: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The reason the peer was last banned, if it is banned.
+    ban_reason: Option<String>,
+    /// The gossip and RPC traffic exchanged with the peer.
+    #[serde(skip)]
+    traffic: PeerTrafficStats,
 }
 
 impl<E: EthSpec> Default for PeerInfo<E> {
@@ -74,6 +80,8 @@ impl<E: EthSpec> Default for PeerInfo<E> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            ban_reason: None,
+            traffic: PeerTrafficStats::default(),
         }
     }
 }
@@ -88,6 +96,25 @@ impl<E: EthSpec> PeerInfo<E> {
         }
     }
 
+    /// Return a PeerInfo struct for a disconnected peer restored from a previous run of the node.
+    pub(super) fn from_persisted(
+        score: Score,
+        seen_multiaddrs: HashSet<Multiaddr>,
+        enr: Option<Enr>,
+        ban_reason: Option<String>,
+    ) -> Self {
+        PeerInfo {
+            score,
+            connection_status: Disconnected {
+                since: Instant::now(),
+            },
+            seen_multiaddrs,
+            enr,
+            ban_reason,
+            ..Default::default()
+        }
+    }
+
     /// Returns if the peer is subscribed to a given `Subnet` from the metadata attnets/syncnets field.
     /// Also returns true if the peer is assigned to custody a given data column `Subnet` computed from the metadata `custody_column_count` field or ENR `csc` field.
     pub fn on_subnet_metadata(&self, subnet: &Subnet) -> bool {
@@ -257,6 +284,16 @@ impl<E: EthSpec> PeerInfo<E> {
         })
     }
 
+    /// Returns the reason the peer was last banned, if it is banned.
+    pub fn ban_reason(&self) -> Option<&str> {
+        self.ban_reason.as_deref()
+    }
+
+    /// Returns the gossip and RPC traffic exchanged with the peer.
+    pub fn traffic(&self) -> &PeerTrafficStats {
+        &self.traffic
+    }
+
     /// Returns the connection status of the peer.
     pub fn connection_status(&self) -> &PeerConnectionStatus {
         &self.connection_status
@@ -393,6 +430,16 @@ impl<E: EthSpec> PeerInfo<E> {
         self.subnets.remove(subnet);
     }
 
+    /// Records why the peer was banned, or clears the reason once the peer is unbanned.
+    pub(super) fn set_ban_reason(&mut self, ban_reason: Option<String>) {
+        self.ban_reason = ban_reason;
+    }
+
+    /// Returns a mutable reference to the traffic exchanged with the peer.
+    pub(super) fn traffic_mut(&mut self) -> &mut PeerTrafficStats {
+        &mut self.traffic
+    }
+
     /// Removes all subnets from the peer.
     pub(super) fn clear_subnets(&mut self) {
         self.subnets.clear()
@@ -0,0 +1,111 @@
+//! The parts of the `PeerDB` which are persisted across restarts.
+//!
+//! Only the information needed to keep bad peers banned and to reconnect to good peers is kept:
+//! a peer's score, ban state and the addresses we have seen it on.
+use super::peer_info::PeerInfo;
+use crate::{Enr, Multiaddr, PeerId};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::EthSpec;
+
+/// The reputation of a single peer, as persisted across restarts.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct PersistedPeer {
+    /// The peer id, as bytes.
+    pub peer_id: Vec<u8>,
+    /// The bits of the peer's total score at the time it was persisted.
+    pub score: u64,
+    /// The bits of the lighthouse component of the peer's score.
+    pub lighthouse_score: u64,
+    /// The number of seconds before a banned peer's score begins to decay.
+    pub ban_remaining_secs: u64,
+    /// The UTF-8 encoded reason the peer was banned. Empty if the peer is not banned.
+    pub ban_reason: Vec<u8>,
+    /// The addresses we have seen the peer on, as bytes.
+    pub seen_multiaddrs: Vec<Vec<u8>>,
+    /// The base64 encoded ENR of the peer. Empty if the ENR is unknown.
+    pub enr: Vec<u8>,
+}
+
+impl PersistedPeer {
+    /// Returns the persisted form of a peer, or `None` for trusted peers which always have the
+    /// maximum score.
+    pub fn new<E: EthSpec>(peer_id: &PeerId, info: &PeerInfo<E>) -> Option<Self> {
+        let (lighthouse_score, ban_remaining) = info.score().persisted_parts()?;
+        Some(Self {
+            peer_id: peer_id.to_bytes(),
+            score: info.score().score().to_bits(),
+            lighthouse_score: lighthouse_score.to_bits(),
+            ban_remaining_secs: ban_remaining.as_secs(),
+            ban_reason: info
+                .ban_reason()
+                .map(|reason| reason.as_bytes().to_vec())
+                .unwrap_or_default(),
+            seen_multiaddrs: info.seen_multiaddrs().map(|addr| addr.to_vec()).collect(),
+            enr: info
+                .enr()
+                .map(|enr| enr.to_base64().into_bytes())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn peer_id(&self) -> Option<PeerId> {
+        PeerId::from_bytes(&self.peer_id).ok()
+    }
+
+    /// The peer's total score at the time it was persisted.
+    pub fn score(&self) -> f64 {
+        f64::from_bits(self.score)
+    }
+
+    pub fn lighthouse_score(&self) -> f64 {
+        f64::from_bits(self.lighthouse_score)
+    }
+
+    pub fn ban_remaining(&self) -> Duration {
+        Duration::from_secs(self.ban_remaining_secs)
+    }
+
+    pub fn ban_reason(&self) -> Option<String> {
+        (!self.ban_reason.is_empty()).then(|| String::from_utf8_lossy(&self.ban_reason).into())
+    }
+
+    /// The seen addresses of the peer, skipping any which fail to decode.
+    pub fn seen_multiaddrs(&self) -> HashSet<Multiaddr> {
+        self.seen_multiaddrs
+            .iter()
+            .filter_map(|bytes| Multiaddr::try_from(bytes.clone()).ok())
+            .collect()
+    }
+
+    pub fn enr(&self) -> Option<Enr> {
+        let enr = std::str::from_utf8(&self.enr).ok()?;
+        Enr::from_str(enr).ok()
+    }
+}
+
+/// The reputations of all known, untrusted peers.
+#[derive(Debug, Clone, Default, PartialEq, Encode, Decode)]
+pub struct PersistedPeerDB {
+    /// The number of seconds since the UNIX epoch at which the peers were persisted.
+    pub saved_at: u64,
+    pub peers: Vec<PersistedPeer>,
+}
+
+impl PersistedPeerDB {
+    /// The time that has passed since the peers were persisted, i.e. the time the node was
+    /// offline.
+    pub fn offline_duration(&self) -> Duration {
+        Duration::from_secs(unix_timestamp().saturating_sub(self.saved_at))
+    }
+}
+
+/// The current number of seconds since the UNIX epoch.
+pub(super) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
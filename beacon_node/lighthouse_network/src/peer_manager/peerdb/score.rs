@@ -246,6 +246,43 @@ impl RealScore {
         }
     }
 
+    /// Returns the lighthouse component of the score and the time remaining before a banned
+    /// peer's score begins to decay.
+    fn persisted_parts(&self) -> (f64, Duration) {
+        let ban_remaining = self
+            .last_updated
+            .checked_duration_since(Instant::now())
+            .unwrap_or_default();
+        (self.lighthouse_score, ban_remaining)
+    }
+
+    /// Rebuilds a score from its persisted parts, applying the decay for the time the node was
+    /// offline.
+    fn from_persisted(lighthouse_score: f64, ban_remaining: Duration, offline: Duration) -> Self {
+        let now = Instant::now();
+        // A banned peer's score only starts decaying once its ban period is over.
+        let ban_remaining = ban_remaining.min(BANNED_BEFORE_DECAY);
+        let last_updated = match ban_remaining.checked_sub(offline) {
+            Some(ban_remaining) => now + ban_remaining,
+            None => now
+                .checked_sub(offline.saturating_sub(ban_remaining))
+                .unwrap_or(now),
+        };
+        let lighthouse_score = if lighthouse_score.is_finite() {
+            lighthouse_score.clamp(MIN_SCORE, MAX_SCORE)
+        } else {
+            DEFAULT_SCORE
+        };
+        let mut score = RealScore {
+            lighthouse_score,
+            last_updated,
+            ..Default::default()
+        };
+        score.recompute_score();
+        score.update_at(now);
+        score
+    }
+
     pub fn update_gossipsub_score(&mut self, new_score: f64, ignore: bool) {
         // we only update gossipsub if last_updated is in the past which means either the peer is
         // not banned or the BANNED_BEFORE_DECAY time is over.
@@ -309,6 +346,29 @@ impl Score {
         Self::Max
     }
 
+    /// Returns the lighthouse component of a non-trusted peer's score and the time remaining
+    /// before its score begins to decay, for persisting across restarts.
+    pub(crate) fn persisted_parts(&self) -> Option<(f64, Duration)> {
+        match self {
+            Self::Max => None,
+            Self::Real(score) => Some(score.persisted_parts()),
+        }
+    }
+
+    /// Rebuilds a score persisted by `Self::persisted_parts`, decaying it for the time the node
+    /// was offline.
+    pub(crate) fn from_persisted(
+        lighthouse_score: f64,
+        ban_remaining: Duration,
+        offline: Duration,
+    ) -> Self {
+        Self::Real(RealScore::from_persisted(
+            lighthouse_score,
+            ban_remaining,
+            offline,
+        ))
+    }
+
     /// Returns the expected state of the peer given it's score.
     pub(crate) fn state(&self) -> ScoreState {
         match self.score() {
@@ -403,6 +463,48 @@ mod tests {
         assert!(score.score() > MIN_SCORE_BEFORE_BAN);
     }
 
+    #[test]
+    fn test_persisted_score_decays_while_offline() {
+        let mut score = RealScore::default();
+        score.test_add(-40.0);
+        let (lighthouse_score, ban_remaining) = score.persisted_parts();
+        assert_eq!(ban_remaining, Duration::ZERO);
+
+        // A restart without downtime restores the same score.
+        let restored = RealScore::from_persisted(lighthouse_score, ban_remaining, Duration::ZERO);
+        assert!((restored.score() - -40.0).abs() < 0.01);
+
+        // One half-life of downtime halves the score.
+        let restored = RealScore::from_persisted(
+            lighthouse_score,
+            ban_remaining,
+            Duration::from_secs(SCORE_HALFLIFE as u64),
+        );
+        assert!((restored.score() - -20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_persisted_ban_outlasts_restart() {
+        let mut score = Score::default();
+        score.apply_peer_action(PeerAction::Fatal);
+        assert_eq!(score.state(), ScoreState::Banned);
+        let (lighthouse_score, ban_remaining) = score.persisted_parts().unwrap();
+        assert!(ban_remaining > Duration::ZERO);
+
+        // The ban is still in force if the node was offline for less than the ban period.
+        let restored =
+            Score::from_persisted(lighthouse_score, ban_remaining, Duration::from_secs(3600));
+        assert_eq!(restored.state(), ScoreState::Banned);
+
+        // Once the ban period has passed the score decays as usual.
+        let restored = Score::from_persisted(
+            lighthouse_score,
+            ban_remaining,
+            BANNED_BEFORE_DECAY + Duration::from_secs(3600 * 2),
+        );
+        assert_eq!(restored.state(), ScoreState::Healthy);
+    }
+
     #[test]
     fn test_very_negative_gossipsub_score() {
         let mut score = Score::default();
@@ -0,0 +1,117 @@
+//! Accounting of the gossip and RPC traffic exchanged with a peer.
+//!
+//! The counts are kept per peer so that it is possible to see which peers use our bandwidth and
+//! how useful each of them is.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::AsRefStr;
+
+/// The direction of traffic relative to the local node.
+#[derive(Debug, Clone, Copy, PartialEq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum TrafficDirection {
+    Inbound,
+    Outbound,
+}
+
+/// The kinds of gossip messages a peer can be the first to deliver to us.
+#[derive(Debug, Clone, Copy, PartialEq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum FirstSeenMessage {
+    Block,
+    Attestation,
+}
+
+/// Gossip message bytes exchanged with a peer on a single topic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GossipTraffic {
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+/// Counts of the traffic exchanged with a peer since it was first seen.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerTrafficStats {
+    /// Gossip message bytes exchanged with the peer, keyed by topic kind.
+    pub gossip: BTreeMap<String, GossipTraffic>,
+    /// RPC requests the peer made which we served, keyed by protocol.
+    pub rpc_requests_served: BTreeMap<String, u64>,
+    /// RPC requests we made to the peer, keyed by protocol.
+    pub rpc_requests_made: BTreeMap<String, u64>,
+    /// The number of valid blocks that the peer was the first to deliver to us.
+    pub first_seen_blocks: u64,
+    /// The number of valid attestations that the peer was the first to deliver to us.
+    pub first_seen_attestations: u64,
+}
+
+impl PeerTrafficStats {
+    /// Adds gossip message bytes exchanged on `topic`.
+    pub fn add_gossip_bytes(&mut self, topic: String, bytes_in: u64, bytes_out: u64) {
+        let traffic = self.gossip.entry(topic).or_default();
+        traffic.bytes_in = traffic.bytes_in.saturating_add(bytes_in);
+        traffic.bytes_out = traffic.bytes_out.saturating_add(bytes_out);
+    }
+
+    /// Counts an RPC request for `protocol` in the given direction.
+    pub fn add_rpc_request(&mut self, protocol: &str, direction: TrafficDirection) {
+        let requests = match direction {
+            TrafficDirection::Inbound => &mut self.rpc_requests_served,
+            TrafficDirection::Outbound => &mut self.rpc_requests_made,
+        };
+        *requests.entry(protocol.to_string()).or_default() += 1;
+    }
+
+    /// Counts a message the peer was the first to deliver.
+    pub fn add_first_seen(&mut self, message: FirstSeenMessage) {
+        match message {
+            FirstSeenMessage::Block => self.first_seen_blocks += 1,
+            FirstSeenMessage::Attestation => self.first_seen_attestations += 1,
+        }
+    }
+
+    /// The total gossip bytes received from and sent to the peer.
+    pub fn total_gossip_bytes(&self) -> GossipTraffic {
+        self.gossip
+            .values()
+            .fold(GossipTraffic::default(), |total, traffic| GossipTraffic {
+                bytes_in: total.bytes_in.saturating_add(traffic.bytes_in),
+                bytes_out: total.bytes_out.saturating_add(traffic.bytes_out),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traffic_accounting() {
+        let mut stats = PeerTrafficStats::default();
+        stats.add_gossip_bytes("beacon_block".into(), 100, 0);
+        stats.add_gossip_bytes("beacon_block".into(), 50, 20);
+        stats.add_gossip_bytes("beacon_attestation_1".into(), 10, 10);
+        stats.add_rpc_request("blocks_by_range", TrafficDirection::Inbound);
+        stats.add_rpc_request("blocks_by_range", TrafficDirection::Inbound);
+        stats.add_rpc_request("status", TrafficDirection::Outbound);
+        stats.add_first_seen(FirstSeenMessage::Block);
+
+        assert_eq!(
+            stats.gossip["beacon_block"],
+            GossipTraffic {
+                bytes_in: 150,
+                bytes_out: 20
+            }
+        );
+        assert_eq!(
+            stats.total_gossip_bytes(),
+            GossipTraffic {
+                bytes_in: 160,
+                bytes_out: 30
+            }
+        );
+        assert_eq!(stats.rpc_requests_served["blocks_by_range"], 2);
+        assert_eq!(stats.rpc_requests_made["status"], 1);
+        assert_eq!(stats.first_seen_blocks, 1);
+        assert_eq!(stats.first_seen_attestations, 0);
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
     fmt::{Debug, Display},
+    fs::File,
+    path::Path,
     str::FromStr,
     time::Duration,
 };
@@ -265,6 +267,104 @@ impl FromStr for RateLimiterConfig {
     }
 }
 
+/// Rate limiter configurations for regular and trusted peers.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PeerClassRateLimiterConfig {
+    pub regular: RateLimiterConfig,
+    pub trusted: RateLimiterConfig,
+}
+
+impl From<RateLimiterConfig> for PeerClassRateLimiterConfig {
+    /// Applies the same quotas to regular and trusted peers.
+    fn from(config: RateLimiterConfig) -> Self {
+        Self {
+            regular: config.clone(),
+            trusted: config,
+        }
+    }
+}
+
+/// The quotas for one direction of the rate limits file, as lists of [`ProtocolQuota`].
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PeerClassQuotas {
+    /// Quotas for regular peers. Protocols that are not specified use the default values.
+    #[serde(default)]
+    pub regular: Vec<String>,
+    /// Quotas for trusted peers. Protocols that are not specified use the quota for regular
+    /// peers.
+    #[serde(default)]
+    pub trusted: Vec<String>,
+}
+
+impl PeerClassQuotas {
+    fn parse(quotas: &[String]) -> Result<RateLimiterConfig, String> {
+        if quotas.is_empty() {
+            return Ok(RateLimiterConfig::default());
+        }
+        quotas
+            .join(";")
+            .parse()
+            .map_err(|e: &str| format!("Invalid quota in {:?}: {}", quotas, e))
+    }
+
+    pub fn to_config(&self) -> Result<PeerClassRateLimiterConfig, String> {
+        let regular = Self::parse(&self.regular)?;
+        // Quotas given first take precedence, so the trusted quotas override the regular ones.
+        let trusted_quotas = self
+            .trusted
+            .iter()
+            .chain(&self.regular)
+            .cloned()
+            .collect::<Vec<_>>();
+        let trusted = Self::parse(&trusted_quotas)?;
+        Ok(PeerClassRateLimiterConfig { regular, trusted })
+    }
+}
+
+/// The contents of a YAML file of RPC rate limiter quotas, which can be reloaded while the node
+/// is running.
+///
+/// A direction which is not present in the file uses the quotas given on the command line. A
+/// limiter which is disabled on the command line stays disabled.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitsFile {
+    /// Quotas for requests received by this node.
+    #[serde(default)]
+    pub inbound: Option<PeerClassQuotas>,
+    /// Quotas for requests made by this node.
+    #[serde(default)]
+    pub outbound: Option<PeerClassQuotas>,
+}
+
+impl RateLimitsFile {
+    /// Reads and validates the rate limits file at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Unable to open rate limits file {:?}: {:?}", path, e))?;
+        let rate_limits: Self = serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse rate limits file {:?}: {:?}", path, e))?;
+        rate_limits.inbound_config()?;
+        rate_limits.outbound_config()?;
+        Ok(rate_limits)
+    }
+
+    pub fn inbound_config(&self) -> Result<Option<PeerClassRateLimiterConfig>, String> {
+        self.inbound
+            .as_ref()
+            .map(PeerClassQuotas::to_config)
+            .transpose()
+    }
+
+    pub fn outbound_config(&self) -> Result<Option<PeerClassRateLimiterConfig>, String> {
+        self.outbound
+            .as_ref()
+            .map(PeerClassQuotas::to_config)
+            .transpose()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,4 +380,45 @@ mod tests {
         };
         assert_eq!(quota.to_string().parse(), Ok(quota))
     }
+
+    #[test]
+    fn test_peer_class_quotas() {
+        let rate_limits: RateLimitsFile = serde_yaml::from_str(
+            "
+inbound:
+  regular:
+    - blocks_by_range:64/10
+    - status:2/10
+  trusted:
+    - blocks_by_range:1024/10
+",
+        )
+        .unwrap();
+        assert_eq!(rate_limits.outbound_config(), Ok(None));
+
+        let inbound = rate_limits.inbound_config().unwrap().unwrap();
+        assert_eq!(
+            inbound.regular.blocks_by_range_quota,
+            Quota::n_every(64, 10)
+        );
+        assert_eq!(inbound.regular.status_quota, Quota::n_every(2, 10));
+        assert_eq!(
+            inbound.regular.ping_quota,
+            RateLimiterConfig::DEFAULT_PING_QUOTA
+        );
+        assert_eq!(
+            inbound.trusted.blocks_by_range_quota,
+            Quota::n_every(1024, 10)
+        );
+        assert_eq!(inbound.trusted.status_quota, Quota::n_every(2, 10));
+
+        let invalid = RateLimitsFile {
+            inbound: Some(PeerClassQuotas {
+                regular: vec!["blocks_by_range:64".into()],
+                trusted: vec![],
+            }),
+            outbound: None,
+        };
+        assert!(invalid.inbound_config().is_err());
+    }
 }
@@ -4,6 +4,8 @@
 //! direct peer-to-peer communication primarily for sending/receiving chain information for
 //! syncing.
 
+use crate::metrics;
+use crate::service::utils::is_webrtc_address;
 use futures::future::FutureExt;
 use handler::RPCHandler;
 use libp2p::core::transport::PortUse;
@@ -13,8 +15,9 @@ use libp2p::swarm::{
 };
 use libp2p::swarm::{ConnectionClosed, FromSwarm, SubstreamProtocol, THandlerInEvent};
 use libp2p::PeerId;
-use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
+use rate_limiter::{PeerClassRateLimiter as RateLimiter, RateLimitedErr};
 use slog::{crit, debug, o, trace};
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -35,7 +38,7 @@ pub use methods::{
 };
 pub use protocol::{max_rpc_size, Protocol, RPCError};
 
-use self::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use self::config::PeerClassRateLimiterConfig;
 use self::protocol::RPCProtocol;
 use self::self_limiter::SelfRateLimiter;
 
@@ -171,8 +174,9 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
     pub fn new(
         fork_context: Arc<ForkContext>,
         enable_light_client_server: bool,
-        inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
-        outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
+        inbound_rate_limiter_config: Option<PeerClassRateLimiterConfig>,
+        outbound_rate_limiter_config: Option<PeerClassRateLimiterConfig>,
+        trusted_peers: HashSet<PeerId>,
         log: slog::Logger,
         network_params: NetworkParams,
         seq_number: u64,
@@ -181,12 +185,13 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
 
         let inbound_limiter = inbound_rate_limiter_config.map(|config| {
             debug!(log, "Using inbound rate limiting params"; "config" => ?config);
-            RateLimiter::new_with_config(config.0)
+            RateLimiter::new(config, trusted_peers.clone())
                 .expect("Inbound limiter configuration parameters are valid")
         });
 
         let self_limiter = outbound_rate_limiter_config.map(|config| {
-            SelfRateLimiter::new(config, log.clone()).expect("Configuration parameters are valid")
+            SelfRateLimiter::new(config, trusted_peers, log.clone())
+                .expect("Configuration parameters are valid")
         });
 
         RPC {
@@ -201,6 +206,26 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
         }
     }
 
+    /// Replaces the quotas of the inbound and self rate limiters. Limiters which are disabled stay
+    /// disabled.
+    pub fn update_rate_limits(
+        &mut self,
+        inbound_rate_limiter_config: Option<PeerClassRateLimiterConfig>,
+        outbound_rate_limiter_config: Option<PeerClassRateLimiterConfig>,
+    ) -> Result<(), &'static str> {
+        if let (Some(limiter), Some(config)) = (self.limiter.as_mut(), inbound_rate_limiter_config)
+        {
+            debug!(self.log, "Updating inbound rate limiting params"; "config" => ?config);
+            limiter.update_config(config)?;
+        }
+        if let (Some(self_limiter), Some(config)) =
+            (self.self_limiter.as_mut(), outbound_rate_limiter_config)
+        {
+            self_limiter.update_config(config)?;
+        }
+        Ok(())
+    }
+
     /// Sends an RPC response.
     ///
     /// The peer must be connected for this to succeed.
@@ -278,7 +303,7 @@ where
         connection_id: ConnectionId,
         peer_id: PeerId,
         _local_addr: &libp2p::Multiaddr,
-        _remote_addr: &libp2p::Multiaddr,
+        remote_addr: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
         let protocol = SubstreamProtocol::new(
             RPCProtocol {
@@ -287,6 +312,9 @@ where
                 enable_light_client_server: self.enable_light_client_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
+                // Browser light clients connect over WebRTC and may only use the light client
+                // protocols.
+                light_client_only: is_webrtc_address(remote_addr),
             },
             (),
         );
@@ -318,6 +346,7 @@ where
                 enable_light_client_server: self.enable_light_client_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
+                light_client_only: false,
             },
             (),
         );
@@ -407,7 +436,14 @@ where
             })) => {
                 if let Some(limiter) = self.limiter.as_mut() {
                     // check if the request is conformant to the quota
-                    match limiter.allows(&peer_id, &r#type) {
+                    let allowed = limiter.allows(&peer_id, &r#type);
+                    if allowed.is_err() {
+                        metrics::inc_counter_vec(
+                            &metrics::RPC_RATE_LIMITED_REQUESTS,
+                            &[r#type.versioned_protocol().protocol().as_ref(), "inbound"],
+                        );
+                    }
+                    match allowed {
                         Err(RateLimitedErr::TooLarge) => {
                             // we set the batch sizes, so this is a coding/config err for most protocols
                             let protocol = r#type.versioned_protocol().protocol();
@@ -488,6 +488,9 @@ pub struct RPCProtocol<E: EthSpec> {
     pub enable_light_client_server: bool,
     pub phantom: PhantomData<E>,
     pub ttfb_timeout: Duration,
+    /// Only support the light client protocols, along with `Ping` and `Goodbye`. Used for
+    /// connections from browser light clients.
+    pub light_client_only: bool,
 }
 
 impl<E: EthSpec> UpgradeInfo for RPCProtocol<E> {
@@ -496,7 +499,14 @@ impl<E: EthSpec> UpgradeInfo for RPCProtocol<E> {
 
     /// The list of supported RPC protocols for Lighthouse.
     fn protocol_info(&self) -> Self::InfoIter {
-        let mut supported_protocols = SupportedProtocol::currently_supported(&self.fork_context);
+        let mut supported_protocols = if self.light_client_only {
+            vec![
+                ProtocolId::new(SupportedProtocol::GoodbyeV1, Encoding::SSZSnappy),
+                ProtocolId::new(SupportedProtocol::PingV1, Encoding::SSZSnappy),
+            ]
+        } else {
+            SupportedProtocol::currently_supported(&self.fork_context)
+        };
         if self.enable_light_client_server {
             supported_protocols.push(ProtocolId::new(
                 SupportedProtocol::LightClientBootstrapV1,
@@ -510,6 +520,10 @@ impl<E: EthSpec> UpgradeInfo for RPCProtocol<E> {
                 SupportedProtocol::LightClientFinalityUpdateV1,
                 Encoding::SSZSnappy,
             ));
+            supported_protocols.push(ProtocolId::new(
+                SupportedProtocol::LightClientUpdatesByRangeV1,
+                Encoding::SSZSnappy,
+            ));
         }
         supported_protocols
     }
@@ -1,8 +1,10 @@
-use super::config::RateLimiterConfig;
+use super::config::{PeerClassRateLimiterConfig, RateLimiterConfig};
 use crate::rpc::Protocol;
 use fnv::FnvHashMap;
+use futures::FutureExt;
 use libp2p::PeerId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::future::Future;
 use std::hash::Hash;
 use std::pin::Pin;
@@ -380,6 +382,67 @@ impl Future for RPCRateLimiter {
     }
 }
 
+/// Manages rate limiting of requests per peer, applying separate quotas to trusted peers.
+pub struct PeerClassRateLimiter {
+    /// Rate limiter for regular peers.
+    regular: RPCRateLimiter,
+    /// Rate limiter for trusted peers.
+    trusted: RPCRateLimiter,
+    /// The peers to which the trusted quotas apply.
+    trusted_peers: HashSet<PeerId>,
+}
+
+impl PeerClassRateLimiter {
+    pub fn new(
+        config: PeerClassRateLimiterConfig,
+        trusted_peers: HashSet<PeerId>,
+    ) -> Result<Self, &'static str> {
+        Ok(PeerClassRateLimiter {
+            regular: RPCRateLimiter::new_with_config(config.regular)?,
+            trusted: RPCRateLimiter::new_with_config(config.trusted)?,
+            trusted_peers,
+        })
+    }
+
+    /// Replaces the quotas of both peer classes. The tokens used by peers under the previous
+    /// quotas are forgotten.
+    ///
+    /// If the new configuration is invalid the current quotas are kept.
+    pub fn update_config(
+        &mut self,
+        config: PeerClassRateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        let regular = RPCRateLimiter::new_with_config(config.regular)?;
+        let trusted = RPCRateLimiter::new_with_config(config.trusted)?;
+        self.regular = regular;
+        self.trusted = trusted;
+        Ok(())
+    }
+
+    pub fn allows<Item: RateLimiterItem>(
+        &mut self,
+        peer_id: &PeerId,
+        request: &Item,
+    ) -> Result<(), RateLimitedErr> {
+        if self.trusted_peers.contains(peer_id) {
+            self.trusted.allows(peer_id, request)
+        } else {
+            self.regular.allows(peer_id, request)
+        }
+    }
+}
+
+impl Future for PeerClassRateLimiter {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let _ = self.regular.poll_unpin(cx);
+        let _ = self.trusted.poll_unpin(cx);
+
+        Poll::Pending
+    }
+}
+
 /// Per key rate limiter using the token bucket / leaky bucket as a meter rate limiting algorithm,
 /// with the GCRA implementation.
 pub struct Limiter<Key: Hash + Eq + Clone> {
@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     task::{Context, Poll},
     time::Duration,
 };
@@ -12,10 +12,11 @@ use tokio_util::time::DelayQueue;
 use types::EthSpec;
 
 use super::{
-    config::OutboundRateLimiterConfig,
-    rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr},
+    config::PeerClassRateLimiterConfig,
+    rate_limiter::{PeerClassRateLimiter as RateLimiter, RateLimitedErr},
     BehaviourAction, Protocol, RPCSend, ReqId, RequestType,
 };
+use crate::metrics;
 
 /// A request that was rate limited or waiting on rate limited requests for the same peer and
 /// protocol.
@@ -50,9 +51,13 @@ pub enum Error {
 
 impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
     /// Creates a new [`SelfRateLimiter`] based on configration values.
-    pub fn new(config: OutboundRateLimiterConfig, log: Logger) -> Result<Self, &'static str> {
+    pub fn new(
+        config: PeerClassRateLimiterConfig,
+        trusted_peers: HashSet<PeerId>,
+        log: Logger,
+    ) -> Result<Self, &'static str> {
         debug!(log, "Using self rate limiting params"; "config" => ?config);
-        let limiter = RateLimiter::new_with_config(config.0)?;
+        let limiter = RateLimiter::new(config, trusted_peers)?;
 
         Ok(SelfRateLimiter {
             delayed_requests: Default::default(),
@@ -63,6 +68,16 @@ impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
         })
     }
 
+    /// Replaces the quotas of the limiter. Requests which are already queued are sent once the
+    /// new quotas allow it.
+    pub fn update_config(
+        &mut self,
+        config: PeerClassRateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        debug!(self.log, "Updating self rate limiting params"; "config" => ?config);
+        self.limiter.update_config(config)
+    }
+
     /// Checks if the rate limiter allows the request. If it's allowed, returns the
     /// [`ToSwarm`] that should be emitted. When not allowed, the request is delayed
     /// until it can be sent.
@@ -112,6 +127,10 @@ impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
             }),
             Err(e) => {
                 let protocol = req.versioned_protocol();
+                metrics::inc_counter_vec(
+                    &metrics::RPC_RATE_LIMITED_REQUESTS,
+                    &[protocol.protocol().as_ref(), "outbound"],
+                );
                 match e {
                     RateLimitedErr::TooLarge => {
                         // this should never happen with default parameters. Let's just send the request.
@@ -208,12 +227,13 @@ impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::config::{OutboundRateLimiterConfig, RateLimiterConfig};
+    use crate::rpc::config::RateLimiterConfig;
     use crate::rpc::rate_limiter::Quota;
     use crate::rpc::self_limiter::SelfRateLimiter;
     use crate::rpc::{Ping, Protocol, RequestType};
     use crate::service::api_types::{AppRequestId, RequestId, SyncRequestId};
     use libp2p::PeerId;
+    use std::collections::HashSet;
     use std::time::Duration;
     use types::MainnetEthSpec;
 
@@ -221,12 +241,12 @@ mod tests {
     #[tokio::test]
     async fn test_next_peer_request_ready() {
         let log = logging::test_logger();
-        let config = OutboundRateLimiterConfig(RateLimiterConfig {
+        let config = RateLimiterConfig {
             ping_quota: Quota::n_every(1, 2),
             ..Default::default()
-        });
+        };
         let mut limiter: SelfRateLimiter<RequestId, MainnetEthSpec> =
-            SelfRateLimiter::new(config, log).unwrap();
+            SelfRateLimiter::new(config.into(), HashSet::new(), log).unwrap();
         let peer_id = PeerId::random();
 
         for i in 1..=5u32 {
@@ -0,0 +1,341 @@
+//! A gossipsub `TraceSink` which writes message propagation events to a size-rotated JSONL file,
+//! for post-mortem analysis of late blocks and missed attestations.
+//!
+//! Each line is a [`GossipTraceRecord`]. When the file exceeds its maximum size it is renamed to
+//! `<path>.1` (shifting older files up to `<path>.ROTATED_TRACE_FILES`) and a new file is started.
+
+use gossipsub::{TraceEvent, TraceSink};
+use serde::{Deserialize, Serialize};
+use slog::{warn, Logger};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The default maximum size of a trace file before it is rotated, in bytes.
+pub const DEFAULT_GOSSIP_TRACE_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+/// The number of rotated trace files which are kept in addition to the current one.
+pub const ROTATED_TRACE_FILES: usize = 4;
+
+/// A serializable form of a gossipsub [`TraceEvent`]. Peer ids, topics and message ids are
+/// recorded in their string form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GossipTraceEvent {
+    PublishMessage {
+        message_id: String,
+        topic: String,
+    },
+    DeliverMessage {
+        message_id: String,
+        topic: String,
+        peer_id: String,
+    },
+    DuplicateMessage {
+        message_id: String,
+        topic: String,
+        peer_id: String,
+    },
+    RejectMessage {
+        message_id: Option<String>,
+        topic: String,
+        peer_id: String,
+        reason: String,
+    },
+    RecvIhave {
+        peer_id: String,
+        topic: String,
+        message_ids: Vec<String>,
+    },
+    SendIhave {
+        peer_id: String,
+        topic: String,
+        message_ids: Vec<String>,
+    },
+    RecvIwant {
+        peer_id: String,
+        message_ids: Vec<String>,
+    },
+    SendIwant {
+        peer_id: String,
+        message_ids: Vec<String>,
+    },
+}
+
+impl From<TraceEvent> for GossipTraceEvent {
+    fn from(event: TraceEvent) -> Self {
+        let ids = |ids: Vec<gossipsub::MessageId>| -> Vec<String> {
+            ids.iter().map(ToString::to_string).collect()
+        };
+        match event {
+            TraceEvent::PublishMessage { message_id, topic } => Self::PublishMessage {
+                message_id: message_id.to_string(),
+                topic: topic.to_string(),
+            },
+            TraceEvent::DeliverMessage {
+                message_id,
+                topic,
+                received_from,
+            } => Self::DeliverMessage {
+                message_id: message_id.to_string(),
+                topic: topic.to_string(),
+                peer_id: received_from.to_string(),
+            },
+            TraceEvent::DuplicateMessage {
+                message_id,
+                topic,
+                received_from,
+            } => Self::DuplicateMessage {
+                message_id: message_id.to_string(),
+                topic: topic.to_string(),
+                peer_id: received_from.to_string(),
+            },
+            TraceEvent::RejectMessage {
+                message_id,
+                topic,
+                received_from,
+                reason,
+            } => Self::RejectMessage {
+                message_id: message_id.map(|id| id.to_string()),
+                topic: topic.to_string(),
+                peer_id: received_from.to_string(),
+                reason,
+            },
+            TraceEvent::RecvIHave {
+                peer_id,
+                topic,
+                message_ids,
+            } => Self::RecvIhave {
+                peer_id: peer_id.to_string(),
+                topic: topic.to_string(),
+                message_ids: ids(message_ids),
+            },
+            TraceEvent::SendIHave {
+                peer_id,
+                topic,
+                message_ids,
+            } => Self::SendIhave {
+                peer_id: peer_id.to_string(),
+                topic: topic.to_string(),
+                message_ids: ids(message_ids),
+            },
+            TraceEvent::RecvIWant {
+                peer_id,
+                message_ids,
+            } => Self::RecvIwant {
+                peer_id: peer_id.to_string(),
+                message_ids: ids(message_ids),
+            },
+            TraceEvent::SendIWant {
+                peer_id,
+                message_ids,
+            } => Self::SendIwant {
+                peer_id: peer_id.to_string(),
+                message_ids: ids(message_ids),
+            },
+        }
+    }
+}
+
+impl GossipTraceEvent {
+    /// The peer the event relates to, if any. This is `None` for messages we published.
+    pub fn peer_id(&self) -> Option<&str> {
+        match self {
+            Self::PublishMessage { .. } => None,
+            Self::DeliverMessage { peer_id, .. }
+            | Self::DuplicateMessage { peer_id, .. }
+            | Self::RejectMessage { peer_id, .. }
+            | Self::RecvIhave { peer_id, .. }
+            | Self::SendIhave { peer_id, .. }
+            | Self::RecvIwant { peer_id, .. }
+            | Self::SendIwant { peer_id, .. } => Some(peer_id),
+        }
+    }
+
+    /// Returns true if the event refers to the message with the given id.
+    pub fn involves(&self, id: &str) -> bool {
+        match self {
+            Self::PublishMessage { message_id, .. }
+            | Self::DeliverMessage { message_id, .. }
+            | Self::DuplicateMessage { message_id, .. } => message_id == id,
+            Self::RejectMessage { message_id, .. } => message_id.as_deref() == Some(id),
+            Self::RecvIhave { message_ids, .. }
+            | Self::SendIhave { message_ids, .. }
+            | Self::RecvIwant { message_ids, .. }
+            | Self::SendIwant { message_ids, .. } => message_ids.iter().any(|m| m == id),
+        }
+    }
+}
+
+/// A single line of a gossip trace file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GossipTraceRecord {
+    /// Milliseconds since the UNIX epoch at which the event happened.
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub event: GossipTraceEvent,
+}
+
+/// Writes gossipsub trace events to a size-rotated JSONL file.
+pub struct GossipTraceWriter {
+    path: PathBuf,
+    max_file_size: u64,
+    file: BufWriter<File>,
+    /// The number of bytes in the current file.
+    file_size: u64,
+    log: Logger,
+}
+
+impl GossipTraceWriter {
+    /// Opens the trace file at `path`, appending to it if it already exists.
+    pub fn new(path: PathBuf, max_file_size: u64, log: Logger) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Unable to open gossip trace file {:?}: {}", path, e))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("Unable to read gossip trace file {:?}: {}", path, e))?
+            .len();
+
+        Ok(Self {
+            path,
+            max_file_size,
+            file: BufWriter::new(file),
+            file_size,
+            log,
+        })
+    }
+
+    fn write_record(&mut self, record: &GossipTraceRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.file_size += line.len() as u64;
+
+        if self.file_size >= self.max_file_size {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    /// Shifts the existing trace files up by one, dropping the oldest, and starts a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for i in (1..ROTATED_TRACE_FILES).rev() {
+            let from = rotated_path(&self.path, i);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, i + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        self.file = BufWriter::new(File::create(&self.path)?);
+        self.file_size = 0;
+        Ok(())
+    }
+}
+
+impl TraceSink for GossipTraceWriter {
+    fn trace(&mut self, timestamp: SystemTime, event: TraceEvent) {
+        let record = GossipTraceRecord {
+            timestamp_ms: timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            event: event.into(),
+        };
+        if let Err(e) = self.write_record(&record) {
+            warn!(self.log, "Failed to write gossip trace"; "error" => %e);
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Err(e) = self.file.flush() {
+            warn!(self.log, "Failed to flush gossip trace"; "error" => %e);
+        }
+    }
+}
+
+impl Drop for GossipTraceWriter {
+    fn drop(&mut self) {
+        TraceSink::flush(self);
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut file_name = path.as_os_str().to_owned();
+    file_name.push(format!(".{}", index));
+    PathBuf::from(file_name)
+}
+
+/// Reads all records of the trace at `path`, including its rotated files, oldest first.
+pub fn read_gossip_trace(path: &Path) -> Result<Vec<GossipTraceRecord>, String> {
+    let mut files = (1..=ROTATED_TRACE_FILES)
+        .rev()
+        .map(|i| rotated_path(path, i))
+        .filter(|p| p.exists())
+        .collect::<Vec<_>>();
+    files.push(path.to_path_buf());
+
+    let mut records = vec![];
+    for file_path in files {
+        let file = File::open(&file_path)
+            .map_err(|e| format!("Unable to open gossip trace {:?}: {}", file_path, e))?;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line =
+                line.map_err(|e| format!("Unable to read gossip trace {:?}: {}", file_path, e))?;
+            if line.is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid record at {:?} line {}: {}", file_path, i + 1, e))?;
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gossipsub::{MessageId, TopicHash};
+    use libp2p::PeerId;
+
+    #[test]
+    fn trace_file_rotates_and_reads_back_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gossip_trace.jsonl");
+        let log = logging::test_logger();
+
+        // Each record is well over 100 bytes, so every write rotates the file.
+        let mut writer = GossipTraceWriter::new(path.clone(), 100, log).unwrap();
+        let peer_id = PeerId::random();
+        let events = (0..ROTATED_TRACE_FILES as u8 + 2)
+            .map(|i| TraceEvent::DeliverMessage {
+                message_id: MessageId::new(&[i]),
+                topic: TopicHash::from_raw("topic"),
+                received_from: peer_id,
+            })
+            .collect::<Vec<_>>();
+        for (i, event) in events.iter().enumerate() {
+            writer.trace(
+                UNIX_EPOCH + std::time::Duration::from_millis(i as u64),
+                event.clone(),
+            );
+        }
+        drop(writer);
+
+        // The two oldest records have been dropped, the rest are read back oldest first.
+        let records = read_gossip_trace(&path).unwrap();
+        assert_eq!(records.len(), ROTATED_TRACE_FILES);
+        for (record, (i, event)) in records.iter().zip(events.into_iter().enumerate().skip(2)) {
+            assert_eq!(record.timestamp_ms, i as u64);
+            assert_eq!(record.event, GossipTraceEvent::from(event));
+            assert!(record
+                .event
+                .involves(&MessageId::new(&[i as u8]).to_string()));
+        }
+    }
+}
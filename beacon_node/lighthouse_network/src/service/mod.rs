@@ -5,9 +5,10 @@ use crate::discovery::{
 };
 use crate::peer_manager::{
     config::Config as PeerManagerCfg, peerdb::score::PeerAction, peerdb::score::ReportSource,
-    ConnectionDirection, PeerManager, PeerManagerEvent,
+    peerdb::traffic::TrafficDirection, ConnectionDirection, PeerManager, PeerManagerEvent,
 };
 use crate::peer_manager::{MIN_OUTBOUND_ONLY_FACTOR, PEER_EXCESS_FACTOR, PRIORITY_PEER_EXCESS};
+use crate::rpc::config::RateLimitsFile;
 use crate::rpc::methods::MetadataRequest;
 use crate::rpc::{
     self, GoodbyeReason, HandlerErr, NetworkParams, Protocol, RPCError, RPCMessage, RPCReceived,
@@ -47,6 +48,7 @@ use utils::{build_transport, strip_peer_id, Context as ServiceContext};
 
 pub mod api_types;
 mod gossip_cache;
+pub mod gossip_trace;
 pub mod gossipsub_scoring_parameters;
 pub mod utils;
 /// The number of peers we target per subnet for discovery queries.
@@ -335,6 +337,35 @@ impl<E: EthSpec> Network<E> {
                 gossipsub.add_explicit_peer(&PeerId::from(explicit_peer.clone()));
             }
 
+            if let Some(trace_file) = &config.gossip_trace_file {
+                let writer = gossip_trace::GossipTraceWriter::new(
+                    trace_file.clone(),
+                    config.gossip_trace_max_file_size,
+                    log.clone(),
+                )?;
+                gossipsub.with_trace_sink(Box::new(writer));
+                info!(log, "Writing gossip trace"; "path" => ?trace_file);
+            }
+
+            // Relay gossip to the private nodes we are a sentry for with priority. They are also
+            // trusted, and therefore explicit peers.
+            for private_peer in config.sentry_of.iter() {
+                gossipsub.add_priority_peer(&PeerId::from(private_peer.clone()));
+            }
+
+            // Browser light clients connecting over WebRTC may only use the light client topics.
+            if config.webrtc_port.is_some() {
+                gossipsub.set_restricted_topic_filter(|topic| {
+                    GossipTopic::decode(topic.as_str()).is_ok_and(|topic| {
+                        matches!(
+                            topic.kind(),
+                            GossipKind::LightClientFinalityUpdate
+                                | GossipKind::LightClientOptimisticUpdate
+                        )
+                    })
+                });
+            }
+
             // If we are using metrics, then register which topics we want to make sure to keep
             // track of
             if ctx.libp2p_registry.is_some() {
@@ -359,11 +390,23 @@ impl<E: EthSpec> Network<E> {
             ttfb_timeout: ctx.chain_spec.ttfb_timeout(),
             resp_timeout: ctx.chain_spec.resp_timeout(),
         };
+        let rate_limits = config
+            .rate_limits_file
+            .as_deref()
+            .map(RateLimitsFile::load)
+            .transpose()?;
+        let (inbound_rate_limiter_config, outbound_rate_limiter_config) =
+            config.rate_limiter_configs(rate_limits.as_ref())?;
         let eth2_rpc = RPC::new(
             ctx.fork_context.clone(),
             config.enable_light_client_server,
-            config.inbound_rate_limiter_config.clone(),
-            config.outbound_rate_limiter_config.clone(),
+            inbound_rate_limiter_config,
+            outbound_rate_limiter_config,
+            config
+                .trusted_peers
+                .iter()
+                .map(|peer| PeerId::from(peer.clone()))
+                .collect(),
             log.clone(),
             network_params,
             seq_number,
@@ -449,9 +492,17 @@ impl<E: EthSpec> Network<E> {
             }
         };
 
-        // Set up the transport - tcp/quic with noise and mplex
-        let transport = build_transport(local_keypair.clone(), !config.disable_quic_support)
-            .map_err(|e| format!("Failed to build transport: {:?}", e))?;
+        // Set up the transport - tcp/quic with noise and mplex, and webrtc for light clients
+        let webrtc_certificate = config
+            .webrtc_port
+            .map(|_| utils::load_or_build_webrtc_certificate(&config.network_dir, &log))
+            .transpose()?;
+        let transport = build_transport(
+            local_keypair.clone(),
+            !config.disable_quic_support,
+            webrtc_certificate,
+        )
+        .map_err(|e| format!("Failed to build transport: {:?}", e))?;
 
         // use the executor for libp2p
         struct Executor(task_executor::TaskExecutor);
@@ -523,7 +574,15 @@ impl<E: EthSpec> Network<E> {
         info!(self.log, "Libp2p Starting"; "peer_id" => %enr.peer_id(), "bandwidth_config" => format!("{}-{}", config.network_load, NetworkLoad::from(config.network_load).name));
         debug!(self.log, "Attempting to open listening ports"; config.listen_addrs(), "discovery_enabled" => !config.disable_discovery, "quic_enabled" => !config.disable_quic_support);
 
-        for listen_multiaddr in config.listen_addrs().libp2p_addresses() {
+        let webrtc_multiaddrs = config
+            .webrtc_port
+            .into_iter()
+            .flat_map(|port| config.listen_addrs().webrtc_addresses(port));
+        for listen_multiaddr in config
+            .listen_addrs()
+            .libp2p_addresses()
+            .chain(webrtc_multiaddrs)
+        {
             // If QUIC is disabled, ignore listening on QUIC ports
             if config.disable_quic_support
                 && listen_multiaddr.iter().any(|v| v == MProtocol::QuicV1)
@@ -973,11 +1032,29 @@ impl<E: EthSpec> Network<E> {
             return Err((request_id, RPCError::Disconnected));
         }
 
+        self.record_rpc_request(
+            &peer_id,
+            request.versioned_protocol().protocol(),
+            TrafficDirection::Outbound,
+        );
         self.eth2_rpc_mut()
             .send_request(peer_id, RequestId::Application(request_id), request);
         Ok(())
     }
 
+    /// Counts an RPC request exchanged with a peer in its traffic statistics.
+    fn record_rpc_request(
+        &mut self,
+        peer_id: &PeerId,
+        protocol: Protocol,
+        direction: TrafficDirection,
+    ) {
+        self.network_globals
+            .peers
+            .write()
+            .record_rpc_request(peer_id, protocol, direction);
+    }
+
     /// Send a successful response to a peer over RPC.
     pub fn send_response(
         &mut self,
@@ -1157,8 +1234,21 @@ impl<E: EthSpec> Network<E> {
         utils::save_metadata_to_disk(&self.network_dir, meta_data, &self.log);
     }
 
+    /// Replaces the RPC rate limiter quotas with those in `rate_limits`. Quotas which are not in
+    /// `rate_limits` revert to those given on the command line.
+    pub fn update_rate_limits(&mut self, rate_limits: &RateLimitsFile) -> Result<(), String> {
+        let (inbound, outbound) = self
+            .network_globals
+            .config
+            .rate_limiter_configs(Some(rate_limits))?;
+        self.eth2_rpc_mut()
+            .update_rate_limits(inbound, outbound)
+            .map_err(String::from)
+    }
+
     /// Sends a Ping request to the peer.
     fn ping(&mut self, peer_id: PeerId) {
+        self.record_rpc_request(&peer_id, Protocol::Ping, TrafficDirection::Outbound);
         self.eth2_rpc_mut().ping(peer_id, RequestId::Internal);
     }
 
@@ -1172,6 +1262,7 @@ impl<E: EthSpec> Network<E> {
             // We always prefer sending V2 requests otherwise
             RequestType::MetaData(MetadataRequest::new_v2())
         };
+        self.record_rpc_request(&peer_id, Protocol::MetaData, TrafficDirection::Outbound);
         self.eth2_rpc_mut()
             .send_request(peer_id, RequestId::Internal, event);
     }
@@ -1434,6 +1525,11 @@ impl<E: EthSpec> Network<E> {
                 }
             }
             Ok(RPCReceived::Request(request)) => {
+                self.record_rpc_request(
+                    &peer_id,
+                    request.r#type.versioned_protocol().protocol(),
+                    TrafficDirection::Inbound,
+                );
                 match request.r#type {
                     /* Behaviour managed protocols: Ping and Metadata */
                     RequestType::Ping(ping) => {
@@ -1808,6 +1904,8 @@ impl<E: EthSpec> Network<E> {
                 // perform gossipsub score updates when necessary
                 _ = self.update_gossipsub_scores.tick() => {
                     let this = self.swarm.behaviour_mut();
+                    let traffic = this.gossipsub.take_peer_traffic();
+                    this.peer_manager.record_gossip_traffic(traffic);
                     this.peer_manager.update_gossipsub_scores(&this.gossipsub);
                 }
                 // poll the gossipsub cache to clear expired messages
@@ -1854,7 +1952,15 @@ impl<E: EthSpec> Network<E> {
                 #[allow(unreachable_patterns)]
                 BehaviourEvent::ConnectionLimits(le) => void::unreachable(le),
             },
-            SwarmEvent::ConnectionEstablished { .. } => None,
+            SwarmEvent::ConnectionEstablished {
+                peer_id, endpoint, ..
+            } => {
+                if utils::is_webrtc_address(endpoint.get_remote_address()) {
+                    debug!(self.log, "Light client connected over WebRTC"; "peer_id" => %peer_id);
+                    self.gossipsub_mut().restrict_peer(&peer_id);
+                }
+                None
+            }
             SwarmEvent::ConnectionClosed { .. } => None,
             SwarmEvent::IncomingConnection {
                 local_addr,
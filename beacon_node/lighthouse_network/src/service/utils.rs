@@ -24,6 +24,9 @@ use types::{
 pub const NETWORK_KEY_FILENAME: &str = "key";
 /// The filename to store our local metadata.
 pub const METADATA_FILENAME: &str = "metadata";
+/// The filename to store the certificate of the WebRTC listener. The certificate hash is part of
+/// the advertised multiaddr, so it must be stable across restarts.
+pub const WEBRTC_CERTIFICATE_FILENAME: &str = "webrtc_certificate.pem";
 
 pub struct Context<'a> {
     pub config: Arc<NetworkConfig>,
@@ -36,10 +39,12 @@ pub struct Context<'a> {
 type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
 
 /// The implementation supports TCP/IP, QUIC (experimental) over UDP, noise as the encryption layer, and
-/// mplex/yamux as the multiplexing layer (when using TCP).
+/// mplex/yamux as the multiplexing layer (when using TCP). If a certificate is given, WebRTC-direct
+/// is also supported for browser light clients.
 pub fn build_transport(
     local_private_key: Keypair,
     quic_support: bool,
+    webrtc_certificate: Option<libp2p_webrtc::tokio::Certificate>,
 ) -> std::io::Result<BoxedTransport> {
     // mplex config
     let mut mplex_config = libp2p_mplex::MplexConfig::new();
@@ -73,6 +78,19 @@ pub fn build_transport(
         tcp.boxed()
     };
 
+    let transport = if let Some(certificate) = webrtc_certificate {
+        let webrtc = libp2p_webrtc::tokio::Transport::new(local_private_key, certificate);
+        transport
+            .or_transport(webrtc)
+            .map(|either_output, _| match either_output {
+                Either::Left((peer_id, muxer)) => (peer_id, muxer),
+                Either::Right((peer_id, connection)) => (peer_id, StreamMuxerBox::new(connection)),
+            })
+            .boxed()
+    } else {
+        transport
+    };
+
     // Enables DNS over the transport.
     let transport = libp2p::dns::tokio::Transport::system(transport)?.boxed();
 
@@ -146,6 +164,45 @@ pub fn load_private_key(config: &NetworkConfig, log: &slog::Logger) -> Keypair {
     local_private_key.into()
 }
 
+/// Loads the WebRTC certificate from disk. If this fails, a new certificate is generated and is
+/// then saved to disk.
+pub fn load_or_build_webrtc_certificate(
+    network_dir: &Path,
+    log: &slog::Logger,
+) -> Result<libp2p_webrtc::tokio::Certificate, String> {
+    let certificate_path = network_dir.join(WEBRTC_CERTIFICATE_FILENAME);
+    if let Ok(pem) = std::fs::read_to_string(&certificate_path) {
+        match libp2p_webrtc::tokio::Certificate::from_pem(&pem) {
+            Ok(certificate) => {
+                debug!(log, "Loaded WebRTC certificate from disk");
+                return Ok(certificate);
+            }
+            Err(e) => warn!(log, "WebRTC certificate file is invalid"; "error" => ?e),
+        }
+    }
+
+    let certificate = libp2p_webrtc::tokio::Certificate::generate(&mut rand::thread_rng())
+        .map_err(|e| format!("Unable to generate WebRTC certificate: {:?}", e))?;
+    let _ = std::fs::create_dir_all(network_dir);
+    match std::fs::write(&certificate_path, certificate.serialize_pem()) {
+        Ok(_) => debug!(log, "New WebRTC certificate generated and written to disk"),
+        Err(e) => warn!(
+            log,
+            "Could not write WebRTC certificate to file";
+            "path" => ?certificate_path,
+            "error" => %e
+        ),
+    }
+    Ok(certificate)
+}
+
+/// Returns true if the address is a WebRTC-direct address, which is only used by browser light
+/// clients.
+pub fn is_webrtc_address(addr: &Multiaddr) -> bool {
+    addr.iter()
+        .any(|protocol| protocol == Protocol::WebRTCDirect)
+}
+
 /// Generate authenticated XX Noise config from identity keys
 fn generate_noise_config(identity_keypair: &Keypair) -> noise::Config {
     noise::Config::new(identity_keypair).expect("signing can fail only once during starting a node")
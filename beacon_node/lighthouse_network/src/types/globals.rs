@@ -18,6 +18,8 @@ pub struct NetworkGlobals<E: EthSpec> {
     pub listen_multiaddrs: RwLock<Vec<Multiaddr>>,
     /// The collection of known peers.
     pub peers: RwLock<PeerDB<E>>,
+    /// The peers allowed to connect in private network mode, or `None` if any peer may connect.
+    pub peer_allowlist: Option<RwLock<HashSet<PeerId>>>,
     // The local meta data of our node.
     pub local_metadata: RwLock<MetaData<E>>,
     /// The current gossipsub topic subscriptions.
@@ -74,12 +76,30 @@ impl<E: EthSpec> NetworkGlobals<E> {
             (vec![], vec![])
         };
 
+        // A private peer only ever connects to its sentries, which are its trusted peers.
+        let allowed_peers = if config.private_peer {
+            &[][..]
+        } else {
+            &config.peer_allowlist[..]
+        };
+        let peer_allowlist = config.private_network.then(|| {
+            RwLock::new(
+                allowed_peers
+                    .iter()
+                    .cloned()
+                    .map(PeerId::from)
+                    .chain(trusted_peers.iter().copied())
+                    .collect(),
+            )
+        });
+
         NetworkGlobals {
             local_enr: RwLock::new(enr.clone()),
             peer_id: RwLock::new(enr.peer_id()),
             listen_multiaddrs: RwLock::new(Vec::new()),
             local_metadata: RwLock::new(local_metadata),
             peers: RwLock::new(PeerDB::new(trusted_peers, disable_peer_scoring, log)),
+            peer_allowlist,
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::Paused),
@@ -121,6 +141,45 @@ impl<E: EthSpec> NetworkGlobals<E> {
         self.peers.read().connected_or_dialing_peers().count()
     }
 
+    /// Returns `true` if the peer may connect, i.e. if private network mode is disabled or the
+    /// peer is in the allow-list.
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        self.peer_allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.read().contains(peer_id))
+    }
+
+    /// Returns the peers allowed to connect, or `None` if private network mode is disabled.
+    pub fn peer_allowlist(&self) -> Option<Vec<PeerId>> {
+        self.peer_allowlist
+            .as_ref()
+            .map(|allowlist| allowlist.read().iter().copied().collect())
+    }
+
+    /// Adds and removes peers from the allow-list, returning the updated allow-list.
+    ///
+    /// Connected peers that are no longer allowed are disconnected at the next heartbeat. The
+    /// allow-list of a private peer is fixed to its sentries.
+    pub fn update_peer_allowlist(
+        &self,
+        add: impl IntoIterator<Item = PeerId>,
+        remove: impl IntoIterator<Item = PeerId>,
+    ) -> Result<Vec<PeerId>, String> {
+        if self.config.private_peer {
+            return Err("The allow-list of a private peer only contains its sentries".into());
+        }
+        let allowlist = self
+            .peer_allowlist
+            .as_ref()
+            .ok_or("Private network mode is not enabled")?;
+        let mut allowlist = allowlist.write();
+        allowlist.extend(add);
+        for peer_id in remove {
+            allowlist.remove(&peer_id);
+        }
+        Ok(allowlist.iter().copied().collect())
+    }
+
     /// Returns in the node is syncing.
     pub fn is_syncing(&self) -> bool {
         self.sync_state.read().is_syncing()
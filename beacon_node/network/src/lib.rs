@@ -5,6 +5,7 @@ mod metrics;
 mod nat;
 mod network_beacon_processor;
 mod persisted_dht;
+mod persisted_peers;
 mod router;
 mod status;
 mod subnet_service;
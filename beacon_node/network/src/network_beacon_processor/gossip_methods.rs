@@ -19,7 +19,9 @@ use beacon_chain::{
     AvailabilityProcessingStatus, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
     GossipVerifiedBlock, NotifyExecutionLayer,
 };
-use lighthouse_network::{Client, MessageAcceptance, MessageId, PeerAction, PeerId, ReportSource};
+use lighthouse_network::{
+    Client, FirstSeenMessage, MessageAcceptance, MessageId, PeerAction, PeerId, ReportSource,
+};
 use operation_pool::ReceivedPreCapella;
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
@@ -185,6 +187,17 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         })
     }
 
+    /// Credits `peer_id` with being the first peer to deliver a valid gossip message.
+    ///
+    /// Gossip verification rejects messages that have already been seen, so any message which
+    /// passes it was first delivered by its propagation source.
+    fn record_first_seen(&self, peer_id: &PeerId, message: FirstSeenMessage) {
+        self.network_globals
+            .peers
+            .write()
+            .record_first_seen(peer_id, message);
+    }
+
     /* Processing functions */
 
     /// Process the unaggregated attestation received from the gossip network and:
@@ -312,6 +325,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Ok(verified_attestation) => {
                 let indexed_attestation = &verified_attestation.indexed_attestation;
                 let beacon_block_root = indexed_attestation.data().beacon_block_root;
+                self.record_first_seen(&peer_id, FirstSeenMessage::Attestation);
 
                 // Register the attestation with any monitored validators.
                 self.chain
@@ -518,6 +532,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Ok(verified_aggregate) => {
                 let aggregate = &verified_aggregate.signed_aggregate;
                 let indexed_attestation = &verified_aggregate.indexed_attestation;
+                self.record_first_seen(&peer_id, FirstSeenMessage::Attestation);
 
                 // If the attestation is still timely, propagate it.
                 self.propagate_attestation_if_timely(
@@ -1171,6 +1186,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "root" => ?verified_block.block_root
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
+                self.record_first_seen(&peer_id, FirstSeenMessage::Block);
 
                 // Log metrics to keep track of propagation delay times.
                 if let Some(duration) = SystemTime::now()
@@ -118,6 +118,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipAttestation {
                 attestation: Box::new(GossipAttestationPackage {
                     message_id,
@@ -164,6 +165,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let beacon_block_root = aggregate.message().aggregate().data().beacon_block_root;
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipAggregate {
                 aggregate: Box::new(GossipAggregatePackage {
                     message_id,
@@ -208,6 +210,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipBlock(Box::pin(process_fn)),
         })
     }
@@ -238,6 +241,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipBlobSidecar(Box::pin(process_fn)),
         })
     }
@@ -268,6 +272,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipDataColumnSidecar(Box::pin(process_fn)),
         })
     }
@@ -294,6 +299,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipSyncSignature(Box::new(process_fn)),
         })
     }
@@ -318,6 +324,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipSyncContribution(Box::new(process_fn)),
         })
     }
@@ -335,6 +342,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipVoluntaryExit(Box::new(process_fn)),
         })
     }
@@ -353,6 +361,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipProposerSlashing(Box::new(process_fn)),
         })
     }
@@ -377,6 +386,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipLightClientFinalityUpdate(Box::new(process_fn)),
         })
     }
@@ -403,6 +413,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::GossipLightClientOptimisticUpdate(Box::new(process_fn)),
         })
     }
@@ -421,6 +432,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipAttesterSlashing(Box::new(process_fn)),
         })
     }
@@ -443,6 +455,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::GossipBlsToExecutionChange(Box::new(process_fn)),
         })
     }
@@ -464,6 +477,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         );
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::RpcBlock { process_fn },
         })
     }
@@ -489,6 +503,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         );
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::RpcBlobs { process_fn },
         })
     }
@@ -505,6 +520,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let s = self.clone();
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::RpcCustodyColumn(Box::pin(async move {
                 s.process_rpc_custody_columns(
                     block_root,
@@ -529,6 +545,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let s = self.clone();
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::RpcVerifyDataColumn(Box::pin(async move {
                 let result = s
                     .clone()
@@ -548,6 +565,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let nbp = self.clone();
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work: Work::SamplingResult(Box::pin(async move {
                 nbp.process_sampling_completed(block_root).await;
             })),
@@ -589,6 +607,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: None,
             work,
         })
     }
@@ -604,6 +623,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::Status(Box::new(process_fn)),
         })
     }
@@ -632,6 +652,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::BlocksByRangeRequest(Box::pin(process_fn)),
         })
     }
@@ -660,6 +681,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::BlocksByRootsRequest(Box::pin(process_fn)),
         })
     }
@@ -686,6 +708,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::BlobsByRangeRequest(Box::new(process_fn)),
         })
     }
@@ -712,6 +735,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::BlobsByRootsRequest(Box::new(process_fn)),
         })
     }
@@ -738,6 +762,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::DataColumnsByRootsRequest(Box::new(process_fn)),
         })
     }
@@ -764,6 +789,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
+            peer_id: Some(peer_id),
             work: Work::DataColumnsByRangeRequest(Box::new(process_fn)),
         })
     }
@@ -790,6 +816,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::LightClientBootstrapRequest(Box::new(process_fn)),
         })
     }
@@ -814,6 +841,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::LightClientOptimisticUpdateRequest(Box::new(process_fn)),
         })
     }
@@ -838,6 +866,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::LightClientFinalityUpdateRequest(Box::new(process_fn)),
         })
     }
@@ -864,6 +893,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: true,
+            peer_id: Some(peer_id),
             work: Work::LightClientUpdatesByRangeRequest(Box::new(process_fn)),
         })
     }
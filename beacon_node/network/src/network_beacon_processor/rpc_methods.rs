@@ -206,12 +206,42 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     send_block_count += 1;
                 }
                 Ok(None) => {
-                    debug!(
-                        self.log,
-                        "Peer requested unknown block";
-                        "peer" => %peer_id,
-                        "request_root" => ?root
-                    );
+                    // Fall back to the historical block provider for blocks older than our
+                    // oldest block.
+                    let historical_block = self
+                        .chain
+                        .historical_block_provider
+                        .as_ref()
+                        .map(|provider| provider.block_by_root(&root));
+                    match historical_block {
+                        Some(Ok(Some(block))) => {
+                            self.send_response(
+                                peer_id,
+                                Response::BlocksByRoot(Some(block)),
+                                connection_id,
+                                substream_id,
+                                request_id,
+                            );
+                            send_block_count += 1;
+                        }
+                        Some(Err(e)) => {
+                            debug!(
+                                self.log,
+                                "Error fetching historical block for peer";
+                                "peer" => %peer_id,
+                                "request_root" => ?root,
+                                "error" => e,
+                            );
+                        }
+                        Some(Ok(None)) | None => {
+                            debug!(
+                                self.log,
+                                "Peer requested unknown block";
+                                "peer" => %peer_id,
+                                "request_root" => ?root
+                            );
+                        }
+                    }
                 }
                 Err(BeaconChainError::BlockHashMissingFromExecutionLayer(_)) => {
                     debug!(
@@ -677,10 +707,58 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             ));
         }
 
-        let forwards_block_root_iter = match self
+        // Serve the part of the range before our oldest block from the historical block provider,
+        // if there is one.
+        let start_slot = Slot::from(*req.start_slot());
+        let end_slot = start_slot.saturating_add(*req.count());
+        let oldest_block_slot = self.chain.store.get_oldest_block_slot();
+        let mut db_start_slot = start_slot;
+        let mut historical_blocks_sent = 0;
+        if let Some(provider) = self
             .chain
-            .forwards_iter_block_roots(Slot::from(*req.start_slot()))
+            .historical_block_provider
+            .as_ref()
+            .filter(|_| start_slot < oldest_block_slot)
         {
+            let count = std::cmp::min(end_slot, oldest_block_slot) - start_slot;
+            match provider.blocks_by_range(start_slot, count.as_u64()) {
+                Ok(blocks) => {
+                    for block in blocks {
+                        historical_blocks_sent += 1;
+                        self.send_network_message(NetworkMessage::SendResponse {
+                            peer_id,
+                            request_id,
+                            response: Response::BlocksByRange(Some(block)),
+                            id: (connection_id, substream_id),
+                        });
+                    }
+                }
+                Err(e) => {
+                    error!(self.log, "Unable to load historical blocks";
+                        "request" => ?req,
+                        "peer" => %peer_id,
+                        "error" => e
+                    );
+                    return Err((RpcErrorResponse::ServerError, "Historical block error"));
+                }
+            }
+
+            if end_slot <= oldest_block_slot {
+                debug!(
+                    self.log,
+                    "BlocksByRange outgoing response processed";
+                    "peer" => %peer_id,
+                    "start_slot" => req.start_slot(),
+                    "requested" => req.count(),
+                    "returned" => historical_blocks_sent,
+                    "source" => "historical_block_provider",
+                );
+                return Ok(());
+            }
+            db_start_slot = oldest_block_slot;
+        }
+
+        let forwards_block_root_iter = match self.chain.forwards_iter_block_roots(db_start_slot) {
             Ok(iter) => iter,
             Err(BeaconChainError::HistoricalBlockOutOfRange {
                 slot,
@@ -775,7 +853,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
 
         // Fetching blocks is async because it may have to hit the execution layer for payloads.
-        let mut blocks_sent = 0;
+        let mut blocks_sent = historical_blocks_sent;
         while let Some((root, result)) = block_stream.next().await {
             match result.as_ref() {
                 Ok(Some(block)) => {
@@ -9,13 +9,16 @@ use crate::{
     sync::{manager::BlockProcessType, SyncMessage},
 };
 use beacon_chain::block_verification_types::RpcBlock;
+use beacon_chain::historical_block_provider::HistoricalBlockProvider;
 use beacon_chain::test_utils::{
     test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
 use beacon_chain::{BeaconChain, WhenSlotSkipped};
 use beacon_processor::{work_reprocessing_queue::*, *};
 use lighthouse_network::discovery::ConnectionId;
-use lighthouse_network::rpc::methods::BlobsByRangeRequest;
+use lighthouse_network::rpc::methods::{
+    BlobsByRangeRequest, BlocksByRangeRequest, BlocksByRootRequest,
+};
 use lighthouse_network::rpc::{RequestId, SubstreamId};
 use lighthouse_network::{
     discv5::enr::{self, CombinedKey},
@@ -23,6 +26,7 @@ use lighthouse_network::{
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
     Client, MessageId, NetworkConfig, NetworkGlobals, PeerId, Response,
 };
+use parking_lot::Mutex;
 use slot_clock::SlotClock;
 use std::iter::Iterator;
 use std::sync::Arc;
@@ -83,23 +87,33 @@ impl TestRig {
         Self::new_parametric(
             chain_length,
             BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+            None,
         )
         .await
     }
 
-    pub async fn new_parametric(chain_length: u64, enable_backfill_rate_limiting: bool) -> Self {
+    pub async fn new_parametric(
+        chain_length: u64,
+        enable_backfill_rate_limiting: bool,
+        historical_block_provider: Option<Arc<dyn HistoricalBlockProvider<E>>>,
+    ) -> Self {
         // This allows for testing voluntary exits without building out a massive chain.
         let mut spec = test_spec::<E>();
         spec.shard_committee_period = 2;
         let spec = Arc::new(spec);
 
-        let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        let mut harness_builder = BeaconChainHarness::builder(MainnetEthSpec)
             .spec(spec.clone())
             .deterministic_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
             .mock_execution_layer()
-            .chain_config(<_>::default())
-            .build();
+            .chain_config(<_>::default());
+        if let Some(provider) = historical_block_provider {
+            harness_builder = harness_builder.initial_mutator(Box::new(move |builder| {
+                builder.historical_block_provider(provider)
+            }));
+        }
+        let harness = harness_builder.build();
 
         harness.advance_slot();
 
@@ -1096,7 +1110,7 @@ async fn test_backfill_sync_processing() {
 #[tokio::test]
 async fn test_backfill_sync_processing_rate_limiting_disabled() {
     let enable_backfill_rate_limiting = false;
-    let mut rig = TestRig::new_parametric(SMALL_CHAIN, enable_backfill_rate_limiting).await;
+    let mut rig = TestRig::new_parametric(SMALL_CHAIN, enable_backfill_rate_limiting, None).await;
 
     for _ in 0..3 {
         rig.enqueue_backfill_batch();
@@ -1153,3 +1167,147 @@ async fn test_blobs_by_range() {
     }
     assert_eq!(blob_count, actual_count);
 }
+
+/// Serves a fixed set of blocks and records the ranges requested from it.
+#[derive(Default)]
+struct TestBlockProvider {
+    blocks: Mutex<Vec<Arc<SignedBeaconBlock<E>>>>,
+    range_requests: Mutex<Vec<(Slot, u64)>>,
+}
+
+impl HistoricalBlockProvider<E> for TestBlockProvider {
+    fn blocks_by_range(
+        &self,
+        start_slot: Slot,
+        count: u64,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+        self.range_requests.lock().push((start_slot, count));
+        let end_slot = start_slot + count;
+        Ok(self
+            .blocks
+            .lock()
+            .iter()
+            .filter(|block| block.slot() >= start_slot && block.slot() < end_slot)
+            .cloned()
+            .collect())
+    }
+
+    fn block_by_root(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<Arc<SignedBeaconBlock<E>>>, String> {
+        Ok(self
+            .blocks
+            .lock()
+            .iter()
+            .find(|block| block.canonical_root() == *block_root)
+            .cloned())
+    }
+}
+
+impl TestRig {
+    /// Return the blocks sent in response to a blocks request, up to the stream termination.
+    async fn receive_blocks_response(&mut self) -> Vec<Arc<SignedBeaconBlock<E>>> {
+        let mut blocks = vec![];
+        while let Some(next) = self._network_rx.recv().await {
+            match next {
+                NetworkMessage::SendResponse {
+                    response: Response::BlocksByRange(block) | Response::BlocksByRoot(block),
+                    ..
+                } => match block {
+                    Some(block) => blocks.push(block),
+                    None => break,
+                },
+                other => panic!("unexpected message {:?}", other),
+            }
+        }
+        blocks
+    }
+}
+
+#[tokio::test]
+async fn test_blocks_by_range_historical_block_provider() {
+    let provider = Arc::new(TestBlockProvider::default());
+    let mut rig = TestRig::new_parametric(
+        LONG_CHAIN,
+        BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+        Some(provider.clone()),
+    )
+    .await;
+
+    // Pretend that the blocks before `oldest_block_slot` were pruned, and are only available from
+    // the provider.
+    let oldest_block_slot = Slot::new(SLOTS_PER_EPOCH / 2);
+    let mut canonical_blocks = vec![];
+    for slot in 0..SLOTS_PER_EPOCH {
+        let root = rig
+            .chain
+            .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        let block = Arc::new(rig.chain.get_block(&root).await.unwrap().unwrap());
+        if block.slot() < oldest_block_slot {
+            provider.blocks.lock().push(block.clone());
+        }
+        canonical_blocks.push(block);
+    }
+    let anchor = rig.chain.store.get_anchor_info();
+    let mut new_anchor = anchor.clone();
+    new_anchor.oldest_block_slot = oldest_block_slot;
+    rig.chain
+        .store
+        .compare_and_set_anchor_info_with_write(anchor, new_anchor)
+        .unwrap();
+
+    rig.network_beacon_processor
+        .send_blocks_by_range_request(
+            PeerId::random(),
+            ConnectionId::new_unchecked(42),
+            SubstreamId::new(24),
+            RequestId::new_unchecked(0),
+            BlocksByRangeRequest::new(0, SLOTS_PER_EPOCH),
+        )
+        .unwrap();
+
+    assert_eq!(rig.receive_blocks_response().await, canonical_blocks);
+    assert_eq!(
+        *provider.range_requests.lock(),
+        vec![(Slot::new(0), oldest_block_slot.as_u64())]
+    );
+}
+
+#[tokio::test]
+async fn test_blocks_by_root_historical_block_provider() {
+    let provider = Arc::new(TestBlockProvider::default());
+    let mut rig = TestRig::new_parametric(
+        SMALL_CHAIN,
+        BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+        Some(provider.clone()),
+    )
+    .await;
+
+    // The next block isn't in the database, so it can only be served by the provider.
+    let head_block = rig.chain.head_snapshot().beacon_block.clone();
+    let unknown_root = Hash256::repeat_byte(0xff);
+    provider.blocks.lock().push(rig.next_block.clone());
+    let block_roots = vec![
+        head_block.canonical_root(),
+        rig.next_block.canonical_root(),
+        unknown_root,
+    ];
+
+    rig.network_beacon_processor
+        .send_blocks_by_roots_request(
+            PeerId::random(),
+            ConnectionId::new_unchecked(42),
+            SubstreamId::new(24),
+            RequestId::new_unchecked(0),
+            BlocksByRootRequest::new(block_roots, &rig.chain.spec),
+        )
+        .unwrap();
+
+    assert_eq!(
+        rig.receive_blocks_response().await,
+        vec![head_block, rig.next_block.clone()]
+    );
+}
@@ -0,0 +1,89 @@
+use lighthouse_network::PersistedPeerDB;
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedPeers`. All zero because `PersistedPeers` has its own
+/// column.
+pub const PEERS_DB_KEY: Hash256 = Hash256::ZERO;
+
+/// Load the peer reputations persisted by a previous run of the node, if any.
+pub fn load_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Option<PersistedPeerDB> {
+    match store.get_item(&PEERS_DB_KEY) {
+        Ok(Some(p)) => {
+            let p: PersistedPeers = p;
+            Some(p.peer_db)
+        }
+        _ => None,
+    }
+}
+
+/// Attempt to persist the peer reputations to `self.store`.
+pub fn persist_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peer_db: PersistedPeerDB,
+) -> Result<(), store::Error> {
+    store.put_item(&PEERS_DB_KEY, &PersistedPeers { peer_db })
+}
+
+/// Wrapper around the peer reputations for persistence to disk.
+pub struct PersistedPeers {
+    pub peer_db: PersistedPeerDB,
+}
+
+impl StoreItem for PersistedPeers {
+    fn db_column() -> DBColumn {
+        DBColumn::PeerReputation
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.peer_db.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(PersistedPeers {
+            peer_db: PersistedPeerDB::from_ssz_bytes(bytes)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_network::peer_manager::peerdb::persisted::PersistedPeer;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::MemoryStore;
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal().into(), log)
+            .unwrap();
+        let store = Arc::new(store);
+        assert_eq!(load_peers(store.clone()), None);
+
+        let peer_db = PersistedPeerDB {
+            saved_at: 1_700_000_000,
+            peers: vec![PersistedPeer {
+                peer_id: lighthouse_network::PeerId::random().to_bytes(),
+                score: (-30.0f64).to_bits(),
+                lighthouse_score: (-30.0f64).to_bits(),
+                ban_remaining_secs: 0,
+                ban_reason: vec![],
+                seen_multiaddrs: vec![],
+                enr: vec![],
+            }],
+        };
+        persist_peers(store.clone(), peer_db.clone()).unwrap();
+        assert_eq!(load_peers(store), Some(peer_db));
+    }
+}
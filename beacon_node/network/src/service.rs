@@ -2,6 +2,7 @@ use crate::metrics;
 use crate::nat;
 use crate::network_beacon_processor::InvalidBlockStorage;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
+use crate::persisted_peers::{load_peers, persist_peers};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::{SubnetService, SubnetServiceMessage, Subscription};
 use crate::NetworkConfig;
@@ -11,6 +12,7 @@ use futures::channel::mpsc::Sender;
 use futures::future::OptionFuture;
 use futures::prelude::*;
 use futures::StreamExt;
+use lighthouse_network::rpc::config::RateLimitsFile;
 use lighthouse_network::rpc::{RequestId, RequestType};
 use lighthouse_network::service::Network;
 use lighthouse_network::types::GossipKind;
@@ -26,6 +28,8 @@ use lighthouse_network::{
 };
 use slog::{crit, debug, error, info, o, trace, warn};
 use std::collections::BTreeSet;
+#[cfg(target_family = "unix")]
+use std::path::PathBuf;
 use std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use strum::IntoStaticStr;
@@ -101,6 +105,8 @@ pub enum NetworkMessage<E: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Replaces the RPC rate limiter quotas with those from a reloaded rate limits file.
+    UpdateRateLimits { rate_limits: RateLimitsFile },
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -246,6 +252,11 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             );
         }
 
+        #[cfg(target_family = "unix")]
+        if let Some(path) = config.rate_limits_file.clone() {
+            spawn_rate_limits_reloader(path, network_senders.network_send(), &executor);
+        }
+
         // get a reference to the beacon chain store
         let store = beacon_chain.store.clone();
 
@@ -295,6 +306,19 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             }
         }
 
+        // Restore the scores and bans of the peers known before the last shutdown.
+        if let Some(persisted_peers) =
+            load_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone())
+        {
+            debug!(
+                network_log,
+                "Restoring persisted peers"; "peers" => persisted_peers.peers.len()
+            );
+            libp2p
+                .peer_manager_mut()
+                .restore_persisted_peers(persisted_peers);
+        }
+
         let invalid_block_storage = config
             .invalid_block_storage
             .clone()
@@ -674,6 +698,14 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdateRateLimits { rate_limits } => {
+                match self.libp2p.update_rate_limits(&rate_limits) {
+                    Ok(()) => {
+                        info!(self.log, "Updated RPC rate limits"; "rate_limits" => ?rate_limits)
+                    }
+                    Err(e) => warn!(self.log, "Failed to update RPC rate limits"; "error" => e),
+                }
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -945,6 +977,43 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     }
 }
 
+/// Reloads the RPC rate limits file each time the process receives a SIGHUP, passing the new
+/// quotas to the network service.
+#[cfg(target_family = "unix")]
+fn spawn_rate_limits_reloader<E: EthSpec>(
+    path: PathBuf,
+    network_send: mpsc::UnboundedSender<NetworkMessage<E>>,
+    executor: &task_executor::TaskExecutor,
+) {
+    let log = executor.log().clone();
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!(log, "Could not register SIGHUP handler"; "error" => %e);
+            return;
+        }
+    };
+    executor.spawn(
+        async move {
+            while hangup.recv().await.is_some() {
+                info!(log, "Reloading RPC rate limits"; "path" => ?path);
+                match RateLimitsFile::load(&path) {
+                    Ok(rate_limits) => {
+                        if network_send
+                            .send(NetworkMessage::UpdateRateLimits { rate_limits })
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Err(e) => warn!(log, "Failed to reload RPC rate limits"; "error" => e),
+                }
+            }
+        },
+        "rate_limits_reloader",
+    );
+}
+
 /// Returns a `Sleep` that triggers after the next change in the beacon chain fork version.
 /// If there is no scheduled fork, `None` is returned.
 fn next_fork_delay<T: BeaconChainTypes>(
@@ -995,6 +1064,19 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
                 "Saved DHT state";
             ),
         }
+
+        let persisted_peers = self.network_globals.peers.read().persisted_peers();
+        debug!(
+            self.log,
+            "Persisting peers to store";
+            "peers" => persisted_peers.peers.len(),
+        );
+        if let Err(e) = persist_peers::<T::EthSpec, T::HotStore, T::ColdStore>(
+            self.store.clone(),
+            persisted_peers,
+        ) {
+            error!(self.log, "Failed to persist peers on drop"; "error" => ?e);
+        }
         info!(self.log, "Network service shutdown");
     }
 }
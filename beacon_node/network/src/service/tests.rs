@@ -2,6 +2,7 @@
 #[cfg(test)]
 mod tests {
     use crate::persisted_dht::load_dht;
+    use crate::persisted_peers::load_peers;
     use crate::{NetworkConfig, NetworkService};
     use beacon_chain::test_utils::BeaconChainHarness;
     use beacon_chain::BeaconChainTypes;
@@ -102,7 +103,7 @@ mod tests {
         raw_runtime.shutdown_timeout(tokio::time::Duration::from_secs(300));
 
         // Load the persisted dht from the store
-        let persisted_enrs = load_dht(store);
+        let persisted_enrs = load_dht(store.clone());
         assert!(
             persisted_enrs.contains(&enrs[0]),
             "should have persisted the first ENR to store"
@@ -111,6 +112,12 @@ mod tests {
             persisted_enrs.contains(&enrs[1]),
             "should have persisted the second ENR to store"
         );
+
+        // The peer reputations are persisted alongside the DHT.
+        assert!(
+            load_peers(store).is_some(),
+            "should have persisted the peers to store"
+        );
     }
 
     // Test removing topic weight on old topics when a fork happens.
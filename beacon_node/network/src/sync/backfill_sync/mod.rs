@@ -159,10 +159,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     ) -> Self {
         // Determine if backfill is enabled or not.
         // If, for some reason a backfill has already been completed (or we've used a trusted
-        // genesis root) then backfill has been completed.
+        // genesis root) then backfill has been completed. Blocks which are backfilled from a
+        // trusted HTTP source are not fetched from peers.
         let anchor_info = beacon_chain.store.get_anchor_info();
         let (state, current_start) =
-            if anchor_info.block_backfill_complete(beacon_chain.genesis_backfill_slot) {
+            if anchor_info.block_backfill_complete(beacon_chain.network_backfill_target_slot()) {
                 (BackFillState::Completed, Epoch::new(0))
             } else {
                 (
@@ -273,7 +274,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             remaining: self
                 .current_start
                 .start_slot(T::EthSpec::slots_per_epoch())
-                .saturating_sub(self.beacon_chain.genesis_backfill_slot)
+                .saturating_sub(self.beacon_chain.network_backfill_target_slot())
                 .as_usize(),
         })
     }
@@ -1122,7 +1123,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     /// not required.
     fn reset_start_epoch(&mut self) -> Result<(), ResetEpochError> {
         let anchor_info = self.beacon_chain.store.get_anchor_info();
-        if anchor_info.block_backfill_complete(self.beacon_chain.genesis_backfill_slot) {
+        if anchor_info.block_backfill_complete(self.beacon_chain.network_backfill_target_slot()) {
             Err(ResetEpochError::SyncCompleted)
         } else {
             self.current_start = anchor_info
@@ -1138,7 +1139,8 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             // Check that the beacon chain agrees
             let anchor_info = self.beacon_chain.store.get_anchor_info();
             // Conditions that we have completed a backfill sync
-            if anchor_info.block_backfill_complete(self.beacon_chain.genesis_backfill_slot) {
+            if anchor_info.block_backfill_complete(self.beacon_chain.network_backfill_target_slot())
+            {
                 return true;
             } else {
                 error!(self.log, "Backfill out of sync with beacon chain");
@@ -1152,7 +1154,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         start_epoch
             <= self
                 .beacon_chain
-                .genesis_backfill_slot
+                .network_backfill_target_slot()
                 .epoch(T::EthSpec::slots_per_epoch())
     }
 
@@ -4,6 +4,8 @@ mod attester_slashing;
 mod bls_to_execution_changes;
 mod max_cover;
 mod metrics;
+mod overrides;
+mod packing;
 mod persistence;
 mod reward_cache;
 mod sync_aggregate_id;
@@ -12,8 +14,14 @@ pub use crate::bls_to_execution_changes::ReceivedPreCapella;
 pub use attestation::{earliest_attestation_validators, AttMaxCover};
 pub use attestation_storage::{CompactAttestationRef, SplitAttestation};
 pub use max_cover::MaxCover;
+pub use overrides::OperationOverrides;
+pub use packing::{
+    AttestationPacking, AttestationPackingOutcome, AttestationPackingStrategy, ExactPacking,
+    GreedyPacking, UniqueAttestersPacking, DEFAULT_EXACT_PACKING_TIME_BUDGET_MS,
+};
 pub use persistence::{
-    PersistedOperationPool, PersistedOperationPoolV15, PersistedOperationPoolV20,
+    DiscardedOperations, PersistedOperation, PersistedOperationKind, PersistedOperationPool,
+    PersistedOperationPoolV15, PersistedOperationPoolV20, PersistedOperationPoolV23,
 };
 pub use reward_cache::RewardCache;
 use state_processing::epoch_cache::is_epoch_cache_initialized;
@@ -24,7 +32,7 @@ use crate::bls_to_execution_changes::BlsToExecutionChanges;
 use crate::sync_aggregate_id::SyncAggregateId;
 use attester_slashing::AttesterSlashingMaxCover;
 use max_cover::maximum_cover;
-use parking_lot::{RwLock, RwLockWriteGuard};
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use state_processing::per_block_processing::errors::AttestationValidationError;
@@ -32,16 +40,20 @@ use state_processing::per_block_processing::{
     get_slashable_indices_modular, verify_exit, VerifySignatures,
 };
 use state_processing::{SigVerifiedOp, VerifyOperation};
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
+use tree_hash::TreeHash;
 use types::{
     sync_aggregate::Error as SyncAggregateError, typenum::Unsigned, AbstractExecPayload,
     Attestation, AttestationData, AttesterSlashing, BeaconState, BeaconStateError, ChainSpec,
-    Epoch, EthSpec, ProposerSlashing, SignedBeaconBlock, SignedBlsToExecutionChange,
+    Epoch, EthSpec, Hash256, ProposerSlashing, SignedBeaconBlock, SignedBlsToExecutionChange,
     SignedVoluntaryExit, Slot, SyncAggregate, SyncCommitteeContribution, Validator,
 };
 
+/// Number of slots for which to keep the outcomes of attestation packing.
+const PACKING_OUTCOMES_RETAINED_SLOTS: u64 = 8192;
+
 type SyncContributions<E> = RwLock<HashMap<SyncAggregateId, Vec<SyncCommitteeContribution<E>>>>;
 
 #[derive(Default, Debug)]
@@ -60,6 +72,13 @@ pub struct OperationPool<E: EthSpec + Default> {
     bls_to_execution_changes: RwLock<BlsToExecutionChanges<E>>,
     /// Reward cache for accelerating attestation packing.
     reward_cache: RwLock<RewardCache>,
+    /// Operations forced into, or kept out of, the next block.
+    overrides: RwLock<OperationOverrides>,
+    /// Strategy for packing attestations into blocks.
+    attestation_packing: RwLock<AttestationPacking>,
+    /// How well attestations were packed into recent blocks produced by this node, by slot and
+    /// parent root.
+    packing_outcomes: RwLock<BTreeMap<(Slot, Hash256), AttestationPackingOutcome>>,
     _phantom: PhantomData<E>,
 }
 
@@ -217,6 +236,26 @@ impl<E: EthSpec> OperationPool<E> {
         self.attestations.read().stats()
     }
 
+    /// Check that the epoch cache of `state` is initialized and update the reward cache for it.
+    fn prepare_reward_cache(
+        &self,
+        state: &BeaconState<E>,
+    ) -> Result<RwLockReadGuard<RewardCache>, OpPoolError> {
+        if !matches!(state, BeaconState::Base(_)) {
+            // Epoch cache must be initialized to fetch base reward values in the max cover `score`
+            // function. Currently max cover ignores items on errors. If epoch cache is not
+            // initialized, this function returns an error.
+            if !is_epoch_cache_initialized(state).map_err(OpPoolError::EpochCacheError)? {
+                return Err(OpPoolError::EpochCacheNotInitialized);
+            }
+        }
+
+        let _timer = metrics::start_timer(&metrics::BUILD_REWARD_CACHE_TIME);
+        let mut reward_cache = self.reward_cache.write();
+        reward_cache.update(state)?;
+        Ok(RwLockWriteGuard::downgrade(reward_cache))
+    }
+
     /// Return all valid attestations for the given epoch, for use in max cover.
     #[allow(clippy::too_many_arguments)]
     fn get_valid_attestations_for_epoch<'a>(
@@ -231,10 +270,7 @@ impl<E: EthSpec> OperationPool<E> {
     ) -> impl Iterator<Item = AttMaxCover<'a, E>> + Send {
         all_attestations
             .get_attestations(checkpoint_key)
-            .filter(|att| {
-                att.data.slot + spec.min_attestation_inclusion_delay <= state.slot()
-                    && state.slot() <= att.data.slot + E::slots_per_epoch()
-            })
+            .filter(|att| is_within_inclusion_window(att, state, spec))
             .filter(validity_filter)
             .filter_map(move |att| {
                 AttMaxCover::new(att, state, reward_cache, total_active_balance, spec)
@@ -247,6 +283,8 @@ impl<E: EthSpec> OperationPool<E> {
     /// before an approximately optimal bundle is constructed. We use it to provide access
     /// to the fork choice data from the `BeaconChain` struct that doesn't logically belong
     /// in the operation pool.
+    ///
+    /// Attestations forced in by the overrides are packed first, regardless of their score.
     pub fn get_attestations(
         &self,
         state: &BeaconState<E>,
@@ -254,15 +292,25 @@ impl<E: EthSpec> OperationPool<E> {
         curr_epoch_validity_filter: impl for<'a> FnMut(&CompactAttestationRef<'a, E>) -> bool + Send,
         spec: &ChainSpec,
     ) -> Result<Vec<Attestation<E>>, OpPoolError> {
+        self.pack_attestations(
+            state,
+            prev_epoch_validity_filter,
+            curr_epoch_validity_filter,
+            spec,
+        )
+        .map(|(attestations, _)| attestations)
+    }
+
+    /// As `get_attestations`, also returning how well the attestations were packed according to
+    /// the configured `AttestationPacking` strategy.
+    pub fn pack_attestations(
+        &self,
+        state: &BeaconState<E>,
+        mut prev_epoch_validity_filter: impl for<'a> FnMut(&CompactAttestationRef<'a, E>) -> bool + Send,
+        mut curr_epoch_validity_filter: impl for<'a> FnMut(&CompactAttestationRef<'a, E>) -> bool + Send,
+        spec: &ChainSpec,
+    ) -> Result<(Vec<Attestation<E>>, AttestationPackingOutcome), OpPoolError> {
         let fork_name = state.fork_name_unchecked();
-        if !matches!(state, BeaconState::Base(_)) {
-            // Epoch cache must be initialized to fetch base reward values in the max cover `score`
-            // function. Currently max cover ignores items on errors. If epoch cache is not
-            // initialized, this function returns an error.
-            if !is_epoch_cache_initialized(state).map_err(OpPoolError::EpochCacheError)? {
-                return Err(OpPoolError::EpochCacheNotInitialized);
-            }
-        }
 
         // Attestations for the current fork, which may be from the current or previous epoch.
         let (prev_epoch_key, curr_epoch_key) = CheckpointKey::keys_for_state(state);
@@ -270,12 +318,8 @@ impl<E: EthSpec> OperationPool<E> {
             .get_total_active_balance()
             .map_err(OpPoolError::GetAttestationsTotalBalanceError)?;
 
-        // Update the reward cache.
-        let reward_timer = metrics::start_timer(&metrics::BUILD_REWARD_CACHE_TIME);
-        let mut reward_cache = self.reward_cache.write();
-        reward_cache.update(state)?;
-        let reward_cache = RwLockWriteGuard::downgrade(reward_cache);
-        drop(reward_timer);
+        let reward_cache = self.prepare_reward_cache(state)?;
+        let overrides = self.overrides.read().clone();
 
         // Split attestations for the previous & current epochs, so that we
         // can optimise them individually in parallel.
@@ -292,6 +336,62 @@ impl<E: EthSpec> OperationPool<E> {
 
         let all_attestations = parking_lot::RwLockWriteGuard::downgrade(all_attestations);
 
+        let curr_epoch_limit = if fork_name.electra_enabled() {
+            E::MaxAttestationsElectra::to_usize()
+        } else {
+            E::MaxAttestations::to_usize()
+        };
+        let prev_epoch_limit = if let BeaconState::Base(base_state) = state {
+            std::cmp::min(
+                E::MaxPendingAttestations::to_usize()
+                    .saturating_sub(base_state.previous_epoch_attestations.len()),
+                E::MaxAttestations::to_usize(),
+            )
+        } else {
+            curr_epoch_limit
+        };
+
+        // Attestations forced in by an override take up space before the others are packed.
+        let mut forced_prev = vec![];
+        let mut forced_curr = vec![];
+        if !overrides.include.is_empty() {
+            if prev_epoch_key != curr_epoch_key {
+                forced_prev = self
+                    .get_valid_attestations_for_epoch(
+                        &prev_epoch_key,
+                        &*all_attestations,
+                        state,
+                        &reward_cache,
+                        total_active_balance,
+                        |att| {
+                            overrides.includes_attestation(att) && prev_epoch_validity_filter(att)
+                        },
+                        spec,
+                    )
+                    .take(prev_epoch_limit)
+                    .collect::<Vec<_>>();
+            }
+            forced_curr = self
+                .get_valid_attestations_for_epoch(
+                    &curr_epoch_key,
+                    &*all_attestations,
+                    state,
+                    &reward_cache,
+                    total_active_balance,
+                    |att| overrides.includes_attestation(att) && curr_epoch_validity_filter(att),
+                    spec,
+                )
+                .collect::<Vec<_>>();
+        }
+        let forced = forced_curr
+            .iter()
+            .chain(&forced_prev)
+            .take(curr_epoch_limit)
+            .cloned()
+            .collect::<Vec<_>>();
+        let prev_epoch_limit = prev_epoch_limit.saturating_sub(forced_prev.len());
+        let curr_epoch_limit = curr_epoch_limit.saturating_sub(forced.len());
+
         let prev_epoch_att = self
             .get_valid_attestations_for_epoch(
                 &prev_epoch_key,
@@ -299,9 +399,10 @@ impl<E: EthSpec> OperationPool<E> {
                 state,
                 &reward_cache,
                 total_active_balance,
-                prev_epoch_validity_filter,
+                |att| overrides.leaves_attestation(att) && prev_epoch_validity_filter(att),
                 spec,
             )
+            .map(|att| without_covered_validators(att, &forced_prev))
             .inspect(|_| num_prev_valid += 1);
         let curr_epoch_att = self
             .get_valid_attestations_for_epoch(
@@ -310,26 +411,14 @@ impl<E: EthSpec> OperationPool<E> {
                 state,
                 &reward_cache,
                 total_active_balance,
-                curr_epoch_validity_filter,
+                |att| overrides.leaves_attestation(att) && curr_epoch_validity_filter(att),
                 spec,
             )
+            .map(|att| without_covered_validators(att, &forced_curr))
             .inspect(|_| num_curr_valid += 1);
 
-        let curr_epoch_limit = if fork_name.electra_enabled() {
-            E::MaxAttestationsElectra::to_usize()
-        } else {
-            E::MaxAttestations::to_usize()
-        };
-        let prev_epoch_limit = if let BeaconState::Base(base_state) = state {
-            std::cmp::min(
-                E::MaxPendingAttestations::to_usize()
-                    .saturating_sub(base_state.previous_epoch_attestations.len()),
-                E::MaxAttestations::to_usize(),
-            )
-        } else {
-            curr_epoch_limit
-        };
-
+        let strategy = self.attestation_packing.read().strategy::<E>();
+        let strategy = &*strategy;
         let (prev_cover, curr_cover) = rayon::join(
             move || {
                 let _timer = metrics::start_timer(&metrics::ATTESTATION_PREV_EPOCH_PACKING_TIME);
@@ -337,23 +426,88 @@ impl<E: EthSpec> OperationPool<E> {
                 if prev_epoch_key == curr_epoch_key {
                     vec![]
                 } else {
-                    maximum_cover(prev_epoch_att, prev_epoch_limit, "prev_epoch_attestations")
+                    strategy.pack(
+                        prev_epoch_att.collect(),
+                        prev_epoch_limit,
+                        "prev_epoch_attestations",
+                    )
                 }
             },
             move || {
                 let _timer = metrics::start_timer(&metrics::ATTESTATION_CURR_EPOCH_PACKING_TIME);
-                maximum_cover(curr_epoch_att, curr_epoch_limit, "curr_epoch_attestations")
+                strategy.pack(
+                    curr_epoch_att.collect(),
+                    curr_epoch_limit,
+                    "curr_epoch_attestations",
+                )
             },
         );
 
         metrics::set_gauge(&metrics::NUM_PREV_EPOCH_ATTESTATIONS, num_prev_valid);
         metrics::set_gauge(&metrics::NUM_CURR_EPOCH_ATTESTATIONS, num_curr_valid);
 
-        Ok(max_cover::merge_solutions(
+        let candidates = forced.len() + (num_prev_valid + num_curr_valid) as usize;
+        let mut packed = forced;
+        packed.extend(max_cover::merge_solutions(
             curr_cover,
             prev_cover,
             curr_epoch_limit,
-        ))
+            |att| strategy.score(att),
+        ));
+        let outcome = AttestationPackingOutcome::new(strategy.name(), candidates, &packed);
+        let attestations = packed
+            .iter()
+            .map(|att| att.att.clone_as_attestation())
+            .collect();
+        Ok((attestations, outcome))
+    }
+
+    /// Return every attestation in the pool with its max-cover score for a block built on
+    /// `state`, or `None` if it can't be included in such a block.
+    ///
+    /// The score is the proposer reward for the attestation in isolation, in gwei. Overrides are
+    /// ignored.
+    pub fn get_attestation_scores(
+        &self,
+        state: &BeaconState<E>,
+        mut prev_epoch_validity_filter: impl for<'a> FnMut(&CompactAttestationRef<'a, E>) -> bool,
+        mut curr_epoch_validity_filter: impl for<'a> FnMut(&CompactAttestationRef<'a, E>) -> bool,
+        spec: &ChainSpec,
+    ) -> Result<Vec<(Attestation<E>, Option<u64>)>, OpPoolError> {
+        let (prev_epoch_key, curr_epoch_key) = CheckpointKey::keys_for_state(state);
+        let total_active_balance = state
+            .get_total_active_balance()
+            .map_err(OpPoolError::GetAttestationsTotalBalanceError)?;
+        let reward_cache = self.prepare_reward_cache(state)?;
+
+        Ok(self
+            .attestations
+            .read()
+            .iter()
+            .map(|att| {
+                let valid = is_within_inclusion_window(&att, state, spec)
+                    && if *att.checkpoint == curr_epoch_key {
+                        curr_epoch_validity_filter(&att)
+                    } else if *att.checkpoint == prev_epoch_key {
+                        prev_epoch_validity_filter(&att)
+                    } else {
+                        false
+                    };
+                let score = if valid {
+                    AttMaxCover::new(
+                        att.clone(),
+                        state,
+                        &reward_cache,
+                        total_active_balance,
+                        spec,
+                    )
+                    .map(|cover| cover.score() as u64)
+                } else {
+                    None
+                };
+                (att.clone_as_attestation(), score)
+            })
+            .collect())
     }
 
     /// Remove attestations which are too old to be included in a block.
@@ -394,8 +548,15 @@ impl<E: EthSpec> OperationPool<E> {
         Vec<AttesterSlashing<E>>,
         Vec<SignedVoluntaryExit>,
     ) {
+        let all_proposer_slashings = self.proposer_slashings.read();
+        let (forced, rest) = self
+            .overrides
+            .read()
+            .partition(all_proposer_slashings.values(), |slashing| {
+                slashing.as_inner().tree_hash_root()
+            });
         let proposer_slashings = filter_limit_operations(
-            self.proposer_slashings.read().values(),
+            forced.into_iter().chain(rest),
             |slashing| {
                 slashing.signature_is_still_valid(&state.fork())
                     && state
@@ -434,8 +595,28 @@ impl<E: EthSpec> OperationPool<E> {
         to_be_slashed: &mut HashSet<u64>,
     ) -> Vec<AttesterSlashing<E>> {
         let reader = self.attester_slashings.read();
+        let (forced, rest) = self.overrides.read().partition(reader.iter(), |slashing| {
+            slashing.as_inner().tree_hash_root()
+        });
+
+        // Slashings forced in by an override are packed first, provided they still slash someone.
+        let mut attester_slashings = vec![];
+        for slashing in forced {
+            if attester_slashings.len() >= E::MaxAttesterSlashings::to_usize()
+                || !slashing.signature_is_still_valid(&state.fork())
+            {
+                continue;
+            }
+            if let Some(cover) =
+                AttesterSlashingMaxCover::new(slashing.as_inner().to_ref(), to_be_slashed, state)
+                    .filter(|cover| !cover.covering_set().is_empty())
+            {
+                to_be_slashed.extend(cover.covering_set().keys());
+                attester_slashings.push(slashing.as_inner().clone());
+            }
+        }
 
-        let relevant_attester_slashings = reader.iter().flat_map(|slashing| {
+        let relevant_attester_slashings = rest.into_iter().flat_map(|slashing| {
             if slashing.signature_is_still_valid(&state.fork()) {
                 AttesterSlashingMaxCover::new(slashing.as_inner().to_ref(), to_be_slashed, state)
             } else {
@@ -443,17 +624,16 @@ impl<E: EthSpec> OperationPool<E> {
             }
         });
 
-        maximum_cover(
+        let covers = maximum_cover(
             relevant_attester_slashings,
-            E::MaxAttesterSlashings::to_usize(),
+            E::MaxAttesterSlashings::to_usize() - attester_slashings.len(),
             "attester_slashings",
-        )
-        .into_iter()
-        .map(|cover| {
+        );
+        attester_slashings.extend(covers.into_iter().map(|cover| {
             to_be_slashed.extend(cover.covering_set().keys());
             AttesterSlashingMaxCover::convert_to_object(cover.intermediate())
-        })
-        .collect()
+        }));
+        attester_slashings
     }
 
     /// Prune proposer slashings for validators which are exited in the finalized epoch.
@@ -517,8 +697,13 @@ impl<E: EthSpec> OperationPool<E> {
     where
         F: Fn(&SignedVoluntaryExit) -> bool,
     {
+        let all_exits = self.voluntary_exits.read();
+        let (forced, rest) = self
+            .overrides
+            .read()
+            .partition(all_exits.values(), |exit| exit.as_inner().tree_hash_root());
         filter_limit_operations(
-            self.voluntary_exits.read().values(),
+            forced.into_iter().chain(rest),
             |exit| {
                 filter(exit.as_inner())
                     && exit.signature_is_still_valid(&state.fork())
@@ -577,8 +762,15 @@ impl<E: EthSpec> OperationPool<E> {
         state: &BeaconState<E>,
         spec: &ChainSpec,
     ) -> Vec<SignedBlsToExecutionChange> {
+        let all_changes = self.bls_to_execution_changes.read();
+        let (forced, rest) = self
+            .overrides
+            .read()
+            .partition(all_changes.iter_lifo(), |address_change| {
+                address_change.as_inner().tree_hash_root()
+            });
         filter_limit_operations(
-            self.bls_to_execution_changes.read().iter_lifo(),
+            forced.into_iter().chain(rest),
             |address_change| {
                 address_change.signature_is_still_valid(&state.fork())
                     && state
@@ -730,6 +922,94 @@ impl<E: EthSpec> OperationPool<E> {
             .map(|address_change| address_change.as_inner().clone())
             .collect()
     }
+
+    /// Returns the operations forced into, or kept out of, the next block.
+    pub fn overrides(&self) -> OperationOverrides {
+        self.overrides.read().clone()
+    }
+
+    /// Replace the operations forced into, or kept out of, the next block.
+    pub fn set_overrides(&self, overrides: OperationOverrides) {
+        *self.overrides.write() = overrides;
+    }
+
+    /// Clear the overrides once `applied` has been packed into a block, unless they've been
+    /// replaced since.
+    pub fn clear_overrides(&self, applied: &OperationOverrides) {
+        let mut overrides = self.overrides.write();
+        if *overrides == *applied {
+            *overrides = OperationOverrides::default();
+        }
+    }
+
+    /// Returns the strategy used to pack attestations into blocks.
+    pub fn attestation_packing(&self) -> AttestationPacking {
+        *self.attestation_packing.read()
+    }
+
+    /// Set the strategy used to pack attestations into blocks.
+    pub fn set_attestation_packing(&self, attestation_packing: AttestationPacking) {
+        *self.attestation_packing.write() = attestation_packing;
+    }
+
+    /// Record the outcome of packing attestations into a block at `slot` on top of `parent_root`.
+    pub fn record_packing_outcome(
+        &self,
+        slot: Slot,
+        parent_root: Hash256,
+        outcome: AttestationPackingOutcome,
+    ) {
+        metrics::set_int_gauge(
+            &metrics::ATTESTATION_PACKING_ATTESTERS,
+            &[outcome.strategy],
+            outcome.attesters as i64,
+        );
+        metrics::set_int_gauge(
+            &metrics::ATTESTATION_PACKING_REWARD,
+            &[outcome.strategy],
+            outcome.reward as i64,
+        );
+
+        let mut outcomes = self.packing_outcomes.write();
+        outcomes.insert((slot, parent_root), outcome);
+        let oldest_slot = slot.saturating_sub(PACKING_OUTCOMES_RETAINED_SLOTS);
+        outcomes.retain(|(outcome_slot, _), _| *outcome_slot >= oldest_slot);
+    }
+
+    /// Returns the outcome of packing attestations into a block at `slot` on top of
+    /// `parent_root`, if this node produced such a block recently.
+    pub fn packing_outcome(
+        &self,
+        slot: Slot,
+        parent_root: Hash256,
+    ) -> Option<AttestationPackingOutcome> {
+        self.packing_outcomes
+            .read()
+            .get(&(slot, parent_root))
+            .cloned()
+    }
+}
+
+/// Remove the validators covered by `packed` attestations from the covering set of `att`.
+fn without_covered_validators<'a, E: EthSpec>(
+    mut att: AttMaxCover<'a, E>,
+    packed: &[AttMaxCover<'a, E>],
+) -> AttMaxCover<'a, E> {
+    for packed_att in packed {
+        att.update_covering_set(packed_att.intermediate(), packed_att.covering_set());
+    }
+    att
+}
+
+/// Return `true` if `att` is old enough, and recent enough, to be included in a block built on
+/// `state`.
+fn is_within_inclusion_window<E: EthSpec>(
+    att: &CompactAttestationRef<E>,
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+) -> bool {
+    att.data.slot + spec.min_attestation_inclusion_delay <= state.slot()
+        && state.slot() <= att.data.slot + E::slots_per_epoch()
 }
 
 /// Filter up to a maximum number of operations out of an iterator.
@@ -797,10 +1077,12 @@ mod release_tests {
         test_spec, BeaconChainHarness, EphemeralHarnessType, RelativeSyncCommittee,
     };
     use maplit::hashset;
+    use ssz::Encode;
     use state_processing::epoch_cache::initialize_epoch_cache;
     use state_processing::{common::get_attesting_indices_from_state, VerifyOperation};
     use std::collections::BTreeSet;
     use std::sync::{Arc, LazyLock};
+    use store::StoreItem;
     use types::consts::altair::SYNC_COMMITTEE_SUBNET_COUNT;
     use types::*;
 
@@ -1605,6 +1887,150 @@ mod release_tests {
         assert_eq!(best_slashings.1, vec![slashing_2, slashing_3]);
     }
 
+    // Overrides take precedence over max coverage, and only apply to the next block.
+    #[test]
+    fn overrides_attester_slashings() {
+        let harness = get_harness(32, None);
+        let spec = &harness.spec;
+        let state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slashing_1 = harness.make_attester_slashing(vec![1]);
+        let slashing_2 = harness.make_attester_slashing(vec![2, 3]);
+        let slashing_3 = harness.make_attester_slashing(vec![4, 5, 6]);
+        let slashing_4 = harness.make_attester_slashing(vec![7, 8, 9, 10]);
+
+        op_pool.insert_attester_slashing(slashing_1.clone().validate(&state, spec).unwrap());
+        op_pool.insert_attester_slashing(slashing_2.clone().validate(&state, spec).unwrap());
+        op_pool.insert_attester_slashing(slashing_3.clone().validate(&state, spec).unwrap());
+        op_pool.insert_attester_slashing(slashing_4.clone().validate(&state, spec).unwrap());
+
+        let overrides = OperationOverrides {
+            include: hashset! {slashing_1.tree_hash_root()},
+            exclude: hashset! {slashing_4.tree_hash_root()},
+        };
+        op_pool.set_overrides(overrides.clone());
+        let best_slashings = op_pool.get_slashings_and_exits(&state, spec);
+        assert_eq!(best_slashings.1, vec![slashing_1, slashing_3.clone()]);
+
+        // Overrides which were replaced after packing are kept.
+        op_pool.clear_overrides(&OperationOverrides::default());
+        assert_eq!(op_pool.overrides(), overrides);
+
+        op_pool.clear_overrides(&overrides);
+        let best_slashings = op_pool.get_slashings_and_exits(&state, spec);
+        assert_eq!(best_slashings.1, vec![slashing_4, slashing_3]);
+    }
+
+    #[test]
+    fn overrides_exclude_proposer_slashing() {
+        let harness = get_harness(32, None);
+        let spec = &harness.spec;
+        let state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slashing_1 = harness.make_proposer_slashing(0);
+        let slashing_2 = harness.make_proposer_slashing(1);
+        op_pool.insert_proposer_slashing(slashing_1.clone().validate(&state, spec).unwrap());
+        op_pool.insert_proposer_slashing(slashing_2.clone().validate(&state, spec).unwrap());
+
+        op_pool.set_overrides(OperationOverrides {
+            include: HashSet::new(),
+            exclude: hashset! {slashing_1.tree_hash_root()},
+        });
+        assert_eq!(
+            op_pool.get_slashings_and_exits(&state, spec).0,
+            vec![slashing_2]
+        );
+    }
+
+    #[test]
+    fn attestation_packing_strategies() {
+        let step_sizes = [2, 4];
+        let num_committees = 4;
+
+        let (harness, ref spec) = attestation_test_state::<MainnetEthSpec>(num_committees);
+        let mut state = get_current_state_initialize_epoch_cache(&harness, &spec);
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slot = state.slot();
+        let num_validators = num_committees
+            * MainnetEthSpec::slots_per_epoch() as usize
+            * spec.target_committee_size;
+        let attestations = harness.make_attestations(
+            (0..num_validators).collect::<Vec<_>>().as_slice(),
+            &state,
+            Hash256::zero(),
+            SignedBeaconBlockHash::from(Hash256::zero()),
+            slot,
+        );
+
+        // Overlapping aggregates of different sizes, so that strategies have a choice to make.
+        for (atts, _) in attestations {
+            for step_size in step_sizes {
+                for chunk in atts.chunks_exact(step_size) {
+                    let mut agg = chunk[0].0.clone();
+                    for (att, _) in &chunk[1..] {
+                        agg.aggregate(att.to_ref());
+                    }
+                    let attesting_indices =
+                        get_attesting_indices_from_state(&state, agg.to_ref()).unwrap();
+                    op_pool.insert_attestation(agg, attesting_indices).unwrap();
+                }
+            }
+        }
+
+        *state.slot_mut() += spec.min_attestation_inclusion_delay;
+        let pack = |packing| {
+            op_pool.set_attestation_packing(packing);
+            let (attestations, outcome) = op_pool
+                .pack_attestations(&state, |_| true, |_| true, spec)
+                .unwrap();
+            assert_eq!(outcome.packed, attestations.len());
+            assert!(outcome.candidates >= outcome.packed);
+            assert!(outcome.attesters > 0);
+            outcome
+        };
+
+        let greedy = pack(AttestationPacking::Greedy);
+        assert_eq!(greedy.strategy, "greedy");
+
+        let exact = pack(AttestationPacking::Exact {
+            time_budget_ms: 100,
+        });
+        assert_eq!(exact.strategy, "exact");
+        assert!(exact.reward >= greedy.reward);
+
+        let unique = pack(AttestationPacking::UniqueAttesters);
+        assert_eq!(unique.strategy, "unique_attesters");
+        assert!(unique.attesters >= greedy.attesters);
+    }
+
+    #[test]
+    fn packing_outcomes_are_pruned() {
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        let outcome = AttestationPackingOutcome {
+            strategy: "greedy",
+            candidates: 2,
+            packed: 1,
+            attesters: 3,
+            reward: 4,
+        };
+        let parent_root = Hash256::repeat_byte(1);
+
+        op_pool.record_packing_outcome(Slot::new(1), parent_root, outcome.clone());
+        assert_eq!(
+            op_pool.packing_outcome(Slot::new(1), parent_root),
+            Some(outcome.clone())
+        );
+        assert_eq!(op_pool.packing_outcome(Slot::new(1), Hash256::zero()), None);
+
+        let later = Slot::new(1 + PACKING_OUTCOMES_RETAINED_SLOTS + 1);
+        op_pool.record_packing_outcome(later, parent_root, outcome.clone());
+        assert_eq!(op_pool.packing_outcome(Slot::new(1), parent_root), None);
+        assert_eq!(op_pool.packing_outcome(later, parent_root), Some(outcome));
+    }
+
     /// End-to-end test of basic sync contribution handling.
     #[tokio::test]
     async fn sync_contribution_aggregation_insert_get_prune() {
@@ -2167,4 +2593,148 @@ mod release_tests {
         op_pool.prune_attester_slashings(&bellatrix_head.beacon_state);
         assert_eq!(op_pool.attester_slashings.read().len(), 1);
     }
+
+    /// Build a pool containing a proposer slashing and an attester slashing.
+    fn persistence_test_pool(
+        harness: &BeaconChainHarness<EphemeralHarnessType<MainnetEthSpec>>,
+    ) -> OperationPool<MainnetEthSpec> {
+        let state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        op_pool.insert_proposer_slashing(
+            harness
+                .make_proposer_slashing(0)
+                .validate(&state, &harness.spec)
+                .unwrap(),
+        );
+        op_pool.insert_attester_slashing(
+            harness
+                .make_attester_slashing(vec![1, 3, 5])
+                .validate(&state, &harness.spec)
+                .unwrap(),
+        );
+        op_pool
+    }
+
+    fn persisted_v23(
+        op_pool: &OperationPool<MainnetEthSpec>,
+    ) -> PersistedOperationPoolV23<MainnetEthSpec> {
+        match PersistedOperationPool::from_operation_pool(op_pool) {
+            PersistedOperationPool::V23(pool_v23) => pool_v23,
+            _ => panic!("op pool should be persisted as V23"),
+        }
+    }
+
+    #[test]
+    fn persisted_op_pool_round_trip() {
+        let harness = get_harness(32, None);
+        let op_pool = persistence_test_pool(&harness);
+
+        let bytes = PersistedOperationPool::from_operation_pool(&op_pool).as_store_bytes();
+        let (restored, discarded) =
+            PersistedOperationPool::<MainnetEthSpec>::from_store_bytes(&bytes)
+                .unwrap()
+                .into_operation_pool_with_discarded()
+                .unwrap();
+
+        assert!(discarded.is_empty());
+        assert_eq!(restored, op_pool);
+        assert_eq!(restored.num_proposer_slashings(), 1);
+        assert_eq!(restored.num_attester_slashings(), 1);
+    }
+
+    #[test]
+    fn persisted_op_pool_v20_upgrade() {
+        let harness = get_harness(32, None);
+        let op_pool = persistence_test_pool(&harness);
+
+        let (pool_v20, discarded) = persisted_v23(&op_pool).decode_operations();
+        assert!(discarded.is_empty());
+
+        // Upgrading the previous format must preserve every operation.
+        let pool_v23 = PersistedOperationPoolV23::from(pool_v20.clone());
+        assert_eq!(pool_v23.operations.len(), 2);
+        let upgraded = PersistedOperationPool::V23(pool_v23)
+            .into_operation_pool()
+            .unwrap();
+        assert_eq!(upgraded, op_pool);
+        assert_eq!(
+            PersistedOperationPool::V20(pool_v20)
+                .into_operation_pool()
+                .unwrap(),
+            op_pool
+        );
+    }
+
+    #[test]
+    fn persisted_op_pool_discards_undecodable_operations() {
+        let harness = get_harness(32, None);
+        let op_pool = persistence_test_pool(&harness);
+
+        let mut pool_v23 = persisted_v23(&op_pool);
+        pool_v23.operations.push(PersistedOperation {
+            kind: PersistedOperationKind::VoluntaryExit.as_u8(),
+            version: PersistedOperationKind::VoluntaryExit.current_version(),
+            bytes: vec![1, 2, 3],
+        });
+        pool_v23.operations.push(PersistedOperation {
+            kind: PersistedOperationKind::ProposerSlashing.as_u8(),
+            version: u64::MAX,
+            bytes: vec![],
+        });
+        pool_v23.operations.push(PersistedOperation {
+            kind: u8::MAX,
+            version: 0,
+            bytes: vec![],
+        });
+
+        let (restored, discarded) = PersistedOperationPool::V23(pool_v23)
+            .into_operation_pool_with_discarded()
+            .unwrap();
+
+        assert_eq!(
+            discarded,
+            DiscardedOperations {
+                voluntary_exits: 1,
+                proposer_slashings: 1,
+                unknown: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(discarded.total(), 3);
+        assert_eq!(
+            discarded.to_string(),
+            "proposer_slashings: 1, voluntary_exits: 1, unknown: 1"
+        );
+        assert_eq!(restored, op_pool);
+    }
+
+    #[test]
+    fn persisted_op_pool_upgrades_old_encodings() {
+        let spec = ForkName::Base.make_genesis_spec(MainnetEthSpec::default_spec());
+        let harness = get_harness(32, Some(spec));
+        let state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slashing = harness
+            .make_attester_slashing(vec![1, 3, 5])
+            .validate(&state, &harness.spec)
+            .unwrap();
+        op_pool.insert_attester_slashing(slashing.clone());
+
+        // Store the slashing using the base-only encoding introduced in schema v15.
+        let slashing_v15: SigVerifiedOp<AttesterSlashingBase<MainnetEthSpec>, MainnetEthSpec> =
+            slashing.try_into().unwrap();
+        let mut pool_v23 = persisted_v23(&OperationPool::new());
+        pool_v23.operations.push(PersistedOperation {
+            kind: PersistedOperationKind::AttesterSlashing.as_u8(),
+            version: 15,
+            bytes: slashing_v15.as_ssz_bytes(),
+        });
+
+        let (restored, discarded) = PersistedOperationPool::V23(pool_v23)
+            .into_operation_pool_with_discarded()
+            .unwrap();
+        assert!(discarded.is_empty());
+        assert_eq!(restored, op_pool);
+    }
 }
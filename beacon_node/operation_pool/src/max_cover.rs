@@ -101,7 +101,12 @@ where
 }
 
 /// Perform a greedy merge of two max cover solutions, preferring higher-score values.
-pub fn merge_solutions<I1, I2, T>(cover1: I1, cover2: I2, limit: usize) -> Vec<T::Object>
+pub fn merge_solutions<I1, I2, T>(
+    cover1: I1,
+    cover2: I2,
+    limit: usize,
+    score: impl Fn(&T) -> u64,
+) -> Vec<T>
 where
     I1: IntoIterator<Item = T>,
     I2: IntoIterator<Item = T>,
@@ -109,9 +114,8 @@ where
 {
     cover1
         .into_iter()
-        .merge_by(cover2, |item1, item2| item1.score() >= item2.score())
+        .merge_by(cover2, |item1, item2| score(item1) >= score(item2))
         .take(limit)
-        .map(|item| T::convert_to_object(item.intermediate()))
         .collect()
 }
 
@@ -38,3 +38,24 @@ pub static MAX_COVER_NON_ZERO_ITEMS: LazyLock<Result<IntGaugeVec>> = LazyLock::n
         &["label"],
     )
 });
+pub static EXACT_PACKING_SEARCHES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "op_pool_exact_packing_searches_total",
+        "Count of exact attestation packing searches, by whether they finished within budget",
+        &["result"],
+    )
+});
+pub static ATTESTATION_PACKING_ATTESTERS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "op_pool_attestation_packing_attesters",
+        "Number of fresh attesters covered by the last block packed, by packing strategy",
+        &["strategy"],
+    )
+});
+pub static ATTESTATION_PACKING_REWARD: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "op_pool_attestation_packing_reward_gwei",
+        "Proposer reward for the attestations in the last block packed, by packing strategy",
+        &["strategy"],
+    )
+});
@@ -0,0 +1,59 @@
+use crate::attestation_storage::CompactAttestationRef;
+use std::collections::HashSet;
+use tree_hash::TreeHash;
+use types::{EthSpec, Hash256};
+
+/// Operations which are forced into, or kept out of, the next block packed from the pool.
+///
+/// Operations are identified by their tree hash root. Note that the root of an attestation
+/// changes as more attestations are aggregated into it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationOverrides {
+    /// Operations to pack ahead of all others, provided they're valid for the block.
+    pub include: HashSet<Hash256>,
+    /// Operations which must not be packed.
+    pub exclude: HashSet<Hash256>,
+}
+
+impl OperationOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Split `operations` into those forced in by an override and the rest, dropping excluded
+    /// operations and keeping the order of each.
+    ///
+    /// Roots are only computed if there are overrides.
+    pub fn partition<T>(
+        &self,
+        operations: impl IntoIterator<Item = T>,
+        root_of: impl Fn(&T) -> Hash256,
+    ) -> (Vec<T>, Vec<T>) {
+        if self.is_empty() {
+            return (vec![], operations.into_iter().collect());
+        }
+        operations
+            .into_iter()
+            .filter(|op| !self.exclude.contains(&root_of(op)))
+            .partition(|op| self.include.contains(&root_of(op)))
+    }
+
+    /// Return `true` if `att` is forced in by an override.
+    pub fn includes_attestation<E: EthSpec>(&self, att: &CompactAttestationRef<E>) -> bool {
+        !self.include.is_empty() && self.include.contains(&attestation_root(att))
+    }
+
+    /// Return `true` if `att` should be packed according to its score: it isn't excluded or forced
+    /// in by an override.
+    pub fn leaves_attestation<E: EthSpec>(&self, att: &CompactAttestationRef<E>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let root = attestation_root(att);
+        !self.include.contains(&root) && !self.exclude.contains(&root)
+    }
+}
+
+fn attestation_root<E: EthSpec>(att: &CompactAttestationRef<E>) -> Hash256 {
+    att.clone_as_attestation().tree_hash_root()
+}
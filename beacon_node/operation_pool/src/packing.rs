@@ -0,0 +1,490 @@
+use crate::attestation::AttMaxCover;
+use crate::attestation_storage::CompactAttestationRef;
+use crate::max_cover::{maximum_cover, MaxCover};
+use crate::metrics;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use types::{Attestation, EthSpec};
+
+/// Default time budget for each run of the exact solver, in milliseconds.
+pub const DEFAULT_EXACT_PACKING_TIME_BUDGET_MS: u64 = 50;
+
+/// Check the exact solver's deadline every this many search nodes.
+const DEADLINE_CHECK_INTERVAL: u64 = 256;
+
+/// A method for choosing which attestations to pack into a block.
+///
+/// Previous and current epoch attestations are packed separately, and the two solutions are
+/// merged by `score` if they don't fit in the block together.
+pub trait AttestationPackingStrategy<E: EthSpec>: Send + Sync {
+    /// Short name used in logs, metrics and the block packing analysis.
+    fn name(&self) -> &'static str;
+
+    /// The value of `attestation` to the strategy's objective, given the validators it covers.
+    fn score(&self, attestation: &AttMaxCover<E>) -> u64;
+
+    /// Choose at most `limit` attestations from `attestations`.
+    ///
+    /// The result is in order of decreasing score, and each validator is in the covering set of at
+    /// most one attestation.
+    fn pack<'a>(
+        &self,
+        attestations: Vec<AttMaxCover<'a, E>>,
+        limit: usize,
+        label: &str,
+    ) -> Vec<AttMaxCover<'a, E>>;
+}
+
+/// Pick the attestation which pays the most proposer reward, until the block is full.
+///
+/// This is the approximation that Lighthouse has always used, and gets within `1 - 1/e` of the
+/// optimal reward.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyPacking;
+
+impl<E: EthSpec> AttestationPackingStrategy<E> for GreedyPacking {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn score(&self, attestation: &AttMaxCover<E>) -> u64 {
+        attestation.score() as u64
+    }
+
+    fn pack<'a>(
+        &self,
+        attestations: Vec<AttMaxCover<'a, E>>,
+        limit: usize,
+        label: &str,
+    ) -> Vec<AttMaxCover<'a, E>> {
+        maximum_cover(attestations, limit, label)
+    }
+}
+
+/// Maximise the number of validators whose attestations are rewarded, rather than the reward
+/// paid to the proposer.
+///
+/// Attestations are picked greedily by the number of fresh validators they cover.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniqueAttestersPacking;
+
+impl<E: EthSpec> AttestationPackingStrategy<E> for UniqueAttestersPacking {
+    fn name(&self) -> &'static str {
+        "unique_attesters"
+    }
+
+    fn score(&self, attestation: &AttMaxCover<E>) -> u64 {
+        attestation.fresh_validators_rewards.len() as u64
+    }
+
+    fn pack<'a>(
+        &self,
+        attestations: Vec<AttMaxCover<'a, E>>,
+        limit: usize,
+        label: &str,
+    ) -> Vec<AttMaxCover<'a, E>> {
+        maximum_cover(attestations.into_iter().map(ByAttesters), limit, label)
+            .into_iter()
+            .map(|ByAttesters(att)| att)
+            .collect()
+    }
+}
+
+/// An attestation scored by the number of validators it covers.
+#[derive(Debug, Clone)]
+struct ByAttesters<'a, E: EthSpec>(AttMaxCover<'a, E>);
+
+impl<'a, E: EthSpec> MaxCover for ByAttesters<'a, E> {
+    type Object = Attestation<E>;
+    type Intermediate = CompactAttestationRef<'a, E>;
+    type Set = HashMap<u64, u64>;
+
+    fn intermediate(&self) -> &CompactAttestationRef<'a, E> {
+        self.0.intermediate()
+    }
+
+    fn convert_to_object(att_ref: &CompactAttestationRef<'a, E>) -> Attestation<E> {
+        AttMaxCover::convert_to_object(att_ref)
+    }
+
+    fn covering_set(&self) -> &HashMap<u64, u64> {
+        self.0.covering_set()
+    }
+
+    fn update_covering_set(
+        &mut self,
+        best_att: &CompactAttestationRef<'a, E>,
+        covered_validators: &HashMap<u64, u64>,
+    ) {
+        self.0.update_covering_set(best_att, covered_validators)
+    }
+
+    fn score(&self) -> usize {
+        self.0.fresh_validators_rewards.len()
+    }
+}
+
+/// Find the attestations paying the most proposer reward using branch and bound.
+///
+/// The search explores the greedy solution first and then tries to improve on it until
+/// `time_budget` runs out, so the result is never worse than `GreedyPacking`. It is optimal if
+/// the search finishes within the budget.
+#[derive(Debug, Clone, Copy)]
+pub struct ExactPacking {
+    pub time_budget: Duration,
+}
+
+impl<E: EthSpec> AttestationPackingStrategy<E> for ExactPacking {
+    fn name(&self) -> &'static str {
+        "exact"
+    }
+
+    fn score(&self, attestation: &AttMaxCover<E>) -> u64 {
+        attestation.score() as u64
+    }
+
+    fn pack<'a>(
+        &self,
+        attestations: Vec<AttMaxCover<'a, E>>,
+        limit: usize,
+        label: &str,
+    ) -> Vec<AttMaxCover<'a, E>> {
+        let attestations = attestations
+            .into_iter()
+            .filter(|att| att.score() != 0)
+            .collect::<Vec<_>>();
+
+        metrics::set_int_gauge(
+            &metrics::MAX_COVER_NON_ZERO_ITEMS,
+            &[label],
+            attestations.len() as i64,
+        );
+
+        let sets = attestations
+            .iter()
+            .map(|att| {
+                att.fresh_validators_rewards
+                    .iter()
+                    .map(|(&validator, &reward)| (validator, reward))
+                    .collect()
+            })
+            .collect();
+        let mut search = BranchAndBound::new(sets, limit, self.time_budget);
+        search.run();
+
+        metrics::inc_counter_vec(
+            &metrics::EXACT_PACKING_SEARCHES,
+            &[if search.timed_out {
+                "timed_out"
+            } else {
+                "complete"
+            }],
+        );
+
+        // Order the solution by decreasing reward, removing the validators covered by earlier
+        // attestations from later ones, as the greedy algorithm would.
+        let mut chosen = search.best;
+        chosen.sort_unstable_by_key(|&i| std::cmp::Reverse(attestations[i].score()));
+        let mut solution: Vec<AttMaxCover<'a, E>> = Vec::with_capacity(chosen.len());
+        for i in chosen {
+            let mut att = attestations[i].clone();
+            for prev in &solution {
+                att.update_covering_set(prev.intermediate(), prev.covering_set());
+            }
+            solution.push(att);
+        }
+        solution.sort_by_key(|att| std::cmp::Reverse(att.score()));
+        solution
+    }
+}
+
+/// Depth-first search over subsets of attestations, pruning branches which can't beat the best
+/// solution found so far.
+struct BranchAndBound {
+    /// The validators covered by each attestation, with their rewards.
+    sets: Vec<Vec<(u64, u64)>>,
+    limit: usize,
+    deadline: Instant,
+    /// Attestations in the current partial solution.
+    current: Vec<usize>,
+    /// Validators covered by the current partial solution.
+    covered: HashSet<u64>,
+    best: Vec<usize>,
+    best_value: u64,
+    /// Set once the first (greedy) solution has been found, after which the search may stop.
+    found_greedy: bool,
+    nodes: u64,
+    timed_out: bool,
+}
+
+impl BranchAndBound {
+    fn new(sets: Vec<Vec<(u64, u64)>>, limit: usize, time_budget: Duration) -> Self {
+        Self {
+            sets,
+            limit,
+            deadline: Instant::now() + time_budget,
+            current: vec![],
+            covered: HashSet::new(),
+            best: vec![],
+            best_value: 0,
+            found_greedy: false,
+            nodes: 0,
+            timed_out: false,
+        }
+    }
+
+    fn run(&mut self) {
+        let candidates = (0..self.sets.len()).collect::<Vec<_>>();
+        self.search(&candidates, 0);
+    }
+
+    fn marginal(&self, i: usize) -> u64 {
+        self.sets[i]
+            .iter()
+            .filter(|(validator, _)| !self.covered.contains(validator))
+            .map(|(_, reward)| reward)
+            .sum()
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        self.nodes += 1;
+        if self.found_greedy
+            && self.nodes % DEADLINE_CHECK_INTERVAL == 0
+            && Instant::now() >= self.deadline
+        {
+            self.timed_out = true;
+        }
+        self.timed_out
+    }
+
+    /// Extend the current solution with subsets of `candidates`, whose value is `value`.
+    fn search(&mut self, candidates: &[usize], value: u64) {
+        if value > self.best_value {
+            self.best_value = value;
+            self.best.clone_from(&self.current);
+        }
+
+        let remaining = self.limit.saturating_sub(self.current.len());
+        let mut marginals = candidates
+            .iter()
+            .filter_map(|&i| Some((self.marginal(i), i)).filter(|(marginal, _)| *marginal != 0))
+            .collect::<Vec<_>>();
+        if remaining == 0 || marginals.is_empty() {
+            self.found_greedy = true;
+            return;
+        }
+        if self.out_of_time() {
+            return;
+        }
+        marginals.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Branch on including each candidate, having excluded all of those before it. The first
+        // branch at every level takes the best candidate, so the first solution is the greedy one.
+        for j in 0..marginals.len() {
+            // Adding an attestation never increases the marginal value of the others, so the best
+            // possible solution takes the top `remaining` of those left.
+            let bound = value
+                + marginals[j..]
+                    .iter()
+                    .take(remaining)
+                    .map(|(marginal, _)| marginal)
+                    .sum::<u64>();
+            if bound <= self.best_value {
+                return;
+            }
+
+            let (marginal, i) = marginals[j];
+            let newly_covered = self.sets[i]
+                .iter()
+                .map(|(validator, _)| *validator)
+                .filter(|validator| self.covered.insert(*validator))
+                .collect::<Vec<_>>();
+            self.current.push(i);
+
+            let rest = marginals[j + 1..]
+                .iter()
+                .map(|(_, i)| *i)
+                .collect::<Vec<_>>();
+            self.search(&rest, value + marginal);
+
+            self.current.pop();
+            for validator in newly_covered {
+                self.covered.remove(&validator);
+            }
+            if self.timed_out {
+                return;
+            }
+        }
+    }
+}
+
+/// Which `AttestationPackingStrategy` to use when producing blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AttestationPacking {
+    #[default]
+    Greedy,
+    Exact {
+        time_budget_ms: u64,
+    },
+    UniqueAttesters,
+}
+
+impl AttestationPacking {
+    pub fn strategy<E: EthSpec>(&self) -> Box<dyn AttestationPackingStrategy<E>> {
+        match self {
+            Self::Greedy => Box::new(GreedyPacking),
+            Self::Exact { time_budget_ms } => Box::new(ExactPacking {
+                time_budget: Duration::from_millis(*time_budget_ms),
+            }),
+            Self::UniqueAttesters => Box::new(UniqueAttestersPacking),
+        }
+    }
+}
+
+impl FromStr for AttestationPacking {
+    type Err = String;
+
+    /// Parse a strategy name, using the default time budget for the exact solver.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greedy" => Ok(Self::Greedy),
+            "exact" => Ok(Self::Exact {
+                time_budget_ms: DEFAULT_EXACT_PACKING_TIME_BUDGET_MS,
+            }),
+            "unique-attesters" => Ok(Self::UniqueAttesters),
+            other => Err(format!("unknown attestation packing strategy: {other}")),
+        }
+    }
+}
+
+/// How well attestations were packed into a block produced by this node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttestationPackingOutcome {
+    /// Name of the `AttestationPackingStrategy` used.
+    pub strategy: &'static str,
+    /// Number of valid attestations available to pack.
+    pub candidates: usize,
+    /// Number of attestations packed.
+    pub packed: usize,
+    /// Number of validators whose attestations are rewarded for the first time by the block.
+    pub attesters: usize,
+    /// Proposer reward for the packed attestations, in gwei.
+    pub reward: u64,
+}
+
+impl AttestationPackingOutcome {
+    pub fn new<E: EthSpec>(
+        strategy: &'static str,
+        candidates: usize,
+        packed: &[AttMaxCover<E>],
+    ) -> Self {
+        Self {
+            strategy,
+            candidates,
+            packed: packed.len(),
+            attesters: packed
+                .iter()
+                .map(|att| att.fresh_validators_rewards.len())
+                .sum(),
+            reward: packed.iter().map(|att| att.score() as u64).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Best reward from `limit` of `sets`, by brute force.
+    fn optimal(sets: &[Vec<(u64, u64)>], limit: usize) -> u64 {
+        (0_u32..1 << sets.len())
+            .filter(|subset| subset.count_ones() as usize <= limit)
+            .map(|subset| {
+                let mut covered = HashMap::new();
+                for (i, set) in sets.iter().enumerate() {
+                    if subset & (1 << i) != 0 {
+                        covered.extend(set.iter().copied());
+                    }
+                }
+                covered.values().sum()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn run(sets: Vec<Vec<(u64, u64)>>, limit: usize, time_budget: Duration) -> BranchAndBound {
+        let mut search = BranchAndBound::new(sets, limit, time_budget);
+        search.run();
+        search
+    }
+
+    fn unit(validators: &[u64]) -> Vec<(u64, u64)> {
+        validators.iter().map(|&v| (v, 1)).collect()
+    }
+
+    // The same system as `max_cover::test::suboptimal`, for which greedy finds 11 of 15.
+    #[test]
+    fn exact_beats_greedy() {
+        let sets = vec![
+            unit(&[0, 1, 8, 11, 14]),
+            unit(&[2, 3, 7, 9, 10]),
+            unit(&[4, 5, 6, 12, 13]),
+            unit(&[9, 10]),
+            unit(&[5, 6, 7, 8]),
+            unit(&[0, 1, 2, 3, 4]),
+        ];
+        let search = run(sets, 3, Duration::from_secs(60));
+        assert!(!search.timed_out);
+        assert_eq!(search.best_value, 15);
+        let mut best = search.best;
+        best.sort_unstable();
+        assert_eq!(best, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn exact_matches_brute_force() {
+        let sets = vec![
+            vec![(0, 10), (1, 3), (2, 3)],
+            vec![(1, 3), (2, 3), (3, 7)],
+            vec![(0, 10), (4, 1)],
+            vec![(3, 7), (5, 2), (6, 2)],
+            vec![(5, 2), (6, 2), (7, 9)],
+            vec![(8, 4)],
+            vec![(2, 3), (8, 4), (9, 6)],
+        ];
+        for limit in 0..=sets.len() {
+            let search = run(sets.clone(), limit, Duration::from_secs(60));
+            assert_eq!(search.best_value, optimal(&sets, limit), "limit {limit}");
+            assert!(search.best.len() <= limit);
+        }
+    }
+
+    // With no time at all the search still returns the greedy solution.
+    #[test]
+    fn exact_without_time_returns_greedy() {
+        let sets = (0..2000_u64)
+            .map(|i| unit(&[i % 97, i % 89, i % 83, 100 + i]))
+            .collect::<Vec<_>>();
+        let search = run(sets, 16, Duration::ZERO);
+        assert_eq!(search.best.len(), 16);
+        assert_eq!(search.best_value, 64);
+    }
+
+    #[test]
+    fn parse_strategy() {
+        assert_eq!("greedy".parse(), Ok(AttestationPacking::Greedy));
+        assert_eq!(
+            "exact".parse(),
+            Ok(AttestationPacking::Exact {
+                time_budget_ms: DEFAULT_EXACT_PACKING_TIME_BUDGET_MS
+            })
+        );
+        assert_eq!(
+            "unique-attesters".parse(),
+            Ok(AttestationPacking::UniqueAttesters)
+        );
+        assert!("ilp".parse::<AttestationPacking>().is_err());
+    }
+}
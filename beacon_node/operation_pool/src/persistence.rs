@@ -5,11 +5,12 @@ use crate::OpPoolError;
 use crate::OperationPool;
 use derivative::Derivative;
 use parking_lot::RwLock;
-use ssz::{Decode, Encode};
+use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use state_processing::SigVerifiedOp;
 use std::collections::HashSet;
-use std::mem;
+use std::fmt;
+use std::marker::PhantomData;
 use store::{DBColumn, Error as StoreError, StoreItem};
 use types::attestation::AttestationOnDisk;
 use types::*;
@@ -20,8 +21,12 @@ type PersistedSyncContributions<E> = Vec<(SyncAggregateId, Vec<SyncCommitteeCont
 ///
 /// Operations are stored in arbitrary order, so it's not a good idea to compare instances
 /// of this type (or its encoded form) for equality. Convert back to an `OperationPool` first.
+///
+/// From `V23` onwards each operation is encoded separately and tagged with the version of its
+/// encoding (see `PersistedOperation`), so that a single operation which can't be decoded
+/// doesn't prevent the rest of the pool from loading.
 #[superstruct(
-    variants(V15, V20),
+    variants(V15, V20, V23),
     variant_attributes(
         derive(Derivative, PartialEq, Debug, Encode, Decode),
         derivative(Clone),
@@ -37,6 +42,7 @@ pub struct PersistedOperationPool<E: EthSpec> {
     #[superstruct(only(V20))]
     pub attestations: Vec<(AttestationOnDisk<E>, Vec<u64>)>,
     /// Mapping from sync contribution ID to sync contributions and aggregate.
+    #[superstruct(only(V15, V20))]
     pub sync_contributions: PersistedSyncContributions<E>,
     #[superstruct(only(V15))]
     pub attester_slashings_v15: Vec<SigVerifiedOp<AttesterSlashingBase<E>, E>>,
@@ -44,14 +50,162 @@ pub struct PersistedOperationPool<E: EthSpec> {
     #[superstruct(only(V20))]
     pub attester_slashings: Vec<SigVerifiedOp<AttesterSlashing<E>, E>>,
     /// Proposer slashings with fork information.
+    #[superstruct(only(V15, V20))]
     pub proposer_slashings: Vec<SigVerifiedOp<ProposerSlashing, E>>,
     /// Voluntary exits with fork information.
+    #[superstruct(only(V15, V20))]
     pub voluntary_exits: Vec<SigVerifiedOp<SignedVoluntaryExit, E>>,
     /// BLS to Execution Changes
+    #[superstruct(only(V15, V20))]
     pub bls_to_execution_changes: Vec<SigVerifiedOp<SignedBlsToExecutionChange, E>>,
+    /// All operations, individually encoded.
+    #[superstruct(only(V23))]
+    pub operations: Vec<PersistedOperation>,
     /// Validator indices with BLS to Execution Changes to be broadcast at the
     /// Capella fork.
     pub capella_bls_change_broadcast_indices: Vec<u64>,
+    #[superstruct(only(V23))]
+    #[ssz(skip_serializing, skip_deserializing)]
+    pub _phantom: PhantomData<E>,
+}
+
+/// The kinds of operation stored in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistedOperationKind {
+    Attestation,
+    SyncContribution,
+    AttesterSlashing,
+    ProposerSlashing,
+    VoluntaryExit,
+    BlsToExecutionChange,
+}
+
+impl PersistedOperationKind {
+    pub fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            0 => Some(Self::Attestation),
+            1 => Some(Self::SyncContribution),
+            2 => Some(Self::AttesterSlashing),
+            3 => Some(Self::ProposerSlashing),
+            4 => Some(Self::VoluntaryExit),
+            5 => Some(Self::BlsToExecutionChange),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Attestation => 0,
+            Self::SyncContribution => 1,
+            Self::AttesterSlashing => 2,
+            Self::ProposerSlashing => 3,
+            Self::VoluntaryExit => 4,
+            Self::BlsToExecutionChange => 5,
+        }
+    }
+
+    /// The schema version at which the current encoding of this kind of operation was introduced.
+    ///
+    /// Bump this whenever the encoding changes, and teach `decode_operation` to upgrade the
+    /// previous encoding.
+    pub fn current_version(self) -> u64 {
+        match self {
+            Self::Attestation | Self::AttesterSlashing => 20,
+            Self::SyncContribution
+            | Self::ProposerSlashing
+            | Self::VoluntaryExit
+            | Self::BlsToExecutionChange => 15,
+        }
+    }
+}
+
+/// A single SSZ-encoded operation tagged with its kind and encoding version.
+#[derive(PartialEq, Debug, Clone, Encode, Decode)]
+pub struct PersistedOperation {
+    /// The `PersistedOperationKind` of the operation.
+    pub kind: u8,
+    /// The schema version at which the encoding of `bytes` was introduced.
+    pub version: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl PersistedOperation {
+    /// Encode each of `operations` using the current encoding for `kind`.
+    fn encode_all<T: Encode>(
+        kind: PersistedOperationKind,
+        operations: &[T],
+    ) -> impl Iterator<Item = Self> + '_ {
+        operations.iter().map(move |operation| Self {
+            kind: kind.as_u8(),
+            version: kind.current_version(),
+            bytes: operation.as_ssz_bytes(),
+        })
+    }
+}
+
+/// Counts of operations that were dropped while loading a persisted pool because they could not
+/// be decoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiscardedOperations {
+    pub attestations: usize,
+    pub sync_contributions: usize,
+    pub attester_slashings: usize,
+    pub proposer_slashings: usize,
+    pub voluntary_exits: usize,
+    pub bls_to_execution_changes: usize,
+    /// Operations of a kind unknown to this version of Lighthouse.
+    pub unknown: usize,
+}
+
+impl DiscardedOperations {
+    fn increment(&mut self, kind: PersistedOperationKind) {
+        let count = match kind {
+            PersistedOperationKind::Attestation => &mut self.attestations,
+            PersistedOperationKind::SyncContribution => &mut self.sync_contributions,
+            PersistedOperationKind::AttesterSlashing => &mut self.attester_slashings,
+            PersistedOperationKind::ProposerSlashing => &mut self.proposer_slashings,
+            PersistedOperationKind::VoluntaryExit => &mut self.voluntary_exits,
+            PersistedOperationKind::BlsToExecutionChange => &mut self.bls_to_execution_changes,
+        };
+        *count += 1;
+    }
+
+    fn counts(&self) -> [(&'static str, usize); 7] {
+        [
+            ("attestations", self.attestations),
+            ("sync_contributions", self.sync_contributions),
+            ("attester_slashings", self.attester_slashings),
+            ("proposer_slashings", self.proposer_slashings),
+            ("voluntary_exits", self.voluntary_exits),
+            ("bls_to_execution_changes", self.bls_to_execution_changes),
+            ("unknown", self.unknown),
+        ]
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts().iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+/// Lists the non-zero counts, e.g. `attestations: 2, voluntary_exits: 1`.
+impl fmt::Display for DiscardedOperations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = self
+            .counts()
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(kind, count)| format!("{kind}: {count}"))
+            .collect::<Vec<_>>();
+        if counts.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", counts.join(", "))
+        }
+    }
 }
 
 impl<E: EthSpec> PersistedOperationPool<E> {
@@ -111,89 +265,209 @@ impl<E: EthSpec> PersistedOperationPool<E> {
             .copied()
             .collect();
 
-        PersistedOperationPool::V20(PersistedOperationPoolV20 {
-            attestations,
-            sync_contributions,
-            attester_slashings,
-            proposer_slashings,
-            voluntary_exits,
-            bls_to_execution_changes,
-            capella_bls_change_broadcast_indices,
-        })
+        PersistedOperationPool::V23(
+            PersistedOperationPoolV20 {
+                attestations,
+                sync_contributions,
+                attester_slashings,
+                proposer_slashings,
+                voluntary_exits,
+                bls_to_execution_changes,
+                capella_bls_change_broadcast_indices,
+            }
+            .into(),
+        )
     }
 
     /// Reconstruct an `OperationPool`.
-    pub fn into_operation_pool(mut self) -> Result<OperationPool<E>, OpPoolError> {
-        let attester_slashings = match &self {
-            PersistedOperationPool::V15(pool_v15) => RwLock::new(
-                pool_v15
-                    .attester_slashings_v15
-                    .iter()
-                    .map(|slashing| slashing.clone().into())
-                    .collect(),
-            ),
-            PersistedOperationPool::V20(pool_v20) => {
-                RwLock::new(pool_v20.attester_slashings.iter().cloned().collect())
+    pub fn into_operation_pool(self) -> Result<OperationPool<E>, OpPoolError> {
+        self.into_operation_pool_with_discarded()
+            .map(|(operation_pool, _)| operation_pool)
+    }
+
+    /// Reconstruct an `OperationPool`, also returning counts of the operations that had to be
+    /// discarded because they could not be decoded.
+    pub fn into_operation_pool_with_discarded(
+        self,
+    ) -> Result<(OperationPool<E>, DiscardedOperations), OpPoolError> {
+        let (pool_v20, discarded) = match self {
+            PersistedOperationPool::V15(pool_v15) => {
+                (pool_v15.into(), DiscardedOperations::default())
             }
+            PersistedOperationPool::V20(pool_v20) => (pool_v20, DiscardedOperations::default()),
+            PersistedOperationPool::V23(pool_v23) => pool_v23.decode_operations(),
+        };
+        Ok((pool_v20.into_operation_pool(), discarded))
+    }
+}
+
+impl<E: EthSpec> From<PersistedOperationPoolV15<E>> for PersistedOperationPoolV20<E> {
+    fn from(pool_v15: PersistedOperationPoolV15<E>) -> Self {
+        PersistedOperationPoolV20 {
+            attestations: pool_v15
+                .attestations_v15
+                .into_iter()
+                .map(|(att, attesting_indices)| (Attestation::Base(att).into(), attesting_indices))
+                .collect(),
+            sync_contributions: pool_v15.sync_contributions,
+            attester_slashings: pool_v15
+                .attester_slashings_v15
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            proposer_slashings: pool_v15.proposer_slashings,
+            voluntary_exits: pool_v15.voluntary_exits,
+            bls_to_execution_changes: pool_v15.bls_to_execution_changes,
+            capella_bls_change_broadcast_indices: pool_v15.capella_bls_change_broadcast_indices,
+        }
+    }
+}
+
+impl<E: EthSpec> From<PersistedOperationPoolV20<E>> for PersistedOperationPoolV23<E> {
+    fn from(pool_v20: PersistedOperationPoolV20<E>) -> Self {
+        let operations = PersistedOperation::encode_all(
+            PersistedOperationKind::Attestation,
+            &pool_v20.attestations,
+        )
+        .chain(PersistedOperation::encode_all(
+            PersistedOperationKind::SyncContribution,
+            &pool_v20.sync_contributions,
+        ))
+        .chain(PersistedOperation::encode_all(
+            PersistedOperationKind::AttesterSlashing,
+            &pool_v20.attester_slashings,
+        ))
+        .chain(PersistedOperation::encode_all(
+            PersistedOperationKind::ProposerSlashing,
+            &pool_v20.proposer_slashings,
+        ))
+        .chain(PersistedOperation::encode_all(
+            PersistedOperationKind::VoluntaryExit,
+            &pool_v20.voluntary_exits,
+        ))
+        .chain(PersistedOperation::encode_all(
+            PersistedOperationKind::BlsToExecutionChange,
+            &pool_v20.bls_to_execution_changes,
+        ))
+        .collect();
+
+        PersistedOperationPoolV23 {
+            operations,
+            capella_bls_change_broadcast_indices: pool_v20.capella_bls_change_broadcast_indices,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EthSpec> PersistedOperationPoolV23<E> {
+    /// Decode every operation, upgrading those stored in an older encoding.
+    ///
+    /// Operations which can't be decoded are dropped and counted in the returned
+    /// `DiscardedOperations`.
+    pub fn decode_operations(self) -> (PersistedOperationPoolV20<E>, DiscardedOperations) {
+        let mut pool_v20 = PersistedOperationPoolV20 {
+            attestations: vec![],
+            sync_contributions: vec![],
+            attester_slashings: vec![],
+            proposer_slashings: vec![],
+            voluntary_exits: vec![],
+            bls_to_execution_changes: vec![],
+            capella_bls_change_broadcast_indices: self.capella_bls_change_broadcast_indices,
         };
+        let mut discarded = DiscardedOperations::default();
+
+        for operation in self.operations {
+            let Some(kind) = PersistedOperationKind::from_u8(operation.kind) else {
+                discarded.unknown += 1;
+                continue;
+            };
+            if decode_operation(&mut pool_v20, kind, operation.version, &operation.bytes).is_err() {
+                discarded.increment(kind);
+            }
+        }
+
+        (pool_v20, discarded)
+    }
+}
+
+/// Decode a single operation into `pool`, upgrading it from the encoding introduced at schema
+/// `version` if necessary.
+fn decode_operation<E: EthSpec>(
+    pool: &mut PersistedOperationPoolV20<E>,
+    kind: PersistedOperationKind,
+    version: u64,
+    bytes: &[u8],
+) -> Result<(), DecodeError> {
+    match (kind, version) {
+        (PersistedOperationKind::Attestation, 20) => {
+            pool.attestations.push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (PersistedOperationKind::Attestation, 15) => {
+            let (att, attesting_indices) = <(AttestationBase<E>, Vec<u64>)>::from_ssz_bytes(bytes)?;
+            pool.attestations
+                .push((Attestation::Base(att).into(), attesting_indices));
+        }
+        (PersistedOperationKind::SyncContribution, 15) => {
+            pool.sync_contributions.push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (PersistedOperationKind::AttesterSlashing, 20) => {
+            pool.attester_slashings.push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (PersistedOperationKind::AttesterSlashing, 15) => {
+            let slashing = SigVerifiedOp::<AttesterSlashingBase<E>, E>::from_ssz_bytes(bytes)?;
+            pool.attester_slashings.push(slashing.into());
+        }
+        (PersistedOperationKind::ProposerSlashing, 15) => {
+            pool.proposer_slashings.push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (PersistedOperationKind::VoluntaryExit, 15) => {
+            pool.voluntary_exits.push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (PersistedOperationKind::BlsToExecutionChange, 15) => {
+            pool.bls_to_execution_changes
+                .push(Decode::from_ssz_bytes(bytes)?);
+        }
+        (kind, version) => {
+            return Err(DecodeError::BytesInvalid(format!(
+                "unsupported encoding version {version} for {kind:?}"
+            )));
+        }
+    }
+    Ok(())
+}
 
+impl<E: EthSpec> PersistedOperationPoolV20<E> {
+    fn into_operation_pool(self) -> OperationPool<E> {
+        let attester_slashings = RwLock::new(self.attester_slashings.into_iter().collect());
         let proposer_slashings = RwLock::new(
-            self.proposer_slashings()
-                .iter()
-                .cloned()
+            self.proposer_slashings
+                .into_iter()
                 .map(|slashing| (slashing.as_inner().proposer_index(), slashing))
                 .collect(),
         );
         let voluntary_exits = RwLock::new(
-            self.voluntary_exits()
-                .iter()
-                .cloned()
+            self.voluntary_exits
+                .into_iter()
                 .map(|exit| (exit.as_inner().message.validator_index, exit))
                 .collect(),
         );
-        let sync_contributions = RwLock::new(self.sync_contributions().iter().cloned().collect());
-        let attestations = match &self {
-            PersistedOperationPool::V15(pool_v15) => {
-                let mut map = AttestationMap::default();
-                for (att, attesting_indices) in
-                    pool_v15
-                        .attestations_v15
-                        .iter()
-                        .map(|(att, attesting_indices)| {
-                            (Attestation::Base(att.clone()), attesting_indices.clone())
-                        })
-                {
-                    map.insert(att, attesting_indices);
-                }
-                RwLock::new(map)
-            }
-            PersistedOperationPool::V20(pool_v20) => {
-                let mut map = AttestationMap::default();
-                for (att, attesting_indices) in
-                    pool_v20
-                        .attestations
-                        .iter()
-                        .map(|(att, attesting_indices)| {
-                            (
-                                AttestationRef::from(att.to_ref()).clone_as_attestation(),
-                                attesting_indices.clone(),
-                            )
-                        })
-                {
-                    map.insert(att, attesting_indices);
-                }
-                RwLock::new(map)
-            }
-        };
+        let sync_contributions = RwLock::new(self.sync_contributions.into_iter().collect());
+
+        let mut attestations = AttestationMap::default();
+        for (att, attesting_indices) in self.attestations {
+            attestations.insert(
+                AttestationRef::from(att.to_ref()).clone_as_attestation(),
+                attesting_indices,
+            );
+        }
 
         let mut bls_to_execution_changes = BlsToExecutionChanges::default();
-        let persisted_changes = mem::take(self.bls_to_execution_changes_mut());
-        let broadcast_indices: HashSet<_> =
-            mem::take(self.capella_bls_change_broadcast_indices_mut())
-                .into_iter()
-                .collect();
+        let broadcast_indices: HashSet<_> = self
+            .capella_bls_change_broadcast_indices
+            .into_iter()
+            .collect();
 
-        for bls_to_execution_change in persisted_changes {
+        for bls_to_execution_change in self.bls_to_execution_changes {
             let received_pre_capella = if broadcast_indices
                 .contains(&bls_to_execution_change.as_inner().message.validator_index)
             {
@@ -204,17 +478,19 @@ impl<E: EthSpec> PersistedOperationPool<E> {
             bls_to_execution_changes.insert(bls_to_execution_change, received_pre_capella);
         }
 
-        let op_pool = OperationPool {
-            attestations,
+        OperationPool {
+            attestations: RwLock::new(attestations),
             sync_contributions,
             attester_slashings,
             proposer_slashings,
             voluntary_exits,
             bls_to_execution_changes: RwLock::new(bls_to_execution_changes),
             reward_cache: Default::default(),
+            overrides: Default::default(),
+            attestation_packing: Default::default(),
+            packing_outcomes: Default::default(),
             _phantom: Default::default(),
-        };
-        Ok(op_pool)
+        }
     }
 }
 
@@ -246,7 +522,21 @@ impl<E: EthSpec> StoreItem for PersistedOperationPoolV20<E> {
     }
 }
 
-/// Deserialization for `PersistedOperationPool` defaults to `PersistedOperationPool::V12`.
+impl<E: EthSpec> StoreItem for PersistedOperationPoolV23<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::OpPool
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        PersistedOperationPoolV23::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
+/// Deserialization for `PersistedOperationPool` defaults to `PersistedOperationPool::V23`.
 impl<E: EthSpec> StoreItem for PersistedOperationPool<E> {
     fn db_column() -> DBColumn {
         DBColumn::OpPool
@@ -258,8 +548,8 @@ impl<E: EthSpec> StoreItem for PersistedOperationPool<E> {
 
     fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
         // Default deserialization to the latest variant.
-        PersistedOperationPoolV20::from_ssz_bytes(bytes)
-            .map(Self::V20)
+        PersistedOperationPoolV23::from_ssz_bytes(bytes)
+            .map(Self::V23)
             .map_err(Into::into)
     }
 }
@@ -373,7 +373,6 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::SetTrue)
                 .help_heading(FLAG_HEADER)
                 .help("Disables the discv5 discovery protocol. The node will not search for new peers or participate in the discovery protocol.")
-                .hide(true)
                 .display_order(0)
         )
         .arg(
@@ -403,6 +402,50 @@ pub fn cli_app() -> Command {
                 .display_order(0)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("private-network")
+                .long("private-network")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Only allow connections to and from the peers in --peer-allowlist and \
+                       --trusted-peers. Other peers are rejected as soon as their peer id is \
+                       authenticated, before any protocol handshake. The allow-list can be \
+                       changed at runtime via the /lighthouse/peers/allowlist HTTP API endpoint.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("peer-allowlist")
+                .long("peer-allowlist")
+                .value_name("PEERS")
+                .help("One or more comma-delimited peer ids or ENRs which may connect when \
+                       --private-network is set. ENRs are identified by their public key.")
+                .action(ArgAction::Set)
+                .requires("private-network")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("private-peer")
+                .long("private-peer")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Run as a private node behind one or more sentry nodes. The node only \
+                       connects to its sentries, which must be given via --trusted-peers (and \
+                       dialed via --libp2p-addresses). Implies --private-network, \
+                       --disable-discovery and --disable-upnp so that the node is never \
+                       advertised on the network.")
+                .conflicts_with("sentry-of")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("sentry-of")
+                .long("sentry-of")
+                .value_name("PEERS")
+                .help("One or more comma-delimited peer ids or ENRs of private nodes this node is \
+                       a sentry for. These peers are added to the trusted peers, are never scored \
+                       out of gossip and have gossip relayed to them with priority.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("genesis-backfill")
                 .long("genesis-backfill")
@@ -483,6 +526,42 @@ pub fn cli_app() -> Command {
             .conflicts_with("disable-inbound-rate-limiter")
             .display_order(0)
         )
+        .arg(
+            Arg::new("rpc-rate-limits-file")
+                .long("rpc-rate-limits-file")
+                .value_name("PATH")
+                .help(
+                    "Path to a YAML file of inbound and self rate limiter quotas, which may give \
+                    trusted peers different quotas from regular peers. The quotas in the file \
+                    override those set by the inbound-rate-limiter-protocols and \
+                    self-limiter-protocols flags. The file is reloaded when the node receives a \
+                    SIGHUP, which no longer shuts the node down, or on a POST to \
+                    /lighthouse/rate_limits/reload."
+                )
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-trace-file")
+                .long("gossip-trace-file")
+                .value_name("PATH")
+                .help("Write gossipsub message propagation events (publish, deliver, duplicate, \
+                       reject, IHAVE and IWANT) to this file as JSON lines. The trace of a \
+                       message can be summarised with `lcli gossip-trace`.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-trace-max-file-size")
+                .long("gossip-trace-max-file-size")
+                .value_name("MEGABYTES")
+                .help("The size at which the gossip trace file is rotated. Up to 4 rotated \
+                       files are kept.")
+                .default_value("100")
+                .requires("gossip-trace-file")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-backfill-rate-limiting")
                 .long("disable-backfill-rate-limiting")
@@ -1022,6 +1101,18 @@ pub fn cli_app() -> Command {
                 .default_value("1")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("epochs-per-column-stats")
+                .long("epochs-per-column-stats")
+                .value_name("EPOCHS")
+                .help("The epoch interval with which to recompute the number of keys and bytes \
+                       stored in each database column. Computing them iterates every key in \
+                       the database on a background thread, sizes are estimated from a sample \
+                       of values. Set to 0 to disable.")
+                .action(ArgAction::Set)
+                .default_value("225")
+                .display_order(0)
+        )
         .arg(
             Arg::new("blob-prune-margin-epochs")
                 .long("blob-prune-margin-epochs")
@@ -1032,6 +1123,91 @@ pub fn cli_app() -> Command {
                 .default_value("0")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("blob-retention")
+                .long("blob-retention")
+                .value_name("POLICY")
+                .help("Retention policy for finalized blobs: keep_all, keep_last_n_epochs:N or \
+                       keep_finalized_checkpoints_only. Blobs within the data availability \
+                       period are always kept. Overrides --prune-blobs.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("data-column-retention")
+                .long("data-column-retention")
+                .value_name("POLICY")
+                .help("Retention policy for finalized data columns: keep_all, \
+                       keep_last_n_epochs:N or keep_finalized_checkpoints_only. Data columns \
+                       within the data availability period are always kept. Defaults to the \
+                       blob retention policy.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("payload-retention")
+                .long("payload-retention")
+                .value_name("POLICY")
+                .help("Retention policy for finalized execution payloads: keep_all, \
+                       keep_last_n_epochs:N or keep_finalized_checkpoints_only. \
+                       Overrides --prune-payloads.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("historic-state-retention")
+                .long("historic-state-retention")
+                .value_name("POLICY")
+                .help("Retention policy for historic states in the freezer database: keep_all, \
+                       keep_last_n_epochs:N or keep_finalized_checkpoints_only. States are \
+                       pruned up to the last snapshot before the cutoff. \
+                       keep_finalized_checkpoints_only keeps only the genesis state. \
+                       Incompatible with --reconstruct-historic-states.")
+                .action(ArgAction::Set)
+                .conflicts_with("reconstruct-historic-states")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("historical-blocks-era-dir")
+                .long("historical-blocks-era-dir")
+                .value_name("DIR")
+                .help("Serve blocks older than the oldest block in the database to peers from the \
+                       era files in this directory. Lookups by block root are not supported.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("historical-blocks-datadir")
+                .long("historical-blocks-datadir")
+                .value_name("DIR")
+                .help("Serve blocks older than the oldest block in the database to peers from the \
+                       database of another Lighthouse node, given by its `beacon` directory. The \
+                       database is only read from, and the other node must not be running.")
+                .action(ArgAction::Set)
+                .conflicts_with("historical-blocks-era-dir")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("db-encryption-key-file")
+                .long("db-encryption-key-file")
+                .value_name("PATH")
+                .help("Encrypt database values at rest using the hex-encoded 32-byte key in \
+                       this file. Only a new, empty database can be encrypted, and an encrypted \
+                       database can't be opened without its key.")
+                .action(ArgAction::Set)
+                .conflicts_with("db-encryption-key-env")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("db-encryption-key-env")
+                .long("db-encryption-key-env")
+                .value_name("VAR")
+                .help("Encrypt database values at rest using the hex-encoded 32-byte key in \
+                       this environment variable. Only a new, empty database can be encrypted, \
+                       and an encrypted database can't be opened without its key.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Misc.
          */
@@ -1229,12 +1405,25 @@ pub fn cli_app() -> Command {
         .arg(
             Arg::new("checkpoint-sync-url")
                 .long("checkpoint-sync-url")
-                .help("Set the remote beacon node HTTP endpoint to use for checkpoint sync.")
-                .value_name("BEACON_NODE")
+                .help("Set the remote beacon node HTTP endpoint to use for checkpoint sync. \
+                       Several comma-separated endpoints can be given, in which case they must \
+                       agree on the finalized checkpoint, see --checkpoint-sync-quorum.")
+                .value_name("BEACON_NODES")
                 .action(ArgAction::Set)
                 .conflicts_with("checkpoint-state")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("checkpoint-sync-quorum")
+                .long("checkpoint-sync-quorum")
+                .help("The number of --checkpoint-sync-url endpoints which must agree on the \
+                       finalized block and state roots for checkpoint sync to proceed. Defaults \
+                       to all of them.")
+                .value_name("COUNT")
+                .action(ArgAction::Set)
+                .requires("checkpoint-sync-url")
+                .display_order(0)
+        )
         .arg(
             Arg::new("checkpoint-sync-url-timeout")
                 .long("checkpoint-sync-url-timeout")
@@ -1256,6 +1445,27 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("backfill-beacon-node-url")
+                .long("backfill-beacon-node-url")
+                .value_name("BEACON_NODE")
+                .help("Backfill historical blocks and blobs from the HTTP API of this trusted \
+                       beacon node instead of from peers. Blocks are still verified against the \
+                       checkpoint before being stored.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("backfill-era-mirror-url")
+                .long("backfill-era-mirror-url")
+                .value_name("URL")
+                .help("Backfill historical blocks from the era files served under this URL \
+                       instead of from peers. Era files don't contain blobs, so blocks within \
+                       the data availability window are still backfilled from peers.")
+                .action(ArgAction::Set)
+                .conflicts_with("backfill-beacon-node-url")
+                .display_order(0)
+        )
         .arg(
             Arg::new("reconstruct-historic-states")
                 .long("reconstruct-historic-states")
@@ -1320,7 +1530,7 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .value_name("PERCENT")
                 .help("Percentage of head vote weight below which to attempt a proposer reorg. \
-                       Default: 20%")
+                       Default: REORG_HEAD_WEIGHT_THRESHOLD from the network config, or 20%")
                 .conflicts_with("disable-proposer-reorgs")
                 .display_order(0)
         )
@@ -1329,7 +1539,7 @@ pub fn cli_app() -> Command {
                 .long("proposer-reorg-parent-threshold")
                 .value_name("PERCENT")
                 .help("Percentage of parent vote weight above which to attempt a proposer reorg. \
-                       Default: 160%")
+                       Default: REORG_PARENT_WEIGHT_THRESHOLD from the network config, or 160%")
                 .conflicts_with("disable-proposer-reorgs")
                 .action(ArgAction::Set)
                 .display_order(0)
@@ -1340,7 +1550,8 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .value_name("EPOCHS")
                 .help("Maximum number of epochs since finalization at which proposer reorgs are \
-                       allowed. Default: 2")
+                       allowed. Default: REORG_MAX_EPOCHS_SINCE_FINALIZATION from the network \
+                       config, or 2")
                 .conflicts_with("disable-proposer-reorgs")
                 .display_order(0)
         )
@@ -1403,6 +1614,47 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("fork-choice-journal")
+                .long("fork-choice-journal")
+                .help("Journal every input to fork choice and every head it selects to a \
+                       rotating set of files, so that head selection can be replayed offline \
+                       with `lcli fork-choice-replay`.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("fork-choice-journal-dir")
+                .long("fork-choice-journal-dir")
+                .help("Set the directory of the fork choice journal. Defaults to \
+                       `fork_choice_journal` in the data directory.")
+                .value_name("PATH")
+                .action(ArgAction::Set)
+                .requires("fork-choice-journal")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("fork-choice-journal-max-file-size")
+                .long("fork-choice-journal-max-file-size")
+                .help("Size after which a fork choice journal file is rotated.")
+                .value_name("MEGABYTES")
+                .default_value("256")
+                .action(ArgAction::Set)
+                .requires("fork-choice-journal")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("fork-choice-journal-max-files")
+                .long("fork-choice-journal-max-files")
+                .help("Number of fork choice journal files to keep, including the one being \
+                       written.")
+                .value_name("COUNT")
+                .default_value("8")
+                .action(ArgAction::Set)
+                .requires("fork-choice-journal")
+                .display_order(0)
+        )
         .arg(
             Arg::new("paranoid-block-proposal")
                 .long("paranoid-block-proposal")
@@ -1415,6 +1667,31 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("attestation-packing")
+                .long("attestation-packing")
+                .value_name("STRATEGY")
+                .help("How to choose the attestations included in blocks proposed by this node. \
+                       \"greedy\" repeatedly picks the attestation paying the most proposer \
+                       reward. \"exact\" searches for the attestations paying the most reward \
+                       within a time budget, starting from the greedy solution. \
+                       \"unique-attesters\" maximises the number of validators whose \
+                       attestations are included, rather than the reward.")
+                .value_parser(["greedy", "exact", "unique-attesters"])
+                .default_value("greedy")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("attestation-packing-time-budget")
+                .long("attestation-packing-time-budget")
+                .value_name("MILLISECONDS")
+                .help("Time allowed for the \"exact\" attestation packing strategy to improve on \
+                       the greedy solution, for each of the previous and current epochs.")
+                .default_value("50")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-fallback-skips")
                 .long("builder-fallback-skips")
@@ -1510,6 +1787,30 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("light-client-historical-updates")
+                .long("light-client-historical-updates")
+                .help("Generate light client updates for past sync committee periods from the \
+                       blocks and states in the freezer database, so that they can be served to \
+                       light clients. Requires the historic states of those periods, see \
+                       --reconstruct-historic-states. [experimental]")
+                .requires("light-client-server")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("webrtc-port")
+                .long("webrtc-port")
+                .value_name("PORT")
+                .help("The UDP port to accept WebRTC-direct connections from browser light \
+                       clients on. These connections may only use the light client protocols \
+                       and gossip topics. The certificate hash is included in the advertised \
+                       listen address. [experimental]")
+                .requires("light-client-server")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("gui")
                 .long("gui")
@@ -1591,5 +1892,44 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-processor-scheduler")
+                .long("beacon-processor-scheduler")
+                .value_name("MODE")
+                .help("Specifies how queued work is scheduled. \"strict\" processes each queue in \
+                       arrival order. \"fair\" shares each queue between peers, so that a single \
+                       peer sending large amounts of work can't delay the work of other peers, and \
+                       drops the work of the busiest peer first when a queue is full.")
+                .value_parser(["strict", "fair"])
+                .hide(true)
+                .default_value("strict")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-flow-weights")
+                .long("beacon-processor-flow-weights")
+                .value_name("FLOW=WEIGHT,...")
+                .help("Assigns weights to the flows sharing each queue with \
+                       \"--beacon-processor-scheduler fair\", e.g. \"local=8,16Uiu2HAm...=4\". \
+                       A flow is either \"local\" for work created by this node, or a peer id. \
+                       Flows take as many items as their weight in turn. Local work has weight 8 \
+                       and unlisted peers have weight 1 by default.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-priorities")
+                .long("beacon-processor-priorities")
+                .value_name("TYPE=LANE,...")
+                .help("Assigns work types to priority lanes, e.g. \
+                       \"blocks_by_range_request=0,chain_segment_backfill=2\". Work types in lower \
+                       lanes are processed first, and unlisted work types are in lane 1. Within a \
+                       lane work types keep their default order.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .group(ArgGroup::new("enable_http").args(["http", "gui", "staking"]).multiple(true))
 }
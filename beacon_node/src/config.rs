@@ -1,15 +1,20 @@
 use account_utils::{read_input_from_user, STDIN_INPUTS_FLAG};
 use beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
-    DEFAULT_RE_ORG_HEAD_THRESHOLD, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
-    DEFAULT_RE_ORG_PARENT_THRESHOLD,
+    AttestationPacking, DisallowedReOrgOffsets, ReOrgThreshold,
+    DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
 };
+use beacon_chain::fork_choice_journal::ForkChoiceJournalConfig;
 use beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_chain::historical_block_provider::HistoricalBlockSource;
+use beacon_chain::http_backfill::BackfillSource;
 use beacon_chain::TrustedSetup;
+use beacon_processor::work_queues::DEFAULT_PRIORITY_ORDER;
+use beacon_processor::{FlowWeights, SchedulerMode, WorkType};
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use clap_utils::{parse_flag, parse_required};
 use client::{ClientConfig, ClientGenesis};
+use db_encryption::KeySource;
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use environment::RuntimeContext;
 use execution_layer::DEFAULT_JWT_FILE;
@@ -183,6 +188,10 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.enable_light_client_server = true;
     }
 
+    if cli_args.get_flag("light-client-historical-updates") {
+        client_config.chain.light_client_historical_updates = true;
+    }
+
     if let Some(cache_size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
         client_config.chain.shuffling_cache_size = cache_size;
     }
@@ -452,12 +461,44 @@ pub fn get_config<E: EthSpec>(
         client_config.store.epochs_per_blob_prune = epochs_per_blob_prune;
     }
 
+    if let Some(epochs_per_column_stats) =
+        clap_utils::parse_optional(cli_args, "epochs-per-column-stats")?
+    {
+        client_config.store.epochs_per_column_stats = epochs_per_column_stats;
+    }
+
     if let Some(blob_prune_margin_epochs) =
         clap_utils::parse_optional(cli_args, "blob-prune-margin-epochs")?
     {
         client_config.store.blob_prune_margin_epochs = blob_prune_margin_epochs;
     }
 
+    if let Some(blob_retention) = clap_utils::parse_optional(cli_args, "blob-retention")? {
+        client_config.store.blob_retention = Some(blob_retention);
+    }
+
+    if let Some(data_column_retention) =
+        clap_utils::parse_optional(cli_args, "data-column-retention")?
+    {
+        client_config.store.data_column_retention = Some(data_column_retention);
+    }
+
+    if let Some(payload_retention) = clap_utils::parse_optional(cli_args, "payload-retention")? {
+        client_config.store.payload_retention = Some(payload_retention);
+    }
+
+    if let Some(historic_state_retention) =
+        clap_utils::parse_optional(cli_args, "historic-state-retention")?
+    {
+        client_config.store.historic_state_retention = Some(historic_state_retention);
+    }
+
+    if let Some(path) = clap_utils::parse_optional(cli_args, "db-encryption-key-file")? {
+        client_config.store.encryption_key_source = Some(KeySource::File(path));
+    } else if let Some(name) = clap_utils::parse_optional(cli_args, "db-encryption-key-env")? {
+        client_config.store.encryption_key_source = Some(KeySource::Env(name));
+    }
+
     if let Some(malicious_withhold_count) =
         clap_utils::parse_optional(cli_args, "malicious-withhold-count")?
     {
@@ -522,19 +563,18 @@ pub fn get_config<E: EthSpec>(
 
     let genesis_state_url_opt =
         clap_utils::parse_optional::<String>(cli_args, "genesis-state-url")?;
-    let checkpoint_sync_url_opt =
-        clap_utils::parse_optional::<String>(cli_args, "checkpoint-sync-url")?;
+    let checkpoint_sync_urls = parse_checkpoint_sync_urls(cli_args)?;
 
     // If the `--genesis-state-url` is defined, use that to download the
     // genesis state bytes. If it's not defined, try `--checkpoint-sync-url`.
     client_config.genesis_state_url = if let Some(genesis_state_url) = genesis_state_url_opt {
         Some(genesis_state_url)
-    } else if let Some(checkpoint_sync_url) = checkpoint_sync_url_opt {
+    } else if let Some(checkpoint_sync_url) = checkpoint_sync_urls.first() {
         // If the checkpoint sync URL is going to be used to download the
         // genesis state, adopt the timeout from the checkpoint sync URL too.
         client_config.genesis_state_url_timeout =
             Duration::from_secs(client_config.chain.checkpoint_sync_url_timeout);
-        Some(checkpoint_sync_url)
+        Some(checkpoint_sync_url.full.to_string())
     } else {
         None
     };
@@ -569,11 +609,21 @@ pub fn get_config<E: EthSpec>(
                 anchor_block_bytes,
                 anchor_blobs_bytes,
             }
-        } else if let Some(remote_bn_url) = cli_args.get_one::<String>("checkpoint-sync-url") {
-            let url = SensitiveUrl::parse(remote_bn_url)
-                .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))?;
+        } else if !checkpoint_sync_urls.is_empty() {
+            let quorum = clap_utils::parse_optional(cli_args, "checkpoint-sync-quorum")?
+                .unwrap_or(checkpoint_sync_urls.len());
+            if quorum == 0 || quorum > checkpoint_sync_urls.len() {
+                return Err(format!(
+                    "--checkpoint-sync-quorum must be between 1 and the number of checkpoint \
+                     sync URLs ({})",
+                    checkpoint_sync_urls.len()
+                ));
+            }
 
-            ClientGenesis::CheckpointSyncUrl { url }
+            ClientGenesis::CheckpointSyncUrl {
+                urls: checkpoint_sync_urls,
+                quorum,
+            }
         } else {
             ClientGenesis::GenesisState
         }
@@ -766,22 +816,22 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.re_org_head_threshold = None;
         client_config.chain.re_org_parent_threshold = None;
     } else {
-        client_config.chain.re_org_head_threshold = Some(
+        // The thresholds default to the values in the network config, which may disable re-orgs
+        // by setting them to `null`.
+        client_config.chain.re_org_head_threshold =
             clap_utils::parse_optional(cli_args, "proposer-reorg-threshold")?
-                .map(ReOrgThreshold)
-                .unwrap_or(DEFAULT_RE_ORG_HEAD_THRESHOLD),
-        );
+                .or(spec.reorg_head_weight_threshold)
+                .map(ReOrgThreshold);
         client_config.chain.re_org_max_epochs_since_finalization =
             clap_utils::parse_optional(cli_args, "proposer-reorg-epochs-since-finalization")?
-                .unwrap_or(DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION);
+                .unwrap_or(Epoch::new(spec.reorg_max_epochs_since_finalization));
         client_config.chain.re_org_cutoff_millis =
             clap_utils::parse_optional(cli_args, "proposer-reorg-cutoff")?;
 
-        client_config.chain.re_org_parent_threshold = Some(
+        client_config.chain.re_org_parent_threshold =
             clap_utils::parse_optional(cli_args, "proposer-reorg-parent-threshold")?
-                .map(ReOrgThreshold)
-                .unwrap_or(DEFAULT_RE_ORG_PARENT_THRESHOLD),
-        );
+                .or(spec.reorg_parent_weight_threshold)
+                .map(ReOrgThreshold);
 
         if let Some(disallowed_offsets_str) =
             clap_utils::parse_optional::<String>(cli_args, "proposer-reorg-disallowed-offsets")?
@@ -820,10 +870,41 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
+    if cli_args.get_flag("fork-choice-journal") {
+        let dir =
+            if let Some(dir) = clap_utils::parse_optional(cli_args, "fork-choice-journal-dir")? {
+                dir
+            } else {
+                client_config.data_dir().join("fork_choice_journal")
+            };
+        let max_file_size_mb: u64 =
+            clap_utils::parse_required(cli_args, "fork-choice-journal-max-file-size")?;
+        let max_files = clap_utils::parse_required(cli_args, "fork-choice-journal-max-files")?;
+        if max_files == 0 {
+            return Err("--fork-choice-journal-max-files must be a non-zero value".to_string());
+        }
+        client_config.chain.fork_choice_journal = Some(ForkChoiceJournalConfig {
+            dir,
+            max_file_size: max_file_size_mb * 1024 * 1024,
+            max_files,
+        });
+    }
+
     client_config.chain.always_reset_payload_statuses = cli_args.get_flag("reset-payload-statuses");
 
     client_config.chain.paranoid_block_proposal = cli_args.get_flag("paranoid-block-proposal");
 
+    client_config.chain.attestation_packing =
+        match clap_utils::parse_required(cli_args, "attestation-packing")? {
+            AttestationPacking::Exact { .. } => AttestationPacking::Exact {
+                time_budget_ms: clap_utils::parse_required(
+                    cli_args,
+                    "attestation-packing-time-budget",
+                )?,
+            },
+            packing => packing,
+        };
+
     /*
      * Builder fallback configs.
      */
@@ -852,6 +933,22 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.genesis_backfill = true;
     }
 
+    if let Some(dir) = clap_utils::parse_optional(cli_args, "historical-blocks-era-dir")? {
+        client_config.chain.historical_block_source = Some(HistoricalBlockSource::EraDir(dir));
+    } else if let Some(dir) = clap_utils::parse_optional(cli_args, "historical-blocks-datadir")? {
+        client_config.chain.historical_block_source = Some(HistoricalBlockSource::Datadir(dir));
+    }
+
+    if let Some(url) = cli_args.get_one::<String>("backfill-beacon-node-url") {
+        let url = SensitiveUrl::parse(url)
+            .map_err(|e| format!("Invalid backfill beacon node URL: {:?}", e))?;
+        client_config.chain.backfill_source = Some(BackfillSource::BeaconNode(url));
+    } else if let Some(url) = cli_args.get_one::<String>("backfill-era-mirror-url") {
+        let url = SensitiveUrl::parse(url)
+            .map_err(|e| format!("Invalid backfill era mirror URL: {:?}", e))?;
+        client_config.chain.backfill_source = Some(BackfillSource::EraMirror(url));
+    }
+
     // Backfill sync rate-limiting
     client_config.beacon_processor.enable_backfill_rate_limiting =
         !cli_args.get_flag("disable-backfill-rate-limiting");
@@ -882,10 +979,82 @@ pub fn get_config<E: EthSpec>(
         .beacon_processor
         .max_gossip_aggregate_batch_size =
         clap_utils::parse_required(cli_args, "beacon-processor-aggregate-batch-size")?;
+    if let Some(mode) = cli_args.get_one::<String>("beacon-processor-scheduler") {
+        client_config.beacon_processor.scheduler_mode = match mode.as_str() {
+            "strict" => SchedulerMode::Strict,
+            "fair" => SchedulerMode::FairQueuing,
+            other => return Err(format!("Invalid beacon processor scheduler: {other}")),
+        };
+    }
+    if let Some(weights) = cli_args.get_one::<String>("beacon-processor-flow-weights") {
+        client_config.beacon_processor.flow_weights = parse_flow_weights(weights)?;
+    }
+    if let Some(priorities) = cli_args.get_one::<String>("beacon-processor-priorities") {
+        client_config.beacon_processor.work_type_priorities =
+            parse_work_type_priorities(priorities)?;
+    }
 
     Ok(client_config)
 }
 
+/// Parses `--beacon-processor-flow-weights` as a list of `flow=weight` pairs, where a flow is
+/// `local` or a peer id.
+fn parse_flow_weights(weights: &str) -> Result<FlowWeights, String> {
+    let mut flow_weights = FlowWeights::default();
+    for pair in weights.split(',') {
+        let (flow, weight) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid flow weight {pair:?}, expected FLOW=WEIGHT"))?;
+        let weight = weight
+            .trim()
+            .parse()
+            .ok()
+            .filter(|weight| *weight > 0)
+            .ok_or_else(|| {
+                format!("Invalid flow weight {weight:?}, expected a positive integer")
+            })?;
+        match flow.trim() {
+            "local" => flow_weights.local = weight,
+            peer_id => flow_weights.peers.push((peer_id.parse()?, weight)),
+        }
+    }
+    Ok(flow_weights)
+}
+
+/// Parses `--beacon-processor-priorities` as a list of `work_type=lane` pairs.
+fn parse_work_type_priorities(priorities: &str) -> Result<Vec<(WorkType, u8)>, String> {
+    priorities
+        .split(',')
+        .map(|pair| {
+            let (work_type, lane) = pair.split_once('=').ok_or_else(|| {
+                format!("Invalid work type priority {pair:?}, expected TYPE=LANE")
+            })?;
+            let work_type = WorkType::from_str(work_type.trim())
+                .ok()
+                .filter(|work_type| DEFAULT_PRIORITY_ORDER.contains(work_type))
+                .ok_or_else(|| format!("Unknown or unqueued work type {work_type:?}"))?;
+            let lane = lane
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid priority lane {lane:?}: {e}"))?;
+            Ok((work_type, lane))
+        })
+        .collect()
+}
+
+/// Parses the comma-separated `--checkpoint-sync-url` endpoints, if any.
+fn parse_checkpoint_sync_urls(cli_args: &ArgMatches) -> Result<Vec<SensitiveUrl>, String> {
+    let Some(urls) = cli_args.get_one::<String>("checkpoint-sync-url") else {
+        return Ok(vec![]);
+    };
+    urls.split(',')
+        .map(|url| {
+            SensitiveUrl::parse(url.trim())
+                .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))
+        })
+        .collect()
+}
+
 /// Gets the listening_addresses for lighthouse based on the cli options.
 pub fn parse_listening_addresses(
     cli_args: &ArgMatches,
@@ -1210,6 +1379,49 @@ pub fn set_network_config(
         }
     }
 
+    if let Some(sentry_of_str) = cli_args.get_one::<String>("sentry-of") {
+        config.sentry_of = sentry_of_str
+            .split(',')
+            .map(PeerIdSerialized::from_peer_id_or_enr)
+            .collect::<Result<Vec<_>, _>>()?;
+        // Private nodes are trusted so that they are never pruned or scored out and are kept as
+        // explicit gossipsub peers.
+        for private_peer in &config.sentry_of {
+            if !config.trusted_peers.contains(private_peer) {
+                config.trusted_peers.push(private_peer.clone());
+            }
+        }
+    }
+
+    if parse_flag(cli_args, "private-peer") {
+        if config.trusted_peers.is_empty() {
+            return Err(
+                "--private-peer requires the sentries to be set via --trusted-peers".into(),
+            );
+        }
+        // A private node only connects to its sentries and is never advertised.
+        config.private_peer = true;
+        config.private_network = true;
+        config.disable_discovery = true;
+        config.upnp_enabled = false;
+    }
+
+    if parse_flag(cli_args, "private-network") {
+        config.private_network = true;
+        if let Some(allowlist_str) = cli_args.get_one::<String>("peer-allowlist") {
+            config.peer_allowlist = allowlist_str
+                .split(',')
+                .map(PeerIdSerialized::from_peer_id_or_enr)
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+        if config.peer_allowlist.is_empty() && config.trusted_peers.is_empty() {
+            warn!(
+                log,
+                "Private network mode is enabled without any allowed peers"
+            );
+        }
+    }
+
     if let Some(enr_udp_port_str) = cli_args.get_one::<String>("enr-udp-port") {
         config.enr_udp4_port = Some(
             enr_udp_port_str
@@ -1410,6 +1622,7 @@ pub fn set_network_config(
 
     // Light client server config.
     config.enable_light_client_server = parse_flag(cli_args, "light-client-server");
+    config.webrtc_port = clap_utils::parse_optional(cli_args, "webrtc-port")?;
 
     // The self limiter is enabled by default. If the `self-limiter-protocols` flag is not provided,
     // the default params will be used.
@@ -1447,6 +1660,13 @@ pub fn set_network_config(
         }
     };
 
+    config.rate_limits_file = clap_utils::parse_optional(cli_args, "rpc-rate-limits-file")?;
+
+    config.gossip_trace_file = clap_utils::parse_optional(cli_args, "gossip-trace-file")?;
+    let gossip_trace_max_file_size_mb: u64 =
+        clap_utils::parse_required(cli_args, "gossip-trace-max-file-size")?;
+    config.gossip_trace_max_file_size = gossip_trace_max_file_size_mb * 1024 * 1024;
+
     if let Some(idontwant_message_size_threshold) =
         cli_args.get_one::<String>("idontwant-message-size-threshold")
     {
@@ -0,0 +1,97 @@
+//! Key counts and sizes of each `DBColumn`, for monitoring disk usage.
+use crate::{DBColumn, Error, KeyValueStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+use types::{Epoch, EthSpec};
+
+/// Number of keys in a column and the total size of its keys and values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub num_keys: u64,
+    pub total_bytes: u64,
+}
+
+/// Statistics for the non-empty columns of each database, keyed by column ID (see `DBColumn`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseColumnStats {
+    /// The finalized epoch at the time the statistics were computed.
+    pub epoch: Epoch,
+    pub hot_db: BTreeMap<String, ColumnStats>,
+    pub freezer_db: BTreeMap<String, ColumnStats>,
+    pub blobs_db: BTreeMap<String, ColumnStats>,
+}
+
+/// Read the value of one in this many keys when estimating column sizes in the background.
+pub const DEFAULT_COLUMN_STATS_SAMPLE_INTERVAL: u64 = 64;
+
+/// Compute statistics for all non-empty columns of `db`.
+///
+/// Keys are counted exactly, but only one in `sample_interval` values is read and the size of the
+/// others is extrapolated from them. A `sample_interval` of 1 reads every value, which can take a
+/// long time for large databases.
+pub fn compute_column_stats<E: EthSpec>(
+    db: &impl KeyValueStore<E>,
+    sample_interval: u64,
+) -> Result<BTreeMap<String, ColumnStats>, Error> {
+    let sample_interval = std::cmp::max(sample_interval, 1);
+    let mut all_stats = BTreeMap::new();
+    for column in DBColumn::iter() {
+        let mut num_keys = 0;
+        let mut key_bytes = 0;
+        let mut num_sampled = 0;
+        let mut sampled_value_bytes = 0;
+        for res in db.iter_raw_keys(column, &[]) {
+            let key = res?;
+            // The key may have been deleted since it was iterated, in which case it isn't sampled.
+            if num_keys % sample_interval == 0 {
+                if let Some(value) = db.get_bytes(column.into(), &key)? {
+                    num_sampled += 1;
+                    sampled_value_bytes += value.len() as u64;
+                }
+            }
+            num_keys += 1;
+            key_bytes += (column.as_bytes().len() + key.len()) as u64;
+        }
+        if num_keys > 0 {
+            let value_bytes = (sampled_value_bytes as u128 * num_keys as u128)
+                .checked_div(num_sampled as u128)
+                .unwrap_or(0);
+            all_stats.insert(
+                column.as_str().to_string(),
+                ColumnStats {
+                    num_keys,
+                    total_bytes: key_bytes + value_bytes as u64,
+                },
+            );
+        }
+    }
+    Ok(all_stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryStore;
+    use types::MinimalEthSpec;
+
+    #[test]
+    fn sampled_column_stats() {
+        let db = MemoryStore::<MinimalEthSpec>::open();
+        let column = DBColumn::BeaconBlockRoots;
+        for i in 0..10u64 {
+            db.put_bytes(column.into(), &i.to_be_bytes(), &[0; 100])
+                .unwrap();
+        }
+        let expected = ColumnStats {
+            num_keys: 10,
+            total_bytes: 10 * (column.as_bytes().len() + 8 + 100) as u64,
+        };
+
+        for sample_interval in [0, 1, 3, 64] {
+            let stats = compute_column_stats(&db, sample_interval).unwrap();
+            assert_eq!(stats.len(), 1);
+            assert_eq!(stats[column.as_str()], expected);
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use crate::hdiff::HierarchyConfig;
+use crate::retention::RetentionPolicy;
 use crate::{AnchorInfo, DBColumn, Error, Split, StoreItem};
+use db_encryption::{DbCipher, KeySource};
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::io::Write;
 use std::num::NonZeroUsize;
+use std::sync::Arc;
 use superstruct::superstruct;
 use types::non_zero_usize::new_non_zero_usize;
 use types::EthSpec;
@@ -20,6 +23,7 @@ pub const DEFAULT_HDIFF_BUFFER_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(16)
 const EST_COMPRESSION_FACTOR: usize = 2;
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_EPOCHS_PER_COLUMN_STATS: u64 = 225;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,6 +53,21 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Frequency of recomputing per-column key counts and sizes in epochs. 0 disables them.
+    /// Default: 225 (roughly daily on mainnet).
+    pub epochs_per_column_stats: u64,
+    /// Retention policy for blobs. Defaults to the policy implied by `prune_blobs`.
+    pub blob_retention: Option<RetentionPolicy>,
+    /// Retention policy for data columns. Defaults to the blob retention policy.
+    pub data_column_retention: Option<RetentionPolicy>,
+    /// Retention policy for execution payloads. Defaults to the policy implied by
+    /// `prune_payloads`.
+    pub payload_retention: Option<RetentionPolicy>,
+    /// Retention policy for historic states. Defaults to the state limits chosen when the
+    /// database was initialized.
+    pub historic_state_retention: Option<RetentionPolicy>,
+    /// Where to load the key for encrypting database values at rest. Disabled if `None`.
+    pub encryption_key_source: Option<KeySource>,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -68,7 +87,7 @@ pub struct OnDiskStoreConfig {
 }
 
 impl OnDiskStoreConfigV22 {
-    fn new(hierarchy_config: HierarchyConfig) -> Self {
+    pub(crate) fn new(hierarchy_config: HierarchyConfig) -> Self {
         Self {
             version_byte: 22,
             hierarchy_config,
@@ -108,6 +127,12 @@ impl Default for StoreConfig {
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            epochs_per_column_stats: DEFAULT_EPOCHS_PER_COLUMN_STATS,
+            blob_retention: None,
+            data_column_retention: None,
+            payload_retention: None,
+            historic_state_retention: None,
+            encryption_key_source: None,
         }
     }
 }
@@ -142,6 +167,38 @@ impl StoreConfig {
         }
     }
 
+    /// Load the cipher for encrypting database values, if encryption is enabled.
+    pub fn load_cipher(&self) -> Result<Option<Arc<DbCipher>>, Error> {
+        self.encryption_key_source
+            .as_ref()
+            .map(|source| Ok(Arc::new(source.load_cipher()?)))
+            .transpose()
+    }
+
+    /// Return the retention policy for blobs, falling back to `prune_blobs`.
+    pub fn effective_blob_retention(&self) -> RetentionPolicy {
+        self.blob_retention.unwrap_or(if self.prune_blobs {
+            RetentionPolicy::KeepLastNEpochs(0)
+        } else {
+            RetentionPolicy::KeepAll
+        })
+    }
+
+    /// Return the retention policy for data columns, falling back to the blob policy.
+    pub fn effective_data_column_retention(&self) -> RetentionPolicy {
+        self.data_column_retention
+            .unwrap_or_else(|| self.effective_blob_retention())
+    }
+
+    /// Return the retention policy for execution payloads, falling back to `prune_payloads`.
+    pub fn effective_payload_retention(&self) -> RetentionPolicy {
+        self.payload_retention.unwrap_or(if self.prune_payloads {
+            RetentionPolicy::KeepLastNEpochs(0)
+        } else {
+            RetentionPolicy::KeepAll
+        })
+    }
+
     /// Check that the configuration is valid.
     pub fn verify<E: EthSpec>(&self) -> Result<(), StoreConfigError> {
         self.verify_compression_level()?;
@@ -0,0 +1,437 @@
+//! Reading and writing of era files.
+//!
+//! An era file is an [e2store] file holding the blocks of one `SLOTS_PER_HISTORICAL_ROOT` window
+//! followed by the state at the end of that window:
+//!
+//! ```text
+//! era := Version | CompressedSignedBeaconBlock* | CompressedBeaconState | SlotIndex(block)? | SlotIndex(state)
+//! ```
+//!
+//! Era `N` contains the blocks with slots in `[(N - 1) * SLOTS_PER_HISTORICAL_ROOT, N *
+//! SLOTS_PER_HISTORICAL_ROOT)` and the state at slot `N * SLOTS_PER_HISTORICAL_ROOT`. Era 0
+//! contains only the genesis state.
+//!
+//! [e2store]: https://github.com/eth-clients/e2store-format-specs/blob/main/formats/e2store.md
+use snap::read::FrameDecoder;
+use snap::write::FrameEncoder;
+use ssz::Decode;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+pub const E2_VERSION: [u8; 2] = [0x65, 0x32];
+pub const E2_COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+pub const E2_COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+pub const E2_SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+/// Length of the record header: 2 bytes type, 4 bytes little-endian length, 2 reserved bytes.
+pub const E2_HEADER_LEN: usize = 8;
+
+/// Extension used for era files.
+pub const ERA_FILE_EXTENSION: &str = "era";
+
+/// Length of the state slot index record which ends every era file.
+const STATE_SLOT_INDEX_LEN: u64 = E2_HEADER_LEN as u64 + 3 * 8;
+
+/// Return the era whose file contains the block at `slot`.
+pub fn era_of_block<E: EthSpec>(slot: Slot) -> u64 {
+    slot.as_u64() / E::slots_per_historical_root() as u64 + 1
+}
+
+/// Return the historical root that identifies `era` in its file name.
+///
+/// This is the root of the previous era's block and state roots from the accumulator, or the
+/// genesis validators root for era 0. `state` must be at or after the end of `era`.
+pub fn era_root<E: EthSpec>(state: &BeaconState<E>, era: u64) -> Result<Hash256, String> {
+    let Some(index) = era.checked_sub(1).map(|index| index as usize) else {
+        return Ok(state.genesis_validators_root());
+    };
+    let historical_roots = state.historical_roots();
+    if let Some(root) = historical_roots.get(index) {
+        return Ok(*root);
+    }
+    state
+        .historical_summaries()
+        .ok()
+        .and_then(|summaries| summaries.get(index - historical_roots.len()))
+        .map(|summary| summary.tree_hash_root())
+        .ok_or_else(|| format!("Missing historical root for era {era}"))
+}
+
+/// Return the standard file name of an era file: `<config-name>-<era-number>-<short-root>.era`.
+pub fn era_file_name(config_name: Option<&str>, era: u64, era_root: Hash256) -> String {
+    format!(
+        "{}-{:05}-{}.{ERA_FILE_EXTENSION}",
+        config_name.unwrap_or("unknown"),
+        era,
+        hex::encode(&era_root[..4]),
+    )
+}
+
+/// Read the blocks and state from the era file at `path`.
+///
+/// Blocks are returned in the order they appear in the file, which is slot-ascending.
+pub fn read_era_file<E: EthSpec>(
+    path: &Path,
+    spec: &ChainSpec,
+) -> Result<(Vec<SignedBeaconBlock<E>>, BeaconState<E>), String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {path:?}: {e:?}"))?;
+    let mut reader = BufReader::new(file);
+    let read_err = |e: String| format!("Invalid era file {path:?}: {e}");
+
+    match read_record(&mut reader).map_err(read_err)? {
+        Some(record) if record.record_type == E2_VERSION => {}
+        _ => return Err(read_err("missing version record".into())),
+    }
+
+    let mut blocks = vec![];
+    let mut state = None;
+    while let Some(record) = read_record(&mut reader).map_err(read_err)? {
+        match record.record_type {
+            E2_COMPRESSED_SIGNED_BEACON_BLOCK => {
+                let bytes = decompress(&record.data).map_err(read_err)?;
+                let block = SignedBeaconBlock::from_ssz_bytes(&bytes, spec)
+                    .map_err(|e| read_err(format!("invalid block: {e:?}")))?;
+                blocks.push(block);
+            }
+            E2_COMPRESSED_BEACON_STATE => {
+                if state.is_some() {
+                    return Err(read_err("more than one state".into()));
+                }
+                let bytes = decompress(&record.data).map_err(read_err)?;
+                let decoded = BeaconState::from_ssz_bytes(&bytes, spec)
+                    .map_err(|e| read_err(format!("invalid state: {e:?}")))?;
+                state = Some(decoded);
+            }
+            // Slot indices are only needed for random access, and unknown record types must be
+            // skipped by readers.
+            _ => {}
+        }
+    }
+
+    let state = state.ok_or_else(|| read_err("missing state".into()))?;
+    Ok((blocks, state))
+}
+
+/// Read the blocks with slots in `[start_slot, end_slot)` from the era file at `path`.
+///
+/// Only the requested blocks are read, using the block slot index at the end of the file. Blocks
+/// are returned in slot-ascending order.
+pub fn read_era_blocks<E: EthSpec>(
+    path: &Path,
+    start_slot: Slot,
+    end_slot: Slot,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBeaconBlock<E>>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Unable to open {path:?}: {e:?}"))?;
+    read_era_blocks_from(&mut file, start_slot, end_slot, spec)
+        .map_err(|e| format!("Invalid era file {path:?}: {e}"))
+}
+
+/// Read the blocks with slots in `[start_slot, end_slot)` from an era file held by `reader`.
+pub fn read_era_blocks_from<E: EthSpec, R: Read + Seek>(
+    reader: &mut R,
+    start_slot: Slot,
+    end_slot: Slot,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBeaconBlock<E>>, String> {
+    let io_err = |e: io::Error| format!("{e:?}");
+
+    // The block index is immediately followed by the fixed size state index, and ends with the
+    // number of slots it covers.
+    let file_len = reader.seek(SeekFrom::End(0)).map_err(io_err)?;
+    let count_position = file_len
+        .checked_sub(STATE_SLOT_INDEX_LEN + 8)
+        .ok_or_else(|| "file too short".to_string())?;
+    reader
+        .seek(SeekFrom::Start(count_position))
+        .map_err(io_err)?;
+    let mut count = [0; 8];
+    reader.read_exact(&mut count).map_err(io_err)?;
+    let count = u64::from_le_bytes(count);
+
+    let index_len = count
+        .checked_add(2)
+        .and_then(|words| words.checked_mul(8))
+        .and_then(|len| len.checked_add(E2_HEADER_LEN as u64))
+        .ok_or_else(|| "invalid block index".to_string())?;
+    let index_position = (count_position + 8)
+        .checked_sub(index_len)
+        .ok_or_else(|| "invalid block index".to_string())?;
+    let index = read_record_at(reader, index_position)?
+        .filter(|record| {
+            record.record_type == E2_SLOT_INDEX
+                && record.data.len() as u64 + E2_HEADER_LEN as u64 == index_len
+        })
+        .ok_or_else(|| "missing block index".to_string())?;
+
+    let words = index
+        .data
+        .chunks_exact(8)
+        .map(|chunk| i64::from_le_bytes(<[u8; 8]>::try_from(chunk).unwrap_or_default()))
+        .collect::<Vec<_>>();
+    let index_start_slot = Slot::new(words[0] as u64);
+    let offsets = &words[1..words.len() - 1];
+
+    let mut blocks = vec![];
+    for (i, offset) in offsets.iter().enumerate() {
+        let slot = index_start_slot + i as u64;
+        // An offset of 0 marks an empty slot.
+        if slot < start_slot || slot >= end_slot || *offset == 0 {
+            continue;
+        }
+        let position = u64::try_from(index_position as i64 + offset)
+            .map_err(|_| format!("invalid offset for slot {slot}"))?;
+        let record = read_record_at(reader, position)?
+            .filter(|record| record.record_type == E2_COMPRESSED_SIGNED_BEACON_BLOCK)
+            .ok_or_else(|| format!("missing block at slot {slot}"))?;
+        let bytes = decompress(&record.data)?;
+        let block = SignedBeaconBlock::from_ssz_bytes(&bytes, spec)
+            .map_err(|e| format!("invalid block: {e:?}"))?;
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+/// Return the era files in `dir` keyed by era number, which is parsed from the file name.
+pub fn find_era_files(dir: &Path) -> Result<BTreeMap<u64, PathBuf>, String> {
+    let mut era_files = BTreeMap::new();
+    let entries = fs::read_dir(dir).map_err(|e| format!("Unable to read {dir:?}: {e:?}"))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Unable to read {dir:?}: {e:?}"))?
+            .path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(ERA_FILE_EXTENSION) {
+            continue;
+        }
+        let era = parse_era_number(&path)
+            .ok_or_else(|| format!("Unable to parse era number from {path:?}"))?;
+        if let Some(other) = era_files.insert(era, path.clone()) {
+            return Err(format!("Duplicate era files {other:?} and {path:?}"));
+        }
+    }
+    Ok(era_files)
+}
+
+/// Parse the era number from a file named `<config-name>-<era-number>-<short-root>.era`.
+pub fn parse_era_number(path: &Path) -> Option<u64> {
+    let stem = path.file_stem()?.to_str()?;
+    let mut parts = stem.rsplitn(3, '-');
+    let _short_root = parts.next()?;
+    let era = parts.next()?;
+    let _config_name = parts.next()?;
+    era.parse().ok()
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    FrameDecoder::new(data)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("invalid snappy data: {e:?}"))?;
+    Ok(bytes)
+}
+
+pub struct E2Record {
+    pub record_type: [u8; 2],
+    pub data: Vec<u8>,
+}
+
+/// Read the next record, returning `None` at the end of the file.
+pub fn read_record(reader: &mut impl Read) -> Result<Option<E2Record>, String> {
+    let mut header = [0; E2_HEADER_LEN];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err("truncated record header".into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(format!("{e:?}")),
+        }
+    }
+
+    let record_type = [header[0], header[1]];
+    let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+    if header[6..] != [0, 0] {
+        return Err("non-zero reserved bytes in record header".into());
+    }
+
+    let mut data = vec![0; length as usize];
+    reader
+        .read_exact(&mut data)
+        .map_err(|e| format!("truncated record: {e:?}"))?;
+    Ok(Some(E2Record { record_type, data }))
+}
+
+/// Read the record starting at `position`.
+fn read_record_at(
+    reader: &mut (impl Read + Seek),
+    position: u64,
+) -> Result<Option<E2Record>, String> {
+    reader
+        .seek(SeekFrom::Start(position))
+        .map_err(|e| format!("{e:?}"))?;
+    read_record(reader)
+}
+
+pub struct E2Writer<W: Write> {
+    inner: W,
+    position: u64,
+}
+
+impl<W: Write> E2Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, position: 0 }
+    }
+
+    /// Write a record, returning its offset from the start of the file.
+    pub fn write_record(&mut self, record_type: [u8; 2], data: &[u8]) -> io::Result<u64> {
+        let length = u32::try_from(data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too large"))?;
+        let offset = self.position;
+        self.inner.write_all(&record_type)?;
+        self.inner.write_all(&length.to_le_bytes())?;
+        self.inner.write_all(&[0, 0])?;
+        self.inner.write_all(data)?;
+        self.position += (E2_HEADER_LEN + data.len()) as u64;
+        Ok(offset)
+    }
+
+    /// Write a record with snappy framed compression applied to `data`.
+    pub fn write_compressed(&mut self, record_type: [u8; 2], data: &[u8]) -> io::Result<u64> {
+        let mut encoder = FrameEncoder::new(vec![]);
+        encoder.write_all(data)?;
+        encoder.flush()?;
+        self.write_record(record_type, encoder.get_ref())
+    }
+
+    /// Write a slot index for consecutive slots from `start_slot`.
+    ///
+    /// Offsets are relative to the start of the index record, with 0 marking an empty slot.
+    pub fn write_slot_index(
+        &mut self,
+        start_slot: Slot,
+        offsets: &[Option<u64>],
+    ) -> io::Result<u64> {
+        let index_position = self.position as i64;
+        let mut data = Vec::with_capacity(8 * (offsets.len() + 2));
+        data.extend_from_slice(&(start_slot.as_u64() as i64).to_le_bytes());
+        for offset in offsets {
+            let relative = offset.map_or(0, |offset| offset as i64 - index_position);
+            data.extend_from_slice(&relative.to_le_bytes());
+        }
+        data.extend_from_slice(&(offsets.len() as i64).to_le_bytes());
+        self.write_record(E2_SLOT_INDEX, &data)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Signature;
+    use ssz::Encode;
+    use types::{BeaconBlock, MinimalEthSpec};
+
+    #[test]
+    fn e2store_roundtrip() {
+        let mut writer = E2Writer::new(vec![]);
+        writer.write_record(E2_VERSION, &[]).unwrap();
+        let block_offset = writer
+            .write_compressed(E2_COMPRESSED_SIGNED_BEACON_BLOCK, &[1, 2, 3])
+            .unwrap();
+        let index_offset = writer
+            .write_slot_index(Slot::new(8), &[None, Some(block_offset)])
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        assert_eq!(block_offset, E2_HEADER_LEN as u64);
+        assert_eq!(bytes.len() as u64, index_offset + E2_HEADER_LEN as u64 + 32);
+
+        let mut reader = &bytes[..];
+        let version = read_record(&mut reader).unwrap().unwrap();
+        assert_eq!(version.record_type, E2_VERSION);
+        assert!(version.data.is_empty());
+
+        let block = read_record(&mut reader).unwrap().unwrap();
+        assert_eq!(block.record_type, E2_COMPRESSED_SIGNED_BEACON_BLOCK);
+        assert_eq!(decompress(&block.data).unwrap(), vec![1, 2, 3]);
+
+        let index = read_record(&mut reader).unwrap().unwrap();
+        assert_eq!(index.record_type, E2_SLOT_INDEX);
+        let words = index
+            .data
+            .chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            vec![8, 0, block_offset as i64 - index_offset as i64, 2]
+        );
+
+        assert!(read_record(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_blocks_by_slot_index() {
+        let spec = MinimalEthSpec::default_spec();
+        let block_at = |slot: u64| {
+            let mut block = BeaconBlock::<MinimalEthSpec>::empty(&spec);
+            *block.slot_mut() = Slot::new(slot);
+            SignedBeaconBlock::from_block(block, Signature::empty())
+        };
+        let blocks = [block_at(65), block_at(67)];
+
+        // Era 2 covers slots 64..128 with the minimal spec. Slots 65 and 67 hold blocks.
+        let start_slot = Slot::new(64);
+        let mut writer = E2Writer::new(vec![]);
+        writer.write_record(E2_VERSION, &[]).unwrap();
+        let mut offsets = vec![None; 64];
+        for block in &blocks {
+            let offset = writer
+                .write_compressed(E2_COMPRESSED_SIGNED_BEACON_BLOCK, &block.as_ssz_bytes())
+                .unwrap();
+            offsets[(block.slot() - start_slot).as_usize()] = Some(offset);
+        }
+        let state_offset = writer
+            .write_compressed(E2_COMPRESSED_BEACON_STATE, &[0])
+            .unwrap();
+        writer.write_slot_index(start_slot, &offsets).unwrap();
+        writer
+            .write_slot_index(Slot::new(128), &[Some(state_offset)])
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("minimal-00002-00000000.era");
+        fs::write(&path, writer.into_inner()).unwrap();
+
+        let read = |start: u64, end: u64| {
+            read_era_blocks::<MinimalEthSpec>(&path, Slot::new(start), Slot::new(end), &spec)
+                .unwrap()
+        };
+        assert_eq!(read(64, 128), blocks.to_vec());
+        assert_eq!(read(66, 128), vec![blocks[1].clone()]);
+        assert_eq!(read(0, 67), vec![blocks[0].clone()]);
+        assert!(read(68, 200).is_empty());
+        assert_eq!(era_of_block::<MinimalEthSpec>(Slot::new(65)), 2);
+    }
+
+    #[test]
+    fn parse_era_file_names() {
+        assert_eq!(
+            parse_era_number(Path::new("/tmp/mainnet-01234-0a1b2c3d.era")),
+            Some(1234)
+        );
+        assert_eq!(
+            parse_era_number(Path::new("my-devnet-00002-0a1b2c3d.era")),
+            Some(2)
+        );
+        assert_eq!(parse_era_number(Path::new("mainnet.era")), None);
+    }
+}
@@ -69,6 +69,11 @@ pub enum Error {
     RandaoMixOutOfBounds,
     GenesisStateUnknown,
     ArithError(safe_arith::ArithError),
+    Encryption(db_encryption::Error),
+    /// The database is encrypted but no encryption key was provided.
+    DatabaseEncryptedWithoutKey,
+    /// An encryption key was provided but the existing database is not encrypted.
+    DatabaseNotEncrypted,
 }
 
 pub trait HandleUnavailable<T> {
@@ -85,6 +90,12 @@ impl<T> HandleUnavailable<T> for Result<T> {
     }
 }
 
+impl From<db_encryption::Error> for Error {
+    fn from(e: db_encryption::Error) -> Error {
+        Error::Encryption(e)
+    }
+}
+
 impl From<DecodeError> for Error {
     fn from(e: DecodeError) -> Error {
         Error::SszDecodeError(e)
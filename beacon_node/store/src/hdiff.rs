@@ -659,6 +659,12 @@ impl HierarchyModuli {
         }
     }
 
+    /// Return the largest slot less than or equal to `slot` at which a full snapshot is stored.
+    pub fn previous_snapshot_slot(&self, slot: Slot) -> Result<Slot, Error> {
+        let last = self.moduli.last().copied().ok_or(Error::InvalidHierarchy)?;
+        Ok(slot / last * last)
+    }
+
     /// Return `true` if the database ops for this slot should be committed immediately.
     ///
     /// This is the case for all diffs aside from the ones in the leaf layer. To store a diff
@@ -776,6 +782,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn previous_snapshot_slot() {
+        let config = HierarchyConfig::default();
+        config.validate().unwrap();
+
+        let moduli = config.to_moduli().unwrap();
+        let snapshot_freq = Slot::new(1 << 21);
+
+        assert_eq!(moduli.previous_snapshot_slot(Slot::new(0)).unwrap(), 0);
+        assert_eq!(moduli.previous_snapshot_slot(snapshot_freq - 1).unwrap(), 0);
+        assert_eq!(
+            moduli.previous_snapshot_slot(snapshot_freq).unwrap(),
+            snapshot_freq
+        );
+        assert_eq!(
+            moduli
+                .previous_snapshot_slot(snapshot_freq * 2 - 1)
+                .unwrap(),
+            snapshot_freq
+        );
+    }
+
     #[test]
     fn compressed_u64_vs_bytes_diff() {
         let x_values = vec![99u64, 55, 123, 6834857, 0, 12];
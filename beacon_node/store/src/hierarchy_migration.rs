@@ -0,0 +1,398 @@
+//! Re-encoding of the freezer database from one `HierarchyConfig` to another.
+//!
+//! The migration runs in two phases:
+//!
+//! 1. **Re-encode**: every snapshot and diff required by the new hierarchy is computed from the
+//!    states stored under the current hierarchy, and written to the staging columns
+//!    `BeaconStateSnapshotReencoded` and `BeaconStateDiffReencoded`. The freezer remains fully
+//!    readable under the old config during this phase, so it can be interrupted at any point.
+//! 2. **Swap**: values which have no place in the new hierarchy are deleted, the staged values are
+//!    moved into `BeaconStateSnapshot` and `BeaconStateDiff`, and the on-disk config is updated.
+//!    The freezer contains a mix of both encodings during this phase, so `HotColdDB::open` refuses
+//!    to start until it has completed.
+//!
+//! Progress is tracked by a `HierarchyMigration` stored in the hot database, which allows both
+//! phases to be resumed after a crash.
+use crate::config::{OnDiskStoreConfig, OnDiskStoreConfigV22};
+use crate::hdiff::{HDiff, HDiffBuffer, HierarchyConfig, HierarchyModuli, StorageStrategy};
+use crate::hot_cold_store::{HotColdDB, HotColdDBError};
+use crate::metadata::{CONFIG_KEY, HIERARCHY_MIGRATION_KEY};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, StoreItem};
+use lru::LruCache;
+use slog::{debug, info, Logger};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::io::Read;
+use std::num::NonZeroUsize;
+use types::{BeaconState, EthSpec, Slot};
+use zstd::Decoder;
+
+/// Progress of an in-progress hierarchy migration, stored in the hot database.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct HierarchyMigration {
+    /// The hierarchy that the freezer was encoded with prior to the migration.
+    pub from: HierarchyConfig,
+    /// The hierarchy that the freezer is being re-encoded with.
+    pub to: HierarchyConfig,
+    /// All snapshots and diffs for slots prior to this slot have been re-encoded.
+    pub next_slot: Slot,
+    /// Set once re-encoding is complete and the staged values are being moved into place.
+    pub swapping: bool,
+}
+
+impl StoreItem for HierarchyMigration {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Re-encode all historic states in the freezer according to the `to` hierarchy.
+    ///
+    /// The database must be opened with the hierarchy config it is currently encoded with. The
+    /// re-encoded values are written to staging columns and must then be moved into place by
+    /// `swap_reencoded_freezer_states`.
+    ///
+    /// Diffs from the leaf layer of the new hierarchy are written in batches of at most
+    /// `batch_size`. All other values are written immediately, as they are required to compute
+    /// subsequent diffs.
+    pub fn reencode_freezer_states(
+        &self,
+        to: &HierarchyConfig,
+        batch_size: usize,
+    ) -> Result<(), Error> {
+        let from = &self.config.hierarchy_config;
+        let to_moduli = to.to_moduli()?;
+
+        let mut progress = match self
+            .hot_db
+            .get::<HierarchyMigration>(&HIERARCHY_MIGRATION_KEY)?
+        {
+            Some(progress) if progress.from != *from || progress.to != *to => {
+                return Err(HotColdDBError::HierarchyMigrationMismatch {
+                    in_progress: progress.to,
+                    requested: to.clone(),
+                }
+                .into());
+            }
+            Some(progress) if progress.swapping => {
+                debug!(self.log, "Freezer re-encoding already complete");
+                return Ok(());
+            }
+            Some(progress) => {
+                info!(
+                    self.log,
+                    "Resuming freezer re-encoding";
+                    "next_slot" => progress.next_slot,
+                );
+                progress
+            }
+            None => HierarchyMigration {
+                from: from.clone(),
+                to: to.clone(),
+                next_slot: Slot::new(0),
+                swapping: false,
+            },
+        };
+        self.hot_db.put(&HIERARCHY_MIGRATION_KEY, &progress)?;
+
+        // States are available for all slots `<= lower_limit`, and all slots `>= upper_limit`
+        // prior to the split.
+        let split_slot = self.get_split_slot();
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+        let has_gap = upper_limit > lower_limit + 1 && upper_limit < split_slot;
+
+        // The first state above the gap is only loadable if it is a snapshot in the new hierarchy.
+        if has_gap && to_moduli.next_snapshot_slot(upper_limit)? != upper_limit {
+            return Err(HotColdDBError::HierarchyMigrationUnaligned(upper_limit).into());
+        }
+
+        info!(
+            self.log,
+            "Re-encoding freezer states";
+            "from" => %from,
+            "to" => %to,
+            "start_slot" => progress.next_slot,
+            "split_slot" => split_slot,
+        );
+
+        // Each base state is from a different layer, so one buffer per layer is sufficient.
+        let cache_size =
+            NonZeroUsize::new(to.exponents.len()).ok_or(crate::hdiff::Error::InvalidHierarchy)?;
+        let mut buffer_cache = LruCache::new(cache_size);
+        let mut ops = vec![];
+
+        let mut slot = progress.next_slot;
+        while slot < split_slot {
+            let available = slot <= lower_limit || slot >= upper_limit;
+            let mut commit_immediately = false;
+
+            if available {
+                match to_moduli.storage_strategy(slot)? {
+                    StorageStrategy::ReplayFrom(_) => {}
+                    StorageStrategy::Snapshot => {
+                        let state = self.load_cold_state_by_slot(slot)?;
+                        let compressed_bytes = self.config.compress_bytes(&state.as_ssz_bytes())?;
+                        ops.push(KeyValueStoreOp::PutKeyValue(
+                            get_key_for_col(
+                                DBColumn::BeaconStateSnapshotReencoded.into(),
+                                &slot.as_u64().to_be_bytes(),
+                            ),
+                            compressed_bytes,
+                        ));
+                        buffer_cache.put(slot, HDiffBuffer::from_state(state));
+                        commit_immediately = true;
+                    }
+                    StorageStrategy::DiffFrom(from_slot) => {
+                        let base_buffer = self.load_reencoded_hdiff_buffer(
+                            from_slot,
+                            &to_moduli,
+                            &mut buffer_cache,
+                        )?;
+                        let target_buffer = self.load_hdiff_buffer_for_reencode(slot)?;
+                        let diff = HDiff::compute(&base_buffer, &target_buffer, &self.config)?;
+                        ops.push(KeyValueStoreOp::PutKeyValue(
+                            get_key_for_col(
+                                DBColumn::BeaconStateDiffReencoded.into(),
+                                &slot.as_u64().to_be_bytes(),
+                            ),
+                            diff.as_ssz_bytes(),
+                        ));
+
+                        // Diffs outside the leaf layer are the base of other diffs.
+                        commit_immediately = to_moduli.should_commit_immediately(slot)?;
+                        if commit_immediately {
+                            buffer_cache.put(slot, target_buffer);
+                        }
+                    }
+                }
+            }
+
+            slot += 1;
+
+            if commit_immediately || ops.len() >= batch_size || slot == split_slot {
+                self.cold_db.do_atomically(std::mem::take(&mut ops))?;
+                progress.next_slot = slot;
+                self.hot_db.put(&HIERARCHY_MIGRATION_KEY, &progress)?;
+
+                if commit_immediately {
+                    info!(
+                        self.log,
+                        "Freezer re-encoding in progress";
+                        "slot" => slot - 1,
+                        "remaining" => split_slot - slot,
+                    );
+                }
+            }
+        }
+
+        progress.swapping = true;
+        self.hot_db.put(&HIERARCHY_MIGRATION_KEY, &progress)?;
+
+        info!(self.log, "Freezer re-encoding complete"; "split_slot" => split_slot);
+        Ok(())
+    }
+
+    /// Load the `HDiffBuffer` for `slot` as encoded by the current hierarchy.
+    fn load_hdiff_buffer_for_reencode(&self, slot: Slot) -> Result<HDiffBuffer, Error> {
+        match self.hierarchy.storage_strategy(slot)? {
+            StorageStrategy::ReplayFrom(_) => {
+                Ok(HDiffBuffer::from_state(self.load_cold_state_by_slot(slot)?))
+            }
+            StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_) => {
+                Ok(self.load_hdiff_buffer_for_slot(slot)?.1)
+            }
+        }
+    }
+
+    /// Load the `HDiffBuffer` for `slot` from the staging columns, as encoded by `moduli`.
+    fn load_reencoded_hdiff_buffer(
+        &self,
+        slot: Slot,
+        moduli: &HierarchyModuli,
+        cache: &mut LruCache<Slot, HDiffBuffer>,
+    ) -> Result<HDiffBuffer, Error> {
+        if let Some(buffer) = cache.get(&slot) {
+            return Ok(buffer.clone());
+        }
+
+        let buffer = match moduli.storage_strategy(slot)? {
+            StorageStrategy::Snapshot => {
+                let compressed_bytes = self
+                    .cold_db
+                    .get_bytes(
+                        DBColumn::BeaconStateSnapshotReencoded.into(),
+                        &slot.as_u64().to_be_bytes(),
+                    )?
+                    .ok_or(Error::MissingSnapshot(slot))?;
+                let mut ssz_bytes = Vec::with_capacity(
+                    self.config
+                        .estimate_decompressed_size(compressed_bytes.len()),
+                );
+                let mut decoder = Decoder::new(&*compressed_bytes).map_err(Error::Compression)?;
+                decoder
+                    .read_to_end(&mut ssz_bytes)
+                    .map_err(Error::Compression)?;
+                HDiffBuffer::from_state(BeaconState::<E>::from_ssz_bytes(&ssz_bytes, &self.spec)?)
+            }
+            StorageStrategy::DiffFrom(from_slot) => {
+                let mut buffer = self.load_reencoded_hdiff_buffer(from_slot, moduli, cache)?;
+                let diff_bytes = self
+                    .cold_db
+                    .get_bytes(
+                        DBColumn::BeaconStateDiffReencoded.into(),
+                        &slot.as_u64().to_be_bytes(),
+                    )?
+                    .ok_or(HotColdDBError::MissingHDiff(slot))?;
+                HDiff::from_ssz_bytes(&diff_bytes)?.apply(&mut buffer, &self.config)?;
+                buffer
+            }
+            StorageStrategy::ReplayFrom(from_slot) => {
+                return self.load_reencoded_hdiff_buffer(from_slot, moduli, cache);
+            }
+        };
+
+        cache.put(slot, buffer.clone());
+        Ok(buffer)
+    }
+}
+
+/// Move the values staged by `HotColdDB::reencode_freezer_states` into place, completing the
+/// migration.
+///
+/// This function operates on the raw databases because `HotColdDB::open` refuses to open a
+/// database in this phase of the migration. It is idempotent, and may be re-run after a crash.
+pub fn swap_reencoded_freezer_states<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    hot_db: &Hot,
+    cold_db: &Cold,
+    batch_size: usize,
+    log: &Logger,
+) -> Result<(), Error> {
+    let progress = hot_db
+        .get::<HierarchyMigration>(&HIERARCHY_MIGRATION_KEY)?
+        .ok_or(HotColdDBError::MissingHierarchyMigration)?;
+    if !progress.swapping {
+        return Err(HotColdDBError::HierarchyMigrationNotReencoded.into());
+    }
+    let to_moduli = progress.to.to_moduli()?;
+
+    // Delete values which have no place in the new hierarchy. Values which do will be overwritten
+    // by the staged values below, so it is safe to repeat this step after a crash.
+    let mut ops = vec![];
+    let mut num_deleted = 0;
+    for column in [DBColumn::BeaconStateSnapshot, DBColumn::BeaconStateDiff] {
+        for res in cold_db.iter_column_keys::<Vec<u8>>(column) {
+            let key = res?;
+            let slot = Slot::new(u64::from_be_bytes(
+                key.as_slice().try_into().map_err(|_| Error::InvalidKey)?,
+            ));
+            let keep = match to_moduli.storage_strategy(slot)? {
+                StorageStrategy::Snapshot => column == DBColumn::BeaconStateSnapshot,
+                StorageStrategy::DiffFrom(_) => column == DBColumn::BeaconStateDiff,
+                StorageStrategy::ReplayFrom(_) => false,
+            };
+            if !keep {
+                ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    column.into(),
+                    &key,
+                )));
+                num_deleted += 1;
+            }
+            if ops.len() >= batch_size {
+                cold_db.do_atomically(std::mem::take(&mut ops))?;
+            }
+        }
+    }
+    cold_db.do_atomically(std::mem::take(&mut ops))?;
+
+    // Move the staged values into place.
+    let mut num_moved = 0;
+    for (staging_column, column) in [
+        (
+            DBColumn::BeaconStateSnapshotReencoded,
+            DBColumn::BeaconStateSnapshot,
+        ),
+        (
+            DBColumn::BeaconStateDiffReencoded,
+            DBColumn::BeaconStateDiff,
+        ),
+    ] {
+        for res in cold_db.iter_column::<Vec<u8>>(staging_column) {
+            let (key, value) = res?;
+            ops.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(column.into(), &key),
+                value,
+            ));
+            ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                staging_column.into(),
+                &key,
+            )));
+            num_moved += 1;
+            if ops.len() >= batch_size {
+                cold_db.do_atomically(std::mem::take(&mut ops))?;
+            }
+        }
+    }
+    cold_db.do_atomically(ops)?;
+    cold_db.sync()?;
+
+    info!(
+        log,
+        "Re-encoded freezer states moved into place";
+        "deleted" => num_deleted,
+        "moved" => num_moved,
+    );
+
+    // Update the on-disk config and clear the migration atomically.
+    let new_config = OnDiskStoreConfig::V22(OnDiskStoreConfigV22::new(progress.to.clone()));
+    hot_db.do_atomically(vec![
+        new_config.as_kv_store_op(CONFIG_KEY),
+        KeyValueStoreOp::DeleteKey(get_key_for_col(
+            HierarchyMigration::db_column().into(),
+            HIERARCHY_MIGRATION_KEY.as_slice(),
+        )),
+    ])?;
+
+    info!(
+        log,
+        "Freezer hierarchy migration complete";
+        "from" => %progress.from,
+        "to" => %progress.to,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hierarchy_migration_roundtrip() {
+        let progress = HierarchyMigration {
+            from: HierarchyConfig::default(),
+            to: HierarchyConfig {
+                exponents: vec![5, 7, 11],
+            },
+            next_slot: Slot::new(8192),
+            swapping: true,
+        };
+        let bytes = progress.as_store_bytes();
+        assert_eq!(
+            HierarchyMigration::from_store_bytes(&bytes).unwrap(),
+            progress
+        );
+    }
+}
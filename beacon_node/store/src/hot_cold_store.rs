@@ -1,6 +1,10 @@
+use crate::column_stats::{
+    compute_column_stats, DatabaseColumnStats, DEFAULT_COLUMN_STATS_SAMPLE_INTERVAL,
+};
 use crate::config::{OnDiskStoreConfig, StoreConfig};
 use crate::forwards_iter::{HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator};
-use crate::hdiff::{HDiff, HDiffBuffer, HierarchyModuli, StorageStrategy};
+use crate::hdiff::{HDiff, HDiffBuffer, HierarchyConfig, HierarchyModuli, StorageStrategy};
+use crate::hierarchy_migration::HierarchyMigration;
 use crate::historic_state_cache::HistoricStateCache;
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{BlockRootsIterator, ParentRootBlockIterator, RootsIterator};
@@ -10,11 +14,13 @@ use crate::metadata::{
     AnchorInfo, BlobInfo, CompactionTimestamp, DataColumnInfo, PruningCheckpoint, SchemaVersion,
     ANCHOR_FOR_ARCHIVE_NODE, ANCHOR_INFO_KEY, ANCHOR_UNINITIALIZED, BLOB_INFO_KEY,
     COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, DATA_COLUMN_INFO_KEY,
-    PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
+    HIERARCHY_MIGRATION_KEY, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY,
+    STATE_UPPER_LIMIT_NO_RETAIN,
 };
+use crate::retention::RetentionPolicy;
 use crate::state_cache::{PutStateOutcome, StateCache};
 use crate::{
-    get_data_column_key, get_key_for_col, DBColumn, DatabaseBlock, Error, ItemStore,
+    get_data_column_key, get_key_for_col, DBColumn, DatabaseBlock, Error, ItemStore, Key,
     KeyValueStoreOp, StoreItem, StoreOp,
 };
 use crate::{metrics, parse_data_column_key};
@@ -39,6 +45,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use strum::IntoEnumIterator;
 use types::data_column_sidecar::{ColumnIndex, DataColumnSidecar, DataColumnSidecarList};
 use types::*;
 use zstd::{Decoder, Encoder};
@@ -81,6 +88,8 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     /// This cache is never pruned. It is only populated in response to historical queries from the
     /// HTTP API.
     historic_state_cache: Mutex<HistoricStateCache<E>>,
+    /// Most recently computed key counts and sizes for each column.
+    column_stats: RwLock<Option<DatabaseColumnStats>>,
     /// Chain spec.
     pub(crate) spec: Arc<ChainSpec>,
     /// Logger.
@@ -171,6 +180,8 @@ pub enum HotColdDBError {
     MissingFrozenBlockSlot(Hash256),
     MissingFrozenBlock(Slot),
     MissingPathToBlobsDatabase,
+    /// The database has no schema version, so it was never initialized.
+    MissingSchemaVersion,
     BlobsPreviouslyInDefaultStore,
     HotStateSummaryError(BeaconStateError),
     RestorePointDecodeError(ssz::DecodeError),
@@ -194,6 +205,17 @@ pub enum HotColdDBError {
         block_root: Hash256,
     },
     Rollback,
+    /// A hierarchy migration to a different config is already in progress.
+    HierarchyMigrationMismatch {
+        in_progress: HierarchyConfig,
+        requested: HierarchyConfig,
+    },
+    /// The lowest state above the gap in historic states is not a snapshot in the new hierarchy.
+    HierarchyMigrationUnaligned(Slot),
+    /// The freezer is part-way through a hierarchy migration and can't be read.
+    HierarchyMigrationIncomplete,
+    HierarchyMigrationNotReencoded,
+    MissingHierarchyMigration,
 }
 
 impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
@@ -220,6 +242,7 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
                 config.hdiff_buffer_cache_size,
                 config.historic_state_cache_size,
             )),
+            column_stats: RwLock::new(None),
             config,
             hierarchy,
             spec,
@@ -249,7 +272,17 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
         let hierarchy = config.hierarchy_config.to_moduli()?;
 
-        let hot_db = LevelDB::open(hot_path)?;
+        let cipher = config.load_cipher()?;
+        let hot_db = LevelDB::open_with_cipher(hot_path, cipher.clone())?;
+
+        // The freezer contains a mix of two encodings while a hierarchy migration is being moved
+        // into place, so it must be completed with `database_manager` before we can start.
+        if let Some(migration) = hot_db.get::<HierarchyMigration>(&HIERARCHY_MIGRATION_KEY)? {
+            if migration.swapping {
+                return Err(HotColdDBError::HierarchyMigrationIncomplete.into());
+            }
+        }
+
         let anchor_info = RwLock::new(Self::load_anchor_info(&hot_db)?);
 
         let db = HotColdDB {
@@ -257,8 +290,8 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             anchor_info,
             blob_info: RwLock::new(BlobInfo::default()),
             data_column_info: RwLock::new(DataColumnInfo::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            blobs_db: LevelDB::open(blobs_db_path)?,
+            cold_db: LevelDB::open_with_cipher(cold_path, cipher.clone())?,
+            blobs_db: LevelDB::open_with_cipher(blobs_db_path, cipher)?,
             hot_db,
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
@@ -266,6 +299,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
                 config.hdiff_buffer_cache_size,
                 config.historic_state_cache_size,
             )),
+            column_stats: RwLock::new(None),
             config,
             hierarchy,
             spec,
@@ -312,6 +346,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
                 BlobInfo {
                     oldest_blob_slot,
                     blobs_db: true,
+                    oldest_sparse_blob_slot: blob_info.oldest_sparse_blob_slot,
                 }
             }
             // First start.
@@ -319,6 +354,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
                 // Set the oldest blob slot to the Deneb fork slot if it is not yet set.
                 oldest_blob_slot: deneb_fork_slot,
                 blobs_db: true,
+                oldest_sparse_blob_slot: None,
             },
         };
         db.compare_and_set_blob_info_with_write(<_>::default(), new_blob_info.clone())?;
@@ -406,6 +442,82 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         Ok(db)
     }
 
+    /// Open an existing database without writing to it.
+    ///
+    /// Unlike `open`, the on-disk config, blob info and schema version are left untouched, schema
+    /// migrations are never run and no garbage collection or compaction takes place, so the
+    /// database must already be at the current schema version. LevelDB only allows one process
+    /// to open a database at a time, so the node which owns the database must not be running.
+    pub fn open_read_only(
+        hot_path: &Path,
+        cold_path: &Path,
+        blobs_db_path: &Path,
+        config: StoreConfig,
+        spec: Arc<ChainSpec>,
+        log: Logger,
+    ) -> Result<Arc<Self>, Error> {
+        config.verify::<E>()?;
+
+        let hierarchy = config.hierarchy_config.to_moduli()?;
+
+        let cipher = config.load_cipher()?;
+        let hot_db = LevelDB::open_existing_with_cipher(hot_path, cipher.clone())?;
+
+        if let Some(migration) = hot_db.get::<HierarchyMigration>(&HIERARCHY_MIGRATION_KEY)? {
+            if migration.swapping {
+                return Err(HotColdDBError::HierarchyMigrationIncomplete.into());
+            }
+        }
+
+        let anchor_info = RwLock::new(Self::load_anchor_info(&hot_db)?);
+
+        let db = HotColdDB {
+            split: RwLock::new(Split::default()),
+            anchor_info,
+            blob_info: RwLock::new(BlobInfo::default()),
+            data_column_info: RwLock::new(DataColumnInfo::default()),
+            cold_db: LevelDB::open_existing_with_cipher(cold_path, cipher.clone())?,
+            blobs_db: LevelDB::open_existing_with_cipher(blobs_db_path, cipher)?,
+            hot_db,
+            block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
+            state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
+            historic_state_cache: Mutex::new(HistoricStateCache::new(
+                config.hdiff_buffer_cache_size,
+                config.historic_state_cache_size,
+            )),
+            column_stats: RwLock::new(None),
+            config,
+            hierarchy,
+            spec,
+            log,
+            _phantom: PhantomData,
+        };
+
+        match db.load_schema_version()? {
+            Some(schema_version) if schema_version == CURRENT_SCHEMA_VERSION => {}
+            Some(schema_version) => {
+                return Err(HotColdDBError::UnsupportedSchemaVersion {
+                    target_version: CURRENT_SCHEMA_VERSION,
+                    current_version: schema_version,
+                }
+                .into())
+            }
+            None => return Err(HotColdDBError::MissingSchemaVersion.into()),
+        }
+
+        if let Some(split) = db.load_split()? {
+            *db.split.write() = split;
+        }
+        if let Some(blob_info) = db.load_blob_info()? {
+            *db.blob_info.write() = blob_info;
+        }
+        if let Some(data_column_info) = db.load_data_column_info()? {
+            *db.data_column_info.write() = data_column_info;
+        }
+
+        Ok(Arc::new(db))
+    }
+
     /// Return an iterator over the state roots of all temporary states.
     pub fn iter_temporary_state_roots(&self) -> impl Iterator<Item = Result<Hash256, Error>> + '_ {
         let column = DBColumn::BeaconStateTemporary;
@@ -487,6 +599,49 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         );
     }
 
+    /// Return the most recently computed column statistics, if any.
+    pub fn get_column_stats(&self) -> Option<DatabaseColumnStats> {
+        self.column_stats.read_recursive().clone()
+    }
+
+    /// Recompute the key counts and estimated sizes of every column and update the metrics.
+    ///
+    /// This iterates the keys of each database and should not be run on a hot path. Sizes are
+    /// estimated from a sample of values (see `compute_column_stats`).
+    pub fn update_column_stats(&self, finalized_epoch: Epoch) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::STORE_COLUMN_STATS_TIME);
+        let sample_interval = DEFAULT_COLUMN_STATS_SAMPLE_INTERVAL;
+        let stats = DatabaseColumnStats {
+            epoch: finalized_epoch,
+            hot_db: compute_column_stats(&self.hot_db, sample_interval)?,
+            freezer_db: compute_column_stats(&self.cold_db, sample_interval)?,
+            blobs_db: compute_column_stats(&self.blobs_db, sample_interval)?,
+        };
+
+        for (db, db_stats) in [
+            ("hot", &stats.hot_db),
+            ("freezer", &stats.freezer_db),
+            ("blobs", &stats.blobs_db),
+        ] {
+            for column in DBColumn::iter() {
+                let column_stats = db_stats.get(column.as_str()).copied().unwrap_or_default();
+                metrics::set_gauge_vec(
+                    &metrics::STORE_COLUMN_KEYS,
+                    &[db, column.as_str()],
+                    column_stats.num_keys as i64,
+                );
+                metrics::set_gauge_vec(
+                    &metrics::STORE_COLUMN_BYTES,
+                    &[db, column.as_str()],
+                    column_stats.total_bytes as i64,
+                );
+            }
+        }
+
+        *self.column_stats.write() = Some(stats);
+        Ok(())
+    }
+
     /// Store a block and update the LRU cache.
     pub fn put_block(
         &self,
@@ -579,9 +734,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 .put_block(*block_root, full_block.clone());
 
             DatabaseBlock::Full(full_block)
-        } else if !self.config.prune_payloads {
-            // If payload pruning is disabled there's a chance we may have the payload of
-            // this finalized block. Attempt to load it but don't error in case it's missing.
+        } else if self.config.effective_payload_retention() != RetentionPolicy::KeepLastNEpochs(0) {
+            // If payloads are retained after finalization there's a chance we may have the
+            // payload of this finalized block. Attempt to load it but don't error in case it's
+            // missing.
             let fork_name = blinded_block.fork_name(&self.spec)?;
             if let Some(payload) = self.get_execution_payload(block_root, fork_name)? {
                 DatabaseBlock::Full(
@@ -767,7 +923,25 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self,
         sync_committee_period: u64,
     ) -> Result<Option<LightClientUpdate<E>>, Error> {
-        let column = DBColumn::LightClientUpdate;
+        self.get_light_client_update_from_column(DBColumn::LightClientUpdate, sync_committee_period)
+    }
+
+    /// Fetch the light client update generated for a past `sync_committee_period`.
+    pub fn get_historical_light_client_update(
+        &self,
+        sync_committee_period: u64,
+    ) -> Result<Option<LightClientUpdate<E>>, Error> {
+        self.get_light_client_update_from_column(
+            DBColumn::LightClientHistoricalUpdate,
+            sync_committee_period,
+        )
+    }
+
+    fn get_light_client_update_from_column(
+        &self,
+        column: DBColumn,
+        sync_committee_period: u64,
+    ) -> Result<Option<LightClientUpdate<E>>, Error> {
         let res = self
             .hot_db
             .get_bytes(column.into(), &sync_committee_period.to_le_bytes())?;
@@ -833,6 +1007,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    pub fn store_historical_light_client_update(
+        &self,
+        sync_committee_period: u64,
+        light_client_update: &LightClientUpdate<E>,
+    ) -> Result<(), Error> {
+        let column = DBColumn::LightClientHistoricalUpdate;
+
+        self.hot_db.put_bytes(
+            column.into(),
+            &sync_committee_period.to_le_bytes(),
+            &light_client_update.as_ssz_bytes(),
+        )?;
+
+        Ok(())
+    }
+
     /// Check if the blobs for a block exists on disk.
     pub fn blobs_exist(&self, block_root: &Hash256) -> Result<bool, Error> {
         self.blobs_db
@@ -1873,7 +2063,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Returns `HDiffBuffer` for the specified slot, or `HDiffBuffer` for the `ReplayFrom` slot if
     /// the diff for the specified slot is not stored.
-    fn load_hdiff_buffer_for_slot(&self, slot: Slot) -> Result<(Slot, HDiffBuffer), Error> {
+    pub(crate) fn load_hdiff_buffer_for_slot(
+        &self,
+        slot: Slot,
+    ) -> Result<(Slot, HDiffBuffer), Error> {
         if let Some(buffer) = self.historic_state_cache.lock().get_hdiff_buffer(slot) {
             debug!(
                 self.log,
@@ -2251,6 +2444,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         let blob_info = BlobInfo {
             oldest_blob_slot,
             blobs_db: true,
+            oldest_sparse_blob_slot: None,
         };
         self.compare_and_set_blob_info(self.get_blob_info(), blob_info)
     }
@@ -2491,6 +2685,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             DBColumn::BeaconStateSnapshot,
             DBColumn::BeaconStateDiff,
             DBColumn::BeaconStateRoots,
+            DBColumn::BeaconStateSnapshotReencoded,
+            DBColumn::BeaconStateDiffReencoded,
         ];
 
         // We can remove this once schema V21 has been gone for a while.
@@ -2582,7 +2778,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(ops)
     }
 
-    /// Try to prune all execution payloads, returning early if there is no need to prune.
+    /// Try to prune execution payloads according to the payload retention policy, returning early
+    /// if there is no need to prune.
+    ///
+    /// If `force` is set, payloads are pruned even if the retention policy is `KeepAll`, and the
+    /// whole chain is checked rather than stopping at the first payload that was already pruned.
     pub fn try_prune_execution_payloads(&self, force: bool) -> Result<(), Error> {
         let split = self.get_split_info();
 
@@ -2596,43 +2796,65 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             return Ok(());
         };
 
+        let retention = match self.config.effective_payload_retention() {
+            RetentionPolicy::KeepAll if force => RetentionPolicy::KeepLastNEpochs(0),
+            RetentionPolicy::KeepAll => return Ok(()),
+            retention => retention,
+        };
+        let split_epoch = split.slot.epoch(E::slots_per_epoch());
+        let Some(prune_before_epoch) = retention.prune_before(split_epoch, split_epoch) else {
+            return Ok(());
+        };
+        let prune_before_slot = prune_before_epoch.start_slot(E::slots_per_epoch());
+
         // Load the split state so we can backtrack to find execution payloads.
         let split_state = self.get_state(&split.state_root, Some(split.slot))?.ok_or(
             HotColdDBError::MissingSplitState(split.state_root, split.slot),
         )?;
-
-        // The finalized block may or may not have its execution payload stored, depending on
-        // whether it was at a skipped slot. However for a fully pruned database its parent
-        // should *always* have been pruned. In case of a long split (no parent found) we
-        // continue as if the payloads are pruned, as the node probably has other things to worry
-        // about.
         let split_block_root = split_state.get_latest_block_root(split.state_root);
 
-        let already_pruned =
-            process_results(split_state.rev_iter_block_roots(&self.spec), |mut iter| {
-                iter.find(|(_, block_root)| *block_root != split_block_root)
-                    .map_or(Ok(true), |(_, split_parent_root)| {
-                        self.execution_payload_exists(&split_parent_root)
-                            .map(|exists| !exists)
-                    })
-            })??;
-
-        if already_pruned && !force {
-            info!(self.log, "Execution payloads are pruned");
-            return Ok(());
-        }
-
         // Iterate block roots backwards to the Bellatrix fork or the anchor slot, whichever comes
-        // first.
-        warn!(
+        // first. Each block root is visited at every slot it occupies, so we decide whether to
+        // prune a block once we've seen all of its slots (i.e. once the block root changes).
+        //
+        // A block whose payload should be pruned but is already missing means that the previous
+        // run got this far, so we stop there unless `force` is set.
+        debug!(
             self.log,
             "Pruning finalized payloads";
-            "info" => "you may notice degraded I/O performance while this runs"
+            "retention" => %retention,
+            "prune_before_slot" => prune_before_slot,
         );
         let anchor_slot = self.get_anchor_info().anchor_slot;
 
         let mut ops = vec![];
-        let mut last_pruned_block_root = None;
+        // Root of the block being visited, its lowest slot seen so far, and whether it is a
+        // checkpoint block (i.e. occupies the first slot of an epoch).
+        let mut current_block: Option<(Hash256, Slot, bool)> = None;
+        let mut reached_pruned = false;
+
+        let mut visit_block = |block_root: Hash256,
+                               block_slot: Slot,
+                               is_checkpoint: bool,
+                               ops: &mut Vec<StoreOp<E>>|
+         -> Result<bool, Error> {
+            if block_slot >= prune_before_slot || !retention.prunes_block(is_checkpoint) {
+                return Ok(true);
+            }
+            if self.execution_payload_exists(&block_root)? {
+                trace!(
+                    self.log,
+                    "Pruning execution payload";
+                    "slot" => block_slot,
+                    "block_root" => ?block_root,
+                );
+                ops.push(StoreOp::DeleteExecutionPayload(block_root));
+                Ok(true)
+            } else {
+                reached_pruned = true;
+                Ok(force)
+            }
+        };
 
         for res in std::iter::once(Ok((split_block_root, split.slot)))
             .chain(BlockRootsIterator::new(self, &split_state))
@@ -2650,28 +2872,31 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             };
 
             if slot < bellatrix_fork_slot {
-                info!(
+                debug!(
                     self.log,
                     "Payload pruning reached Bellatrix boundary";
                 );
                 break;
             }
 
-            if Some(block_root) != last_pruned_block_root
-                && self.execution_payload_exists(&block_root)?
+            let is_epoch_start = slot % E::slots_per_epoch() == 0;
+            if let Some((_, block_slot, is_checkpoint)) = current_block
+                .as_mut()
+                .filter(|(root, _, _)| *root == block_root)
             {
-                debug!(
-                    self.log,
-                    "Pruning execution payload";
-                    "slot" => slot,
-                    "block_root" => ?block_root,
-                );
-                last_pruned_block_root = Some(block_root);
-                ops.push(StoreOp::DeleteExecutionPayload(block_root));
+                *block_slot = slot;
+                *is_checkpoint |= is_epoch_start;
+            } else {
+                if let Some((root, block_slot, is_checkpoint)) = current_block.take() {
+                    if !visit_block(root, block_slot, is_checkpoint, &mut ops)? {
+                        break;
+                    }
+                }
+                current_block = Some((block_root, slot, is_epoch_start));
             }
 
             if slot == anchor_slot {
-                info!(
+                debug!(
                     self.log,
                     "Payload pruning reached anchor state";
                     "slot" => slot
@@ -2679,12 +2904,21 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 break;
             }
         }
+        if let Some((root, block_slot, is_checkpoint)) = current_block {
+            visit_block(root, block_slot, is_checkpoint, &mut ops)?;
+        }
+
         let payloads_pruned = ops.len();
+        if payloads_pruned == 0 && reached_pruned {
+            debug!(self.log, "Execution payloads are pruned");
+            return Ok(());
+        }
         self.do_atomically_with_block_and_blobs_cache(ops)?;
-        info!(
+        debug!(
             self.log,
             "Execution payload pruning complete";
             "payloads_pruned" => payloads_pruned,
+            "retention" => %retention,
         );
         Ok(())
     }
@@ -2707,10 +2941,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.try_prune_blobs(force, min_data_availability_boundary)
     }
 
-    /// Try to prune blobs older than the data availability boundary.
+    /// Try to prune blobs and data columns according to their retention policies.
     ///
-    /// Blobs from the epoch `data_availability_boundary - blob_prune_margin_epochs` are retained.
-    /// This epoch is an _exclusive_ endpoint for the pruning process.
+    /// Blobs from the epoch `data_availability_boundary - blob_prune_margin_epochs` are always
+    /// retained. This epoch is an _exclusive_ endpoint for the pruning process. If the blob and
+    /// data column policies differ, pruning stops at the older of their cutoffs.
     ///
     /// This function only supports pruning blobs older than the split point, which is older than
     /// (or equal to) finalization. Pruning blobs newer than finalization is not supported.
@@ -2727,15 +2962,25 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             return Ok(());
         }
 
-        let pruning_enabled = self.get_config().prune_blobs;
         let margin_epochs = self.get_config().blob_prune_margin_epochs;
         let epochs_per_blob_prune = self.get_config().epochs_per_blob_prune;
 
-        if !force && !pruning_enabled {
+        // With `force`, a `KeepAll` policy prunes up to the data availability boundary.
+        let forced = |retention: RetentionPolicy| match retention {
+            RetentionPolicy::KeepAll if force => RetentionPolicy::KeepLastNEpochs(0),
+            retention => retention,
+        };
+        let blob_retention = forced(self.get_config().effective_blob_retention());
+        let data_column_retention = forced(self.get_config().effective_data_column_retention());
+
+        if blob_retention == RetentionPolicy::KeepAll
+            && data_column_retention == RetentionPolicy::KeepAll
+        {
             debug!(
                 self.log,
                 "Blob pruning is disabled";
-                "prune_blobs" => pruning_enabled
+                "blob_retention" => %blob_retention,
+                "data_column_retention" => %data_column_retention,
             );
             return Ok(());
         }
@@ -2752,12 +2997,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
         // Prune blobs up until the `data_availability_boundary - margin` or the split
         // slot's epoch, whichever is older. We can't prune blobs newer than the split.
+        // The retention policies may move this cutoff further back.
         // The end epoch is also inclusive (blobs in this epoch will be pruned).
         let split = self.get_split_info();
-        let end_epoch = std::cmp::min(
-            data_availability_boundary - margin_epochs - 1,
-            split.slot.epoch(E::slots_per_epoch()) - 1,
-        );
+        let split_epoch = split.slot.epoch(E::slots_per_epoch());
+        let min_retained_epoch =
+            std::cmp::min(data_availability_boundary - margin_epochs, split_epoch);
+        let Some(prune_before_epoch) = [blob_retention, data_column_retention]
+            .iter()
+            .filter_map(|retention| retention.prune_before(split_epoch, min_retained_epoch))
+            .min()
+        else {
+            return Ok(());
+        };
+        let end_epoch = prune_before_epoch - 1;
         let end_slot = end_epoch.end_slot(E::slots_per_epoch());
 
         let can_prune = end_epoch != 0 && start_epoch <= end_epoch;
@@ -2797,8 +3050,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "data_availability_boundary" => data_availability_boundary,
         );
 
-        let mut ops = vec![];
-        let mut last_pruned_block_root = None;
+        // Collect the blocks in the range first, as a block which occupies the first slot of an
+        // epoch is a checkpoint block that some retention policies keep.
+        let mut blocks: Vec<(Hash256, Slot)> = vec![];
+        let mut checkpoint_block_roots = HashSet::new();
 
         for res in self.forwards_block_roots_iterator_until(oldest_blob_slot, end_slot, || {
             let (_, split_state) = self
@@ -2822,43 +3077,71 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 }
             };
 
-            if Some(block_root) != last_pruned_block_root {
-                if self
-                    .spec
-                    .is_peer_das_enabled_for_epoch(slot.epoch(E::slots_per_epoch()))
-                {
-                    // data columns
-                    let indices = self.get_data_column_keys(block_root)?;
-                    if !indices.is_empty() {
-                        trace!(
-                            self.log,
-                            "Pruning data columns of block";
-                            "slot" => slot,
-                            "block_root" => ?block_root,
-                        );
-                        last_pruned_block_root = Some(block_root);
-                        ops.push(StoreOp::DeleteDataColumns(block_root, indices));
-                    }
-                } else if self.blobs_exist(&block_root)? {
-                    trace!(
-                        self.log,
-                        "Pruning blobs of block";
-                        "slot" => slot,
-                        "block_root" => ?block_root,
-                    );
-                    last_pruned_block_root = Some(block_root);
-                    ops.push(StoreOp::DeleteBlobs(block_root));
-                }
+            if slot % E::slots_per_epoch() == 0 {
+                checkpoint_block_roots.insert(block_root);
+            }
+            if blocks.last().map(|(root, _)| root) != Some(&block_root) {
+                blocks.push((block_root, slot));
             }
 
             if slot >= end_slot {
                 break;
             }
         }
+
+        // Blobs and columns are only contiguous after the last pruned block, so the oldest blob
+        // slot advances past it. Data kept by the retention policies before that block is sparse,
+        // and is recorded separately.
+        let mut oldest_kept_slot = None;
+        let mut oldest_sparse_slot = blob_info.oldest_sparse_blob_slot;
+        let mut ops = vec![];
+        for (block_root, slot) in blocks {
+            let is_checkpoint = checkpoint_block_roots.contains(&block_root);
+            if self
+                .spec
+                .is_peer_das_enabled_for_epoch(slot.epoch(E::slots_per_epoch()))
+            {
+                // data columns
+                let indices = self.get_data_column_keys(block_root)?;
+                if indices.is_empty() {
+                    continue;
+                }
+                if !data_column_retention.prunes_block(is_checkpoint) {
+                    oldest_kept_slot.get_or_insert(slot);
+                    continue;
+                }
+                if let Some(kept_slot) = oldest_kept_slot.take() {
+                    oldest_sparse_slot.get_or_insert(kept_slot);
+                }
+                trace!(
+                    self.log,
+                    "Pruning data columns of block";
+                    "slot" => slot,
+                    "block_root" => ?block_root,
+                );
+                ops.push(StoreOp::DeleteDataColumns(block_root, indices));
+            } else if self.blobs_exist(&block_root)? {
+                if !blob_retention.prunes_block(is_checkpoint) {
+                    oldest_kept_slot.get_or_insert(slot);
+                    continue;
+                }
+                if let Some(kept_slot) = oldest_kept_slot.take() {
+                    oldest_sparse_slot.get_or_insert(kept_slot);
+                }
+                trace!(
+                    self.log,
+                    "Pruning blobs of block";
+                    "slot" => slot,
+                    "block_root" => ?block_root,
+                );
+                ops.push(StoreOp::DeleteBlobs(block_root));
+            }
+        }
         let blob_lists_pruned = ops.len();
         let new_blob_info = BlobInfo {
-            oldest_blob_slot: Some(end_slot + 1),
+            oldest_blob_slot: Some(oldest_kept_slot.unwrap_or(end_slot + 1)),
             blobs_db: blob_info.blobs_db,
+            oldest_sparse_blob_slot: oldest_sparse_slot,
         };
         let update_blob_info = self.compare_and_set_blob_info(blob_info, new_blob_info)?;
         ops.push(StoreOp::KeyValueOp(update_blob_info));
@@ -2906,6 +3189,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             DBColumn::BeaconStateSnapshot,
             DBColumn::BeaconStateDiff,
             DBColumn::BeaconStateRoots,
+            DBColumn::BeaconStateSnapshotReencoded,
+            DBColumn::BeaconStateDiffReencoded,
         ];
 
         // This function is intended to be able to clean up leftover V21 freezer database stuff in
@@ -2961,6 +3246,131 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Prune historic states from the freezer database according to the historic state retention
+    /// policy.
+    ///
+    /// `KeepFinalizedCheckpointsOnly` deletes all states except genesis (see
+    /// `prune_historic_states`). `KeepLastNEpochs` raises the state upper limit to the last
+    /// snapshot before the cutoff, and deletes the states below it. States can only be pruned up
+    /// to a snapshot as later states may be stored as diffs against earlier ones.
+    ///
+    /// Both policies reset the state lower limit to 0, discarding the states rebuilt by state
+    /// reconstruction so far. This is only done with `confirm`, otherwise pruning is skipped with
+    /// a warning while any reconstructed states are stored.
+    pub fn try_prune_historic_states(&self, confirm: bool) -> Result<(), Error> {
+        let Some(retention) = self.config.historic_state_retention else {
+            return Ok(());
+        };
+        let split = self.get_split_info();
+        let anchor = self.get_anchor_info();
+
+        if retention != RetentionPolicy::KeepAll && anchor.state_lower_limit != 0 && !confirm {
+            warn!(
+                self.log,
+                "Not pruning reconstructed historic states";
+                "retention" => %retention,
+                "state_lower_limit" => anchor.state_lower_limit,
+                "info" => "run `lighthouse db prune` with --confirm to delete them",
+            );
+            return Ok(());
+        }
+
+        match retention {
+            RetentionPolicy::KeepAll => Ok(()),
+            RetentionPolicy::KeepFinalizedCheckpointsOnly => {
+                if anchor.full_state_pruning_enabled() {
+                    return Ok(());
+                }
+                let mut genesis_state = self.load_cold_state_by_slot(Slot::new(0))?;
+                let genesis_state_root = genesis_state.canonical_root()?;
+                info!(
+                    self.log,
+                    "Pruning historic states";
+                    "retention" => %retention,
+                );
+                self.prune_historic_states(genesis_state_root, &genesis_state)
+            }
+            RetentionPolicy::KeepLastNEpochs(n) => {
+                let cutoff_slot = split
+                    .slot
+                    .epoch(E::slots_per_epoch())
+                    .saturating_sub(n)
+                    .start_slot(E::slots_per_epoch());
+                let new_upper_limit = if anchor.full_state_pruning_enabled() {
+                    // Start storing states again from the next snapshot.
+                    self.hierarchy.next_snapshot_slot(split.slot)?
+                } else {
+                    std::cmp::max(
+                        anchor.state_upper_limit,
+                        self.hierarchy.previous_snapshot_slot(cutoff_slot)?,
+                    )
+                };
+
+                if anchor.state_lower_limit != 0 || anchor.state_upper_limit != new_upper_limit {
+                    info!(
+                        self.log,
+                        "Pruning historic states";
+                        "retention" => %retention,
+                        "new_state_upper_limit" => new_upper_limit,
+                    );
+                    let new_anchor = AnchorInfo {
+                        state_upper_limit: new_upper_limit,
+                        state_lower_limit: Slot::new(0),
+                        ..anchor.clone()
+                    };
+                    // Commit the anchor change first so that pruned states are never served.
+                    self.compare_and_set_anchor_info_with_write(anchor, new_anchor)?;
+                }
+
+                self.delete_cold_states_below(self.get_anchor_info().state_upper_limit)
+            }
+        }
+    }
+
+    /// Delete all freezer states with slots in `1..upper_limit`, keeping the genesis state.
+    ///
+    /// This is idempotent, and cheap if there is nothing to delete.
+    fn delete_cold_states_below(&self, upper_limit: Slot) -> Result<(), Error> {
+        let mut cold_ops = vec![];
+        for res in self
+            .cold_db
+            .iter_column_from::<Vec<u8>>(DBColumn::BeaconStateRoots, &1u64.to_be_bytes())
+        {
+            let (key, state_root_bytes) = res?;
+            let slot_bytes: [u8; 8] = key.as_slice().try_into().map_err(|_| Error::InvalidKey)?;
+            if Slot::new(u64::from_be_bytes(slot_bytes)) >= upper_limit {
+                break;
+            }
+            let state_root = <Hash256 as Key>::from_bytes(&state_root_bytes)?;
+            cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                DBColumn::BeaconColdStateSummary.into(),
+                state_root.as_slice(),
+            )));
+            for column in [
+                DBColumn::BeaconStateRoots,
+                DBColumn::BeaconStateSnapshot,
+                DBColumn::BeaconStateDiff,
+            ] {
+                cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    column.into(),
+                    &key,
+                )));
+            }
+        }
+
+        if cold_ops.is_empty() {
+            return Ok(());
+        }
+        info!(
+            self.log,
+            "Deleting historic states";
+            "delete_ops" => cold_ops.len(),
+            "state_upper_limit" => upper_limit,
+        );
+        self.cold_db.do_atomically(cold_ops)?;
+        Ok(())
+    }
+
     /// Prune states from the hot database which are prior to the split.
     ///
     /// This routine is important for cleaning up advanced states which are stored in the database
@@ -3059,13 +3469,16 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
+    // Payloads kept for the last N epochs are pruned later by `try_prune_execution_payloads`.
+    let payload_retention = store.config.effective_payload_retention();
+
     // Then, iterate states in slot ascending order, as they are stored wrt previous states.
     for (block_root, state_root, slot) in state_roots.into_iter().rev() {
-        // Delete the execution payload if payload pruning is enabled. At a skipped slot we may
-        // delete the payload for the finalized block itself, but that's OK as we only guarantee
-        // that payloads are present for slots >= the split slot. The payload fetching code is also
-        // forgiving of missing payloads.
-        if store.config.prune_payloads {
+        // Delete the execution payload if payloads are only kept until finalization. At a skipped
+        // slot we may delete the payload for the finalized block itself, but that's OK as we only
+        // guarantee that payloads are present for slots >= the split slot. The payload fetching
+        // code is also forgiving of missing payloads.
+        if payload_retention == RetentionPolicy::KeepLastNEpochs(0) {
             hot_db_ops.push(StoreOp::DeleteExecutionPayload(block_root));
         }
 
@@ -3136,10 +3549,14 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     // E.g. if `current_split_slot` = (Epoch A slot 0) and `finalized_state.slot()` = (Epoch C slot 31)
     // and (Epoch D slot 0) is a skipped slot, we will have pruned a `sync_committee_branch`
     // for a checkpoint block root.
+    // The same applies to payloads if only checkpoint payloads are retained.
     non_checkpoint_block_roots
         .into_iter()
         .for_each(|block_root| {
             hot_db_ops.push(StoreOp::DeleteSyncCommitteeBranch(block_root));
+            if payload_retention == RetentionPolicy::KeepFinalizedCheckpointsOnly {
+                hot_db_ops.push(StoreOp::DeleteExecutionPayload(block_root));
+            }
         });
 
     // Warning: Critical section.  We have to take care not to put any of the two databases in an
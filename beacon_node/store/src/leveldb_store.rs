@@ -1,5 +1,7 @@
 use super::*;
 use crate::hot_cold_store::HotColdDBError;
+use crate::metadata::ENCRYPTION_MARKER_KEY;
+use db_encryption::DbCipher;
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
@@ -11,29 +13,133 @@ use parking_lot::Mutex;
 use std::marker::PhantomData;
 use std::path::Path;
 
+/// Plaintext of the marker value which records that a database is encrypted.
+const ENCRYPTION_MARKER: &[u8] = b"lighthouse-encrypted-db";
+
 /// A wrapped leveldb database.
 pub struct LevelDB<E: EthSpec> {
     db: Database<BytesKey>,
     /// A mutex to synchronise sensitive read-write transactions.
     transaction_mutex: Mutex<()>,
+    /// Cipher for encrypting values at rest, if enabled.
+    ///
+    /// Keys are stored in plaintext so that the database remains ordered and iterable. Each value
+    /// is encrypted with its full key (column and key) as associated data.
+    cipher: Option<Arc<DbCipher>>,
     _phantom: PhantomData<E>,
 }
 
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::open_with_cipher(path, None)
+    }
+
+    /// Open a database at `path` which is encrypted with `cipher` (if any), creating a new
+    /// database if one does not already exist.
+    ///
+    /// Error if the database is encrypted but no cipher is provided, or vice versa. An existing
+    /// unencrypted database can't be encrypted in place.
+    pub fn open_with_cipher(path: &Path, cipher: Option<Arc<DbCipher>>) -> Result<Self, Error> {
+        Self::open_with_options(path, cipher, true)
+    }
+
+    /// Open an existing database at `path` which is encrypted with `cipher` (if any).
+    ///
+    /// Error if there is no database at `path`. Nothing is written to the database, so an empty
+    /// database is not marked as encrypted.
+    pub fn open_existing_with_cipher(
+        path: &Path,
+        cipher: Option<Arc<DbCipher>>,
+    ) -> Result<Self, Error> {
+        Self::open_with_options(path, cipher, false)
+    }
+
+    fn open_with_options(
+        path: &Path,
+        cipher: Option<Arc<DbCipher>>,
+        create_if_missing: bool,
+    ) -> Result<Self, Error> {
         let mut options = Options::new();
 
-        options.create_if_missing = true;
+        options.create_if_missing = create_if_missing;
 
         let db = Database::open(path, options)?;
         let transaction_mutex = Mutex::new(());
 
-        Ok(Self {
+        let db = Self {
             db,
             transaction_mutex,
+            cipher,
             _phantom: PhantomData,
-        })
+        };
+        db.check_encryption_marker(create_if_missing)?;
+        Ok(db)
+    }
+
+    /// Check that the database is encrypted iff we have a cipher, marking a new database as
+    /// encrypted if `write_marker` is set.
+    fn check_encryption_marker(&self, write_marker: bool) -> Result<(), Error> {
+        let marker_key = get_key_for_col(
+            DBColumn::BeaconMeta.into(),
+            ENCRYPTION_MARKER_KEY.as_slice(),
+        );
+        let marker = self
+            .db
+            .get(self.read_options(), BytesKey::from_vec(marker_key.clone()))?;
+
+        match (marker, &self.cipher) {
+            (Some(marker), Some(cipher)) => {
+                if cipher.decrypt(&marker_key, &marker)? == ENCRYPTION_MARKER {
+                    Ok(())
+                } else {
+                    Err(db_encryption::Error::DecryptionFailed.into())
+                }
+            }
+            (Some(_), None) => Err(Error::DatabaseEncryptedWithoutKey),
+            (None, Some(cipher)) => {
+                if self.keys_iter().next().is_some() {
+                    return Err(Error::DatabaseNotEncrypted);
+                }
+                if !write_marker {
+                    return Ok(());
+                }
+                let marker = cipher.encrypt(&marker_key, ENCRYPTION_MARKER)?;
+                self.db
+                    .put(
+                        self.write_options_sync(),
+                        BytesKey::from_vec(marker_key),
+                        &marker,
+                    )
+                    .map_err(Into::into)
+            }
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Return `true` if values in this database are encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Encrypt `value` for storage at `column_key`, if encryption is enabled.
+    fn encrypt_value<'a>(
+        &self,
+        column_key: &[u8],
+        value: &'a [u8],
+    ) -> Result<std::borrow::Cow<'a, [u8]>, Error> {
+        match &self.cipher {
+            Some(cipher) => Ok(cipher.encrypt(column_key, value)?.into()),
+            None => Ok(value.into()),
+        }
+    }
+
+    /// Decrypt `value` read from `column_key`, if encryption is enabled.
+    fn decrypt_value(&self, column_key: &[u8], value: Vec<u8>) -> Result<Vec<u8>, Error> {
+        match &self.cipher {
+            Some(cipher) => Ok(cipher.decrypt(column_key, &value)?),
+            None => Ok(value),
+        }
     }
 
     fn read_options(&self) -> ReadOptions<BytesKey> {
@@ -58,13 +164,14 @@ impl<E: EthSpec> LevelDB<E> {
         opts: WriteOptions,
     ) -> Result<(), Error> {
         let column_key = get_key_for_col(col, key);
+        let val = self.encrypt_value(&column_key, val)?;
 
         metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[col]);
         metrics::inc_counter_vec_by(&metrics::DISK_DB_WRITE_BYTES, &[col], val.len() as u64);
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
         self.db
-            .put(opts, BytesKey::from_vec(column_key), val)
+            .put(opts, BytesKey::from_vec(column_key), &val)
             .map_err(Into::into)
     }
 
@@ -95,7 +202,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
 
         self.db
-            .get(self.read_options(), BytesKey::from_vec(column_key))
+            .get(self.read_options(), BytesKey::from_vec(column_key.clone()))
             .map_err(Into::into)
             .map(|opt| {
                 opt.inspect(|bytes| {
@@ -106,7 +213,9 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                     );
                     metrics::stop_timer(timer);
                 })
-            })
+            })?
+            .map(|bytes| self.decrypt_value(&column_key, bytes))
+            .transpose()
     }
 
     /// Return `true` if `key` exists in `column`.
@@ -137,6 +246,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         for op in ops_batch {
             match op {
                 KeyValueStoreOp::PutKeyValue(key, value) => {
+                    let value = self.encrypt_value(&key, &value)?;
                     let col = get_col_from_key(&key).unwrap_or("unknown".to_owned());
                     metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[&col]);
                     metrics::inc_counter_vec_by(
@@ -187,6 +297,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         Box::new(
             iter.take_while(move |(key, _)| key.matches_column(column))
                 .map(move |(bytes_key, value)| {
+                    let value = self.decrypt_value(&bytes_key.key, value)?;
                     let key = bytes_key.remove_column_variable(column).ok_or_else(|| {
                         HotColdDBError::IterationError {
                             unexpected_key: bytes_key.clone(),
@@ -206,6 +317,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         Box::new(
             iter.take_while(move |(key, _)| key.key.starts_with(start_key.key.as_slice()))
                 .map(move |(bytes_key, value)| {
+                    let value = self.decrypt_value(&bytes_key.key, value)?;
                     let subkey = &bytes_key.key[column.as_bytes().len()..];
                     Ok((Vec::from(subkey), value))
                 }),
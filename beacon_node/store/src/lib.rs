@@ -9,12 +9,15 @@
 //! tests for implementation examples.
 pub mod chunked_iter;
 pub mod chunked_vector;
+pub mod column_stats;
 pub mod config;
 pub mod consensus_context;
+pub mod era;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
 pub mod hdiff;
+pub mod hierarchy_migration;
 pub mod historic_state_cache;
 pub mod hot_cold_store;
 mod impls;
@@ -24,6 +27,7 @@ pub mod metadata;
 pub mod metrics;
 pub mod partial_beacon_state;
 pub mod reconstruct;
+pub mod retention;
 pub mod state_cache;
 
 pub mod iter;
@@ -40,7 +44,7 @@ pub use metadata::AnchorInfo;
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
 use std::sync::Arc;
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoStaticStr};
 pub use types::*;
 
 const DATA_COLUMN_DB_KEY_SIZE: usize = 32 + 8;
@@ -245,7 +249,7 @@ pub enum StoreOp<'a, E: EthSpec> {
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -270,6 +274,16 @@ pub enum DBColumn {
     /// For compact `BeaconStateDiff`s in the freezer DB.
     #[strum(serialize = "bsd")]
     BeaconStateDiff,
+    /// Staging column for snapshots re-encoded with a new `HierarchyConfig`.
+    ///
+    /// Only populated while a hierarchy migration is in progress, see `hierarchy_migration`.
+    #[strum(serialize = "hms")]
+    BeaconStateSnapshotReencoded,
+    /// Staging column for diffs re-encoded with a new `HierarchyConfig`.
+    ///
+    /// Only populated while a hierarchy migration is in progress, see `hierarchy_migration`.
+    #[strum(serialize = "hmd")]
+    BeaconStateDiffReencoded,
     /// Mapping from state root to `HotStateSummary` in the hot DB.
     ///
     /// Previously this column also served a role in the freezer DB, mapping state roots to
@@ -332,6 +346,9 @@ pub enum DBColumn {
     BeaconRandaoMixes,
     #[strum(serialize = "dht")]
     DhtEnrs,
+    /// For persisting peer scores and bans across restarts.
+    #[strum(serialize = "prp")]
+    PeerReputation,
     /// For Optimistically Imported Merge Transition Blocks
     #[strum(serialize = "otb")]
     OptimisticTransitionBlock,
@@ -349,6 +366,10 @@ pub enum DBColumn {
     /// For helping persist eagerly computed light client bootstrap data
     #[strum(serialize = "scm")]
     SyncCommittee,
+    /// For light client updates of past sync committee periods, generated from freezer states
+    /// and backfilled blocks rather than while following the chain.
+    #[strum(serialize = "lch")]
+    LightClientHistoricalUpdate,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -387,6 +408,7 @@ impl DBColumn {
             | Self::PubkeyCache
             | Self::BeaconRestorePoint
             | Self::DhtEnrs
+            | Self::PeerReputation
             | Self::OptimisticTransitionBlock => 32,
             Self::BeaconBlockRoots
             | Self::BeaconBlockRootsChunked
@@ -397,9 +419,12 @@ impl DBColumn {
             | Self::BeaconRandaoMixes
             | Self::BeaconStateSnapshot
             | Self::BeaconStateDiff
+            | Self::BeaconStateSnapshotReencoded
+            | Self::BeaconStateDiffReencoded
             | Self::SyncCommittee
             | Self::SyncCommitteeBranch
-            | Self::LightClientUpdate => 8,
+            | Self::LightClientUpdate
+            | Self::LightClientHistoricalUpdate => 8,
             Self::BeaconDataColumn => DATA_COLUMN_DB_KEY_SIZE,
         }
     }
@@ -480,6 +505,62 @@ mod tests {
         test_impl(store);
     }
 
+    fn cipher(byte: u8) -> Option<Arc<db_encryption::DbCipher>> {
+        Some(Arc::new(
+            db_encryption::DbCipher::new(&[byte; db_encryption::KEY_LEN]).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn encrypteddiskdb() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let store = LevelDB::open_with_cipher(path, cipher(1)).unwrap();
+
+        test_impl(store);
+    }
+
+    #[test]
+    fn encrypteddiskdb_marker() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let key = Hash256::random();
+        let item = StorableThing { a: 1, b: 42 };
+
+        let store = LevelDB::<MinimalEthSpec>::open_with_cipher(path, cipher(1)).unwrap();
+        store.put(&key, &item).unwrap();
+        drop(store);
+
+        assert!(matches!(
+            LevelDB::<MinimalEthSpec>::open(path),
+            Err(Error::DatabaseEncryptedWithoutKey)
+        ));
+        assert!(matches!(
+            LevelDB::<MinimalEthSpec>::open_with_cipher(path, cipher(2)),
+            Err(Error::Encryption(_))
+        ));
+
+        let store = LevelDB::<MinimalEthSpec>::open_with_cipher(path, cipher(1)).unwrap();
+        assert_eq!(store.get::<StorableThing>(&key).unwrap(), Some(item));
+    }
+
+    #[test]
+    fn unencrypted_diskdb_rejects_key() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+
+        let store = LevelDB::<MinimalEthSpec>::open(path).unwrap();
+        store
+            .put(&Hash256::random(), &StorableThing { a: 1, b: 42 })
+            .unwrap();
+        drop(store);
+
+        assert!(matches!(
+            LevelDB::<MinimalEthSpec>::open_with_cipher(path, cipher(1)),
+            Err(Error::DatabaseNotEncrypted)
+        ));
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
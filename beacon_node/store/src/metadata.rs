@@ -4,7 +4,7 @@ use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use types::{Checkpoint, Hash256, Slot};
 
-pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(22);
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(23);
 
 // All the keys that get stored under the `BeaconMeta` column.
 //
@@ -17,6 +17,8 @@ pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
 pub const BLOB_INFO_KEY: Hash256 = Hash256::repeat_byte(6);
 pub const DATA_COLUMN_INFO_KEY: Hash256 = Hash256::repeat_byte(7);
+pub const HIERARCHY_MIGRATION_KEY: Hash256 = Hash256::repeat_byte(8);
+pub const ENCRYPTION_MARKER_KEY: Hash256 = Hash256::repeat_byte(9);
 
 /// State upper limit value used to indicate that a node is not storing historic states.
 pub const STATE_UPPER_LIMIT_NO_RETAIN: Slot = Slot::new(u64::MAX);
@@ -204,6 +206,18 @@ pub struct BlobInfo {
     pub oldest_blob_slot: Option<Slot>,
     /// A separate blobs database is in use (deprecated, always `true`).
     pub blobs_db: bool,
+    /// The slot of the oldest blobs kept by a retention policy below `oldest_blob_slot`.
+    ///
+    /// Blobs between this slot and `oldest_blob_slot` are not contiguous, only some blocks (e.g.
+    /// finalized checkpoints) have them. `None` if no such blobs were kept.
+    pub oldest_sparse_blob_slot: Option<Slot>,
+}
+
+/// `BlobInfo` as stored before sparse blob retention was recorded.
+#[derive(Encode, Decode)]
+struct LegacyBlobInfo {
+    oldest_blob_slot: Option<Slot>,
+    blobs_db: bool,
 }
 
 impl StoreItem for BlobInfo {
@@ -211,12 +225,29 @@ impl StoreItem for BlobInfo {
         DBColumn::BeaconMeta
     }
 
+    /// Use the legacy encoding unless sparse blobs are retained, so that older versions can still
+    /// read the blob info.
     fn as_store_bytes(&self) -> Vec<u8> {
-        self.as_ssz_bytes()
+        if self.oldest_sparse_blob_slot.is_some() {
+            return self.as_ssz_bytes();
+        }
+        LegacyBlobInfo {
+            oldest_blob_slot: self.oldest_blob_slot,
+            blobs_db: self.blobs_db,
+        }
+        .as_ssz_bytes()
     }
 
     fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        Ok(Self::from_ssz_bytes(bytes)?)
+        if let Ok(blob_info) = Self::from_ssz_bytes(bytes) {
+            return Ok(blob_info);
+        }
+        let legacy = LegacyBlobInfo::from_ssz_bytes(bytes)?;
+        Ok(Self {
+            oldest_blob_slot: legacy.oldest_blob_slot,
+            blobs_db: legacy.blobs_db,
+            oldest_sparse_blob_slot: None,
+        })
     }
 }
 
@@ -246,3 +277,39 @@ impl StoreItem for DataColumnInfo {
         Ok(Self::from_ssz_bytes(bytes)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blob_info_round_trip() {
+        for oldest_sparse_blob_slot in [None, Some(Slot::new(64))] {
+            let blob_info = BlobInfo {
+                oldest_blob_slot: Some(Slot::new(128)),
+                blobs_db: true,
+                oldest_sparse_blob_slot,
+            };
+            let decoded = BlobInfo::from_store_bytes(&blob_info.as_store_bytes()).unwrap();
+            assert_eq!(decoded, blob_info);
+        }
+    }
+
+    #[test]
+    fn blob_info_legacy_encoding() {
+        let blob_info = BlobInfo {
+            oldest_blob_slot: Some(Slot::new(128)),
+            blobs_db: true,
+            oldest_sparse_blob_slot: None,
+        };
+        let legacy = LegacyBlobInfo {
+            oldest_blob_slot: blob_info.oldest_blob_slot,
+            blobs_db: true,
+        };
+        assert_eq!(blob_info.as_store_bytes(), legacy.as_ssz_bytes());
+        assert_eq!(
+            BlobInfo::from_store_bytes(&legacy.as_ssz_bytes()).unwrap(),
+            blob_info
+        );
+    }
+}
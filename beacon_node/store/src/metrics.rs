@@ -19,6 +19,26 @@ pub static FREEZER_DB_SIZE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
         "Size of the on-disk freezer database (bytes)",
     )
 });
+pub static STORE_COLUMN_KEYS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "store_column_keys",
+        "Number of keys in each database column",
+        &["db", "col"],
+    )
+});
+pub static STORE_COLUMN_BYTES: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "store_column_bytes",
+        "Total size of the keys and values in each database column (bytes)",
+        &["db", "col"],
+    )
+});
+pub static STORE_COLUMN_STATS_TIME: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_column_stats_seconds",
+        "Time taken to compute the key counts and sizes of all database columns",
+    )
+});
 pub static DISK_DB_WRITE_BYTES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "store_disk_db_write_bytes_total",
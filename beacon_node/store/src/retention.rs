@@ -0,0 +1,125 @@
+//! Retention policies for finalized data in the database.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use types::Epoch;
+
+/// How long a type of finalized data (blobs, data columns, payloads, historic states) is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionPolicy {
+    /// Never prune the data.
+    KeepAll,
+    /// Keep the data for the last `n` finalized epochs. Data that must be kept for other reasons
+    /// (e.g. blobs within the data availability period) is never pruned.
+    KeepLastNEpochs(u64),
+    /// Keep only the data belonging to finalized checkpoint blocks, i.e. blocks at the first slot
+    /// of an epoch. For historic states only the genesis state is kept, as the freezer stores
+    /// states as diffs which can't be kept in isolation.
+    KeepFinalizedCheckpointsOnly,
+}
+
+impl RetentionPolicy {
+    /// Return the epoch before which data may be pruned, or `None` if nothing may be pruned.
+    ///
+    /// Data from `min_retained_epoch` onwards is always kept, regardless of the policy.
+    pub fn prune_before(&self, finalized_epoch: Epoch, min_retained_epoch: Epoch) -> Option<Epoch> {
+        match self {
+            Self::KeepAll => None,
+            Self::KeepLastNEpochs(n) => Some(std::cmp::min(
+                finalized_epoch.saturating_sub(*n),
+                min_retained_epoch,
+            )),
+            Self::KeepFinalizedCheckpointsOnly => Some(min_retained_epoch),
+        }
+    }
+
+    /// Return `true` if the data of a block before the pruning cutoff should be deleted.
+    pub fn prunes_block(&self, is_checkpoint_block: bool) -> bool {
+        match self {
+            Self::KeepAll => false,
+            Self::KeepLastNEpochs(_) => true,
+            Self::KeepFinalizedCheckpointsOnly => !is_checkpoint_block,
+        }
+    }
+}
+
+impl FromStr for RetentionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "keep_all" => Ok(Self::KeepAll),
+            "keep_finalized_checkpoints_only" => Ok(Self::KeepFinalizedCheckpointsOnly),
+            _ => {
+                let n = s
+                    .strip_prefix("keep_last_n_epochs:")
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid retention policy: {s}. Expected keep_all, \
+                             keep_last_n_epochs:N or keep_finalized_checkpoints_only"
+                        )
+                    })?
+                    .parse()
+                    .map_err(|e| format!("Invalid number of epochs in {s}: {e}"))?;
+                Ok(Self::KeepLastNEpochs(n))
+            }
+        }
+    }
+}
+
+impl fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::KeepAll => write!(f, "keep_all"),
+            Self::KeepLastNEpochs(n) => write!(f, "keep_last_n_epochs:{n}"),
+            Self::KeepFinalizedCheckpointsOnly => write!(f, "keep_finalized_checkpoints_only"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_display_roundtrip() {
+        for policy in [
+            RetentionPolicy::KeepAll,
+            RetentionPolicy::KeepLastNEpochs(0),
+            RetentionPolicy::KeepLastNEpochs(4096),
+            RetentionPolicy::KeepFinalizedCheckpointsOnly,
+        ] {
+            assert_eq!(policy.to_string().parse::<RetentionPolicy>(), Ok(policy));
+        }
+        assert!("keep_last_n_epochs".parse::<RetentionPolicy>().is_err());
+        assert!("keep_last_n_epochs:x".parse::<RetentionPolicy>().is_err());
+        assert!("keep_some".parse::<RetentionPolicy>().is_err());
+    }
+
+    #[test]
+    fn prune_before() {
+        let finalized = Epoch::new(100);
+        let min_retained = Epoch::new(90);
+        assert_eq!(
+            RetentionPolicy::KeepAll.prune_before(finalized, min_retained),
+            None
+        );
+        assert_eq!(
+            RetentionPolicy::KeepLastNEpochs(20).prune_before(finalized, min_retained),
+            Some(Epoch::new(80))
+        );
+        assert_eq!(
+            RetentionPolicy::KeepLastNEpochs(0).prune_before(finalized, min_retained),
+            Some(min_retained)
+        );
+        assert_eq!(
+            RetentionPolicy::KeepLastNEpochs(200).prune_before(finalized, min_retained),
+            Some(Epoch::new(0))
+        );
+        assert_eq!(
+            RetentionPolicy::KeepFinalizedCheckpointsOnly.prune_before(finalized, min_retained),
+            Some(min_retained)
+        );
+    }
+}
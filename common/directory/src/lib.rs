@@ -11,6 +11,7 @@ pub const DEFAULT_VALIDATOR_DIR: &str = "validators";
 pub const DEFAULT_SECRET_DIR: &str = "secrets";
 pub const DEFAULT_WALLET_DIR: &str = "wallets";
 pub const DEFAULT_TRACING_DIR: &str = "tracing";
+pub const DEFAULT_LIGHT_CLIENT_DIR: &str = "light_client";
 
 /// Base directory name for unnamed testnets passed through the --testnet-dir flag
 pub const CUSTOM_TESTNET_DIR: &str = "custom";
@@ -4,30 +4,38 @@ mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
 mod block_rewards;
+mod op_pool;
+mod proofs;
 mod standard_block_rewards;
 mod sync_committee_rewards;
 
 use crate::{
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        Attestation, AttesterSlashing, BlockId, DepositTreeSnapshot, Epoch, EthSpec,
+        ExecutionOptimisticFinalizedResponse, FinalizedExecutionBlock, GenericResponse,
+        ProposerSlashing, SignedBlsToExecutionChange, SignedVoluntaryExit, StateId, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
 use proto_array::core::ProtoArray;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
-use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
+use store::{column_stats::DatabaseColumnStats, AnchorInfo, BlobInfo, Split, StoreConfig};
 
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
 pub use attestation_rewards::StandardAttestationRewards;
 pub use block_packing_efficiency::{
-    BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
+    AttestationPackingEfficiency, BlockPackingEfficiency, BlockPackingEfficiencyQuery,
+    ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use lighthouse_network::rpc::config::RateLimitsFile;
+pub use lighthouse_network::{types::SyncState, PeerInfo, PeerTrafficStats};
+pub use op_pool::{OpPoolInclusionStatus, OpPoolOperation, OpPoolOverrides};
+pub use proofs::{ExecutionPayloadProof, StateProof, StateProofQuery};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
 
@@ -47,6 +55,21 @@ pub struct Peer<E: EthSpec> {
     pub peer_info: PeerInfo<E>,
 }
 
+/// The peers allowed to connect in private network mode, returned by `lighthouse/peers/allowlist`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerAllowlist {
+    pub peers: Vec<String>,
+}
+
+/// Changes to the private network allow-list, as peer ids or ENRs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerAllowlistUpdate {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -363,6 +386,23 @@ pub struct DatabaseInfo {
     pub split: Split,
     pub anchor: AnchorInfo,
     pub blob_info: BlobInfo,
+    /// Key counts and sizes per column, if they have been computed since the node started.
+    pub column_stats: Option<DatabaseColumnStats>,
+}
+
+/// Whether a block proposal attempted to re-org the head, returned by
+/// `lighthouse/fork_choice/reorg_decisions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReOrgDecision {
+    /// Slot of the proposal.
+    pub slot: Slot,
+    /// The head at the time of the proposal.
+    pub head_block_root: Hash256,
+    pub head_slot: Slot,
+    /// Whether the proposal was built on the parent of the head.
+    pub re_org: bool,
+    /// Why the head was or wasn't re-orged.
+    pub reason: String,
 }
 
 impl BeaconNodeHttpClient {
@@ -398,6 +438,69 @@ impl BeaconNodeHttpClient {
      * fairly simply achieved, if desired.
      */
 
+    /// `GET lighthouse/peers/{peer_id}/stats`
+    pub async fn get_lighthouse_peer_stats(
+        &self,
+        peer_id: &str,
+    ) -> Result<GenericResponse<PeerTrafficStats>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push(peer_id)
+            .push("stats");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/allowlist`
+    pub async fn get_lighthouse_peers_allowlist(
+        &self,
+    ) -> Result<GenericResponse<PeerAllowlist>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("allowlist");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/peers/allowlist`
+    pub async fn post_lighthouse_peers_allowlist(
+        &self,
+        update: &PeerAllowlistUpdate,
+    ) -> Result<GenericResponse<PeerAllowlist>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("allowlist");
+
+        self.post_with_response(path, update).await
+    }
+
+    /// `POST lighthouse/rate_limits/reload`
+    pub async fn post_lighthouse_rate_limits_reload(
+        &self,
+    ) -> Result<GenericResponse<RateLimitsFile>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("rate_limits")
+            .push("reload");
+
+        self.post_with_response(path, &()).await
+    }
+
     /// `GET lighthouse/proto_array`
     pub async fn get_lighthouse_proto_array(&self) -> Result<GenericResponse<ProtoArray>, Error> {
         let mut path = self.server.full.clone();
@@ -410,6 +513,104 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/op_pool/{kind}`
+    async fn get_lighthouse_op_pool<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<T>>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool")
+            .push(kind);
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/op_pool/attestations`
+    pub async fn get_lighthouse_op_pool_attestations<E: EthSpec>(
+        &self,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<Attestation<E>>>>, Error> {
+        self.get_lighthouse_op_pool("attestations").await
+    }
+
+    /// `GET lighthouse/op_pool/attester_slashings`
+    pub async fn get_lighthouse_op_pool_attester_slashings<E: EthSpec>(
+        &self,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<AttesterSlashing<E>>>>, Error> {
+        self.get_lighthouse_op_pool("attester_slashings").await
+    }
+
+    /// `GET lighthouse/op_pool/proposer_slashings`
+    pub async fn get_lighthouse_op_pool_proposer_slashings(
+        &self,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<ProposerSlashing>>>, Error> {
+        self.get_lighthouse_op_pool("proposer_slashings").await
+    }
+
+    /// `GET lighthouse/op_pool/voluntary_exits`
+    pub async fn get_lighthouse_op_pool_voluntary_exits(
+        &self,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<SignedVoluntaryExit>>>, Error> {
+        self.get_lighthouse_op_pool("voluntary_exits").await
+    }
+
+    /// `GET lighthouse/op_pool/bls_to_execution_changes`
+    pub async fn get_lighthouse_op_pool_bls_to_execution_changes(
+        &self,
+    ) -> Result<GenericResponse<Vec<OpPoolOperation<SignedBlsToExecutionChange>>>, Error> {
+        self.get_lighthouse_op_pool("bls_to_execution_changes")
+            .await
+    }
+
+    /// `GET lighthouse/op_pool/overrides`
+    pub async fn get_lighthouse_op_pool_overrides(
+        &self,
+    ) -> Result<GenericResponse<OpPoolOverrides>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool")
+            .push("overrides");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/op_pool/overrides`
+    pub async fn post_lighthouse_op_pool_overrides(
+        &self,
+        overrides: &OpPoolOverrides,
+    ) -> Result<GenericResponse<OpPoolOverrides>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool")
+            .push("overrides");
+
+        self.post_with_response(path, overrides).await
+    }
+
+    /// `GET lighthouse/fork_choice/reorg_decisions`
+    pub async fn get_lighthouse_fork_choice_reorg_decisions(
+        &self,
+    ) -> Result<GenericResponse<Vec<ReOrgDecision>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("reorg_decisions");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -528,6 +729,47 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `GET lighthouse/proofs/execution_payload/{block_id}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_proofs_execution_payload(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<ExecutionPayloadProof>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proofs")
+            .push("execution_payload")
+            .push(&block_id.to_string());
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/proofs/state/{state_id}?path`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_proofs_state(
+        &self,
+        state_id: StateId,
+        proof_path: &str,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<StateProof>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proofs")
+            .push("state")
+            .push(&state_id.to_string());
+
+        path.query_pairs_mut().append_pair("path", proof_path);
+
+        self.get_opt(path).await
+    }
+
     /*
      Analysis endpoints.
     */
@@ -25,6 +25,23 @@ pub struct BlockPackingEfficiency {
     pub available_attestations: usize,
     pub included_attestations: usize,
     pub prior_skip_slots: u64,
+    /// How the attestations were packed, if this node produced the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation_packing: Option<AttestationPackingEfficiency>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AttestationPackingEfficiency {
+    /// Name of the strategy used to pack the attestations.
+    pub strategy: String,
+    /// Number of valid aggregate attestations in the operation pool.
+    pub candidate_attestations: usize,
+    /// Number of aggregate attestations packed.
+    pub packed_attestations: usize,
+    /// Number of validators whose attestations are rewarded for the first time by the block.
+    pub attesters: usize,
+    /// Proposer reward for the packed attestations, in gwei.
+    pub proposer_reward: u64,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use types::Hash256;
+
+/// Whether an operation in the pool would be packed into the next block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpPoolInclusionStatus {
+    /// Packed into a block built on the head at the next slot.
+    Included,
+    /// Not valid for inclusion in the block, or left out for more profitable operations.
+    NotIncluded,
+    /// Kept out of the next block by an override.
+    Excluded,
+}
+
+/// An operation in the pool, returned by the `lighthouse/op_pool/*` endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpPoolOperation<T> {
+    /// Tree hash root of the operation, which identifies it in overrides.
+    pub root: Hash256,
+    pub status: OpPoolInclusionStatus,
+    /// Whether the operation is forced into the next block by an override.
+    pub forced: bool,
+    /// For attestations, the proposer reward in gwei for including the attestation on its own,
+    /// if it can be included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u64>,
+    pub operation: T,
+}
+
+/// Operations forced into, or kept out of, the next block produced, by their roots.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpPoolOverrides {
+    #[serde(default)]
+    pub include: Vec<Hash256>,
+    #[serde(default)]
+    pub exclude: Vec<Hash256>,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use types::{ExecutionBlockHash, Hash256, Slot, SszProof};
+
+/// A proof of the execution block hash of a beacon block, returned by
+/// `lighthouse/proofs/execution_payload/{block_id}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionPayloadProof {
+    pub slot: Slot,
+    /// The root the proof is verified against.
+    pub block_root: Hash256,
+    pub execution_block_hash: ExecutionBlockHash,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub execution_block_number: u64,
+    pub proof: SszProof,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateProofQuery {
+    /// A `/`-separated list of field names and list indices, e.g. `balances/7`.
+    pub path: String,
+}
+
+/// A proof of a node of a beacon state, returned by `lighthouse/proofs/state/{state_id}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateProof {
+    /// The root the proof is verified against.
+    pub state_root: Hash256,
+    pub path: String,
+    pub proof: SszProof,
+}
@@ -1,4 +1,6 @@
+use crate::journal::ForkChoiceJournal;
 use crate::metrics::{self, scrape_for_metrics};
+use crate::replay::ForkChoiceEvent;
 use crate::{ForkChoiceStore, InvalidationOperation};
 use proto_array::{
     Block as ProtoBlock, DisallowedReOrgOffsets, ExecutionStatus, ProposerHeadError,
@@ -12,6 +14,7 @@ use state_processing::{
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 use std::marker::PhantomData;
+use std::sync::Arc;
 use std::time::Duration;
 use types::{
     consts::bellatrix::INTERVALS_PER_SLOT, AbstractExecPayload, AttestationShufflingId,
@@ -317,6 +320,8 @@ pub struct ForkChoice<T, E> {
     queued_attestations: Vec<QueuedAttestation>,
     /// Stores a cache of the values required to be sent to the execution layer.
     forkchoice_update_parameters: ForkchoiceUpdateParameters,
+    /// Receives every input to fork choice, if journaling is enabled.
+    journal: Option<Arc<dyn ForkChoiceJournal>>,
     _phantom: PhantomData<E>,
 }
 
@@ -405,6 +410,7 @@ where
                 // This will be updated during the next call to `Self::get_head`.
                 head_root: Hash256::zero(),
             },
+            journal: None,
             _phantom: PhantomData,
         };
 
@@ -422,6 +428,43 @@ where
         self.forkchoice_update_parameters
     }
 
+    /// Send every subsequent input to fork choice to `journal`.
+    pub fn set_journal(&mut self, journal: Arc<dyn ForkChoiceJournal>) {
+        self.journal = Some(journal);
+    }
+
+    /// Record the event returned by `event` to the journal, if any, preceded by a snapshot if the
+    /// journal requires one.
+    ///
+    /// Events are recorded before they're applied, so that a snapshot doesn't already contain
+    /// them. Checkpoint updates are the exception, since applying them twice is harmless.
+    fn journal(&self, event: impl FnOnce() -> ForkChoiceEvent) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        if journal.needs_snapshot() {
+            journal.record(ForkChoiceEvent::Snapshot {
+                current_slot: self.fc_store.get_current_slot(),
+                justified_checkpoint: *self.fc_store.justified_checkpoint(),
+                finalized_checkpoint: *self.fc_store.finalized_checkpoint(),
+                justified_balances: self
+                    .fc_store
+                    .justified_balances()
+                    .effective_balances
+                    .clone(),
+                proposer_boost_root: self.fc_store.proposer_boost_root(),
+                equivocating_indices: self
+                    .fc_store
+                    .equivocating_indices()
+                    .iter()
+                    .copied()
+                    .collect(),
+                proto_array_bytes: self.proto_array.as_bytes(),
+            });
+        }
+        journal.record(event());
+    }
+
     /// Returns the block root of an ancestor of `block_root` at the given `slot`. (Note: `slot` refers
     /// to the block that is *returned*, not the one that is supplied.)
     ///
@@ -492,6 +535,7 @@ where
             current_slot,
             spec,
         )?;
+        self.journal(|| ForkChoiceEvent::GetHead { head_root });
 
         // Cache some values for the next forkchoiceUpdate call to the execution layer.
         let head_hash = self
@@ -611,6 +655,7 @@ where
         &mut self,
         block_root: Hash256,
     ) -> Result<(), Error<T::Error>> {
+        self.journal(|| ForkChoiceEvent::OnValidExecutionPayload { block_root });
         self.proto_array
             .process_execution_payload_validation(block_root)
             .map_err(Error::FailedToProcessValidExecutionPayload)
@@ -621,6 +666,7 @@ where
         &mut self,
         op: &InvalidationOperation,
     ) -> Result<(), Error<T::Error>> {
+        self.journal(|| ForkChoiceEvent::OnInvalidExecutionPayload { op: op.clone() });
         self.proto_array
             .process_execution_payload_invalidation::<E>(op)
             .map_err(Error::FailedToProcessInvalidExecutionPayload)
@@ -856,35 +902,39 @@ where
             ExecutionStatus::irrelevant()
         };
 
+        let proto_block = ProtoBlock {
+            slot: block.slot(),
+            root: block_root,
+            parent_root: Some(block.parent_root()),
+            target_root,
+            current_epoch_shuffling_id: AttestationShufflingId::new(
+                block_root,
+                state,
+                RelativeEpoch::Current,
+            )
+            .map_err(Error::BeaconStateError)?,
+            next_epoch_shuffling_id: AttestationShufflingId::new(
+                block_root,
+                state,
+                RelativeEpoch::Next,
+            )
+            .map_err(Error::BeaconStateError)?,
+            state_root: block.state_root(),
+            justified_checkpoint: state.current_justified_checkpoint(),
+            finalized_checkpoint: state.finalized_checkpoint(),
+            execution_status,
+            unrealized_justified_checkpoint: Some(unrealized_justified_checkpoint),
+            unrealized_finalized_checkpoint: Some(unrealized_finalized_checkpoint),
+        };
+        self.journal(|| ForkChoiceEvent::OnBlock {
+            block: proto_block.clone(),
+            timely: is_before_attesting_interval,
+        });
+
         // This does not apply a vote to the block, it just makes fork choice aware of the block so
         // it can still be identified as the head even if it doesn't have any votes.
-        self.proto_array.process_block::<E>(
-            ProtoBlock {
-                slot: block.slot(),
-                root: block_root,
-                parent_root: Some(block.parent_root()),
-                target_root,
-                current_epoch_shuffling_id: AttestationShufflingId::new(
-                    block_root,
-                    state,
-                    RelativeEpoch::Current,
-                )
-                .map_err(Error::BeaconStateError)?,
-                next_epoch_shuffling_id: AttestationShufflingId::new(
-                    block_root,
-                    state,
-                    RelativeEpoch::Next,
-                )
-                .map_err(Error::BeaconStateError)?,
-                state_root: block.state_root(),
-                justified_checkpoint: state.current_justified_checkpoint(),
-                finalized_checkpoint: state.finalized_checkpoint(),
-                execution_status,
-                unrealized_justified_checkpoint: Some(unrealized_justified_checkpoint),
-                unrealized_finalized_checkpoint: Some(unrealized_finalized_checkpoint),
-            },
-            current_slot,
-        )?;
+        self.proto_array
+            .process_block::<E>(proto_block, current_slot)?;
 
         Ok(())
     }
@@ -895,16 +945,32 @@ where
         justified_checkpoint: Checkpoint,
         finalized_checkpoint: Checkpoint,
     ) -> Result<(), Error<T::Error>> {
+        let mut updated = false;
+
         // Update justified checkpoint.
         if justified_checkpoint.epoch > self.fc_store.justified_checkpoint().epoch {
             self.fc_store
                 .set_justified_checkpoint(justified_checkpoint)
                 .map_err(Error::UnableToSetJustifiedCheckpoint)?;
+            updated = true;
         }
 
         // Update finalized checkpoint.
         if finalized_checkpoint.epoch > self.fc_store.finalized_checkpoint().epoch {
             self.fc_store.set_finalized_checkpoint(finalized_checkpoint);
+            updated = true;
+        }
+
+        if updated {
+            self.journal(|| ForkChoiceEvent::UpdateCheckpoints {
+                justified_checkpoint: *self.fc_store.justified_checkpoint(),
+                finalized_checkpoint: *self.fc_store.finalized_checkpoint(),
+                justified_balances: self
+                    .fc_store
+                    .justified_balances()
+                    .effective_balances
+                    .clone(),
+            });
         }
 
         Ok(())
@@ -1072,6 +1138,12 @@ where
         self.validate_on_attestation(attestation, is_from_block)?;
 
         if attestation.data().slot < self.fc_store.get_current_slot() {
+            self.journal(|| ForkChoiceEvent::OnAttestation {
+                validator_indices: attestation.attesting_indices_to_vec(),
+                block_root: attestation.data().beacon_block_root,
+                target_epoch: attestation.data().target.epoch,
+                source: None,
+            });
             for validator_index in attestation.attesting_indices_iter() {
                 self.proto_array.process_attestation(
                     *validator_index as usize,
@@ -1106,6 +1178,9 @@ where
         };
         let att1_indices = attesting_indices_set(slashing.attestation_1());
         let att2_indices = attesting_indices_set(slashing.attestation_2());
+        self.journal(|| ForkChoiceEvent::OnAttesterSlashing {
+            validator_indices: att1_indices.intersection(&att2_indices).copied().collect(),
+        });
         self.fc_store
             .extend_equivocating_indices(att1_indices.intersection(&att2_indices).copied());
     }
@@ -1134,8 +1209,7 @@ where
     ///
     /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#on_tick
     fn on_tick(&mut self, time: Slot) -> Result<(), Error<T::Error>> {
-        let store = &mut self.fc_store;
-        let previous_slot = store.get_current_slot();
+        let previous_slot = self.fc_store.get_current_slot();
 
         if time > previous_slot + 1 {
             return Err(Error::InconsistentOnTick {
@@ -1144,6 +1218,12 @@ where
             });
         }
 
+        if time > previous_slot {
+            self.journal(|| ForkChoiceEvent::UpdateTime { current_slot: time });
+        }
+
+        let store = &mut self.fc_store;
+
         // Update store time.
         store.set_current_slot(time);
 
@@ -1191,6 +1271,12 @@ where
             self.fc_store.get_current_slot(),
             &mut self.queued_attestations,
         ) {
+            self.journal(|| ForkChoiceEvent::OnAttestation {
+                validator_indices: attestation.attesting_indices.clone(),
+                block_root: attestation.block_root,
+                target_epoch: attestation.target_epoch,
+                source: None,
+            });
             for validator_index in attestation.attesting_indices.iter() {
                 self.proto_array.process_attestation(
                     *validator_index as usize,
@@ -1442,6 +1528,7 @@ where
                 // Will be updated in the following call to `Self::get_head`.
                 head_root: Hash256::zero(),
             },
+            journal: None,
             _phantom: PhantomData,
         };
 
@@ -0,0 +1,452 @@
+//! A compact binary encoding of `ForkChoiceEvent`s, used to journal the inputs to fork choice.
+//!
+//! A journal file starts with `JOURNAL_MAGIC` and is followed by frames. Each frame is the length
+//! of its payload as a little-endian `u32`, followed by the payload: an SSZ-encoded
+//! `JournalRecord` compressed with raw snappy.
+//!
+//! Attestation sources are not journaled, since `ForkChoice` doesn't know them.
+use crate::replay::ForkChoiceEvent;
+use proto_array::{Block as ProtoBlock, InvalidationOperation};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::io::{self, Read};
+use types::{Checkpoint, Epoch, ExecutionBlockHash, Hash256, Slot};
+
+/// Identifies a fork choice journal file and the version of its encoding.
+pub const JOURNAL_MAGIC: &[u8; 8] = b"FCJRNL01";
+
+/// Frames larger than this are assumed to be corrupt.
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+
+/// A sink for the inputs to `ForkChoice`.
+pub trait ForkChoiceJournal: Send + Sync {
+    /// Returns `true` if a `ForkChoiceEvent::Snapshot` should be recorded before the next event,
+    /// e.g. because a new journal file was started.
+    fn needs_snapshot(&self) -> bool;
+
+    /// Record `event`. Errors must be handled by the journal, fork choice never fails because of
+    /// them.
+    fn record(&self, event: ForkChoiceEvent);
+}
+
+#[derive(Encode, Decode)]
+struct BlockRecord {
+    block: ProtoBlock,
+    timely: bool,
+}
+
+#[derive(Encode, Decode)]
+struct AttestationRecord {
+    validator_indices: Vec<u64>,
+    block_root: Hash256,
+    target_epoch: Epoch,
+}
+
+#[derive(Encode, Decode)]
+struct CheckpointsRecord {
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    justified_balances: Vec<u64>,
+}
+
+/// An `InvalidationOperation`, which is `InvalidateOne` if `latest_valid_ancestor` is `None`.
+#[derive(Encode, Decode)]
+struct InvalidationRecord {
+    block_root: Hash256,
+    always_invalidate_head: bool,
+    latest_valid_ancestor: Option<ExecutionBlockHash>,
+}
+
+#[derive(Encode, Decode)]
+struct SnapshotRecord {
+    current_slot: Slot,
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    justified_balances: Vec<u64>,
+    proposer_boost_root: Hash256,
+    equivocating_indices: Vec<u64>,
+    proto_array_bytes: Vec<u8>,
+}
+
+/// The SSZ representation of a `ForkChoiceEvent`.
+///
+/// New variants must only be appended, to keep existing journals readable.
+#[derive(Encode, Decode)]
+#[ssz(enum_behaviour = "union")]
+enum JournalRecord {
+    UpdateTime(Slot),
+    OnBlock(BlockRecord),
+    OnAttestation(AttestationRecord),
+    OnAttesterSlashing(Vec<u64>),
+    UpdateCheckpoints(CheckpointsRecord),
+    OnValidExecutionPayload(Hash256),
+    OnInvalidExecutionPayload(InvalidationRecord),
+    GetHead(Hash256),
+    Snapshot(SnapshotRecord),
+}
+
+impl From<&ForkChoiceEvent> for JournalRecord {
+    fn from(event: &ForkChoiceEvent) -> Self {
+        match event.clone() {
+            ForkChoiceEvent::UpdateTime { current_slot } => JournalRecord::UpdateTime(current_slot),
+            ForkChoiceEvent::OnBlock { block, timely } => {
+                JournalRecord::OnBlock(BlockRecord { block, timely })
+            }
+            ForkChoiceEvent::OnAttestation {
+                validator_indices,
+                block_root,
+                target_epoch,
+                source: _,
+            } => JournalRecord::OnAttestation(AttestationRecord {
+                validator_indices,
+                block_root,
+                target_epoch,
+            }),
+            ForkChoiceEvent::OnAttesterSlashing { validator_indices } => {
+                JournalRecord::OnAttesterSlashing(validator_indices)
+            }
+            ForkChoiceEvent::UpdateCheckpoints {
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+            } => JournalRecord::UpdateCheckpoints(CheckpointsRecord {
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+            }),
+            ForkChoiceEvent::OnValidExecutionPayload { block_root } => {
+                JournalRecord::OnValidExecutionPayload(block_root)
+            }
+            ForkChoiceEvent::OnInvalidExecutionPayload { op } => {
+                JournalRecord::OnInvalidExecutionPayload(match op {
+                    InvalidationOperation::InvalidateOne { block_root } => InvalidationRecord {
+                        block_root,
+                        always_invalidate_head: false,
+                        latest_valid_ancestor: None,
+                    },
+                    InvalidationOperation::InvalidateMany {
+                        head_block_root,
+                        always_invalidate_head,
+                        latest_valid_ancestor,
+                    } => InvalidationRecord {
+                        block_root: head_block_root,
+                        always_invalidate_head,
+                        latest_valid_ancestor: Some(latest_valid_ancestor),
+                    },
+                })
+            }
+            ForkChoiceEvent::GetHead { head_root } => JournalRecord::GetHead(head_root),
+            ForkChoiceEvent::Snapshot {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+                proposer_boost_root,
+                equivocating_indices,
+                proto_array_bytes,
+            } => JournalRecord::Snapshot(SnapshotRecord {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+                proposer_boost_root,
+                equivocating_indices,
+                proto_array_bytes,
+            }),
+        }
+    }
+}
+
+impl From<JournalRecord> for ForkChoiceEvent {
+    fn from(record: JournalRecord) -> Self {
+        match record {
+            JournalRecord::UpdateTime(current_slot) => ForkChoiceEvent::UpdateTime { current_slot },
+            JournalRecord::OnBlock(BlockRecord { block, timely }) => {
+                ForkChoiceEvent::OnBlock { block, timely }
+            }
+            JournalRecord::OnAttestation(AttestationRecord {
+                validator_indices,
+                block_root,
+                target_epoch,
+            }) => ForkChoiceEvent::OnAttestation {
+                validator_indices,
+                block_root,
+                target_epoch,
+                source: None,
+            },
+            JournalRecord::OnAttesterSlashing(validator_indices) => {
+                ForkChoiceEvent::OnAttesterSlashing { validator_indices }
+            }
+            JournalRecord::UpdateCheckpoints(CheckpointsRecord {
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+            }) => ForkChoiceEvent::UpdateCheckpoints {
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+            },
+            JournalRecord::OnValidExecutionPayload(block_root) => {
+                ForkChoiceEvent::OnValidExecutionPayload { block_root }
+            }
+            JournalRecord::OnInvalidExecutionPayload(record) => {
+                let op = match record.latest_valid_ancestor {
+                    None => InvalidationOperation::InvalidateOne {
+                        block_root: record.block_root,
+                    },
+                    Some(latest_valid_ancestor) => InvalidationOperation::InvalidateMany {
+                        head_block_root: record.block_root,
+                        always_invalidate_head: record.always_invalidate_head,
+                        latest_valid_ancestor,
+                    },
+                };
+                ForkChoiceEvent::OnInvalidExecutionPayload { op }
+            }
+            JournalRecord::GetHead(head_root) => ForkChoiceEvent::GetHead { head_root },
+            JournalRecord::Snapshot(SnapshotRecord {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+                proposer_boost_root,
+                equivocating_indices,
+                proto_array_bytes,
+            }) => ForkChoiceEvent::Snapshot {
+                current_slot,
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances,
+                proposer_boost_root,
+                equivocating_indices,
+                proto_array_bytes,
+            },
+        }
+    }
+}
+
+/// Encode `event` as a journal frame.
+pub fn encode_frame(event: &ForkChoiceEvent) -> Result<Vec<u8>, String> {
+    let payload = snap::raw::Encoder::new()
+        .compress_vec(&JournalRecord::from(event).as_ssz_bytes())
+        .map_err(|e| format!("Unable to compress journal record: {e}"))?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| format!("Journal record too large: {} bytes", payload.len()))?;
+
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// Reads the `ForkChoiceEvent`s from a journal file.
+///
+/// A frame which is cut short at the end of the file, e.g. because the node crashed while writing
+/// it, ends the journal like the end of the file would. `truncated` tells whether that happened.
+pub struct JournalReader<R> {
+    reader: R,
+    done: bool,
+    truncated: bool,
+}
+
+impl<R: Read> JournalReader<R> {
+    /// Check the header of the journal in `reader`.
+    pub fn new(mut reader: R) -> Result<Self, String> {
+        let mut magic = [0; JOURNAL_MAGIC.len()];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|e| format!("Unable to read journal header: {e}"))?;
+        if &magic != JOURNAL_MAGIC {
+            return Err("Not a fork choice journal".into());
+        }
+        Ok(Self {
+            reader,
+            done: false,
+            truncated: false,
+        })
+    }
+
+    /// Returns `true` if the journal ended with a truncated frame, which was skipped.
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    fn read_frame(&mut self) -> Result<Option<ForkChoiceEvent>, String> {
+        let mut len = [0; 4];
+        match self.reader.read(&mut len[..1]) {
+            Ok(0) => return Ok(None),
+            Ok(_) => (),
+            Err(e) => return Err(format!("Unable to read journal: {e}")),
+        }
+        if !self.read_frame_part(&mut len[1..])? {
+            return Ok(None);
+        }
+
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(format!("Journal frame of {len} bytes is too large"));
+        }
+        let mut payload = vec![0; len];
+        if !self.read_frame_part(&mut payload)? {
+            return Ok(None);
+        }
+
+        let bytes = snap::raw::Decoder::new()
+            .decompress_vec(&payload)
+            .map_err(|e| format!("Unable to decompress journal frame: {e}"))?;
+        let record = JournalRecord::from_ssz_bytes(&bytes)
+            .map_err(|e| format!("Invalid journal record: {e:?}"))?;
+        Ok(Some(record.into()))
+    }
+
+    /// Fill `buf` with the next part of a frame, returning `false` if the journal ends first.
+    fn read_frame_part(&mut self, buf: &mut [u8]) -> Result<bool, String> {
+        match self.reader.read_exact(buf) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                self.truncated = true;
+                Ok(false)
+            }
+            Err(e) => Err(format!("Unable to read journal: {e}")),
+        }
+    }
+}
+
+impl<R: Read> Iterator for JournalReader<R> {
+    type Item = Result<ForkChoiceEvent, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.read_frame().transpose();
+        // Stop after the end of the journal or the first error, since the frame boundaries can't
+        // be trusted after an error.
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto_array::ExecutionStatus;
+    use types::{AttestationShufflingId, FixedBytesExtended};
+
+    fn events() -> Vec<ForkChoiceEvent> {
+        let checkpoint = Checkpoint {
+            epoch: Epoch::new(1),
+            root: Hash256::repeat_byte(1),
+        };
+        let shuffling_id = AttestationShufflingId::from_components(Epoch::new(1), Hash256::zero());
+        vec![
+            ForkChoiceEvent::Snapshot {
+                current_slot: Slot::new(32),
+                justified_checkpoint: checkpoint,
+                finalized_checkpoint: checkpoint,
+                justified_balances: vec![32_000_000_000; 4],
+                proposer_boost_root: Hash256::zero(),
+                equivocating_indices: vec![3],
+                proto_array_bytes: vec![1, 2, 3],
+            },
+            ForkChoiceEvent::UpdateTime {
+                current_slot: Slot::new(33),
+            },
+            ForkChoiceEvent::OnBlock {
+                block: ProtoBlock {
+                    slot: Slot::new(33),
+                    root: Hash256::repeat_byte(2),
+                    parent_root: Some(checkpoint.root),
+                    state_root: Hash256::repeat_byte(3),
+                    target_root: checkpoint.root,
+                    current_epoch_shuffling_id: shuffling_id.clone(),
+                    next_epoch_shuffling_id: shuffling_id,
+                    justified_checkpoint: checkpoint,
+                    finalized_checkpoint: checkpoint,
+                    execution_status: ExecutionStatus::Optimistic(ExecutionBlockHash::repeat_byte(
+                        4,
+                    )),
+                    unrealized_justified_checkpoint: Some(checkpoint),
+                    unrealized_finalized_checkpoint: None,
+                },
+                timely: true,
+            },
+            ForkChoiceEvent::OnAttestation {
+                validator_indices: vec![0, 1, 2],
+                block_root: Hash256::repeat_byte(2),
+                target_epoch: Epoch::new(1),
+                source: None,
+            },
+            ForkChoiceEvent::OnAttesterSlashing {
+                validator_indices: vec![3],
+            },
+            ForkChoiceEvent::UpdateCheckpoints {
+                justified_checkpoint: checkpoint,
+                finalized_checkpoint: checkpoint,
+                justified_balances: vec![32_000_000_000; 4],
+            },
+            ForkChoiceEvent::OnValidExecutionPayload {
+                block_root: Hash256::repeat_byte(2),
+            },
+            ForkChoiceEvent::OnInvalidExecutionPayload {
+                op: InvalidationOperation::InvalidateOne {
+                    block_root: Hash256::repeat_byte(2),
+                },
+            },
+            ForkChoiceEvent::OnInvalidExecutionPayload {
+                op: InvalidationOperation::InvalidateMany {
+                    head_block_root: Hash256::repeat_byte(2),
+                    always_invalidate_head: true,
+                    latest_valid_ancestor: ExecutionBlockHash::repeat_byte(5),
+                },
+            },
+            ForkChoiceEvent::GetHead {
+                head_root: Hash256::repeat_byte(2),
+            },
+        ]
+    }
+
+    fn journal(events: &[ForkChoiceEvent]) -> Vec<u8> {
+        let mut bytes = JOURNAL_MAGIC.to_vec();
+        for event in events {
+            bytes.extend(encode_frame(event).unwrap());
+        }
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = events();
+        let bytes = journal(&events);
+        let decoded = JournalReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let events = events();
+        let complete = journal(&events);
+
+        // Cut the last frame short in its length and in its payload.
+        let last_frame_start = complete.len() - encode_frame(events.last().unwrap()).unwrap().len();
+        for len in [last_frame_start + 2, complete.len() - 1] {
+            let mut reader = JournalReader::new(&complete[..len]).unwrap();
+            let decoded = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(decoded, events[..events.len() - 1]);
+            assert!(reader.truncated());
+        }
+
+        let mut reader = JournalReader::new(complete.as_slice()).unwrap();
+        assert_eq!(reader.by_ref().count(), events.len());
+        assert!(!reader.truncated());
+    }
+
+    #[test]
+    fn bad_magic() {
+        assert!(JournalReader::new(&b"not a journal"[..]).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 mod fork_choice;
 mod fork_choice_store;
+pub mod journal;
 mod metrics;
+pub mod replay;
 
 pub use crate::fork_choice::{
     AttestationFromBlock, Error, ForkChoice, ForkChoiceView, ForkchoiceUpdateParameters,
@@ -0,0 +1,487 @@
+//! Offline replay of the inputs to fork choice.
+//!
+//! A `ForkChoiceReplayer` starts from the persisted state of fork choice and applies a recorded
+//! stream of `ForkChoiceEvent`s to the underlying `ProtoArrayForkChoice`, running head selection
+//! after every event. The replay can be altered with `WhatIf` edits to see how head selection
+//! would have differed, e.g. without proposer boost or without some validators' attestations.
+//!
+//! Only the proto-array and the parts of the fork choice store which influence head selection are
+//! modelled. Changes to the store's checkpoints are recorded as events rather than recomputed, so
+//! no states are needed to replay.
+use proto_array::{
+    Block as ProtoBlock, InvalidationOperation, JustifiedBalances, ProtoArrayForkChoice,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::marker::PhantomData;
+use types::{ChainSpec, Checkpoint, Epoch, EthSpec, FixedBytesExtended, Hash256, Slot};
+
+/// An input to fork choice.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ForkChoiceEvent {
+    /// The current slot advanced, expiring the proposer boost of the previous slot.
+    UpdateTime { current_slot: Slot },
+    /// A block was added to fork choice. It is `timely` if it arrived before the attestation
+    /// deadline of its slot.
+    OnBlock { block: ProtoBlock, timely: bool },
+    /// The latest messages of some validators were applied, after any queueing or batching.
+    ///
+    /// The `source` identifies where the attestation came from, such as a peer ID, if known.
+    OnAttestation {
+        validator_indices: Vec<u64>,
+        block_root: Hash256,
+        target_epoch: Epoch,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+    },
+    /// Validators were slashed for equivocating, and their votes no longer count.
+    OnAttesterSlashing { validator_indices: Vec<u64> },
+    /// The justified or finalized checkpoint of the fork choice store changed.
+    UpdateCheckpoints {
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+        justified_balances: Vec<u64>,
+    },
+    /// The execution payload of a block was found to be valid.
+    OnValidExecutionPayload { block_root: Hash256 },
+    /// The execution payloads of some blocks were found to be invalid.
+    OnInvalidExecutionPayload { op: InvalidationOperation },
+    /// The head which fork choice returned when the events were recorded.
+    GetHead { head_root: Hash256 },
+    /// The complete state of fork choice, from which a replay can start.
+    Snapshot {
+        current_slot: Slot,
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+        justified_balances: Vec<u64>,
+        proposer_boost_root: Hash256,
+        equivocating_indices: Vec<u64>,
+        /// The SSZ encoding of the `ProtoArrayForkChoice`.
+        #[serde(with = "serde_utils::hex_vec")]
+        proto_array_bytes: Vec<u8>,
+    },
+}
+
+/// The state of fork choice that a replay starts from.
+pub struct ReplayAnchor {
+    pub proto_array: ProtoArrayForkChoice,
+    pub current_slot: Slot,
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub justified_balances: Vec<u64>,
+    pub proposer_boost_root: Hash256,
+    pub equivocating_indices: BTreeSet<u64>,
+}
+
+impl ReplayAnchor {
+    /// Build an anchor from a `ForkChoiceEvent::Snapshot`.
+    pub fn from_snapshot(event: &ForkChoiceEvent) -> Result<Self, String> {
+        let ForkChoiceEvent::Snapshot {
+            current_slot,
+            justified_checkpoint,
+            finalized_checkpoint,
+            justified_balances,
+            proposer_boost_root,
+            equivocating_indices,
+            proto_array_bytes,
+        } = event
+        else {
+            return Err("Event is not a snapshot".into());
+        };
+
+        Ok(Self {
+            proto_array: ProtoArrayForkChoice::from_bytes(proto_array_bytes)?,
+            current_slot: *current_slot,
+            justified_checkpoint: *justified_checkpoint,
+            finalized_checkpoint: *finalized_checkpoint,
+            justified_balances: justified_balances.clone(),
+            proposer_boost_root: *proposer_boost_root,
+            equivocating_indices: equivocating_indices.iter().copied().collect(),
+        })
+    }
+}
+
+/// Edits to the recorded events which are applied during a replay.
+#[derive(Debug, Clone, Default)]
+pub struct WhatIf {
+    /// Never apply proposer boost.
+    pub disable_proposer_boost: bool,
+    /// Use this proposer boost percentage instead of the one from the spec.
+    pub proposer_score_boost: Option<u64>,
+    /// Ignore the attestations of these validators.
+    pub dropped_validators: HashSet<u64>,
+    /// Ignore attestations with these sources.
+    pub dropped_sources: HashSet<String>,
+}
+
+/// Why a block did or did not receive proposer boost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoostDecision {
+    Boosted,
+    /// The block arrived after the attestation deadline.
+    Late,
+    /// The block is not from the current slot.
+    NotCurrentSlot,
+    /// Another block already has the boost for this slot.
+    AlreadyBoosted(Hash256),
+    /// Proposer boost is disabled.
+    Disabled,
+}
+
+/// The outcome of applying a single event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayStep {
+    pub current_slot: Slot,
+    pub head_root: Hash256,
+    pub head_weight: u64,
+    pub proposer_boost_root: Hash256,
+    /// The boost decision for an `OnBlock` event.
+    pub boost_decision: Option<BoostDecision>,
+    /// The head recorded by a `GetHead` event, which may differ from `head_root`.
+    pub recorded_head: Option<Hash256>,
+    /// Whether the event was ignored, in whole or in part, because of a `WhatIf` edit.
+    pub dropped: bool,
+    /// Blocks without children and their weights, heaviest first.
+    pub tips: Vec<(Hash256, Slot, u64)>,
+}
+
+/// Applies `ForkChoiceEvent`s to a `ProtoArrayForkChoice`.
+pub struct ForkChoiceReplayer<E: EthSpec> {
+    proto_array: ProtoArrayForkChoice,
+    current_slot: Slot,
+    justified_checkpoint: Checkpoint,
+    finalized_checkpoint: Checkpoint,
+    justified_balances: JustifiedBalances,
+    proposer_boost_root: Hash256,
+    equivocating_indices: BTreeSet<u64>,
+    what_if: WhatIf,
+    spec: ChainSpec,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> ForkChoiceReplayer<E> {
+    pub fn new(anchor: ReplayAnchor, what_if: WhatIf, spec: &ChainSpec) -> Result<Self, String> {
+        let mut spec = spec.clone();
+        if let Some(proposer_score_boost) = what_if.proposer_score_boost {
+            spec.proposer_score_boost = Some(proposer_score_boost);
+        }
+        let proposer_boost_root = if what_if.disable_proposer_boost {
+            Hash256::zero()
+        } else {
+            anchor.proposer_boost_root
+        };
+
+        Ok(Self {
+            proto_array: anchor.proto_array,
+            current_slot: anchor.current_slot,
+            justified_checkpoint: anchor.justified_checkpoint,
+            finalized_checkpoint: anchor.finalized_checkpoint,
+            justified_balances: justified_balances(anchor.justified_balances)?,
+            proposer_boost_root,
+            equivocating_indices: anchor.equivocating_indices,
+            what_if,
+            spec,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Apply `event` and run head selection.
+    pub fn apply(&mut self, event: &ForkChoiceEvent) -> Result<ReplayStep, String> {
+        let mut boost_decision = None;
+        let mut recorded_head = None;
+        let mut dropped = false;
+
+        match event {
+            ForkChoiceEvent::UpdateTime { current_slot } => {
+                if *current_slot > self.current_slot {
+                    self.current_slot = *current_slot;
+                    self.proposer_boost_root = Hash256::zero();
+                }
+            }
+            ForkChoiceEvent::OnBlock { block, timely } => {
+                let decision = self.boost_decision(block, *timely);
+                self.proto_array
+                    .process_block::<E>(block.clone(), self.current_slot)?;
+                if decision == BoostDecision::Boosted {
+                    self.proposer_boost_root = block.root;
+                }
+                boost_decision = Some(decision);
+            }
+            ForkChoiceEvent::OnAttestation {
+                validator_indices,
+                block_root,
+                target_epoch,
+                source,
+            } => {
+                let dropped_source = source
+                    .as_ref()
+                    .is_some_and(|source| self.what_if.dropped_sources.contains(source));
+                for validator_index in validator_indices {
+                    if dropped_source || self.what_if.dropped_validators.contains(validator_index) {
+                        dropped = true;
+                        continue;
+                    }
+                    self.proto_array
+                        .process_attestation(*validator_index as usize, *block_root, *target_epoch)
+                        .map_err(|e| format!("process_attestation failed: {e}"))?;
+                }
+            }
+            ForkChoiceEvent::OnAttesterSlashing { validator_indices } => {
+                self.equivocating_indices
+                    .extend(validator_indices.iter().copied());
+            }
+            ForkChoiceEvent::UpdateCheckpoints {
+                justified_checkpoint,
+                finalized_checkpoint,
+                justified_balances: balances,
+            } => {
+                self.justified_checkpoint = *justified_checkpoint;
+                self.finalized_checkpoint = *finalized_checkpoint;
+                self.justified_balances = justified_balances(balances.clone())?;
+            }
+            ForkChoiceEvent::OnValidExecutionPayload { block_root } => {
+                self.proto_array
+                    .process_execution_payload_validation(*block_root)?;
+            }
+            ForkChoiceEvent::OnInvalidExecutionPayload { op } => {
+                self.proto_array
+                    .process_execution_payload_invalidation::<E>(op)?;
+            }
+            ForkChoiceEvent::GetHead { head_root } => recorded_head = Some(*head_root),
+            ForkChoiceEvent::Snapshot { .. } => {
+                let anchor = ReplayAnchor::from_snapshot(event)?;
+                *self = Self::new(anchor, self.what_if.clone(), &self.spec)?;
+            }
+        }
+
+        let head_root = self.proto_array.find_head::<E>(
+            self.justified_checkpoint,
+            self.finalized_checkpoint,
+            &self.justified_balances,
+            self.proposer_boost_root,
+            &self.equivocating_indices,
+            self.current_slot,
+            &self.spec,
+        )?;
+
+        Ok(ReplayStep {
+            current_slot: self.current_slot,
+            head_root,
+            head_weight: self.proto_array.get_weight(&head_root).unwrap_or(0),
+            proposer_boost_root: self.proposer_boost_root,
+            boost_decision,
+            recorded_head,
+            dropped,
+            tips: self.tips(),
+        })
+    }
+
+    /// Decide whether `block` receives proposer boost, following `ForkChoice::on_block`.
+    fn boost_decision(&self, block: &ProtoBlock, timely: bool) -> BoostDecision {
+        if self.what_if.disable_proposer_boost || self.spec.proposer_score_boost.is_none() {
+            BoostDecision::Disabled
+        } else if block.slot != self.current_slot {
+            BoostDecision::NotCurrentSlot
+        } else if !timely {
+            BoostDecision::Late
+        } else if !self.proposer_boost_root.is_zero() {
+            BoostDecision::AlreadyBoosted(self.proposer_boost_root)
+        } else {
+            BoostDecision::Boosted
+        }
+    }
+
+    /// Return the blocks without children, heaviest first.
+    fn tips(&self) -> Vec<(Hash256, Slot, u64)> {
+        let nodes = &self.proto_array.core_proto_array().nodes;
+        let parents = nodes
+            .iter()
+            .filter_map(|node| node.parent)
+            .collect::<HashSet<_>>();
+        let mut tips = nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !parents.contains(index))
+            .map(|(_, node)| (node.root, node.slot, node.weight))
+            .collect::<Vec<_>>();
+        tips.sort_by(|a, b| b.2.cmp(&a.2).then(b.0.cmp(&a.0)));
+        tips
+    }
+}
+
+fn justified_balances(balances: Vec<u64>) -> Result<JustifiedBalances, String> {
+    JustifiedBalances::from_effective_balances(balances)
+        .map_err(|e| format!("Invalid justified balances: {e:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto_array::ExecutionStatus;
+    use types::{AttestationShufflingId, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    const BALANCE: u64 = 32_000_000_000;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    fn anchor() -> ReplayAnchor {
+        let checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: root(100),
+        };
+        let shuffling_id = AttestationShufflingId::from_components(Epoch::new(0), Hash256::zero());
+        let proto_array = ProtoArrayForkChoice::new::<E>(
+            Slot::new(0),
+            Slot::new(0),
+            Hash256::zero(),
+            checkpoint,
+            checkpoint,
+            shuffling_id.clone(),
+            shuffling_id,
+            ExecutionStatus::irrelevant(),
+        )
+        .unwrap();
+        ReplayAnchor {
+            proto_array,
+            current_slot: Slot::new(0),
+            justified_checkpoint: checkpoint,
+            finalized_checkpoint: checkpoint,
+            justified_balances: vec![BALANCE; 32],
+            proposer_boost_root: Hash256::zero(),
+            equivocating_indices: BTreeSet::new(),
+        }
+    }
+
+    fn on_block(root: Hash256, slot: u64, timely: bool) -> ForkChoiceEvent {
+        let checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: self::root(100),
+        };
+        let shuffling_id = AttestationShufflingId::from_components(Epoch::new(0), Hash256::zero());
+        ForkChoiceEvent::OnBlock {
+            block: ProtoBlock {
+                slot: Slot::new(slot),
+                root,
+                parent_root: Some(checkpoint.root),
+                state_root: Hash256::zero(),
+                target_root: checkpoint.root,
+                current_epoch_shuffling_id: shuffling_id.clone(),
+                next_epoch_shuffling_id: shuffling_id,
+                justified_checkpoint: checkpoint,
+                finalized_checkpoint: checkpoint,
+                execution_status: ExecutionStatus::irrelevant(),
+                unrealized_justified_checkpoint: Some(checkpoint),
+                unrealized_finalized_checkpoint: Some(checkpoint),
+            },
+            timely,
+        }
+    }
+
+    /// Competing blocks `root(2)`, which is late, and `root(1)`, which is timely, followed by a
+    /// vote from validator 0 for `root(2)`.
+    fn events() -> Vec<ForkChoiceEvent> {
+        vec![
+            ForkChoiceEvent::UpdateTime {
+                current_slot: Slot::new(1),
+            },
+            on_block(root(2), 1, false),
+            on_block(root(1), 1, true),
+            ForkChoiceEvent::OnAttestation {
+                validator_indices: vec![0],
+                block_root: root(2),
+                target_epoch: Epoch::new(0),
+                source: Some("peer_a".into()),
+            },
+        ]
+    }
+
+    fn replay(what_if: WhatIf) -> Vec<ReplayStep> {
+        let spec = E::default_spec();
+        let mut replayer = ForkChoiceReplayer::<E>::new(anchor(), what_if, &spec).unwrap();
+        events()
+            .iter()
+            .map(|event| replayer.apply(event).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn proposer_boost_and_votes() {
+        let steps = replay(WhatIf::default());
+        assert_eq!(steps[1].boost_decision, Some(BoostDecision::Late));
+        assert_eq!(steps[2].boost_decision, Some(BoostDecision::Boosted));
+        // The boost outweighs the later block root winning the tie.
+        assert_eq!(steps[2].head_root, root(1));
+        // A whole validator's vote outweighs the boost.
+        assert_eq!(steps[3].head_root, root(2));
+        assert_eq!(steps[3].tips.len(), 2);
+        assert_eq!(steps[3].tips[0].0, root(2));
+    }
+
+    #[test]
+    fn what_if_no_proposer_boost() {
+        let steps = replay(WhatIf {
+            disable_proposer_boost: true,
+            ..WhatIf::default()
+        });
+        assert_eq!(steps[2].boost_decision, Some(BoostDecision::Disabled));
+        assert_eq!(steps[2].head_root, root(2));
+    }
+
+    #[test]
+    fn what_if_dropped_attestations() {
+        for what_if in [
+            WhatIf {
+                dropped_validators: HashSet::from([0]),
+                ..WhatIf::default()
+            },
+            WhatIf {
+                dropped_sources: HashSet::from(["peer_a".to_string()]),
+                ..WhatIf::default()
+            },
+        ] {
+            let steps = replay(what_if);
+            assert!(steps[3].dropped);
+            assert_eq!(steps[3].head_root, root(1));
+        }
+    }
+
+    #[test]
+    fn snapshot_resets_replay() {
+        let spec = E::default_spec();
+        let anchor = anchor();
+        let snapshot = ForkChoiceEvent::Snapshot {
+            current_slot: anchor.current_slot,
+            justified_checkpoint: anchor.justified_checkpoint,
+            finalized_checkpoint: anchor.finalized_checkpoint,
+            justified_balances: anchor.justified_balances.clone(),
+            proposer_boost_root: anchor.proposer_boost_root,
+            equivocating_indices: vec![],
+            proto_array_bytes: anchor.proto_array.as_bytes(),
+        };
+        let mut replayer = ForkChoiceReplayer::<E>::new(anchor, WhatIf::default(), &spec).unwrap();
+        for event in events() {
+            replayer.apply(&event).unwrap();
+        }
+
+        let step = replayer.apply(&snapshot).unwrap();
+        assert_eq!(step.current_slot, Slot::new(0));
+        assert_eq!(step.head_root, root(100));
+        assert_eq!(step.tips.len(), 1);
+    }
+
+    #[test]
+    fn events_round_trip_json() {
+        for event in events() {
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ForkChoiceEvent>(&json).unwrap(),
+                event
+            );
+        }
+    }
+}
@@ -7,11 +7,13 @@ use beacon_chain::{
     BeaconChain, BeaconChainError, BeaconForkChoiceStore, ChainConfig, ForkChoiceError,
     StateSkipConfig, WhenSlotSkipped,
 };
+use fork_choice::journal::ForkChoiceJournal;
+use fork_choice::replay::{ForkChoiceEvent, ForkChoiceReplayer, ReplayAnchor, WhatIf};
 use fork_choice::{
     ForkChoiceStore, InvalidAttestation, InvalidBlock, PayloadVerificationStatus, QueuedAttestation,
 };
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use store::MemoryStore;
 use types::{
@@ -1276,3 +1278,51 @@ async fn progressive_balances_cache_proposer_slashing() {
         .apply_blocks(MainnetEthSpec::slots_per_epoch() as usize)
         .await;
 }
+
+/// Keeps journaled events in memory.
+#[derive(Default)]
+struct MemoryJournal(Mutex<Vec<ForkChoiceEvent>>);
+
+impl ForkChoiceJournal for MemoryJournal {
+    fn needs_snapshot(&self) -> bool {
+        self.0.lock().unwrap().is_empty()
+    }
+
+    fn record(&self, event: ForkChoiceEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+/// Replaying the journal must select the same heads as the node did.
+#[tokio::test]
+async fn journal_replays_head_selection() {
+    let journal = Arc::new(MemoryJournal::default());
+    let test = ForkChoiceTest::new();
+    test.harness
+        .chain
+        .canonical_head
+        .fork_choice_write_lock()
+        .set_journal(journal.clone());
+    let test = test
+        .apply_blocks_while(|_, state| state.finalized_checkpoint().epoch == 0)
+        .await
+        .unwrap();
+
+    let events = journal.0.lock().unwrap().clone();
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, ForkChoiceEvent::UpdateCheckpoints { .. })));
+
+    let anchor = ReplayAnchor::from_snapshot(&events[0]).unwrap();
+    let mut replayer =
+        ForkChoiceReplayer::<E>::new(anchor, WhatIf::default(), &test.harness.spec).unwrap();
+    let mut heads = 0;
+    for event in &events[1..] {
+        let step = replayer.apply(event).unwrap();
+        if let Some(recorded_head) = step.recorded_head {
+            assert_eq!(step.head_root, recorded_head);
+            heads += 1;
+        }
+    }
+    assert!(heads > 0);
+}
@@ -1,7 +1,11 @@
 use crate::attestation::AttestationBase;
+use crate::beacon_block_body::NUM_BEACON_BLOCK_BODY_HASH_TREE_ROOT_LEAVES;
+use crate::light_client_update::EXECUTION_PAYLOAD_INDEX;
+use crate::ssz_proof::{execution_payload_header_fields, field_index, ProofLevel};
 use crate::test_utils::TestRandom;
 use crate::*;
 use derivative::Derivative;
+use safe_arith::SafeArith;
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, DecodeError};
 use ssz_derive::{Decode, Encode};
@@ -273,6 +277,36 @@ impl<'a, E: EthSpec, Payload: AbstractExecPayload<E>> BeaconBlockRef<'a, E, Payl
     pub fn execution_payload(&self) -> Result<Payload::Ref<'a>, Error> {
         self.body().execution_payload()
     }
+
+    /// Compute a Merkle proof of the execution block hash against the block root.
+    ///
+    /// The proof is the same for full and blinded blocks, since an execution payload has the same
+    /// tree hash root as its header.
+    pub fn compute_execution_block_hash_proof(&self) -> Result<SszProof, Error> {
+        let header = self.block_header();
+        let block_fields = vec![
+            header.slot.tree_hash_root(),
+            header.proposer_index.tree_hash_root(),
+            header.parent_root,
+            header.state_root,
+            header.body_root,
+        ];
+        let body_fields = self.body().body_merkle_leaves();
+        let execution_payload_index =
+            EXECUTION_PAYLOAD_INDEX.safe_sub(NUM_BEACON_BLOCK_BODY_HASH_TREE_ROOT_LEAVES)?;
+
+        let payload_header = self.execution_payload()?.to_execution_payload_header();
+        let payload_fields = execution_payload_header_fields(payload_header.to_ref());
+        let block_hash_index = field_index(&payload_fields, "block_hash")?;
+        let payload_fields = payload_fields.into_iter().map(|(_, root)| root).collect();
+
+        SszProof::from_levels(&[
+            // The body is the last of the five fields of the block.
+            ProofLevel::container(block_fields, 4)?,
+            ProofLevel::container(body_fields, execution_payload_index)?,
+            ProofLevel::container(payload_fields, block_hash_index)?,
+        ])
+    }
 }
 
 impl<'a, E: EthSpec, Payload: AbstractExecPayload<E>> BeaconBlockRefMut<'a, E, Payload> {
@@ -988,6 +1022,33 @@ mod tests {
         });
     }
 
+    #[test]
+    fn execution_block_hash_proof() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+
+        let deneb_block = BeaconBlock::Deneb(BeaconBlockDeneb {
+            slot: Slot::random_for_test(rng),
+            proposer_index: u64::random_for_test(rng),
+            parent_root: Hash256::random_for_test(rng),
+            state_root: Hash256::random_for_test(rng),
+            body: BeaconBlockBodyDeneb::random_for_test(rng),
+        });
+        let electra_block = BeaconBlock::Electra(BeaconBlockElectra {
+            slot: Slot::random_for_test(rng),
+            proposer_index: u64::random_for_test(rng),
+            parent_root: Hash256::random_for_test(rng),
+            state_root: Hash256::random_for_test(rng),
+            body: BeaconBlockBodyElectra::random_for_test(rng),
+        });
+
+        for block in [deneb_block, electra_block] {
+            let proof = block.to_ref().compute_execution_block_hash_proof().unwrap();
+            let block_hash = block.body().execution_payload().unwrap().block_hash();
+            assert_eq!(proof.leaf, block_hash.tree_hash_root());
+            assert!(proof.verify(block.tree_hash_root()));
+        }
+    }
+
     #[test]
     fn decode_base_and_altair() {
         type E = MainnetEthSpec;
@@ -40,6 +40,7 @@ mod balance;
 mod exit_cache;
 mod iter;
 mod progressive_balances_cache;
+mod proofs;
 mod pubkey_cache;
 mod slashings_cache;
 mod tests;
@@ -156,6 +157,7 @@ pub enum Error {
     },
     TotalActiveBalanceDiffUninitialized,
     IndexNotSupported(usize),
+    InvalidMerkleProofPath(String),
     InvalidFlagIndex(usize),
     MerkleTreeError(merkle_proof::MerkleTreeError),
     PartialWithdrawalCountInvalid(usize),
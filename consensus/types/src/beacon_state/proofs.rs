@@ -0,0 +1,260 @@
+use crate::historical_summary::HistoricalSummary;
+use crate::ssz_proof::{
+    container_level, execution_payload_header_fields, field_index, last_sequence_level,
+    sequence_level, struct_field_names, ProofLevel,
+};
+use crate::*;
+use tree_hash::TreeHash;
+
+impl<E: EthSpec> BeaconState<E> {
+    /// Compute a Merkle proof of the node at `path` against the state root.
+    ///
+    /// The path is a `/`-separated list of field names and list indices, e.g. `balances/7`,
+    /// `latest_execution_payload_header/block_hash` or `historical_summaries/3/block_summary_root`.
+    /// Balances and other basic types are packed several to a chunk, so their proofs are of the
+    /// chunk containing the element.
+    ///
+    /// The tree hash cache must be up to date, see `update_tree_hash_cache`.
+    pub fn compute_merkle_proof_for_path(&self, path: &str) -> Result<SszProof, Error> {
+        let path = path
+            .split('/')
+            .filter(|elem| !elem.is_empty())
+            .collect::<Vec<_>>();
+        let (field, rest) = path
+            .split_first()
+            .ok_or_else(|| Error::InvalidMerkleProofPath(String::new()))?;
+
+        let fields = self
+            .field_names()
+            .iter()
+            .copied()
+            .zip(self.get_beacon_state_leaves())
+            .collect::<Vec<_>>();
+        let index = field_index(&fields, field)?;
+        let chunks = fields.into_iter().map(|(_, root)| root).collect();
+        let mut levels = vec![ProofLevel::container(chunks, index)?];
+
+        match *field {
+            "fork" => container_level(&mut levels, fork_fields(&self.fork()), rest)?,
+            "latest_block_header" => container_level(
+                &mut levels,
+                block_header_fields(self.latest_block_header()),
+                rest,
+            )?,
+            "block_roots" => {
+                last_sequence_level(
+                    &mut levels,
+                    &List::from(self.block_roots().clone()),
+                    false,
+                    rest,
+                )?;
+            }
+            "state_roots" => {
+                last_sequence_level(
+                    &mut levels,
+                    &List::from(self.state_roots().clone()),
+                    false,
+                    rest,
+                )?;
+            }
+            "historical_roots" => {
+                last_sequence_level(&mut levels, self.historical_roots(), true, rest)?;
+            }
+            "eth1_data" => container_level(&mut levels, eth1_data_fields(self.eth1_data()), rest)?,
+            "eth1_data_votes" => {
+                if let Some((eth1_data, rest)) =
+                    sequence_level(&mut levels, self.eth1_data_votes(), true, rest)?
+                {
+                    container_level(&mut levels, eth1_data_fields(eth1_data), rest)?;
+                }
+            }
+            "validators" => {
+                if let Some((validator, rest)) =
+                    sequence_level(&mut levels, self.validators(), true, rest)?
+                {
+                    container_level(&mut levels, validator_fields(validator), rest)?;
+                }
+            }
+            "balances" => {
+                last_sequence_level(&mut levels, self.balances(), true, rest)?;
+            }
+            "randao_mixes" => {
+                last_sequence_level(
+                    &mut levels,
+                    &List::from(self.randao_mixes().clone()),
+                    false,
+                    rest,
+                )?;
+            }
+            "slashings" => {
+                last_sequence_level(
+                    &mut levels,
+                    &List::from(self.slashings().clone()),
+                    false,
+                    rest,
+                )?;
+            }
+            "previous_epoch_participation" => {
+                last_sequence_level(
+                    &mut levels,
+                    self.previous_epoch_participation()?,
+                    true,
+                    rest,
+                )?;
+            }
+            "current_epoch_participation" => {
+                last_sequence_level(&mut levels, self.current_epoch_participation()?, true, rest)?;
+            }
+            "previous_justified_checkpoint" => container_level(
+                &mut levels,
+                checkpoint_fields(&self.previous_justified_checkpoint()),
+                rest,
+            )?,
+            "current_justified_checkpoint" => container_level(
+                &mut levels,
+                checkpoint_fields(&self.current_justified_checkpoint()),
+                rest,
+            )?,
+            "finalized_checkpoint" => container_level(
+                &mut levels,
+                checkpoint_fields(&self.finalized_checkpoint()),
+                rest,
+            )?,
+            "inactivity_scores" => {
+                last_sequence_level(&mut levels, self.inactivity_scores()?, true, rest)?;
+            }
+            "current_sync_committee" => container_level(
+                &mut levels,
+                sync_committee_fields(self.current_sync_committee()?),
+                rest,
+            )?,
+            "next_sync_committee" => container_level(
+                &mut levels,
+                sync_committee_fields(self.next_sync_committee()?),
+                rest,
+            )?,
+            "latest_execution_payload_header" => container_level(
+                &mut levels,
+                execution_payload_header_fields(self.latest_execution_payload_header()?),
+                rest,
+            )?,
+            "historical_summaries" => {
+                if let Some((historical_summary, rest)) =
+                    sequence_level(&mut levels, self.historical_summaries()?, true, rest)?
+                {
+                    container_level(
+                        &mut levels,
+                        historical_summary_fields(historical_summary),
+                        rest,
+                    )?;
+                }
+            }
+            // Proofs of the elements of the remaining fields are not supported, only of the
+            // fields themselves.
+            _ if rest.is_empty() => {}
+            _ => return Err(Error::InvalidMerkleProofPath(path.join("/"))),
+        }
+
+        SszProof::from_levels(&levels)
+    }
+
+    /// The names of the fields of this state, in SSZ order.
+    fn field_names(&self) -> &'static [&'static str] {
+        match self {
+            BeaconState::Base(_) => struct_field_names::<BeaconStateBase<E>>(),
+            BeaconState::Altair(_) => struct_field_names::<BeaconStateAltair<E>>(),
+            BeaconState::Bellatrix(_) => struct_field_names::<BeaconStateBellatrix<E>>(),
+            BeaconState::Capella(_) => struct_field_names::<BeaconStateCapella<E>>(),
+            BeaconState::Deneb(_) => struct_field_names::<BeaconStateDeneb<E>>(),
+            BeaconState::Electra(_) => struct_field_names::<BeaconStateElectra<E>>(),
+        }
+    }
+}
+
+fn fork_fields(fork: &Fork) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("previous_version", fork.previous_version.tree_hash_root()),
+        ("current_version", fork.current_version.tree_hash_root()),
+        ("epoch", fork.epoch.tree_hash_root()),
+    ]
+}
+
+fn block_header_fields(header: &BeaconBlockHeader) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("slot", header.slot.tree_hash_root()),
+        ("proposer_index", header.proposer_index.tree_hash_root()),
+        ("parent_root", header.parent_root.tree_hash_root()),
+        ("state_root", header.state_root.tree_hash_root()),
+        ("body_root", header.body_root.tree_hash_root()),
+    ]
+}
+
+fn eth1_data_fields(eth1_data: &Eth1Data) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("deposit_root", eth1_data.deposit_root.tree_hash_root()),
+        ("deposit_count", eth1_data.deposit_count.tree_hash_root()),
+        ("block_hash", eth1_data.block_hash.tree_hash_root()),
+    ]
+}
+
+fn validator_fields(validator: &Validator) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("pubkey", validator.pubkey.tree_hash_root()),
+        (
+            "withdrawal_credentials",
+            validator.withdrawal_credentials.tree_hash_root(),
+        ),
+        (
+            "effective_balance",
+            validator.effective_balance.tree_hash_root(),
+        ),
+        ("slashed", validator.slashed.tree_hash_root()),
+        (
+            "activation_eligibility_epoch",
+            validator.activation_eligibility_epoch.tree_hash_root(),
+        ),
+        (
+            "activation_epoch",
+            validator.activation_epoch.tree_hash_root(),
+        ),
+        ("exit_epoch", validator.exit_epoch.tree_hash_root()),
+        (
+            "withdrawable_epoch",
+            validator.withdrawable_epoch.tree_hash_root(),
+        ),
+    ]
+}
+
+fn checkpoint_fields(checkpoint: &Checkpoint) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("epoch", checkpoint.epoch.tree_hash_root()),
+        ("root", checkpoint.root.tree_hash_root()),
+    ]
+}
+
+fn sync_committee_fields<E: EthSpec>(
+    sync_committee: &SyncCommittee<E>,
+) -> Vec<(&'static str, Hash256)> {
+    vec![
+        ("pubkeys", sync_committee.pubkeys.tree_hash_root()),
+        (
+            "aggregate_pubkey",
+            sync_committee.aggregate_pubkey.tree_hash_root(),
+        ),
+    ]
+}
+
+fn historical_summary_fields(
+    historical_summary: &HistoricalSummary,
+) -> Vec<(&'static str, Hash256)> {
+    vec![
+        (
+            "block_summary_root",
+            historical_summary.block_summary_root().tree_hash_root(),
+        ),
+        (
+            "state_summary_root",
+            historical_summary.state_summary_root().tree_hash_root(),
+        ),
+    ]
+}
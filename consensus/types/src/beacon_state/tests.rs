@@ -344,6 +344,144 @@ mod get_outstanding_deposit_len {
     }
 }
 
+mod compute_merkle_proof_for_path {
+    use super::*;
+    use beacon_chain::types::light_client_update::{
+        FINALIZED_ROOT_INDEX, FINALIZED_ROOT_INDEX_ELECTRA, NEXT_SYNC_COMMITTEE_INDEX,
+        NEXT_SYNC_COMMITTEE_INDEX_ELECTRA,
+    };
+    use beacon_chain::types::{ForkName, HistoricalSummary, SszProof};
+    use std::sync::Arc;
+    use tree_hash::TreeHash;
+
+    type E = MinimalEthSpec;
+
+    async fn state_at_fork(fork_name: ForkName) -> BeaconState<E> {
+        let spec = Arc::new(fork_name.make_genesis_spec(E::default_spec()));
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec)
+            .keypairs(KEYPAIRS[0..16].to_vec())
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        let mut state = harness.chain.head_beacon_state_cloned();
+
+        // Make sure the lists we prove elements of are non-empty.
+        let eth1_data = state.eth1_data().clone();
+        state.eth1_data_votes_mut().push(eth1_data).unwrap();
+        if fork_name.capella_enabled() {
+            let historical_summary = HistoricalSummary::new(&state);
+            state
+                .historical_summaries_mut()
+                .unwrap()
+                .push(historical_summary)
+                .unwrap();
+        }
+        state
+    }
+
+    fn verified_proof(state: &mut BeaconState<E>, path: &str) -> SszProof {
+        let state_root = state.update_tree_hash_cache().unwrap();
+        let proof = state.compute_merkle_proof_for_path(path).unwrap();
+        assert!(proof.verify(state_root), "invalid proof for {path}");
+        proof
+    }
+
+    #[tokio::test]
+    async fn proofs_are_valid() {
+        for fork_name in ForkName::list_all() {
+            let mut state = state_at_fork(fork_name).await;
+
+            let mut paths = vec![
+                "genesis_time",
+                "slot",
+                "fork/current_version",
+                "latest_block_header/body_root",
+                "block_roots/5",
+                "eth1_data_votes/0/block_hash",
+                "validators/3/effective_balance",
+                "validators/15",
+                "balances/5",
+                "randao_mixes/1",
+                "slashings/2",
+                "justification_bits",
+                "finalized_checkpoint/root",
+            ];
+            if fork_name.altair_enabled() {
+                paths.extend([
+                    "current_epoch_participation/9",
+                    "inactivity_scores/0",
+                    "next_sync_committee/aggregate_pubkey",
+                ]);
+            }
+            if fork_name.bellatrix_enabled() {
+                paths.push("latest_execution_payload_header/block_hash");
+            }
+            if fork_name.capella_enabled() {
+                paths.push("historical_summaries/0/state_summary_root");
+            }
+            if fork_name.electra_enabled() {
+                paths.push("pending_consolidations");
+            }
+
+            for path in paths {
+                verified_proof(&mut state, path);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn proofs_match_light_client_indices() {
+        let mut state = state_at_fork(ForkName::Deneb).await;
+        let proof = verified_proof(&mut state, "finalized_checkpoint/root");
+        assert_eq!(proof.generalized_index, FINALIZED_ROOT_INDEX as u64);
+        assert_eq!(proof.leaf, state.finalized_checkpoint().root);
+        let proof = verified_proof(&mut state, "next_sync_committee");
+        assert_eq!(proof.generalized_index, NEXT_SYNC_COMMITTEE_INDEX as u64);
+
+        let mut state = state_at_fork(ForkName::Electra).await;
+        let proof = verified_proof(&mut state, "finalized_checkpoint/root");
+        assert_eq!(proof.generalized_index, FINALIZED_ROOT_INDEX_ELECTRA as u64);
+        let proof = verified_proof(&mut state, "next_sync_committee");
+        assert_eq!(
+            proof.generalized_index,
+            NEXT_SYNC_COMMITTEE_INDEX_ELECTRA as u64
+        );
+        assert_eq!(
+            proof.leaf,
+            state.next_sync_committee().unwrap().tree_hash_root()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_paths() {
+        let mut state = state_at_fork(ForkName::Deneb).await;
+        state.update_tree_hash_cache().unwrap();
+
+        for path in [
+            "",
+            "unknown",
+            "slot/0",
+            "balances/16",
+            "balances/one",
+            "block_roots/3/x",
+            "state_roots/0/0",
+            "randao_mixes/1/x",
+            "validators/0/unknown",
+            "finalized_checkpoint/root/0",
+            "pending_consolidations",
+        ] {
+            assert!(
+                matches!(
+                    state.compute_merkle_proof_for_path(path),
+                    Err(BeaconStateError::InvalidMerkleProofPath(_))
+                ),
+                "{path} should be invalid"
+            );
+        }
+    }
+}
+
 #[test]
 fn decode_base_and_altair() {
     type E = MainnetEthSpec;
@@ -117,6 +117,7 @@ pub struct ChainSpec {
     pub proposer_score_boost: Option<u64>,
     pub reorg_head_weight_threshold: Option<u64>,
     pub reorg_parent_weight_threshold: Option<u64>,
+    pub reorg_max_epochs_since_finalization: u64,
 
     /*
      * Eth1
@@ -703,6 +704,7 @@ impl ChainSpec {
             proposer_score_boost: Some(40),
             reorg_head_weight_threshold: Some(20),
             reorg_parent_weight_threshold: Some(160),
+            reorg_max_epochs_since_finalization: 2,
 
             /*
              * Eth1
@@ -1017,6 +1019,7 @@ impl ChainSpec {
             proposer_score_boost: Some(40),
             reorg_head_weight_threshold: Some(20),
             reorg_parent_weight_threshold: Some(160),
+            reorg_max_epochs_since_finalization: 2,
 
             /*
              * Eth1
@@ -1277,6 +1280,13 @@ pub struct Config {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     proposer_score_boost: Option<MaybeQuoted<u64>>,
+    #[serde(default = "default_reorg_head_weight_threshold")]
+    reorg_head_weight_threshold: Option<MaybeQuoted<u64>>,
+    #[serde(default = "default_reorg_parent_weight_threshold")]
+    reorg_parent_weight_threshold: Option<MaybeQuoted<u64>>,
+    #[serde(default = "default_reorg_max_epochs_since_finalization")]
+    #[serde(with = "serde_utils::quoted_u64")]
+    reorg_max_epochs_since_finalization: u64,
 
     #[serde(with = "serde_utils::quoted_u64")]
     deposit_chain_id: u64,
@@ -1407,6 +1417,18 @@ const fn default_max_per_epoch_activation_churn_limit() -> u64 {
     8
 }
 
+fn default_reorg_head_weight_threshold() -> Option<MaybeQuoted<u64>> {
+    Some(MaybeQuoted { value: 20 })
+}
+
+fn default_reorg_parent_weight_threshold() -> Option<MaybeQuoted<u64>> {
+    Some(MaybeQuoted { value: 160 })
+}
+
+const fn default_reorg_max_epochs_since_finalization() -> u64 {
+    2
+}
+
 const fn default_gossip_max_size() -> u64 {
     10485760
 }
@@ -1654,6 +1676,13 @@ impl Config {
             max_per_epoch_activation_churn_limit: spec.max_per_epoch_activation_churn_limit,
 
             proposer_score_boost: spec.proposer_score_boost.map(|value| MaybeQuoted { value }),
+            reorg_head_weight_threshold: spec
+                .reorg_head_weight_threshold
+                .map(|value| MaybeQuoted { value }),
+            reorg_parent_weight_threshold: spec
+                .reorg_parent_weight_threshold
+                .map(|value| MaybeQuoted { value }),
+            reorg_max_epochs_since_finalization: spec.reorg_max_epochs_since_finalization,
 
             deposit_chain_id: spec.deposit_chain_id,
             deposit_network_id: spec.deposit_network_id,
@@ -1730,6 +1759,9 @@ impl Config {
             max_per_epoch_activation_churn_limit,
             churn_limit_quotient,
             proposer_score_boost,
+            reorg_head_weight_threshold,
+            reorg_parent_weight_threshold,
+            reorg_max_epochs_since_finalization,
             deposit_chain_id,
             deposit_network_id,
             deposit_contract_address,
@@ -1791,6 +1823,9 @@ impl Config {
             max_per_epoch_activation_churn_limit,
             churn_limit_quotient,
             proposer_score_boost: proposer_score_boost.map(|q| q.value),
+            reorg_head_weight_threshold: reorg_head_weight_threshold.map(|q| q.value),
+            reorg_parent_weight_threshold: reorg_parent_weight_threshold.map(|q| q.value),
+            reorg_max_epochs_since_finalization,
             deposit_chain_id,
             deposit_network_id,
             deposit_contract_address,
@@ -2102,10 +2137,35 @@ mod yaml_tests {
         check_default!(message_domain_invalid_snappy);
         check_default!(message_domain_valid_snappy);
         check_default!(attestation_subnet_prefix_bits);
+        check_default!(reorg_head_weight_threshold);
+        check_default!(reorg_parent_weight_threshold);
+        check_default!(reorg_max_epochs_since_finalization);
 
         assert_eq!(chain_spec.bellatrix_fork_epoch, None);
     }
 
+    #[test]
+    fn reorg_parameters() {
+        let spec = ChainSpec::mainnet();
+        let mut yaml = serde_yaml::to_value(Config::from_chain_spec::<MainnetEthSpec>(&spec))
+            .expect("should serialize config");
+        let mapping = yaml.as_mapping_mut().expect("config should be a mapping");
+        mapping.insert(
+            "REORG_HEAD_WEIGHT_THRESHOLD".into(),
+            serde_yaml::Value::Null,
+        );
+        mapping.insert("REORG_PARENT_WEIGHT_THRESHOLD".into(), "120".into());
+        mapping.insert("REORG_MAX_EPOCHS_SINCE_FINALIZATION".into(), "4".into());
+
+        let new_spec = serde_yaml::from_value::<Config>(yaml)
+            .expect("should deserialize config")
+            .apply_to_chain_spec::<MainnetEthSpec>(&spec)
+            .expect("should apply config");
+        assert_eq!(new_spec.reorg_head_weight_threshold, None);
+        assert_eq!(new_spec.reorg_parent_weight_threshold, Some(120));
+        assert_eq!(new_spec.reorg_max_epochs_since_finalization, 4);
+    }
+
     #[test]
     fn test_total_terminal_difficulty() {
         assert_eq!(
@@ -2122,4 +2182,4 @@ mod yaml_tests {
             [0, 0, 0, 1]
         );
     }
-}
\ No newline at end of file
+}
@@ -107,6 +107,7 @@ pub mod blob_sidecar;
 pub mod data_column_sidecar;
 pub mod data_column_subnet_id;
 pub mod light_client_header;
+pub mod ssz_proof;
 pub mod non_zero_usize;
 pub mod runtime_var_list;
 
@@ -237,6 +238,7 @@ pub use crate::signed_contribution_and_proof::SignedContributionAndProof;
 pub use crate::signed_voluntary_exit::SignedVoluntaryExit;
 pub use crate::signing_data::{SignedRoot, SigningData};
 pub use crate::slot_epoch::{Epoch, Slot};
+pub use crate::ssz_proof::SszProof;
 pub use crate::subnet_id::SubnetId;
 pub use crate::sync_aggregate::SyncAggregate;
 pub use crate::sync_aggregator_selection_data::SyncAggregatorSelectionData;
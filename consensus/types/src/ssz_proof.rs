@@ -0,0 +1,347 @@
+//! Merkle proofs for arbitrary generalized indices in the SSZ tree of a container.
+//!
+//! A proof is built one level at a time: each level is the tree of a single container, list or
+//! vector, together with the position of the chunk containing the next level (or the proven
+//! leaf). The branch is the concatenation of the branches of each level from the bottom up, and
+//! the generalized index is the concatenation of the generalized indices of each level.
+//!
+//! The branches of lists and vectors are read from the hashes cached in their trees, so that
+//! proofs into large lists like the validators are cheap.
+//!
+//! https://github.com/ethereum/consensus-specs/blob/dev/ssz/merkle-proofs.md
+use crate::{
+    BeaconStateError as Error, EthSpec, ExecutionPayloadHeaderRef, FixedBytesExtended, Hash256,
+    List, Unsigned,
+};
+use merkle_proof::{verify_merkle_proof, MerkleTree};
+use milhouse::level_iter::LevelNode;
+use milhouse::{Tree, Value};
+use safe_arith::SafeArith;
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tree_hash::{TreeHash, TreeHashType};
+
+/// A proof that `leaf` is the chunk at `generalized_index` in a tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SszProof {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub generalized_index: u64,
+    pub leaf: Hash256,
+    /// Sibling nodes from the leaf up to the root.
+    pub branch: Vec<Hash256>,
+}
+
+impl SszProof {
+    /// Build the proof for the innermost level of `levels`, which are ordered from the root
+    /// down.
+    pub(crate) fn from_levels(levels: &[ProofLevel]) -> Result<Self, Error> {
+        let leaf = levels
+            .last()
+            .map(|level| level.leaf)
+            .ok_or_else(|| Error::InvalidMerkleProofPath(String::new()))?;
+
+        let mut generalized_index = 1u64;
+        for level in levels {
+            generalized_index =
+                concat_generalized_indices(generalized_index, level.generalized_index()?)?;
+        }
+
+        let mut branch = vec![];
+        for level in levels.iter().rev() {
+            branch.extend_from_slice(&level.branch);
+            if let Some(length) = level.length {
+                branch.push(Hash256::from_low_u64_le(length as u64));
+            }
+        }
+
+        Ok(Self {
+            generalized_index,
+            leaf,
+            branch,
+        })
+    }
+
+    /// Returns `true` if the proof is valid for a tree with the given `root`.
+    pub fn verify(&self, root: Hash256) -> bool {
+        let Some(depth) = self.generalized_index.checked_ilog2() else {
+            return false;
+        };
+        let Ok(index) = 1u64
+            .safe_shl(depth)
+            .and_then(|first_index| self.generalized_index.safe_sub(first_index))
+        else {
+            return false;
+        };
+        verify_merkle_proof(
+            self.leaf,
+            &self.branch,
+            depth as usize,
+            index as usize,
+            root,
+        )
+    }
+}
+
+/// The tree of a single container, list or vector, and the position of one of its chunks.
+pub(crate) struct ProofLevel {
+    /// The chunk at `index`.
+    leaf: Hash256,
+    /// Sibling nodes from the chunk up to the root of the tree, without the length of a list.
+    branch: Vec<Hash256>,
+    depth: usize,
+    index: usize,
+    /// The length mixed into the root of a list, `None` for containers and vectors.
+    length: Option<usize>,
+}
+
+impl ProofLevel {
+    /// A level for the field at `index` of a container whose field roots are `chunks`.
+    pub(crate) fn container(chunks: Vec<Hash256>, index: usize) -> Result<Self, Error> {
+        let leaf = *chunks.get(index).ok_or(Error::IndexNotSupported(index))?;
+        let depth = chunks.len().next_power_of_two().ilog2() as usize;
+        let (_, branch) = MerkleTree::create(&chunks, depth).generate_proof(index, depth)?;
+        Ok(Self {
+            leaf,
+            branch,
+            depth,
+            index,
+            length: None,
+        })
+    }
+
+    /// The generalized index of the chunk, relative to the root of this level.
+    fn generalized_index(&self) -> Result<u64, Error> {
+        let mut generalized_index = 1u64
+            .safe_shl(self.depth as u32)?
+            .safe_add(self.index as u64)?;
+        if self.length.is_some() {
+            // The chunks of a list are the left subtree of its root.
+            generalized_index = concat_generalized_indices(2, generalized_index)?;
+        }
+        Ok(generalized_index)
+    }
+}
+
+/// Returns the generalized index of `child`, a generalized index relative to the node at
+/// `parent`.
+fn concat_generalized_indices(parent: u64, child: u64) -> Result<u64, Error> {
+    let child_depth = child
+        .checked_ilog2()
+        .ok_or(Error::IndexNotSupported(child as usize))?;
+    let child_index = child.safe_sub(1u64.safe_shl(child_depth)?)?;
+    Ok(parent.safe_shl(child_depth)?.safe_add(child_index)?)
+}
+
+/// Add the level for the element of a list or vector at the index given by the next element of
+/// `path`. Vectors are passed as a `List` of the same length, with `is_list` set to `false`.
+///
+/// Returns the element and the remainder of the path if the element is a composite type which
+/// can be descended into. Basic types are packed into chunks, so the proof ends at the chunk
+/// containing the element.
+///
+/// The tree hash cache of `list` must be up to date.
+pub(crate) fn sequence_level<'a, 'p, T: Value + Send + Sync, N: Unsigned>(
+    levels: &mut Vec<ProofLevel>,
+    list: &'a List<T, N>,
+    is_list: bool,
+    path: &'p [&'p str],
+) -> Result<Option<(&'a T, &'p [&'p str])>, Error> {
+    let Some((elem, rest)) = path.split_first() else {
+        return Ok(None);
+    };
+    let index = elem
+        .parse::<usize>()
+        .map_err(|_| Error::InvalidMerkleProofPath(elem.to_string()))?;
+    let is_basic = matches!(T::tree_hash_type(), TreeHashType::Basic);
+    let item = list
+        .get(index)
+        .filter(|_| !is_basic || rest.is_empty())
+        .ok_or_else(|| Error::InvalidMerkleProofPath(path.join("/")))?;
+
+    let packing_factor = if is_basic {
+        T::tree_hash_packing_factor()
+    } else {
+        1
+    };
+    let depth = N::to_usize()
+        .div_ceil(packing_factor)
+        .next_power_of_two()
+        .ilog2() as usize;
+    let chunk_index = index.safe_div(packing_factor)?;
+    let (leaf, branch) = tree_branch(list, depth, chunk_index)?;
+    levels.push(ProofLevel {
+        leaf,
+        branch,
+        depth,
+        index: chunk_index,
+        length: is_list.then_some(list.len()),
+    });
+
+    Ok((!is_basic).then_some((item, rest)))
+}
+
+/// Add the level for the element of a list or vector at the index given by `path`, which must
+/// not descend any further.
+pub(crate) fn last_sequence_level<T: Value + Send + Sync, N: Unsigned>(
+    levels: &mut Vec<ProofLevel>,
+    list: &List<T, N>,
+    is_list: bool,
+    path: &[&str],
+) -> Result<(), Error> {
+    match sequence_level(levels, list, is_list, path)? {
+        Some((_, rest)) if !rest.is_empty() => Err(Error::InvalidMerkleProofPath(path.join("/"))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the chunk at `chunk_index` of the tree of `list` with the given `depth`, and its
+/// branch from the bottom up, using the hashes cached in the tree.
+fn tree_branch<T: Value + Send + Sync, N: Unsigned>(
+    list: &List<T, N>,
+    depth: usize,
+    chunk_index: usize,
+) -> Result<(Hash256, Vec<Hash256>), Error> {
+    // The first node on the level of index 0 is the root.
+    let Some(LevelNode::Internal(mut node)) = list.level_iter_from(0)?.next() else {
+        return Err(Error::TreeHashCacheInconsistent);
+    };
+    let mut branch = Vec::with_capacity(depth);
+    for level in (0..depth).rev() {
+        let Tree::Node { left, right, .. } = node.as_ref() else {
+            return Err(Error::TreeHashCacheInconsistent);
+        };
+        if chunk_index.safe_shr(level as u32)? & 1 == 0 {
+            branch.push(right.tree_hash());
+            node = left;
+        } else {
+            branch.push(left.tree_hash());
+            node = right;
+        }
+    }
+    branch.reverse();
+    Ok((node.tree_hash(), branch))
+}
+
+/// Add the level for the field of a container named by the next element of `path`, which must
+/// be the last element of the path.
+pub(crate) fn container_level(
+    levels: &mut Vec<ProofLevel>,
+    fields: Vec<(&'static str, Hash256)>,
+    path: &[&str],
+) -> Result<(), Error> {
+    match path {
+        [] => Ok(()),
+        [name] => {
+            let index = field_index(&fields, name)?;
+            let chunks = fields.into_iter().map(|(_, root)| root).collect();
+            levels.push(ProofLevel::container(chunks, index)?);
+            Ok(())
+        }
+        _ => Err(Error::InvalidMerkleProofPath(path.join("/"))),
+    }
+}
+
+/// Returns the position of the field called `name`.
+pub(crate) fn field_index(fields: &[(&'static str, Hash256)], name: &str) -> Result<usize, Error> {
+    fields
+        .iter()
+        .position(|(field, _)| *field == name)
+        .ok_or_else(|| Error::InvalidMerkleProofPath(name.to_string()))
+}
+
+/// Returns the names of the fields of the struct `T` in declaration order, as listed by its
+/// derived `Deserialize` implementation.
+///
+/// Fields skipped by serde are not listed, so this is only suitable for structs whose serde
+/// fields are exactly their SSZ fields.
+pub(crate) fn struct_field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    match T::deserialize(FieldNamesDeserializer) {
+        Err(FieldNames(fields)) => fields,
+        Ok(_) => &[],
+    }
+}
+
+/// A deserializer which fails as soon as it is asked for a struct, returning its field names.
+struct FieldNamesDeserializer;
+
+#[derive(Debug)]
+struct FieldNames(&'static [&'static str]);
+
+impl fmt::Display for FieldNames {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "fields {:?}", self.0)
+    }
+}
+
+impl std::error::Error for FieldNames {}
+
+impl de::Error for FieldNames {
+    fn custom<T: fmt::Display>(_: T) -> Self {
+        FieldNames(&[])
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldNamesDeserializer {
+    type Error = FieldNames;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(FieldNames(&[]))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(FieldNames(fields))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// The names and roots of the fields of an execution payload header.
+///
+/// The roots of the fields of an `ExecutionPayload` are the same as those of its header, except
+/// for the transactions and withdrawals, which the header replaces with their roots.
+pub(crate) fn execution_payload_header_fields<E: EthSpec>(
+    header: ExecutionPayloadHeaderRef<E>,
+) -> Vec<(&'static str, Hash256)> {
+    let mut fields = vec![
+        ("parent_hash", header.parent_hash().tree_hash_root()),
+        ("fee_recipient", header.fee_recipient().tree_hash_root()),
+        ("state_root", header.state_root().tree_hash_root()),
+        ("receipts_root", header.receipts_root().tree_hash_root()),
+        ("logs_bloom", header.logs_bloom().tree_hash_root()),
+        ("prev_randao", header.prev_randao().tree_hash_root()),
+        ("block_number", header.block_number().tree_hash_root()),
+        ("gas_limit", header.gas_limit().tree_hash_root()),
+        ("gas_used", header.gas_used().tree_hash_root()),
+        ("timestamp", header.timestamp().tree_hash_root()),
+        ("extra_data", header.extra_data().tree_hash_root()),
+        (
+            "base_fee_per_gas",
+            header.base_fee_per_gas().tree_hash_root(),
+        ),
+        ("block_hash", header.block_hash().tree_hash_root()),
+        (
+            "transactions_root",
+            header.transactions_root().tree_hash_root(),
+        ),
+    ];
+    if let Ok(withdrawals_root) = header.withdrawals_root() {
+        fields.push(("withdrawals_root", withdrawals_root.tree_hash_root()));
+    }
+    if let Ok(blob_gas_used) = header.blob_gas_used() {
+        fields.push(("blob_gas_used", blob_gas_used.tree_hash_root()));
+    }
+    if let Ok(excess_blob_gas) = header.excess_blob_gas() {
+        fields.push(("excess_blob_gas", excess_blob_gas.tree_hash_root()));
+    }
+    fields
+}
@@ -0,0 +1,272 @@
+//! Encryption at rest for Lighthouse databases.
+//!
+//! Values are encrypted with AES-256-GCM under a key derived from a 32-byte master key, which is
+//! read from a file or an environment variable. Each encrypted value is laid out as:
+//!
+//! ```text
+//! nonce (12 bytes) || ciphertext || tag (16 bytes)
+//! ```
+//!
+//! Callers pass the location of the value (e.g. its database key) as associated data, so that an
+//! encrypted value can't be moved to a different location without detection.
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+/// Length of the master key in bytes.
+pub const KEY_LEN: usize = 32;
+/// Number of bytes added to each value by encryption.
+pub const OVERHEAD_LEN: usize = NONCE_LEN + 16;
+
+const ENCRYPTION_KEY_LABEL: &[u8] = b"lighthouse-db-encryption-key";
+const NONCE_KEY_LABEL: &[u8] = b"lighthouse-db-nonce-key";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The key file could not be read.
+    UnableToReadKeyFile(PathBuf, std::io::Error),
+    /// The environment variable holding the key is not set.
+    MissingKeyEnvVar(String),
+    /// The key is not 32 hex-encoded bytes.
+    InvalidKey(String),
+    /// Unable to generate a random nonce.
+    RandomnessUnavailable,
+    /// Encryption failed.
+    EncryptionFailed,
+    /// The value was modified, moved, or encrypted under a different key.
+    DecryptionFailed,
+}
+
+/// Where to load the master key from.
+///
+/// The key is 32 bytes, hex-encoded with an optional `0x` prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeySource {
+    /// Read the key from a file.
+    File(PathBuf),
+    /// Read the key from an environment variable with the given name.
+    Env(String),
+}
+
+impl KeySource {
+    /// Load the key and construct a cipher from it.
+    pub fn load_cipher(&self) -> Result<DbCipher, Error> {
+        let hex_key = match self {
+            Self::File(path) => Zeroizing::new(
+                std::fs::read_to_string(path)
+                    .map_err(|e| Error::UnableToReadKeyFile(path.clone(), e))?,
+            ),
+            Self::Env(name) => Zeroizing::new(
+                std::env::var(name).map_err(|_| Error::MissingKeyEnvVar(name.clone()))?,
+            ),
+        };
+        let hex_key = hex_key.trim();
+        let hex_key = hex_key.strip_prefix("0x").unwrap_or(hex_key);
+        let key = Zeroizing::new(
+            hex::decode(hex_key).map_err(|e| Error::InvalidKey(format!("invalid hex: {e}")))?,
+        );
+        let key: &[u8; KEY_LEN] = key.as_slice().try_into().map_err(|_| {
+            Error::InvalidKey(format!("expected {KEY_LEN} bytes, got {}", key.len()))
+        })?;
+        DbCipher::new(key)
+    }
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(name) => write!(f, "environment variable {name}"),
+        }
+    }
+}
+
+/// An AEAD cipher for database values.
+pub struct DbCipher {
+    key: LessSafeKey,
+    /// Key for deriving nonces for deterministic encryption.
+    nonce_key: hmac::Key,
+    rng: SystemRandom,
+}
+
+impl DbCipher {
+    /// Create a cipher from the master key.
+    ///
+    /// Separate keys for encryption and nonce derivation are derived from the master key.
+    pub fn new(master_key: &[u8; KEY_LEN]) -> Result<Self, Error> {
+        let master_key = hmac::Key::new(hmac::HMAC_SHA256, master_key);
+        let encryption_key = hmac::sign(&master_key, ENCRYPTION_KEY_LABEL);
+        let nonce_key = hmac::sign(&master_key, NONCE_KEY_LABEL);
+
+        let key = UnboundKey::new(&AES_256_GCM, encryption_key.as_ref())
+            .map_err(|_| Error::InvalidKey("unable to construct AES key".into()))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            nonce_key: hmac::Key::new(hmac::HMAC_SHA256, nonce_key.as_ref()),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Encrypt `plaintext` bound to `aad`, using a random nonce.
+    pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| Error::RandomnessUnavailable)?;
+        self.seal(nonce, aad, plaintext)
+    }
+
+    /// Encrypt `plaintext` bound to `aad`, using a nonce derived from both.
+    ///
+    /// Equal plaintexts encrypt to equal ciphertexts, which allows encrypted values to be looked
+    /// up by equality. Only use this for values which must be looked up, as it reveals which
+    /// values are equal.
+    pub fn encrypt_deterministic(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut context = hmac::Context::with_key(&self.nonce_key);
+        context.update(&(aad.len() as u64).to_le_bytes());
+        context.update(aad);
+        context.update(plaintext);
+        let tag = context.sign();
+
+        let mut nonce = [0; NONCE_LEN];
+        nonce.copy_from_slice(&tag.as_ref()[..NONCE_LEN]);
+        self.seal(nonce, aad, plaintext)
+    }
+
+    fn seal(&self, nonce: [u8; NONCE_LEN], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut in_out = Vec::with_capacity(plaintext.len() + OVERHEAD_LEN);
+        in_out.extend_from_slice(&nonce);
+        in_out.extend_from_slice(plaintext);
+
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut in_out[NONCE_LEN..],
+            )
+            .map_err(|_| Error::EncryptionFailed)?;
+        in_out.extend_from_slice(tag.as_ref());
+        Ok(in_out)
+    }
+
+    /// Decrypt a value produced by `encrypt` or `encrypt_deterministic` with the same `aad`.
+    pub fn decrypt(&self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        if ciphertext.len() < OVERHEAD_LEN {
+            return Err(Error::DecryptionFailed);
+        }
+        let (nonce, sealed) = ciphertext.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| Error::DecryptionFailed)?;
+
+        let mut in_out = sealed.to_vec();
+        let plaintext_len = self
+            .key
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| Error::DecryptionFailed)?
+            .len();
+        in_out.truncate(plaintext_len);
+        Ok(in_out)
+    }
+}
+
+impl fmt::Debug for DbCipher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DbCipher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn cipher(byte: u8) -> DbCipher {
+        DbCipher::new(&[byte; KEY_LEN]).unwrap()
+    }
+
+    #[test]
+    fn roundtrip() {
+        let cipher = cipher(1);
+        for plaintext in [&b""[..], b"a", &[0xab; 1024]] {
+            let ciphertext = cipher.encrypt(b"aad", plaintext).unwrap();
+            assert_eq!(ciphertext.len(), plaintext.len() + OVERHEAD_LEN);
+            assert_eq!(cipher.decrypt(b"aad", &ciphertext).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn random_nonces() {
+        let cipher = cipher(1);
+        assert_ne!(
+            cipher.encrypt(b"aad", b"value").unwrap(),
+            cipher.encrypt(b"aad", b"value").unwrap()
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let cipher = cipher(1);
+        let ciphertext = cipher.encrypt_deterministic(b"aad", b"value").unwrap();
+        assert_eq!(
+            ciphertext,
+            cipher.encrypt_deterministic(b"aad", b"value").unwrap()
+        );
+        assert_ne!(
+            ciphertext,
+            cipher.encrypt_deterministic(b"other", b"value").unwrap()
+        );
+        assert_ne!(
+            ciphertext,
+            cipher.encrypt_deterministic(b"aad", b"other").unwrap()
+        );
+        assert_eq!(cipher.decrypt(b"aad", &ciphertext).unwrap(), b"value");
+    }
+
+    #[test]
+    fn tampering_detected() {
+        let cipher = cipher(1);
+        let mut ciphertext = cipher.encrypt(b"aad", b"value").unwrap();
+
+        assert!(cipher.decrypt(b"other", &ciphertext).is_err());
+        assert!(self::cipher(2).decrypt(b"aad", &ciphertext).is_err());
+        assert!(cipher
+            .decrypt(b"aad", &ciphertext[..OVERHEAD_LEN - 1])
+            .is_err());
+
+        *ciphertext.last_mut().unwrap() ^= 1;
+        assert!(cipher.decrypt(b"aad", &ciphertext).is_err());
+    }
+
+    #[test]
+    fn load_key_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "0x{}", hex::encode([7; KEY_LEN])).unwrap();
+        let loaded = KeySource::File(file.path().into()).load_cipher().unwrap();
+
+        let ciphertext = cipher(7).encrypt(b"aad", b"value").unwrap();
+        assert_eq!(loaded.decrypt(b"aad", &ciphertext).unwrap(), b"value");
+    }
+
+    #[test]
+    fn load_key_from_env() {
+        let name = "LIGHTHOUSE_DB_ENCRYPTION_TEST_KEY";
+        std::env::set_var(name, hex::encode([7; KEY_LEN]));
+        assert!(KeySource::Env(name.into()).load_cipher().is_ok());
+
+        std::env::set_var(name, hex::encode([7; KEY_LEN - 1]));
+        assert!(matches!(
+            KeySource::Env(name.into()).load_cipher(),
+            Err(Error::InvalidKey(_))
+        ));
+
+        std::env::remove_var(name);
+        assert!(matches!(
+            KeySource::Env(name.into()).load_cipher(),
+            Err(Error::MissingKeyEnvVar(_))
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use clap_utils::FLAG_HEADER;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use store::hdiff::HierarchyConfig;
+use store::retention::RetentionPolicy;
 
 use crate::InspectTarget;
 
@@ -57,6 +58,25 @@ pub struct DatabaseManager {
     )]
     pub blobs_dir: Option<PathBuf>,
 
+    #[clap(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "File containing the hex-encoded key of an encrypted database.",
+        conflicts_with = "db_encryption_key_env",
+        display_order = 0
+    )]
+    pub db_encryption_key_file: Option<PathBuf>,
+
+    #[clap(
+        long,
+        global = true,
+        value_name = "VAR",
+        help = "Environment variable containing the hex-encoded key of an encrypted database.",
+        display_order = 0
+    )]
+    pub db_encryption_key_env: Option<String>,
+
     #[clap(
         long,
         global = true,
@@ -80,7 +100,13 @@ pub enum DatabaseManagerSubcommand {
     PrunePayloads(PrunePayloads),
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
+    Prune(Prune),
     Compact(Compact),
+    MigrateHierarchy(MigrateHierarchy),
+    ExportEra(ExportEra),
+    ImportEra(ImportEra),
+    DumpOpPool(DumpOpPool),
+    RestoreOpPool(RestoreOpPool),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -101,10 +127,11 @@ pub struct Inspect {
     #[clap(
         long,
         value_name = "TAG",
-        help = "3-byte column ID (see `DBColumn`)",
+        help = "3-byte column ID (see `DBColumn`). If omitted with `--output sizes` or \
+                `--output total`, show the number of keys and bytes in every column",
         display_order = 0
     )]
-    pub column: String,
+    pub column: Option<String>,
 
     #[clap(
         long,
@@ -192,6 +219,55 @@ pub struct PruneStates {
     pub confirm: bool,
 }
 
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Prune finalized blobs, data columns, payloads and historic states according to \
+                retention policies. Policies which aren't set default to the beacon node's \
+                defaults."
+)]
+pub struct Prune {
+    #[clap(
+        long,
+        value_name = "POLICY",
+        help = "Retention policy for blobs: keep_all, keep_last_n_epochs:N or \
+                keep_finalized_checkpoints_only.",
+        display_order = 0
+    )]
+    pub blob_retention: Option<RetentionPolicy>,
+
+    #[clap(
+        long,
+        value_name = "POLICY",
+        help = "Retention policy for data columns. Defaults to the blob retention policy.",
+        display_order = 0
+    )]
+    pub data_column_retention: Option<RetentionPolicy>,
+
+    #[clap(
+        long,
+        value_name = "POLICY",
+        help = "Retention policy for execution payloads.",
+        display_order = 0
+    )]
+    pub payload_retention: Option<RetentionPolicy>,
+
+    #[clap(
+        long,
+        value_name = "POLICY",
+        help = "Retention policy for historic states. States are only pruned with --confirm.",
+        display_order = 0
+    )]
+    pub historic_state_retention: Option<RetentionPolicy>,
+
+    #[clap(
+        long,
+        help = "Commit to pruning historic states irreversibly.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub confirm: bool,
+}
+
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
 #[clap(about = "Compact database manually.")]
 pub struct Compact {
@@ -229,3 +305,105 @@ pub struct Compact {
     )]
     pub output_dir: Option<PathBuf>,
 }
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Re-encode the freezer database with different hierarchy exponents.",
+    alias = "migrate_hierarchy"
+)]
+pub struct MigrateHierarchy {
+    #[clap(
+        long,
+        value_name = "N0,N1,N2,...",
+        help = "The hierarchy exponents to re-encode the freezer database with. The beacon node \
+                must be started with the same value of --hierarchy-exponents afterwards.",
+        display_order = 0
+    )]
+    pub to: HierarchyConfig,
+
+    #[clap(
+        long,
+        value_name = "N",
+        default_value_t = 256,
+        help = "Maximum number of diffs to write to the freezer database in a single batch.",
+        display_order = 0
+    )]
+    pub batch_size: usize,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Export finalized blocks and states from the freezer database to era files.",
+    alias = "export_era"
+)]
+pub struct ExportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory to write the era files to.",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "N",
+        help = "First era to export. Defaults to 0.",
+        display_order = 0
+    )]
+    pub start_era: Option<u64>,
+
+    #[clap(
+        long,
+        value_name = "N",
+        help = "Last era to export. Defaults to the last era in the freezer database.",
+        display_order = 0
+    )]
+    pub end_era: Option<u64>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Import blocks from era files below the oldest block in the database.",
+    alias = "import_era"
+)]
+pub struct ImportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory containing the era files to import.",
+        display_order = 0
+    )]
+    pub input_dir: PathBuf,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Write the persisted operation pool to a file.",
+    alias = "dump_op_pool"
+)]
+pub struct DumpOpPool {
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File to write the SSZ-encoded operation pool to.",
+        display_order = 0
+    )]
+    pub output: PathBuf,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Replace the persisted operation pool with one read from a file. Operations which \
+        can't be decoded are discarded.",
+    alias = "restore_op_pool"
+)]
+pub struct RestoreOpPool {
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File containing an operation pool written by `dump-op-pool`.",
+        display_order = 0
+    )]
+    pub input: PathBuf,
+}
@@ -0,0 +1,389 @@
+//! Export and import of finalized history as era files.
+//!
+//! See [`store::era`] for the file format.
+//!
+//! Importing works like backfill sync: blocks are added below the oldest block in the database,
+//! and must hash-link to the `oldest_block_parent` of the anchor. Historic states are not written
+//! by the import, they are regenerated from the blocks by state reconstruction.
+use crate::cli::{ExportEra, ImportEra};
+use beacon_node::ClientConfig;
+use environment::RuntimeContext;
+use slog::{info, warn, Logger};
+use ssz::Encode;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use store::era::{
+    era_file_name, era_root, find_era_files, read_era_file, E2Writer, E2_COMPRESSED_BEACON_STATE,
+    E2_COMPRESSED_SIGNED_BEACON_BLOCK, E2_VERSION,
+};
+use store::{
+    get_key_for_col, retention::RetentionPolicy, AnchorInfo, DBColumn, HotColdDB, KeyValueStore,
+    KeyValueStoreOp, LevelDB,
+};
+use types::{BeaconState, EthSpec, SignedBeaconBlock, Slot};
+
+pub struct ExportEraConfig {
+    output_dir: PathBuf,
+    start_era: Option<u64>,
+    end_era: Option<u64>,
+}
+
+pub fn parse_export_era_config(export_era_config: &ExportEra) -> Result<ExportEraConfig, String> {
+    let output_dir = export_era_config.output_dir.clone();
+    let start_era = export_era_config.start_era;
+    let end_era = export_era_config.end_era;
+    if let (Some(start_era), Some(end_era)) = (start_era, end_era) {
+        if start_era > end_era {
+            return Err(format!(
+                "Start era {start_era} is greater than end era {end_era}"
+            ));
+        }
+    }
+    Ok(ExportEraConfig {
+        output_dir,
+        start_era,
+        end_era,
+    })
+}
+
+pub struct ImportEraConfig {
+    input_dir: PathBuf,
+}
+
+pub fn parse_import_era_config(import_era_config: &ImportEra) -> Result<ImportEraConfig, String> {
+    let input_dir = import_era_config.input_dir.clone();
+    Ok(ImportEraConfig { input_dir })
+}
+
+fn open_db<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>, String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log,
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))
+}
+
+pub fn export_era<E: EthSpec>(
+    client_config: ClientConfig,
+    export_era_config: ExportEraConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let db = open_db(client_config, runtime_context, log.clone())?;
+
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let split_slot = db.get_split_slot();
+    let (state_lower_limit, state_upper_limit) = db.get_historic_state_limits();
+    let oldest_block_slot = db.get_oldest_block_slot();
+
+    // The state of each era must already be in the freezer database.
+    let last_era = split_slot.as_u64().saturating_sub(1) / slots_per_era;
+    let start_era = export_era_config.start_era.unwrap_or(0);
+    let end_era = export_era_config.end_era.unwrap_or(last_era);
+    if end_era > last_era {
+        return Err(format!(
+            "Era {end_era} is not finalized yet, the last exportable era is {last_era}"
+        ));
+    }
+
+    fs::create_dir_all(&export_era_config.output_dir)
+        .map_err(|e| format!("Unable to create output directory: {e:?}"))?;
+
+    let mut num_exported = 0;
+    for era in start_era..=end_era {
+        let state_slot = Slot::new(era * slots_per_era);
+        let state_available = state_slot <= state_lower_limit || state_slot >= state_upper_limit;
+        let blocks_available =
+            era == 0 || state_slot.saturating_sub(slots_per_era) >= oldest_block_slot;
+        if !state_available || !blocks_available {
+            warn!(
+                log,
+                "Skipping unavailable era";
+                "era" => era,
+                "state_available" => state_available,
+                "blocks_available" => blocks_available,
+            );
+            continue;
+        }
+
+        let path = write_era_file(&db, era, &export_era_config.output_dir)?;
+        info!(log, "Exported era"; "era" => era, "file" => ?path);
+        num_exported += 1;
+    }
+
+    if num_exported == 0 {
+        return Err("Error: no era in the requested range could be exported".into());
+    }
+
+    info!(log, "Era export complete"; "num_exported" => num_exported);
+    Ok(())
+}
+
+pub fn import_era<E: EthSpec>(
+    client_config: ClientConfig,
+    import_era_config: ImportEraConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let db = open_db(client_config, runtime_context, log.clone())?;
+
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let era_files = find_era_files(&import_era_config.input_dir)?;
+
+    // Blocks are imported backwards from the oldest block in the database, so the newest era must
+    // be processed first.
+    for (era, path) in era_files.into_iter().rev() {
+        let anchor = db.get_anchor_info();
+        if anchor.oldest_block_slot == 0 {
+            break;
+        }
+
+        let state_slot = Slot::new(era * slots_per_era);
+        if era == 0 || state_slot.saturating_sub(slots_per_era) >= anchor.oldest_block_slot {
+            continue;
+        }
+
+        let (blocks, state) = read_era_file::<E>(&path, spec)?;
+        if state.slot() != state_slot {
+            return Err(format!(
+                "Error: state in {path:?} has slot {} but era {era} requires slot {state_slot}",
+                state.slot()
+            ));
+        }
+
+        // The era must reach the parent of the oldest block. Slots between the end of the era and
+        // the oldest block are skipped slots, so the last block root of the era is the parent.
+        let link_slot = std::cmp::min(anchor.oldest_block_slot, state_slot) - 1;
+        let link_root = *state
+            .get_block_root(link_slot)
+            .map_err(|e| format!("Unable to read block root at slot {link_slot}: {e:?}"))?;
+        if link_root != anchor.oldest_block_parent {
+            return Err(if state_slot < anchor.oldest_block_slot {
+                format!(
+                    "Error: missing era files between era {era} and slot {}",
+                    anchor.oldest_block_slot
+                )
+            } else {
+                format!(
+                    "Error: era {era} does not link to the oldest block parent {:?}",
+                    anchor.oldest_block_parent
+                )
+            });
+        }
+
+        let num_blocks = import_era_blocks(&db, anchor, blocks, &state)?;
+        info!(
+            log,
+            "Imported era";
+            "era" => era,
+            "num_blocks" => num_blocks,
+            "oldest_block_slot" => db.get_oldest_block_slot(),
+        );
+    }
+
+    let oldest_block_slot = db.get_oldest_block_slot();
+    if oldest_block_slot == 0 {
+        info!(
+            log,
+            "Block history complete";
+            "info" => "restart the beacon node with --reconstruct-historic-states to regenerate states"
+        );
+    } else {
+        info!(log, "Era import complete"; "oldest_block_slot" => oldest_block_slot);
+    }
+    Ok(())
+}
+
+/// Write era `era` to a new file in `output_dir`, returning its path.
+fn write_era_file<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    era: u64,
+    output_dir: &Path,
+) -> Result<PathBuf, String> {
+    let spec = db.get_chain_spec();
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let state_slot = Slot::new(era * slots_per_era);
+    let start_slot = state_slot.saturating_sub(slots_per_era);
+
+    let state = db
+        .load_cold_state_by_slot(state_slot)
+        .map_err(|e| format!("Unable to load state at slot {state_slot}: {e:?}"))?;
+
+    let file_name = era_file_name(spec.config_name.as_deref(), era, era_root(&state, era)?);
+    let path = output_dir.join(&file_name);
+    let tmp_path = output_dir.join(format!("{file_name}.tmp"));
+
+    let file =
+        File::create(&tmp_path).map_err(|e| format!("Unable to create {tmp_path:?}: {e:?}"))?;
+    let mut writer = E2Writer::new(BufWriter::new(file));
+    let io_err = |e: io::Error| format!("Unable to write {tmp_path:?}: {e:?}");
+
+    writer.write_record(E2_VERSION, &[]).map_err(io_err)?;
+
+    let mut block_offsets = vec![];
+    if era > 0 {
+        let mut prev_block_root = None;
+        for slot in (start_slot.as_u64()..state_slot.as_u64()).map(Slot::new) {
+            let block_root = *state
+                .get_block_root(slot)
+                .map_err(|e| format!("Unable to read block root at slot {slot}: {e:?}"))?;
+
+            // Skipped slots repeat the root of the previous block.
+            if prev_block_root == Some(block_root) {
+                block_offsets.push(None);
+                continue;
+            }
+            prev_block_root = Some(block_root);
+
+            let block = db
+                .get_full_block(&block_root)
+                .map_err(|e| format!("Unable to load block {block_root:?}: {e:?}"))?
+                .ok_or_else(|| format!("Missing block {block_root:?}"))?;
+
+            // The first slot of the window may be a skipped slot pointing at a block from the
+            // previous era.
+            if block.slot() != slot {
+                block_offsets.push(None);
+                continue;
+            }
+
+            let offset = writer
+                .write_compressed(E2_COMPRESSED_SIGNED_BEACON_BLOCK, &block.as_ssz_bytes())
+                .map_err(io_err)?;
+            block_offsets.push(Some(offset));
+        }
+    }
+
+    let state_offset = writer
+        .write_compressed(E2_COMPRESSED_BEACON_STATE, &state.as_ssz_bytes())
+        .map_err(io_err)?;
+
+    if era > 0 {
+        writer
+            .write_slot_index(start_slot, &block_offsets)
+            .map_err(io_err)?;
+    }
+    writer
+        .write_slot_index(state_slot, &[Some(state_offset)])
+        .map_err(io_err)?;
+
+    let file = writer
+        .into_inner()
+        .into_inner()
+        .map_err(|e| io_err(e.into_error()))?;
+    file.sync_all().map_err(io_err)?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Unable to rename {tmp_path:?}: {e:?}"))?;
+
+    Ok(path)
+}
+
+/// Store the blocks of one era below the oldest block in the database.
+///
+/// Blocks must hash-link to the `oldest_block_parent` of `anchor` and match the block roots of the
+/// era `state`. Blocks at or above the anchor's `oldest_block_slot` are ignored.
+///
+/// Return the number of blocks imported.
+fn import_era_blocks<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+    anchor: AnchorInfo,
+    blocks: Vec<SignedBeaconBlock<E>>,
+    state: &BeaconState<E>,
+) -> Result<usize, String> {
+    let mut expected_block_root = anchor.oldest_block_parent;
+    let mut prev_block_slot = anchor.oldest_block_slot;
+    let mut hot_batch = Vec::with_capacity(blocks.len());
+    let mut cold_batch = Vec::with_capacity(blocks.len());
+    let mut num_blocks = 0;
+
+    for block in blocks.into_iter().rev() {
+        if block.slot() >= prev_block_slot {
+            continue;
+        }
+
+        let block_root = block.canonical_root();
+        let state_block_root = state
+            .get_block_root(block.slot())
+            .map_err(|e| format!("Block at slot {} is outside era: {e:?}", block.slot()))?;
+        if block_root != *state_block_root {
+            return Err(format!(
+                "Error: block {block_root:?} at slot {} does not match era state",
+                block.slot()
+            ));
+        }
+        if block_root != expected_block_root {
+            return Err(format!(
+                "Error: block {block_root:?} at slot {} does not match expected parent \
+                 {expected_block_root:?}",
+                block.slot()
+            ));
+        }
+
+        // Store block roots, including at all skip slots in the freezer DB.
+        for slot in (block.slot().as_u64()..prev_block_slot.as_u64()).rev() {
+            cold_batch.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(DBColumn::BeaconBlockRoots.into(), &slot.to_be_bytes()),
+                block_root.as_slice().to_vec(),
+            ));
+        }
+
+        prev_block_slot = block.slot();
+        expected_block_root = block.message().parent_root();
+
+        // Imported blocks are older than any retention window, so only keep their payloads if
+        // payloads are never pruned.
+        if db.get_config().effective_payload_retention() != RetentionPolicy::KeepAll {
+            db.blinded_block_as_kv_store_ops(
+                &block_root,
+                &block.clone_as_blinded(),
+                &mut hot_batch,
+            );
+        } else {
+            db.block_as_kv_store_ops(&block_root, block, &mut hot_batch)
+                .map_err(|e| format!("Unable to store block {block_root:?}: {e:?}"))?;
+        }
+        num_blocks += 1;
+    }
+
+    if num_blocks == 0 {
+        return Ok(0);
+    }
+
+    // Write the blocks before the roots that point at them, and the anchor last.
+    db.hot_db
+        .do_atomically(hot_batch)
+        .map_err(|e| format!("Unable to write blocks: {e:?}"))?;
+    db.cold_db
+        .do_atomically(cold_batch)
+        .map_err(|e| format!("Unable to write block roots: {e:?}"))?;
+
+    let new_anchor = AnchorInfo {
+        oldest_block_slot: prev_block_slot,
+        oldest_block_parent: expected_block_root,
+        ..anchor.clone()
+    };
+    let anchor_op = db
+        .compare_and_set_anchor_info(anchor, new_anchor)
+        .map_err(|e| format!("Unable to update anchor: {e:?}"))?;
+    db.hot_db
+        .do_atomically(vec![anchor_op])
+        .map_err(|e| format!("Unable to write anchor: {e:?}"))?;
+
+    Ok(num_blocks)
+}
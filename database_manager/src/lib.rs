@@ -1,6 +1,10 @@
 pub mod cli;
+mod era;
+mod op_pool;
 use crate::cli::DatabaseManager;
 use crate::cli::Migrate;
+use crate::cli::MigrateHierarchy;
+use crate::cli::Prune;
 use crate::cli::PruneStates;
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, schema_change::migrate_schema,
@@ -10,16 +14,23 @@ use beacon_node::{get_data_dir, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
 use cli::{Compact, Inspect};
+use db_encryption::{DbCipher, KeySource};
 use environment::{Environment, RuntimeContext};
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
 use store::{
+    column_stats::compute_column_stats,
+    config::OnDiskStoreConfig,
     errors::Error,
-    metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    hdiff::HierarchyConfig,
+    hierarchy_migration::{swap_reencoded_freezer_states, HierarchyMigration},
+    metadata::{SchemaVersion, CONFIG_KEY, CURRENT_SCHEMA_VERSION, HIERARCHY_MIGRATION_KEY},
+    retention::RetentionPolicy,
+    DBColumn, HotColdDB, ItemStore, KeyValueStore, LevelDB,
 };
 use strum::{EnumString, EnumVariantNames};
 use types::{BeaconState, EthSpec, Slot};
@@ -40,6 +51,16 @@ fn parse_client_config<E: EthSpec>(
         .clone_from(&database_manager_config.blobs_dir);
     client_config.store.blob_prune_margin_epochs = database_manager_config.blob_prune_margin_epochs;
     client_config.store.hierarchy_config = database_manager_config.hierarchy_exponents.clone();
+    client_config.store.encryption_key_source = database_manager_config
+        .db_encryption_key_file
+        .clone()
+        .map(KeySource::File)
+        .or_else(|| {
+            database_manager_config
+                .db_encryption_key_env
+                .clone()
+                .map(KeySource::Env)
+        });
 
     Ok(client_config)
 }
@@ -100,7 +121,7 @@ pub enum InspectTarget {
 }
 
 pub struct InspectConfig {
-    column: DBColumn,
+    column: Option<DBColumn>,
     target: InspectTarget,
     skip: Option<usize>,
     limit: Option<usize>,
@@ -111,11 +132,21 @@ pub struct InspectConfig {
 }
 
 fn parse_inspect_config(inspect_config: &Inspect) -> Result<InspectConfig, String> {
-    let column: DBColumn = inspect_config
+    let column: Option<DBColumn> = inspect_config
         .column
-        .parse()
+        .as_ref()
+        .map(|column| column.parse())
+        .transpose()
         .map_err(|e| format!("Unable to parse column flag: {e:?}"))?;
     let target: InspectTarget = inspect_config.output.clone();
+    if column.is_none()
+        && !matches!(
+            target,
+            InspectTarget::ValueSizes | InspectTarget::ValueTotal
+        )
+    {
+        return Err("The --column flag is required for this output".into());
+    }
     let skip = inspect_config.skip;
     let limit = inspect_config.limit;
     let freezer = inspect_config.freezer;
@@ -141,15 +172,38 @@ pub fn inspect_db<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
     let blobs_path = client_config.get_blobs_db_path();
 
+    let cipher = client_config
+        .store
+        .load_cipher()
+        .map_err(|e| format!("Unable to load database encryption key: {e:?}"))?;
+
+    let Some(column) = inspect_config.column else {
+        let dbs = if inspect_config.freezer {
+            vec![("freezer_db", cold_path)]
+        } else if inspect_config.blobs_db {
+            vec![("blobs_db", blobs_path)]
+        } else {
+            vec![
+                ("hot_db", hot_path),
+                ("freezer_db", cold_path),
+                ("blobs_db", blobs_path),
+            ]
+        };
+        return inspect_column_stats::<E>(inspect_config.target, &dbs, cipher);
+    };
+
     let mut total = 0;
     let mut num_keys = 0;
 
     let sub_db = if inspect_config.freezer {
-        LevelDB::<E>::open(&cold_path).map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
+        LevelDB::<E>::open_with_cipher(&cold_path, cipher)
+            .map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
     } else if inspect_config.blobs_db {
-        LevelDB::<E>::open(&blobs_path).map_err(|e| format!("Unable to open blobs DB: {e:?}"))?
+        LevelDB::<E>::open_with_cipher(&blobs_path, cipher)
+            .map_err(|e| format!("Unable to open blobs DB: {e:?}"))?
     } else {
-        LevelDB::<E>::open(&hot_path).map_err(|e| format!("Unable to open hot DB: {e:?}"))?
+        LevelDB::<E>::open_with_cipher(&hot_path, cipher)
+            .map_err(|e| format!("Unable to open hot DB: {e:?}"))?
     };
 
     let skip = inspect_config.skip.unwrap_or(0);
@@ -165,11 +219,7 @@ pub fn inspect_db<E: EthSpec>(
             .map_err(|e| format!("Unable to create import directory: {:?}", e))?;
     }
 
-    for res in sub_db
-        .iter_column::<Vec<u8>>(inspect_config.column)
-        .skip(skip)
-        .take(limit)
-    {
+    for res in sub_db.iter_column::<Vec<u8>>(column).skip(skip).take(limit) {
         let (key, value) = res.map_err(|e| format!("{:?}", e))?;
 
         match inspect_config.target {
@@ -195,11 +245,8 @@ pub fn inspect_db<E: EthSpec>(
             }
             InspectTarget::ValueTotal => (),
             InspectTarget::Values => {
-                let file_path = base_path.join(format!(
-                    "{}_{}.ssz",
-                    inspect_config.column.as_str(),
-                    hex::encode(&key)
-                ));
+                let file_path =
+                    base_path.join(format!("{}_{}.ssz", column.as_str(), hex::encode(&key)));
 
                 let write_result = fs::OpenOptions::new()
                     .create(true)
@@ -232,6 +279,38 @@ pub fn inspect_db<E: EthSpec>(
     Ok(())
 }
 
+/// Print the number of keys and bytes in each column of the databases at `dbs`.
+fn inspect_column_stats<E: EthSpec>(
+    target: InspectTarget,
+    dbs: &[(&str, PathBuf)],
+    cipher: Option<Arc<DbCipher>>,
+) -> Result<(), String> {
+    let mut total = 0;
+    let mut num_keys = 0;
+
+    for (db_name, path) in dbs {
+        let db = LevelDB::<E>::open_with_cipher(path, cipher.clone())
+            .map_err(|e| format!("Unable to open {db_name}: {e:?}"))?;
+        let all_stats = compute_column_stats(&db, 1).map_err(|e| format!("{:?}", e))?;
+
+        for (column, stats) in all_stats {
+            if target == InspectTarget::ValueSizes {
+                println!(
+                    "{db_name} {column}: {} keys, {} bytes",
+                    stats.num_keys, stats.total_bytes
+                );
+            }
+            total += stats.total_bytes;
+            num_keys += stats.num_keys;
+        }
+    }
+
+    println!("Num keys: {}", num_keys);
+    println!("Total: {} bytes", total);
+
+    Ok(())
+}
+
 pub struct CompactConfig {
     column: DBColumn,
     freezer: bool,
@@ -261,13 +340,20 @@ pub fn compact_db<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
     let blobs_path = client_config.get_blobs_db_path();
     let column = compact_config.column;
+    let cipher = client_config.store.load_cipher()?;
 
     let (sub_db, db_name) = if compact_config.freezer {
-        (LevelDB::<E>::open(&cold_path)?, "freezer_db")
+        (
+            LevelDB::<E>::open_with_cipher(&cold_path, cipher)?,
+            "freezer_db",
+        )
     } else if compact_config.blobs_db {
-        (LevelDB::<E>::open(&blobs_path)?, "blobs_db")
+        (
+            LevelDB::<E>::open_with_cipher(&blobs_path, cipher)?,
+            "blobs_db",
+        )
     } else {
-        (LevelDB::<E>::open(&hot_path)?, "hot_db")
+        (LevelDB::<E>::open_with_cipher(&hot_path, cipher)?, "hot_db")
     };
     info!(
         log,
@@ -461,6 +547,197 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+pub struct PruneConfig {
+    blob_retention: Option<RetentionPolicy>,
+    data_column_retention: Option<RetentionPolicy>,
+    payload_retention: Option<RetentionPolicy>,
+    historic_state_retention: Option<RetentionPolicy>,
+    confirm: bool,
+}
+
+fn parse_prune_config(prune_config: &Prune) -> Result<PruneConfig, String> {
+    Ok(PruneConfig {
+        blob_retention: prune_config.blob_retention,
+        data_column_retention: prune_config.data_column_retention,
+        payload_retention: prune_config.payload_retention,
+        historic_state_retention: prune_config.historic_state_retention,
+        confirm: prune_config.confirm,
+    })
+}
+
+/// Enforce the retention policies on the database, as the background migrator would.
+pub fn prune<E: EthSpec>(
+    mut client_config: ClientConfig,
+    prune_config: PruneConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    client_config.store.blob_retention = prune_config.blob_retention;
+    client_config.store.data_column_retention = prune_config.data_column_retention;
+    client_config.store.payload_retention = prune_config.payload_retention;
+    client_config.store.historic_state_retention = prune_config.historic_state_retention;
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let config = db.get_config();
+    info!(
+        log,
+        "Pruning database";
+        "blob_retention" => %config.effective_blob_retention(),
+        "data_column_retention" => %config.effective_data_column_retention(),
+        "payload_retention" => %config.effective_payload_retention(),
+    );
+
+    db.try_prune_most_blobs(false)
+        .map_err(|e| format!("Failed to prune blobs: {e:?}"))?;
+    db.try_prune_execution_payloads(false)
+        .map_err(|e| format!("Failed to prune payloads: {e:?}"))?;
+
+    match prune_config.historic_state_retention {
+        None | Some(RetentionPolicy::KeepAll) => {}
+        Some(retention) if !prune_config.confirm => {
+            warn!(
+                log,
+                "Pruning historic states is irreversible";
+                "historic_state_retention" => %retention,
+            );
+            warn!(
+                log,
+                "Re-run this command with --confirm to commit to state deletion"
+            );
+        }
+        Some(_) => {
+            db.try_prune_historic_states(true)
+                .map_err(|e| format!("Failed to prune historic states: {e:?}"))?;
+            db.compact_freezer()
+                .map_err(|e| format!("Failed to compact freezer database: {e:?}"))?;
+        }
+    }
+
+    info!(log, "Database pruning complete");
+    Ok(())
+}
+
+pub struct MigrateHierarchyConfig {
+    to: HierarchyConfig,
+    batch_size: usize,
+}
+
+fn parse_migrate_hierarchy_config(
+    migrate_hierarchy_config: &MigrateHierarchy,
+) -> Result<MigrateHierarchyConfig, String> {
+    let to = migrate_hierarchy_config.to.clone();
+    to.validate()
+        .map_err(|e| format!("Invalid hierarchy exponents: {e:?}"))?;
+    let batch_size = migrate_hierarchy_config.batch_size;
+    if batch_size == 0 {
+        return Err("Batch size must be at least 1".into());
+    }
+    Ok(MigrateHierarchyConfig { to, batch_size })
+}
+
+pub fn migrate_hierarchy<E: EthSpec>(
+    client_config: ClientConfig,
+    migrate_hierarchy_config: MigrateHierarchyConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+    let to = migrate_hierarchy_config.to;
+    let batch_size = migrate_hierarchy_config.batch_size;
+    let cipher = client_config
+        .store
+        .load_cipher()
+        .map_err(|e| format!("Unable to load database encryption key: {e:?}"))?;
+
+    // Read the current config and any interrupted migration directly from the hot DB, as the
+    // `HotColdDB` must be opened with the hierarchy that the freezer is currently encoded with.
+    let (on_disk_config, in_progress) = {
+        let hot_db = LevelDB::<E>::open_with_cipher(&hot_path, cipher.clone())
+            .map_err(|e| format!("Unable to open hot DB: {e:?}"))?;
+        let on_disk_config = hot_db
+            .get::<OnDiskStoreConfig>(&CONFIG_KEY)
+            .map_err(|e| format!("Unable to read on-disk config: {e:?}"))?;
+        let in_progress = hot_db
+            .get::<HierarchyMigration>(&HIERARCHY_MIGRATION_KEY)
+            .map_err(|e| format!("Unable to read hierarchy migration: {e:?}"))?;
+        (on_disk_config, in_progress)
+    };
+
+    let from = match (&in_progress, &on_disk_config) {
+        (Some(migration), _) if migration.to != to => {
+            return Err(format!(
+                "Error: a migration to hierarchy {} is already in progress, re-run with --to {}",
+                migration.to, migration.to
+            ));
+        }
+        (Some(migration), _) => migration.from.clone(),
+        (None, Some(config)) => config
+            .hierarchy_config()
+            .map_err(|_| "Error: database schema must be upgraded before changing hierarchy")?
+            .clone(),
+        (None, None) => return Err("Error: database is not initialized".into()),
+    };
+
+    if in_progress.is_none() && from == to {
+        info!(log, "Hierarchy exponents unchanged"; "hierarchy_exponents" => %to);
+        return Ok(());
+    }
+
+    if !in_progress
+        .as_ref()
+        .is_some_and(|migration| migration.swapping)
+    {
+        let mut store_config = client_config.store;
+        store_config.hierarchy_config = from;
+
+        let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &hot_path,
+            &cold_path,
+            &blobs_path,
+            |_, _, _| Ok(()),
+            store_config,
+            spec.clone(),
+            log.clone(),
+        )
+        .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+        db.reencode_freezer_states(&to, batch_size)
+            .map_err(|e| format!("Failed to re-encode freezer states: {e:?}"))?;
+    }
+
+    let hot_db = LevelDB::<E>::open_with_cipher(&hot_path, cipher.clone())
+        .map_err(|e| format!("Unable to open hot DB: {e:?}"))?;
+    let cold_db = LevelDB::<E>::open_with_cipher(&cold_path, cipher)
+        .map_err(|e| format!("Unable to open freezer DB: {e:?}"))?;
+    swap_reencoded_freezer_states(&hot_db, &cold_db, batch_size, &log)
+        .map_err(|e| format!("Failed to move re-encoded states into place: {e:?}"))?;
+
+    info!(
+        log,
+        "Freezer database re-encoded";
+        "info" => "restart the beacon node with the new --hierarchy-exponents"
+    );
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -518,9 +795,35 @@ pub fn run<E: EthSpec>(
             let genesis_state = get_genesis_state()?;
             prune_states(client_config, prune_config, genesis_state, &context, log)
         }
+        cli::DatabaseManagerSubcommand::Prune(prune_config) => {
+            let prune_config = parse_prune_config(prune_config)?;
+            prune(client_config, prune_config, &context, log)
+        }
         cli::DatabaseManagerSubcommand::Compact(compact_config) => {
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::MigrateHierarchy(migrate_hierarchy_config) => {
+            let migrate_hierarchy_config =
+                parse_migrate_hierarchy_config(migrate_hierarchy_config)?;
+            migrate_hierarchy(client_config, migrate_hierarchy_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::ExportEra(export_era_config) => {
+            let export_era_config = era::parse_export_era_config(export_era_config)?;
+            era::export_era(client_config, export_era_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::ImportEra(import_era_config) => {
+            let import_era_config = era::parse_import_era_config(import_era_config)?;
+            era::import_era(client_config, import_era_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::DumpOpPool(dump_op_pool_config) => {
+            let dump_op_pool_config = op_pool::parse_dump_op_pool_config(dump_op_pool_config)?;
+            op_pool::dump_op_pool(client_config, dump_op_pool_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::RestoreOpPool(restore_op_pool_config) => {
+            let restore_op_pool_config =
+                op_pool::parse_restore_op_pool_config(restore_op_pool_config)?;
+            op_pool::restore_op_pool(client_config, restore_op_pool_config, &context, log)
+        }
     }
 }
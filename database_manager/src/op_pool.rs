@@ -0,0 +1,172 @@
+//! Dump and restore of the persisted operation pool.
+//!
+//! The dump file is the SSZ encoding of the latest `PersistedOperationPool` variant. Each operation
+//! in it is tagged with the version of its encoding, so a dump can be restored by any later version
+//! of Lighthouse: operations in an older encoding are upgraded, and those that can't be decoded are
+//! discarded and reported.
+use crate::cli::{DumpOpPool, RestoreOpPool};
+use beacon_chain::OP_POOL_DB_KEY;
+use beacon_node::ClientConfig;
+use environment::RuntimeContext;
+use operation_pool::{
+    DiscardedOperations, PersistedOperationPool, PersistedOperationPoolV20,
+    PersistedOperationPoolV23,
+};
+use slog::{info, warn, Logger};
+use ssz::{Decode, Encode};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use store::hot_cold_store::HotColdDBError;
+use store::metadata::CURRENT_SCHEMA_VERSION;
+use store::{HotColdDB, LevelDB};
+use types::EthSpec;
+
+pub struct DumpOpPoolConfig {
+    output: PathBuf,
+}
+
+pub fn parse_dump_op_pool_config(
+    dump_op_pool_config: &DumpOpPool,
+) -> Result<DumpOpPoolConfig, String> {
+    let output = dump_op_pool_config.output.clone();
+    Ok(DumpOpPoolConfig { output })
+}
+
+pub struct RestoreOpPoolConfig {
+    input: PathBuf,
+}
+
+pub fn parse_restore_op_pool_config(
+    restore_op_pool_config: &RestoreOpPool,
+) -> Result<RestoreOpPoolConfig, String> {
+    let input = restore_op_pool_config.input.clone();
+    Ok(RestoreOpPoolConfig { input })
+}
+
+/// Open the database, refusing to do so if it needs a schema migration first.
+///
+/// The persisted op pool is only in the latest format once the database has been migrated.
+fn open_db<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>, String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, from, to| {
+            if from == to {
+                Ok(())
+            } else {
+                Err(HotColdDBError::UnsupportedSchemaVersion {
+                    target_version: to,
+                    current_version: from,
+                }
+                .into())
+            }
+        },
+        client_config.store,
+        spec.clone(),
+        log,
+    )
+    .map_err(|e| {
+        format!(
+            "Unable to open database: {e:?}. Run `lighthouse db migrate --to {}` first if the \
+             schema version differs.",
+            CURRENT_SCHEMA_VERSION.as_u64()
+        )
+    })
+}
+
+fn log_op_pool<E: EthSpec>(
+    message: &str,
+    pool_v20: &PersistedOperationPoolV20<E>,
+    discarded: &DiscardedOperations,
+    log: &Logger,
+) {
+    info!(
+        log,
+        "{}", message;
+        "attestations" => pool_v20.attestations.len(),
+        "sync_contributions" => pool_v20.sync_contributions.len(),
+        "attester_slashings" => pool_v20.attester_slashings.len(),
+        "proposer_slashings" => pool_v20.proposer_slashings.len(),
+        "voluntary_exits" => pool_v20.voluntary_exits.len(),
+        "bls_to_execution_changes" => pool_v20.bls_to_execution_changes.len(),
+    );
+    if !discarded.is_empty() {
+        warn!(
+            log,
+            "Discarded undecodable operations";
+            "total" => discarded.total(),
+            "discarded" => %discarded,
+        );
+    }
+}
+
+pub fn dump_op_pool<E: EthSpec>(
+    client_config: ClientConfig,
+    dump_op_pool_config: DumpOpPoolConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let db = open_db(client_config, runtime_context, log.clone())?;
+
+    let pool = db
+        .get_item::<PersistedOperationPoolV23<E>>(&OP_POOL_DB_KEY)
+        .map_err(|e| format!("Unable to read op pool: {e:?}"))?
+        .ok_or("No op pool is persisted in the database")?;
+
+    let (pool_v20, discarded) = pool.clone().decode_operations();
+    log_op_pool("Dumping op pool", &pool_v20, &discarded, &log);
+
+    fs::write(&dump_op_pool_config.output, pool.as_ssz_bytes())
+        .map_err(|e| format!("Unable to write op pool file: {e:?}"))?;
+
+    info!(
+        log,
+        "Op pool dump complete";
+        "file" => ?dump_op_pool_config.output,
+        "operations" => pool.operations.len(),
+    );
+    Ok(())
+}
+
+pub fn restore_op_pool<E: EthSpec>(
+    client_config: ClientConfig,
+    restore_op_pool_config: RestoreOpPoolConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let bytes = fs::read(&restore_op_pool_config.input)
+        .map_err(|e| format!("Unable to read op pool file: {e:?}"))?;
+    let pool = PersistedOperationPoolV23::<E>::from_ssz_bytes(&bytes)
+        .map_err(|e| format!("Unable to decode op pool file: {e:?}"))?;
+
+    let (pool_v20, discarded) = pool.decode_operations();
+    log_op_pool("Restoring op pool", &pool_v20, &discarded, &log);
+
+    let db = open_db(client_config, runtime_context, log.clone())?;
+    if db
+        .item_exists::<PersistedOperationPoolV23<E>>(&OP_POOL_DB_KEY)
+        .map_err(|e| format!("Unable to read op pool: {e:?}"))?
+    {
+        warn!(log, "Replacing the op pool persisted in the database");
+    }
+
+    // Only re-encode the operations that could be decoded, so they aren't reported again when the
+    // beacon node starts.
+    let pool = PersistedOperationPool::V23(pool_v20.into());
+    db.put_item(&OP_POOL_DB_KEY, &pool)
+        .map_err(|e| format!("Unable to write op pool: {e:?}"))?;
+
+    info!(log, "Op pool restore complete");
+    Ok(())
+}
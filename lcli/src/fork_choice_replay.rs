@@ -0,0 +1,307 @@
+//! # Fork Choice Replay
+//!
+//! Use this tool to replay a recorded stream of fork choice events on top of the persisted fork
+//! choice of a beacon node, printing the head, its weight and the proposer boost decision after
+//! every event. Edits can be applied to the events to see how head selection would have changed.
+//!
+//! Events are read from a file with one JSON-encoded `ForkChoiceEvent` per line, or from the
+//! journal written by a beacon node run with `--fork-choice-journal`: either a single journal file
+//! or the whole journal directory.
+//!
+//! The replay starts from the snapshot at the start of the events, if there is one. Otherwise the
+//! fork choice can be loaded from an SSZ file containing a `PersistedForkChoice` or from the
+//! (unencrypted) database of a beacon node which isn't running. Snapshots after the start reset
+//! the replay to the fork choice recorded by the node, so edits only affect the events since the
+//! latest snapshot.
+//!
+//! A journal file which ends with a truncated frame, because the node stopped while writing it,
+//! is replayed up to that frame.
+//!
+//! ## Examples
+//!
+//! Replay events without proposer boost and without the votes of validators 10 and 11:
+//!
+//! ```ignore
+//! lcli fork-choice-replay \
+//!     --datadir ~/.lighthouse/mainnet/beacon \
+//!     --events /tmp/fork_choice_events.jsonl \
+//!     --disable-proposer-boost \
+//!     --drop-validators 10,11
+//! ```
+//!
+//! Replay the journal of a node, starting from the snapshot in its oldest file:
+//!
+//! ```ignore
+//! lcli fork-choice-replay --events ~/.lighthouse/mainnet/beacon/fork_choice_journal
+//! ```
+use beacon_chain::fork_choice_journal::journal_files;
+use beacon_chain::{PersistedForkChoice, FORK_CHOICE_DB_KEY};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use eth2_network_config::Eth2NetworkConfig;
+use fork_choice::journal::{JournalReader, JOURNAL_MAGIC};
+use fork_choice::replay::{
+    BoostDecision, ForkChoiceEvent, ForkChoiceReplayer, ReplayAnchor, ReplayStep, WhatIf,
+};
+use proto_array::ProtoArrayForkChoice;
+use ssz::Decode;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use store::{ItemStore, LevelDB};
+use types::{EthSpec, Hash256};
+
+/// Name of the hot database inside a beacon node data directory.
+const HOT_DB_DIR: &str = "chain_db";
+
+type Events = Box<dyn Iterator<Item = Result<ForkChoiceEvent, String>>>;
+
+pub fn run<E: EthSpec>(
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = network_config.chain_spec::<E>()?;
+
+    let fork_choice_path: Option<PathBuf> = parse_optional(matches, "fork-choice")?;
+    let datadir: Option<PathBuf> = parse_optional(matches, "datadir")?;
+    let events_path: PathBuf = parse_required(matches, "events")?;
+    let num_tips: usize = parse_required(matches, "tips")?;
+
+    let what_if = WhatIf {
+        disable_proposer_boost: matches.get_flag("disable-proposer-boost"),
+        proposer_score_boost: parse_optional(matches, "proposer-score-boost")?,
+        dropped_validators: parse_list(matches, "drop-validators")?
+            .into_iter()
+            .map(|index| {
+                index
+                    .parse()
+                    .map_err(|e| format!("Invalid validator index {index}: {e}"))
+            })
+            .collect::<Result<_, String>>()?,
+        dropped_sources: parse_list(matches, "drop-attestations-from")?
+            .into_iter()
+            .collect(),
+    };
+
+    let mut events = read_events(&events_path)?.peekable();
+    let persisted = match (fork_choice_path, datadir) {
+        (Some(path), _) => {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+            Some(
+                PersistedForkChoice::from_ssz_bytes(&bytes)
+                    .map_err(|e| format!("Invalid persisted fork choice: {e:?}"))?,
+            )
+        }
+        (None, Some(datadir)) => Some(load_from_datadir::<E>(&datadir)?),
+        (None, None) => None,
+    };
+    let anchor = if let Some(persisted) = persisted {
+        let store = persisted.fork_choice_store;
+        ReplayAnchor {
+            proto_array: ProtoArrayForkChoice::from_bytes(
+                &persisted.fork_choice.proto_array_bytes,
+            )?,
+            current_slot: store.time,
+            justified_checkpoint: store.justified_checkpoint,
+            finalized_checkpoint: store.finalized_checkpoint,
+            justified_balances: store.justified_balances,
+            proposer_boost_root: store.proposer_boost_root,
+            equivocating_indices: store.equivocating_indices,
+        }
+    } else if let Some(Ok(snapshot @ ForkChoiceEvent::Snapshot { .. })) = events.peek() {
+        let anchor = ReplayAnchor::from_snapshot(snapshot)?;
+        events.next();
+        anchor
+    } else {
+        return Err(
+            "The events don't start with a snapshot, --fork-choice or --datadir is required".into(),
+        );
+    };
+
+    println!(
+        "Starting at slot {} with justified epoch {} and finalized epoch {}",
+        anchor.current_slot, anchor.justified_checkpoint.epoch, anchor.finalized_checkpoint.epoch
+    );
+    let mut replayer = ForkChoiceReplayer::<E>::new(anchor, what_if, &spec)?;
+
+    let mut prev_head = None;
+    let mut mismatches = 0;
+    for (index, event) in events.enumerate() {
+        let event = event?;
+        let step = replayer
+            .apply(&event)
+            .map_err(|e| format!("Unable to apply event #{index}: {e}"))?;
+
+        print_step(index, &event, &step, prev_head, num_tips);
+        if step
+            .recorded_head
+            .is_some_and(|recorded| recorded != step.head_root)
+        {
+            mismatches += 1;
+        }
+        prev_head = Some(step.head_root);
+    }
+
+    if mismatches > 0 {
+        println!("{mismatches} recorded heads differ from the replay");
+    }
+    Ok(())
+}
+
+/// Read the events from a JSON lines file, a journal file or a journal directory.
+fn read_events(path: &Path) -> Result<Events, String> {
+    if path.is_dir() {
+        let files = journal_files(path)
+            .map_err(|e| format!("Unable to list journal files in {}: {e}", path.display()))?;
+        if files.is_empty() {
+            return Err(format!("No journal files in {}", path.display()));
+        }
+        let readers = files
+            .iter()
+            .map(|path| open_journal(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Box::new(readers.into_iter().flatten()));
+    }
+
+    let mut file =
+        File::open(path).map_err(|e| format!("Unable to open {}: {e}", path.display()))?;
+    let mut magic = [0; JOURNAL_MAGIC.len()];
+    let is_journal = file.read_exact(&mut magic).is_ok() && &magic == JOURNAL_MAGIC;
+    if is_journal {
+        return open_journal(path);
+    }
+
+    file.rewind()
+        .map_err(|e| format!("Unable to read {}: {e}", path.display()))?;
+    let events = BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line.map_err(|e| format!("Unable to read events: {e}"))?;
+            serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid event on line {}: {e}", index + 1))
+        });
+    Ok(Box::new(events))
+}
+
+fn open_journal(path: &Path) -> Result<Events, String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {}: {e}", path.display()))?;
+    let path = path.display().to_string();
+    let mut reader =
+        JournalReader::new(BufReader::new(file)).map_err(|e| format!("{path}: {e}"))?;
+    Ok(Box::new(std::iter::from_fn(move || {
+        let event = reader.next();
+        if event.is_none() && reader.truncated() {
+            eprintln!("{path} ends with a truncated frame, which was skipped");
+        }
+        event.map(|event| event.map_err(|e| format!("{path}: {e}")))
+    })))
+}
+
+/// Load the persisted fork choice from the hot database in `datadir`.
+///
+/// The database is opened without creating it, so a wrong `datadir` isn't left with an empty one.
+fn load_from_datadir<E: EthSpec>(datadir: &Path) -> Result<PersistedForkChoice, String> {
+    let path = datadir.join(HOT_DB_DIR);
+    LevelDB::<E>::open_existing_with_cipher(&path, None)
+        .map_err(|e| format!("Unable to open database {}: {e:?}", path.display()))?
+        .get::<PersistedForkChoice>(&FORK_CHOICE_DB_KEY)
+        .map_err(|e| format!("Unable to read persisted fork choice: {e:?}"))?
+        .ok_or_else(|| "Database contains no persisted fork choice".to_string())
+}
+
+fn parse_list(matches: &ArgMatches, name: &str) -> Result<Vec<String>, String> {
+    Ok(parse_optional::<String>(matches, name)?
+        .map(|list| {
+            list.split(',')
+                .map(|item| item.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn print_step(
+    index: usize,
+    event: &ForkChoiceEvent,
+    step: &ReplayStep,
+    prev_head: Option<Hash256>,
+    num_tips: usize,
+) {
+    let head_changed = prev_head.is_some_and(|prev_head| prev_head != step.head_root);
+    println!(
+        "#{index} slot {}: {}{}",
+        step.current_slot,
+        describe(event),
+        if step.dropped { " (dropped)" } else { "" }
+    );
+    println!(
+        "    head {:?} weight {}{}",
+        step.head_root,
+        step.head_weight,
+        if head_changed { " (new head)" } else { "" }
+    );
+    if let Some(decision) = step.boost_decision {
+        let decision = match decision {
+            BoostDecision::Boosted => "boosted".to_string(),
+            BoostDecision::Late => "not boosted, arrived after the attestation deadline".into(),
+            BoostDecision::NotCurrentSlot => "not boosted, not from the current slot".into(),
+            BoostDecision::AlreadyBoosted(root) => format!("not boosted, {root:?} is boosted"),
+            BoostDecision::Disabled => "not boosted, proposer boost disabled".into(),
+        };
+        println!("    proposer boost: {decision}");
+    }
+    if let Some(recorded) = step.recorded_head {
+        if recorded != step.head_root {
+            println!("    MISMATCH: recorded head {recorded:?}");
+        }
+    }
+    for (root, slot, weight) in step.tips.iter().take(num_tips) {
+        println!("    tip {root:?} slot {slot} weight {weight}");
+    }
+}
+
+fn describe(event: &ForkChoiceEvent) -> String {
+    match event {
+        ForkChoiceEvent::UpdateTime { current_slot } => format!("update_time {current_slot}"),
+        ForkChoiceEvent::OnBlock { block, timely } => format!(
+            "on_block {:?} slot {}{}",
+            block.root,
+            block.slot,
+            if *timely { "" } else { " (late)" }
+        ),
+        ForkChoiceEvent::OnAttestation {
+            validator_indices,
+            block_root,
+            target_epoch,
+            source,
+        } => format!(
+            "on_attestation {} validators for {block_root:?} target epoch {target_epoch}{}",
+            validator_indices.len(),
+            source
+                .as_ref()
+                .map(|source| format!(" from {source}"))
+                .unwrap_or_default()
+        ),
+        ForkChoiceEvent::OnAttesterSlashing { validator_indices } => {
+            format!("on_attester_slashing {validator_indices:?}")
+        }
+        ForkChoiceEvent::UpdateCheckpoints {
+            justified_checkpoint,
+            finalized_checkpoint,
+            ..
+        } => format!(
+            "update_checkpoints justified epoch {} finalized epoch {}",
+            justified_checkpoint.epoch, finalized_checkpoint.epoch
+        ),
+        ForkChoiceEvent::OnValidExecutionPayload { block_root } => {
+            format!("on_valid_execution_payload {block_root:?}")
+        }
+        ForkChoiceEvent::OnInvalidExecutionPayload { op } => {
+            format!("on_invalid_execution_payload {:?}", op.block_root())
+        }
+        ForkChoiceEvent::GetHead { head_root } => format!("get_head {head_root:?}"),
+        ForkChoiceEvent::Snapshot { current_slot, .. } => format!("snapshot at {current_slot}"),
+    }
+}
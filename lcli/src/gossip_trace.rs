@@ -0,0 +1,134 @@
+//! # Gossip Trace
+//!
+//! Use this tool to reconstruct the propagation of a single gossipsub message from the trace
+//! written by a beacon node running with `--gossip-trace-file`.
+//!
+//! The rotated trace files (`<path>.1`, `<path>.2`, ...) are read along with the current one.
+//!
+//! ## Examples
+//!
+//! ```ignore
+//! lcli gossip-trace \
+//!     --trace-file /var/lib/lighthouse/gossip_trace.jsonl \
+//!     --message-id 5c1ddc1a3b6f3b5d2a6b8a1f3e0c9d7b6a5f4e3d
+//! ```
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use lighthouse_network::{read_gossip_trace, GossipTraceEvent, GossipTraceRecord};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+pub fn run(matches: &ArgMatches) -> Result<(), String> {
+    let trace_file: PathBuf = parse_required(matches, "trace-file")?;
+    let message_id: String = parse_required(matches, "message-id")?;
+    let message_id = message_id.trim_start_matches("0x").to_lowercase();
+
+    let records = read_gossip_trace(&trace_file)?
+        .into_iter()
+        .filter(|record| record.event.involves(&message_id))
+        .collect::<Vec<_>>();
+
+    let Some(first) = records.first() else {
+        return Err(format!("Message {} not found in trace", message_id));
+    };
+    let start = first.timestamp_ms;
+
+    println!("Message {}", message_id);
+    println!();
+    for record in &records {
+        println!(
+            "+{:>6}ms  {:<18}  {}",
+            record.timestamp_ms.saturating_sub(start),
+            event_name(&record.event),
+            event_detail(&record.event)
+        );
+    }
+    println!();
+    print_summary(&records, start);
+
+    Ok(())
+}
+
+fn event_name(event: &GossipTraceEvent) -> &'static str {
+    match event {
+        GossipTraceEvent::PublishMessage { .. } => "publish",
+        GossipTraceEvent::DeliverMessage { .. } => "deliver",
+        GossipTraceEvent::DuplicateMessage { .. } => "duplicate",
+        GossipTraceEvent::RejectMessage { .. } => "reject",
+        GossipTraceEvent::RecvIhave { .. } => "recv_ihave",
+        GossipTraceEvent::SendIhave { .. } => "send_ihave",
+        GossipTraceEvent::RecvIwant { .. } => "recv_iwant",
+        GossipTraceEvent::SendIwant { .. } => "send_iwant",
+    }
+}
+
+fn event_detail(event: &GossipTraceEvent) -> String {
+    match event {
+        GossipTraceEvent::PublishMessage { topic, .. } => format!("topic: {}", topic),
+        GossipTraceEvent::RejectMessage {
+            peer_id, reason, ..
+        } => format!("peer: {} reason: {}", peer_id, reason),
+        event => format!("peer: {}", event.peer_id().unwrap_or("-")),
+    }
+}
+
+fn print_summary(records: &[GossipTraceRecord], start: u64) {
+    let first_with = |f: fn(&GossipTraceEvent) -> bool| {
+        records
+            .iter()
+            .find(|record| f(&record.event))
+            .map(|record| (record.timestamp_ms.saturating_sub(start), record))
+    };
+    let peers_with = |f: fn(&GossipTraceEvent) -> bool| {
+        records
+            .iter()
+            .filter(|record| f(&record.event))
+            .filter_map(|record| record.event.peer_id())
+            .collect::<HashSet<_>>()
+            .len()
+    };
+
+    let published = first_with(|e| matches!(e, GossipTraceEvent::PublishMessage { .. }));
+    if let Some((offset, _)) = published {
+        println!("Published locally at +{}ms", offset);
+    }
+    match first_with(|e| matches!(e, GossipTraceEvent::DeliverMessage { .. })) {
+        Some((offset, record)) => println!(
+            "First received at +{}ms from {}",
+            offset,
+            record.event.peer_id().unwrap_or("-")
+        ),
+        None => println!("Never received from a peer"),
+    }
+    let advertised = first_with(|e| matches!(e, GossipTraceEvent::RecvIhave { .. }));
+    if let Some((offset, record)) = advertised {
+        println!(
+            "First advertised at +{}ms by {}",
+            offset,
+            record.event.peer_id().unwrap_or("-")
+        );
+    }
+    println!(
+        "Duplicates received from {} peer(s)",
+        peers_with(|e| matches!(e, GossipTraceEvent::DuplicateMessage { .. }))
+    );
+    println!(
+        "Advertised via IHAVE by {} peer(s), requested via IWANT from {} peer(s)",
+        peers_with(|e| matches!(e, GossipTraceEvent::RecvIhave { .. })),
+        peers_with(|e| matches!(e, GossipTraceEvent::SendIwant { .. }))
+    );
+    println!(
+        "Requested from us via IWANT by {} peer(s)",
+        peers_with(|e| matches!(e, GossipTraceEvent::RecvIwant { .. }))
+    );
+    println!(
+        "Rejected from {} peer(s)",
+        peers_with(|e| matches!(e, GossipTraceEvent::RejectMessage { .. }))
+    );
+    if let Some(last) = records.last() {
+        println!(
+            "Last event at +{}ms",
+            last.timestamp_ms.saturating_sub(start)
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod block_root;
 mod check_deposit_data;
+mod fork_choice_replay;
 mod generate_bootnode_enr;
+mod gossip_trace;
 mod http_sync;
 mod indexed_attestations;
 mod mnemonic_validators;
@@ -621,6 +623,107 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("gossip-trace")
+                .about("Summarises the propagation of a gossip message from a beacon node's \
+                        gossip trace file.")
+                .arg(
+                    Arg::new("trace-file")
+                        .long("trace-file")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the trace file set via --gossip-trace-file.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("message-id")
+                        .long("message-id")
+                        .value_name("HEX")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The gossipsub message id to summarise, as hex.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("fork-choice-replay")
+                .about("Replays recorded fork choice events on top of a persisted fork choice, \
+                        printing the head and proposer boost decision after each event.")
+                .arg(
+                    Arg::new("fork-choice")
+                        .long("fork-choice")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .conflicts_with("datadir")
+                        .help("Path to an SSZ-encoded persisted fork choice. Not required if \
+                               the events start with a snapshot.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("datadir")
+                        .long("datadir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("Beacon node data directory to load the persisted fork choice from. \
+                               The beacon node must not be running. Not required if the events \
+                               start with a snapshot.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("events")
+                        .long("events")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to a file of fork choice events, one JSON object per line, \
+                               or to a fork choice journal file or directory.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("disable-proposer-boost")
+                        .long("disable-proposer-boost")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Replay as if proposer boost were disabled.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("proposer-score-boost")
+                        .long("proposer-score-boost")
+                        .value_name("PERCENT")
+                        .action(ArgAction::Set)
+                        .conflicts_with("disable-proposer-boost")
+                        .help("Override the proposer score boost, as a percentage of the \
+                               committee weight.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("drop-validators")
+                        .long("drop-validators")
+                        .value_name("INDICES")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated validator indices whose attestations are ignored.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("drop-attestations-from")
+                        .long("drop-attestations-from")
+                        .value_name("PEER_IDS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated peer ids whose attestations are ignored.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("tips")
+                        .long("tips")
+                        .value_name("N")
+                        .action(ArgAction::Set)
+                        .default_value("3")
+                        .help("Number of the heaviest chain tips to print after each event.")
+                        .display_order(0)
+                )
+        )
         .get_matches();
 
     let result = matches
@@ -732,6 +835,13 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             http_sync::run::<E>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run http-sync command: {}", e))
         }
+        Some(("gossip-trace", matches)) => gossip_trace::run(matches)
+            .map_err(|e| format!("Failed to run gossip-trace command: {}", e)),
+        Some(("fork-choice-replay", matches)) => {
+            let network_config = get_network_config()?;
+            fork_choice_replay::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run fork-choice-replay command: {}", e))
+        }
         Some((other, _)) => Err(format!("Unknown subcommand {}. See --help.", other)),
         _ => Err("No subcommand provided. See --help.".to_string()),
     }
@@ -0,0 +1,153 @@
+use clap::{builder::ArgPredicate, Arg, ArgAction, Command};
+use clap_utils::{get_color_style, FLAG_HEADER};
+
+pub fn cli_app() -> Command {
+    Command::new("light_client")
+        .visible_aliases(["light-client", "lc"])
+        .about(
+            "Follows the chain as a light client, verifying the sync committee signatures of \
+                light client updates received from peers or from a beacon node. Provides a HTTP \
+                API for the verified head and finalized headers. This process does *not* verify \
+                blocks or state transitions, so it trusts that the sync committee is honest.",
+        )
+        .styles(get_color_style())
+        .display_order(0)
+        .arg(
+            Arg::new("help")
+                .long("help")
+                .short('h')
+                .help("Prints help information")
+                .action(ArgAction::HelpLong)
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+        .arg(
+            Arg::new("checkpoint-root")
+                .long("checkpoint-root")
+                .value_name("BLOCK_ROOT")
+                .help(
+                    "The root of a trusted block to bootstrap the light client from. This \
+                      should be a recent finalized block, obtained from a source you trust.",
+                )
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("beacon-node")
+                .long("beacon-node")
+                .value_name("URL")
+                .help(
+                    "Fetch light client updates from the REST API of this beacon node, instead \
+                      of from peers over libp2p. The updates are verified in the same way, so \
+                      the beacon node does not need to be trusted.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("genesis-time")
+                .long("genesis-time")
+                .value_name("UNIX_TIMESTAMP")
+                .help(
+                    "The genesis time of the network. Only required when following the chain \
+                      over libp2p on a network whose genesis state is not built in.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        /*
+         * Network parameters.
+         */
+        .arg(
+            Arg::new("listen-address")
+                .long("listen-address")
+                .value_name("ADDRESS")
+                .help("The address to listen on for libp2p connections.")
+                .default_value("0.0.0.0")
+                .action(ArgAction::Set)
+                .conflicts_with("beacon-node")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("port")
+                .long("port")
+                .value_name("PORT")
+                .help(
+                    "The TCP and UDP port to listen on for libp2p connections. The QUIC port is \
+                      the next port.",
+                )
+                .default_value("9200")
+                .action(ArgAction::Set)
+                .conflicts_with("beacon-node")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("boot-nodes")
+                .long("boot-nodes")
+                .allow_hyphen_values(true)
+                .value_name("ENR-LIST")
+                .help(
+                    "One or more comma-delimited base64-encoded ENRs to bootstrap the p2p \
+                      network. Defaults to the boot nodes of the network.",
+                )
+                .action(ArgAction::Set)
+                .conflicts_with("beacon-node")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("target-peers")
+                .long("target-peers")
+                .value_name("NUM")
+                .help("The number of peers to stay connected to.")
+                .default_value("10")
+                .action(ArgAction::Set)
+                .conflicts_with("beacon-node")
+                .display_order(0),
+        )
+        /*
+         * HTTP API parameters.
+         */
+        .arg(
+            Arg::new("http")
+                .long("http")
+                .help("Enable the HTTP API which serves the verified headers.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("http-address")
+                .long("http-address")
+                .requires("http")
+                .value_name("ADDRESS")
+                .help("Set the listen address for the HTTP API server.")
+                .default_value_if("http", ArgPredicate::IsPresent, "127.0.0.1")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("http-port")
+                .long("http-port")
+                .requires("http")
+                .value_name("PORT")
+                .help("Set the listen TCP port for the HTTP API server.")
+                .default_value_if("http", ArgPredicate::IsPresent, "5056")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new("http-allow-origin")
+                .long("http-allow-origin")
+                .requires("http")
+                .value_name("ORIGIN")
+                .help(
+                    "Set the value of the Access-Control-Allow-Origin response HTTP header. \
+                    Use * to allow any origin (not recommended in production). \
+                    If no value is supplied, the CORS allowed origin is set to the listen \
+                    address of this server (e.g., http://localhost:5056).",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
@@ -0,0 +1,85 @@
+use crate::http_api;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use directory::{parse_path_or_default, DEFAULT_LIGHT_CLIENT_DIR, DEFAULT_NETWORK_DIR};
+use environment::RuntimeContext;
+use lighthouse_network::{Enr, NetworkConfig};
+use sensitive_url::SensitiveUrl;
+use std::net::IpAddr;
+use types::{EthSpec, Hash256};
+
+/// Configuration for the light client.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The trusted block root to bootstrap from.
+    pub checkpoint_root: Hash256,
+    /// A beacon node to fetch updates from. Updates are fetched over libp2p if `None`.
+    pub beacon_node: Option<SensitiveUrl>,
+    /// Overrides the genesis time of the network.
+    pub genesis_time: Option<u64>,
+    pub network: NetworkConfig,
+    pub http_api: http_api::Config,
+}
+
+impl Config {
+    pub fn from_cli<E: EthSpec>(
+        cli_args: &ArgMatches,
+        context: &RuntimeContext<E>,
+    ) -> Result<Self, String> {
+        let checkpoint_root = parse_required(cli_args, "checkpoint-root")?;
+        let beacon_node = parse_optional::<String>(cli_args, "beacon-node")?
+            .map(|url| {
+                SensitiveUrl::parse(&url)
+                    .map_err(|e| format!("Unable to parse beacon node URL: {:?}", e))
+            })
+            .transpose()?;
+        let genesis_time = parse_optional(cli_args, "genesis-time")?;
+
+        let mut network = NetworkConfig {
+            network_dir: parse_path_or_default(cli_args, "datadir")?
+                .join(DEFAULT_LIGHT_CLIENT_DIR)
+                .join(DEFAULT_NETWORK_DIR),
+            target_peers: parse_required(cli_args, "target-peers")?,
+            ..NetworkConfig::default()
+        };
+
+        let port: u16 = parse_required(cli_args, "port")?;
+        let quic_port = port.saturating_add(1);
+        match parse_required::<IpAddr>(cli_args, "listen-address")? {
+            IpAddr::V4(addr) => network.set_ipv4_listening_address(addr, port, port, quic_port),
+            IpAddr::V6(addr) => network.set_ipv6_listening_address(addr, port, port, quic_port),
+        }
+
+        if let Some(boot_nodes) = cli_args.get_one::<String>("boot-nodes") {
+            network.boot_nodes_enr = boot_nodes
+                .split(',')
+                .map(|enr| {
+                    enr.parse::<Enr>()
+                        .map_err(|e| format!("Invalid boot node ENR {}: {:?}", enr, e))
+                })
+                .collect::<Result<_, _>>()?;
+        } else if let Some(boot_nodes) = context
+            .eth2_network_config
+            .as_ref()
+            .and_then(|network_config| network_config.boot_enr.as_ref())
+        {
+            network.boot_nodes_enr.clone_from(boot_nodes);
+        }
+
+        let mut http_api = http_api::Config::default();
+        if cli_args.get_flag("http") {
+            http_api.enabled = true;
+            http_api.listen_addr = parse_required(cli_args, "http-address")?;
+            http_api.listen_port = parse_required(cli_args, "http-port")?;
+            http_api.allow_origin = parse_optional(cli_args, "http-allow-origin")?;
+        }
+
+        Ok(Self {
+            checkpoint_root,
+            beacon_node,
+            genesis_time,
+            network,
+            http_api,
+        })
+    }
+}
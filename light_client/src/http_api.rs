@@ -0,0 +1,216 @@
+//! A HTTP server which serves the headers verified by the light client.
+//!
+//! ## Endpoints
+//!
+//! - `GET /light_client/headers/optimistic`: the latest header signed by the sync committee.
+//! - `GET /light_client/headers/finalized`: the latest finalized header.
+//! - `GET /light_client/finalized_state_root`: the state root of the latest finalized header.
+//!
+//! All endpoints return a 503 until the light client has been bootstrapped.
+use crate::LightClient;
+use eth2::types::{GenericResponse, RootData};
+use lighthouse_version::version_with_platform;
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::fork_versioned_response::EmptyMetadata;
+use types::{EthSpec, ForkName, ForkVersionedResponse, Hash256, LightClientHeader};
+use warp::Filter;
+use warp_utils::task::blocking_json_task;
+use warp_utils::uor::UnifyingOrFilter;
+
+#[derive(Debug)]
+pub enum Error {
+    Warp(warp::Error),
+    Other(String),
+}
+
+impl From<warp::Error> for Error {
+    fn from(e: warp::Error) -> Self {
+        Error::Warp(e)
+    }
+}
+
+impl From<String> for Error {
+    fn from(e: String) -> Self {
+        Error::Other(e)
+    }
+}
+
+/// A wrapper around all the items required to spawn the HTTP server.
+pub struct Context<E: EthSpec> {
+    pub config: Config,
+    pub light_client: Arc<LightClient<E>>,
+    pub log: Logger,
+}
+
+/// Configuration for the HTTP server.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub enabled: bool,
+    pub listen_addr: IpAddr,
+    pub listen_port: u16,
+    pub allow_origin: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            listen_port: 5056,
+            allow_origin: None,
+        }
+    }
+}
+
+/// A verified header and its block root.
+#[derive(Debug, Clone, Serialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct HeaderData<E: EthSpec> {
+    pub root: Hash256,
+    pub header: LightClientHeader<E>,
+}
+
+impl<E: EthSpec> HeaderData<E> {
+    fn into_response(self) -> ForkVersionedResponse<Self> {
+        let fork_name = match &self.header {
+            LightClientHeader::Altair(_) => ForkName::Altair,
+            LightClientHeader::Capella(_) => ForkName::Capella,
+            LightClientHeader::Deneb(_) => ForkName::Deneb,
+            LightClientHeader::Electra(_) => ForkName::Electra,
+        };
+        ForkVersionedResponse {
+            version: Some(fork_name),
+            metadata: EmptyMetadata {},
+            data: self,
+        }
+    }
+}
+
+impl<E: EthSpec> From<LightClientHeader<E>> for HeaderData<E> {
+    fn from(header: LightClientHeader<E>) -> Self {
+        Self {
+            root: header.beacon().tree_hash_root(),
+            header,
+        }
+    }
+}
+
+fn not_bootstrapped() -> warp::Rejection {
+    warp_utils::reject::not_synced("light client is not bootstrapped".to_string())
+}
+
+/// Creates a server that will serve requests using information from `ctx`.
+///
+/// The server will shut down gracefully when the `shutdown` future resolves.
+///
+/// ## Returns
+///
+/// This function will bind the server to the provided address and then return a tuple of:
+///
+/// - `SocketAddr`: the address that the HTTP server will listen on.
+/// - `Future`: the actual server future that will need to be awaited.
+///
+/// ## Errors
+///
+/// Returns an error if the server is unable to bind or there is another error during
+/// configuration.
+pub fn serve<E: EthSpec>(
+    ctx: Arc<Context<E>>,
+    shutdown: impl Future<Output = ()> + Send + Sync + 'static,
+) -> Result<(SocketAddr, impl Future<Output = ()>), Error> {
+    let config = &ctx.config;
+    let log = ctx.log.clone();
+
+    // Configure CORS.
+    let cors_builder = {
+        let builder = warp::cors()
+            .allow_method("GET")
+            .allow_headers(vec!["Content-Type"]);
+
+        warp_utils::cors::set_builder_origins(
+            builder,
+            config.allow_origin.as_deref(),
+            (config.listen_addr, config.listen_port),
+        )?
+    };
+
+    let inner_light_client = ctx.light_client.clone();
+    let light_client_filter = warp::any().map(move || inner_light_client.clone());
+
+    let base_path = warp::path("light_client");
+
+    // GET light_client/headers/optimistic
+    let get_optimistic_header = base_path
+        .and(warp::path("headers"))
+        .and(warp::path("optimistic"))
+        .and(warp::path::end())
+        .and(light_client_filter.clone())
+        .then(|light_client: Arc<LightClient<E>>| {
+            blocking_json_task(move || {
+                light_client
+                    .optimistic_header()
+                    .map(|header| HeaderData::from(header).into_response())
+                    .ok_or_else(not_bootstrapped)
+            })
+        });
+
+    // GET light_client/headers/finalized
+    let get_finalized_header = base_path
+        .and(warp::path("headers"))
+        .and(warp::path("finalized"))
+        .and(warp::path::end())
+        .and(light_client_filter.clone())
+        .then(|light_client: Arc<LightClient<E>>| {
+            blocking_json_task(move || {
+                light_client
+                    .finalized_header()
+                    .map(|header| HeaderData::from(header).into_response())
+                    .ok_or_else(not_bootstrapped)
+            })
+        });
+
+    // GET light_client/finalized_state_root
+    let get_finalized_state_root = base_path
+        .and(warp::path("finalized_state_root"))
+        .and(warp::path::end())
+        .and(light_client_filter)
+        .then(|light_client: Arc<LightClient<E>>| {
+            blocking_json_task(move || {
+                light_client
+                    .finalized_header()
+                    .map(|header| GenericResponse::from(RootData::from(header.beacon().state_root)))
+                    .ok_or_else(not_bootstrapped)
+            })
+        });
+
+    let routes = warp::get()
+        .and(
+            get_optimistic_header
+                .uor(get_finalized_header)
+                .uor(get_finalized_state_root),
+        )
+        .recover(warp_utils::reject::handle_rejection)
+        // Add a `Server` header.
+        .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
+        .with(cors_builder.build());
+
+    let (listening_socket, server) = warp::serve(routes).try_bind_with_graceful_shutdown(
+        SocketAddr::new(config.listen_addr, config.listen_port),
+        async {
+            shutdown.await;
+        },
+    )?;
+
+    info!(
+        log,
+        "HTTP API started";
+        "listen_address" => listening_socket.to_string(),
+    );
+
+    Ok((listening_socket, server))
+}
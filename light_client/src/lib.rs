@@ -0,0 +1,328 @@
+//! A light client which follows the chain using only light client bootstraps and updates.
+//!
+//! The client is initialized from a `LightClientBootstrap` for a trusted block root and then
+//! verifies every update it receives against the sync committees it knows of, so that the headers
+//! served by its HTTP API can be trusted as much as the sync committee. Updates are fetched either
+//! from peers over libp2p RPC and gossip, or from the REST API of a beacon node.
+mod cli;
+pub mod config;
+pub mod http_api;
+mod network;
+mod rest;
+pub mod store;
+
+pub use cli::cli_app;
+pub use config::Config;
+
+use crate::store::{sync_committee_period, Error, LightClientStore, SyncProtocolUpdate};
+use environment::RuntimeContext;
+use eth2::types::GenesisData;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use parking_lot::RwLock;
+use slog::{debug, info, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSlotClock};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tree_hash::TreeHash;
+use types::{ChainSpec, EthSpec, Hash256, LightClientBootstrap, LightClientHeader, Slot};
+
+/// Timeout for requests to the beacon node.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// The verified view of the chain shared between the update sources and the HTTP API.
+pub struct LightClient<E: EthSpec> {
+    store: RwLock<Option<LightClientStore<E>>>,
+    checkpoint_root: Hash256,
+    genesis_validators_root: Hash256,
+    slot_clock: SystemTimeSlotClock,
+    spec: Arc<ChainSpec>,
+    log: Logger,
+}
+
+impl<E: EthSpec> LightClient<E> {
+    pub fn new(
+        checkpoint_root: Hash256,
+        genesis_validators_root: Hash256,
+        slot_clock: SystemTimeSlotClock,
+        spec: Arc<ChainSpec>,
+        log: Logger,
+    ) -> Self {
+        Self {
+            store: RwLock::new(None),
+            checkpoint_root,
+            genesis_validators_root,
+            slot_clock,
+            spec,
+            log,
+        }
+    }
+
+    pub fn checkpoint_root(&self) -> Hash256 {
+        self.checkpoint_root
+    }
+
+    pub fn genesis_validators_root(&self) -> Hash256 {
+        self.genesis_validators_root
+    }
+
+    pub fn slot_clock(&self) -> &SystemTimeSlotClock {
+        &self.slot_clock
+    }
+
+    pub fn spec(&self) -> &ChainSpec {
+        &self.spec
+    }
+
+    pub fn is_bootstrapped(&self) -> bool {
+        self.store.read().is_some()
+    }
+
+    pub fn finalized_header(&self) -> Option<LightClientHeader<E>> {
+        self.store
+            .read()
+            .as_ref()
+            .map(|store| store.finalized_header().clone())
+    }
+
+    pub fn optimistic_header(&self) -> Option<LightClientHeader<E>> {
+        self.store
+            .read()
+            .as_ref()
+            .map(|store| store.optimistic_header().clone())
+    }
+
+    /// Returns the first sync committee period for which updates are required, if any.
+    ///
+    /// Updates are required for every period from the finalized one until the current one, and
+    /// for the finalized period while its next sync committee is unknown.
+    pub fn required_update_period(&self) -> Option<(u64, u64)> {
+        let current_slot = self.slot_clock.now()?;
+        let current_period = sync_committee_period::<E>(current_slot, &self.spec).ok()?;
+        let store = self.store.read();
+        let store = store.as_ref()?;
+        let finalized_period = store.finalized_period(&self.spec).ok()?;
+
+        if !store.is_next_sync_committee_known() || finalized_period + 1 < current_period {
+            let count = std::cmp::max(current_period.saturating_sub(finalized_period), 1);
+            Some((finalized_period, count))
+        } else {
+            None
+        }
+    }
+
+    /// Initialize the store from `bootstrap`, if it hasn't been already.
+    pub fn process_bootstrap(&self, bootstrap: &LightClientBootstrap<E>) -> Result<(), Error> {
+        let mut store = self.store.write();
+        if store.is_some() {
+            return Ok(());
+        }
+
+        let new_store =
+            LightClientStore::from_bootstrap(bootstrap, self.checkpoint_root, &self.spec)?;
+        info!(
+            self.log,
+            "Light client bootstrapped";
+            "slot" => new_store.finalized_header().beacon().slot,
+            "root" => ?self.checkpoint_root,
+        );
+        *store = Some(new_store);
+        Ok(())
+    }
+
+    /// Verify `update` and apply it to the store, returning `true` if the optimistic or finalized
+    /// header changed.
+    ///
+    /// Updates received before the store is bootstrapped are ignored.
+    pub fn process_update(&self, update: impl Into<SyncProtocolUpdate<E>>) -> Result<bool, Error> {
+        let update = update.into();
+        let Some(current_slot) = self.slot_clock.now() else {
+            return Ok(false);
+        };
+
+        let mut store = self.store.write();
+        let Some(store) = store.as_mut() else {
+            return Ok(false);
+        };
+
+        let finalized_slot = store.finalized_header().beacon().slot;
+        let changed = store.process_update(
+            &update,
+            current_slot,
+            self.genesis_validators_root,
+            &self.spec,
+        )?;
+
+        if changed {
+            let finalized_header = store.finalized_header().beacon();
+            if finalized_header.slot > finalized_slot {
+                info!(
+                    self.log,
+                    "New finalized header";
+                    "slot" => finalized_header.slot,
+                    "root" => ?finalized_header.tree_hash_root(),
+                    "state_root" => ?finalized_header.state_root,
+                );
+            }
+            let optimistic_header = store.optimistic_header().beacon();
+            debug!(
+                self.log,
+                "Processed light client update";
+                "optimistic_slot" => optimistic_header.slot,
+                "optimistic_root" => ?optimistic_header.tree_hash_root(),
+            );
+        }
+        Ok(changed)
+    }
+
+    /// Run the per-slot processing of the store.
+    fn process_slot(&self, current_slot: Slot) {
+        if let Some(store) = self.store.write().as_mut() {
+            if let Err(e) = store.process_slot(current_slot, &self.spec) {
+                warn!(self.log, "Failed to process slot"; "error" => ?e);
+            }
+        }
+    }
+}
+
+/// Start the light client, running its update source and HTTP API on the `context` executor.
+pub async fn run<E: EthSpec>(context: RuntimeContext<E>, config: Config) -> Result<(), String> {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let spec = context.eth2_config.spec.clone();
+
+    let beacon_node = config
+        .beacon_node
+        .clone()
+        .map(|url| BeaconNodeHttpClient::new(url, Timeouts::set_all(HTTP_TIMEOUT)));
+
+    let known_genesis_validators_root = context
+        .eth2_network_config
+        .as_ref()
+        .filter(|network_config| network_config.genesis_state_is_known())
+        .map(|network_config| network_config.genesis_validators_root::<E>())
+        .transpose()?
+        .flatten();
+    let beacon_node_genesis = match &beacon_node {
+        Some(beacon_node) => Some(
+            beacon_node
+                .get_beacon_genesis()
+                .await
+                .map_err(|e| format!("Unable to read genesis from beacon node: {e:?}"))?
+                .data,
+        ),
+        None => None,
+    };
+    let (genesis_time, genesis_validators_root) = genesis(
+        config.genesis_time,
+        known_genesis_validators_root,
+        beacon_node_genesis,
+    )?;
+
+    let slot_clock = SystemTimeSlotClock::new(
+        spec.genesis_slot,
+        Duration::from_secs(genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+    let light_client = Arc::new(LightClient::new(
+        config.checkpoint_root,
+        genesis_validators_root,
+        slot_clock,
+        spec,
+        log.clone(),
+    ));
+
+    if config.http_api.enabled {
+        let ctx = Arc::new(http_api::Context {
+            config: config.http_api.clone(),
+            light_client: light_client.clone(),
+            log: log.clone(),
+        });
+        let exit = executor.exit();
+        let (_, server) = http_api::serve(ctx, exit)
+            .map_err(|e| format!("Unable to start HTTP API server: {e:?}"))?;
+        executor.spawn_without_exit(server, "light_client_http_api");
+    }
+
+    executor.spawn(
+        slot_timer(light_client.clone(), log.clone()),
+        "light_client_slot_timer",
+    );
+
+    match beacon_node {
+        Some(beacon_node) => {
+            info!(log, "Following the chain via beacon node"; "url" => %beacon_node);
+            rest::start(executor, light_client, beacon_node);
+        }
+        None => {
+            info!(log, "Following the chain via libp2p");
+            network::start(executor, light_client, config.network).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Determine the genesis time and validators root from the configuration and the beacon node.
+///
+/// The genesis validators root is part of the signature domain, so a beacon node which disagrees
+/// with the network configuration is rejected.
+fn genesis(
+    genesis_time: Option<u64>,
+    known_genesis_validators_root: Option<Hash256>,
+    beacon_node_genesis: Option<GenesisData>,
+) -> Result<(u64, Hash256), String> {
+    if let (Some(known), Some(genesis)) = (known_genesis_validators_root, &beacon_node_genesis) {
+        if known != genesis.genesis_validators_root {
+            return Err(format!(
+                "Beacon node is on a different network: genesis validators root {:?} does not \
+                 match {:?}",
+                genesis.genesis_validators_root, known
+            ));
+        }
+    }
+
+    let genesis_time = genesis_time
+        .or(beacon_node_genesis
+            .as_ref()
+            .map(|genesis| genesis.genesis_time))
+        .ok_or("The genesis time is unknown, provide --genesis-time")?;
+    let genesis_validators_root = known_genesis_validators_root
+        .or(beacon_node_genesis.map(|genesis| genesis.genesis_validators_root))
+        .ok_or("The genesis validators root is unknown for this network")?;
+    Ok((genesis_time, genesis_validators_root))
+}
+
+/// Run the per-slot processing of the store and log its status each slot.
+async fn slot_timer<E: EthSpec>(light_client: Arc<LightClient<E>>, log: Logger) {
+    loop {
+        let Some(duration_to_next_slot) = light_client.slot_clock.duration_to_next_slot() else {
+            warn!(log, "Unable to read the current slot");
+            sleep(light_client.slot_clock.slot_duration()).await;
+            continue;
+        };
+        sleep(duration_to_next_slot).await;
+
+        let Some(current_slot) = light_client.slot_clock.now() else {
+            continue;
+        };
+        light_client.process_slot(current_slot);
+
+        let store = light_client.store.read();
+        match store.as_ref() {
+            Some(store) => info!(
+                log,
+                "Following chain";
+                "current_slot" => current_slot,
+                "optimistic_slot" => store.optimistic_header().beacon().slot,
+                "finalized_slot" => store.finalized_header().beacon().slot,
+            ),
+            None => info!(
+                log,
+                "Waiting for bootstrap";
+                "current_slot" => current_slot,
+                "checkpoint_root" => ?light_client.checkpoint_root,
+            ),
+        }
+    }
+}
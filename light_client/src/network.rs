@@ -0,0 +1,335 @@
+//! Follows the chain over libp2p, using the light client RPC methods and gossip topics.
+//!
+//! The light client does not store or serve blocks. It answers `Status` requests with an empty
+//! chain so that peers never try to sync from it, and declines every other request.
+use crate::store::SyncProtocolUpdate;
+use crate::LightClient;
+use lighthouse_network::rpc::methods::LightClientUpdatesByRangeRequest;
+use lighthouse_network::rpc::{
+    self, LightClientBootstrapRequest, RequestType, RpcErrorResponse, StatusMessage,
+};
+use lighthouse_network::service::api_types::AppRequestId;
+use lighthouse_network::service::Network;
+use lighthouse_network::types::{GossipEncoding, GossipTopic, LIGHT_CLIENT_GOSSIP_TOPICS};
+use lighthouse_network::{
+    Context, MessageAcceptance, MessageId, NetworkConfig, NetworkEvent, NetworkGlobals, PeerAction,
+    PeerId, PeerRequestId, PubsubMessage, ReportSource, Response,
+};
+use slog::{debug, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::light_client_update::MAX_REQUEST_LIGHT_CLIENT_UPDATES;
+use types::{Epoch, EthSpec, ForkContext, Hash256, Slot};
+
+/// Start the libp2p service and spawn a task which drives the light client from its events.
+pub async fn start<E: EthSpec>(
+    executor: TaskExecutor,
+    light_client: Arc<LightClient<E>>,
+    config: NetworkConfig,
+) -> Result<(), String> {
+    let log = light_client.log.clone();
+    let spec = light_client.spec.clone();
+    let genesis_validators_root = light_client.genesis_validators_root();
+    let current_slot = light_client.slot_clock().now().unwrap_or(spec.genesis_slot);
+
+    let fork_context = Arc::new(ForkContext::new::<E>(
+        current_slot,
+        genesis_validators_root,
+        &spec,
+    ));
+    let context = Context {
+        config: Arc::new(config),
+        enr_fork_id: spec.enr_fork_id::<E>(current_slot, genesis_validators_root),
+        fork_context: fork_context.clone(),
+        chain_spec: spec,
+        libp2p_registry: None,
+    };
+    let (mut network, network_globals) = Network::new(executor.clone(), context, &log).await?;
+
+    let fork_digest = fork_context
+        .to_context_bytes(fork_context.current_fork())
+        .ok_or("Unknown fork digest for the current fork")?;
+    subscribe_light_client_topics(&mut network, fork_digest);
+
+    let mut service = NetworkService {
+        network,
+        network_globals,
+        fork_context,
+        light_client,
+        log,
+    };
+    executor.spawn(async move { service.run().await }, "light_client_network");
+    Ok(())
+}
+
+fn subscribe_light_client_topics<E: EthSpec>(network: &mut Network<E>, fork_digest: [u8; 4]) {
+    for kind in LIGHT_CLIENT_GOSSIP_TOPICS {
+        network.subscribe(GossipTopic::new(
+            kind,
+            GossipEncoding::default(),
+            fork_digest,
+        ));
+    }
+}
+
+struct NetworkService<E: EthSpec> {
+    network: Network<E>,
+    network_globals: Arc<NetworkGlobals<E>>,
+    fork_context: Arc<ForkContext>,
+    light_client: Arc<LightClient<E>>,
+    log: Logger,
+}
+
+impl<E: EthSpec> NetworkService<E> {
+    async fn run(&mut self) {
+        loop {
+            let slot_clock = self.light_client.slot_clock();
+            let duration_to_next_slot = slot_clock
+                .duration_to_next_slot()
+                .unwrap_or_else(|| slot_clock.slot_duration());
+
+            tokio::select! {
+                _ = sleep(duration_to_next_slot) => self.on_slot(),
+                event = self.network.next_event() => self.on_event(event),
+            }
+        }
+    }
+
+    /// Transition to a new fork if required and request whatever the light client is missing from
+    /// one of our peers.
+    fn on_slot(&mut self) {
+        let Some(current_slot) = self.light_client.slot_clock().now() else {
+            return;
+        };
+        self.update_fork(current_slot);
+
+        let peers = self
+            .network_globals
+            .peers
+            .read()
+            .connected_peer_ids()
+            .copied()
+            .collect::<Vec<_>>();
+        if peers.is_empty() {
+            return;
+        }
+        // Rotate through our peers so that a single unhelpful peer can't stall the client.
+        let peer_id = peers[current_slot.as_usize() % peers.len()];
+
+        if !self.light_client.is_bootstrapped() {
+            let root = self.light_client.checkpoint_root();
+            self.send_request(
+                peer_id,
+                RequestType::LightClientBootstrap(LightClientBootstrapRequest { root }),
+            );
+            return;
+        }
+
+        if let Some((start_period, count)) = self.light_client.required_update_period() {
+            self.send_request(
+                peer_id,
+                RequestType::LightClientUpdatesByRange(LightClientUpdatesByRangeRequest {
+                    start_period,
+                    count: std::cmp::min(count, MAX_REQUEST_LIGHT_CLIENT_UPDATES),
+                }),
+            );
+        }
+        self.send_request(peer_id, RequestType::LightClientFinalityUpdate);
+        self.send_request(peer_id, RequestType::LightClientOptimisticUpdate);
+    }
+
+    /// Subscribe to the topics of the fork at `current_slot` if it has just been activated.
+    ///
+    /// Unlike the beacon node we drop the topics of the previous fork immediately, since updates
+    /// from the previous fork are of no use once the sync committee signs with the new one.
+    fn update_fork(&mut self, current_slot: Slot) {
+        let spec = self.light_client.spec();
+        let new_fork = spec.fork_name_at_slot::<E>(current_slot);
+        let old_fork = self.fork_context.current_fork();
+        if new_fork == old_fork {
+            return;
+        }
+
+        let genesis_validators_root = self.light_client.genesis_validators_root();
+        let enr_fork_id = spec.enr_fork_id::<E>(current_slot, genesis_validators_root);
+        let fork_digest = enr_fork_id.fork_digest;
+        info!(
+            self.log,
+            "Transitioned to new fork";
+            "old_fork" => ?old_fork,
+            "new_fork" => ?new_fork,
+        );
+
+        self.fork_context.update_current_fork(new_fork);
+        self.network.update_fork_version(enr_fork_id);
+        subscribe_light_client_topics(&mut self.network, fork_digest);
+        self.network
+            .unsubscribe_from_fork_topics_except(fork_digest);
+    }
+
+    fn send_request(&mut self, peer_id: PeerId, request: RequestType<E>) {
+        if let Err((_, e)) = self
+            .network
+            .send_request(peer_id, AppRequestId::Router, request)
+        {
+            debug!(self.log, "Unable to send request"; "peer_id" => %peer_id, "error" => ?e);
+        }
+    }
+
+    fn status_message(&self) -> StatusMessage {
+        StatusMessage {
+            fork_digest: self
+                .fork_context
+                .to_context_bytes(self.fork_context.current_fork())
+                .unwrap_or_default(),
+            finalized_root: Hash256::zero(),
+            finalized_epoch: Epoch::new(0),
+            head_root: Hash256::zero(),
+            head_slot: Slot::new(0),
+        }
+    }
+
+    fn on_event(&mut self, event: NetworkEvent<E>) {
+        match event {
+            NetworkEvent::StatusPeer(peer_id) => {
+                self.send_request(peer_id, RequestType::Status(self.status_message()));
+            }
+            NetworkEvent::RequestReceived {
+                peer_id,
+                id,
+                request,
+            } => self.on_request(peer_id, id, request),
+            NetworkEvent::ResponseReceived {
+                peer_id, response, ..
+            } => self.on_response(peer_id, response),
+            NetworkEvent::RPCFailed { peer_id, error, .. } => {
+                debug!(self.log, "RPC request failed"; "peer_id" => %peer_id, "error" => %error);
+            }
+            NetworkEvent::PubsubMessage {
+                id,
+                source,
+                message,
+                ..
+            } => self.on_gossip(id, source, message),
+            NetworkEvent::PeerConnectedOutgoing(_)
+            | NetworkEvent::PeerConnectedIncoming(_)
+            | NetworkEvent::PeerDisconnected(_)
+            | NetworkEvent::NewListenAddr(_)
+            | NetworkEvent::ZeroListeners => {}
+        }
+    }
+
+    fn on_request(&mut self, peer_id: PeerId, id: PeerRequestId, request: rpc::Request<E>) {
+        match request.r#type {
+            RequestType::Status(_) => {
+                let status = self.status_message();
+                self.network
+                    .send_response(peer_id, id, request.id, Response::Status(status));
+            }
+            _ => self.network.send_error_response(
+                peer_id,
+                id,
+                request.id,
+                RpcErrorResponse::ResourceUnavailable,
+                "Light clients do not serve requests".to_string(),
+            ),
+        }
+    }
+
+    fn on_response(&mut self, peer_id: PeerId, response: Response<E>) {
+        let result = match response {
+            Response::LightClientBootstrap(bootstrap) => {
+                self.light_client.process_bootstrap(&bootstrap).map(|_| ())
+            }
+            Response::LightClientUpdatesByRange(Some(update)) => {
+                self.process_update(Arc::unwrap_or_clone(update))
+            }
+            Response::LightClientFinalityUpdate(update) => {
+                self.process_update(Arc::unwrap_or_clone(update))
+            }
+            Response::LightClientOptimisticUpdate(update) => {
+                self.process_update(Arc::unwrap_or_clone(update))
+            }
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            debug!(
+                self.log,
+                "Invalid light client response";
+                "peer_id" => %peer_id,
+                "error" => ?e,
+            );
+            if e.is_invalid() {
+                self.network.report_peer(
+                    &peer_id,
+                    PeerAction::LowToleranceError,
+                    ReportSource::RPC,
+                    "invalid_light_client_response",
+                );
+            }
+        }
+    }
+
+    fn process_update(
+        &self,
+        update: impl Into<SyncProtocolUpdate<E>>,
+    ) -> Result<(), crate::store::Error> {
+        self.light_client.process_update(update).map(|_| ())
+    }
+
+    fn on_gossip(&mut self, id: MessageId, source: PeerId, message: PubsubMessage<E>) {
+        let (update, signature_slot) = match message {
+            PubsubMessage::LightClientFinalityUpdate(update) => {
+                let signature_slot = *update.signature_slot();
+                (SyncProtocolUpdate::from(*update), signature_slot)
+            }
+            PubsubMessage::LightClientOptimisticUpdate(update) => {
+                let signature_slot = *update.signature_slot();
+                (SyncProtocolUpdate::from(*update), signature_slot)
+            }
+            _ => return,
+        };
+
+        let acceptance = if self.is_too_early(signature_slot) {
+            MessageAcceptance::Ignore
+        } else {
+            match self.light_client.process_update(update) {
+                Ok(true) => MessageAcceptance::Accept,
+                // Only updates which advance the store are forwarded.
+                Ok(false) => MessageAcceptance::Ignore,
+                Err(e) if e.is_invalid() => {
+                    debug!(
+                        self.log,
+                        "Invalid light client gossip";
+                        "peer_id" => %source,
+                        "error" => ?e,
+                    );
+                    MessageAcceptance::Reject
+                }
+                Err(_) => MessageAcceptance::Ignore,
+            }
+        };
+        self.network
+            .report_message_validation_result(&source, id, acceptance);
+    }
+
+    /// Updates must not be forwarded before a third of the signature slot has passed, see
+    /// `light_client_optimistic_update_verification.rs` in the beacon chain.
+    fn is_too_early(&self, signature_slot: Slot) -> bool {
+        let slot_clock = self.light_client.slot_clock();
+        let spec = self.light_client.spec();
+        let (Some(now), Some(start_time)) = (
+            slot_clock.now_duration(),
+            slot_clock.start_of(signature_slot),
+        ) else {
+            warn!(self.log, "Unable to read the slot clock");
+            return true;
+        };
+        let one_third_slot_duration = Duration::new(spec.seconds_per_slot / 3, 0);
+        now + spec.maximum_gossip_clock_disparity() < start_time + one_third_slot_duration
+    }
+}
@@ -0,0 +1,109 @@
+//! Follows the chain by polling the light client endpoints of a beacon node.
+use crate::LightClient;
+use eth2::BeaconNodeHttpClient;
+use slog::{debug, warn};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::light_client_update::MAX_REQUEST_LIGHT_CLIENT_UPDATES;
+use types::EthSpec;
+
+/// Spawn a task which fetches a bootstrap and then updates from `beacon_node` once per slot.
+pub fn start<E: EthSpec>(
+    executor: TaskExecutor,
+    light_client: Arc<LightClient<E>>,
+    beacon_node: BeaconNodeHttpClient,
+) {
+    executor.spawn(
+        async move {
+            loop {
+                poll_beacon_node(&light_client, &beacon_node).await;
+
+                // Poll a third of the way into the slot, when the sync committee of the current
+                // slot has had time to sign the head.
+                let slot_clock = light_client.slot_clock();
+                let delay = slot_clock.duration_to_next_slot().unwrap_or_default()
+                    + slot_clock.slot_duration() / 3;
+                sleep(delay).await;
+            }
+        },
+        "light_client_rest",
+    );
+}
+
+async fn poll_beacon_node<E: EthSpec>(
+    light_client: &LightClient<E>,
+    beacon_node: &BeaconNodeHttpClient,
+) {
+    let log = &light_client.log;
+
+    if !light_client.is_bootstrapped() {
+        match beacon_node
+            .get_light_client_bootstrap::<E>(light_client.checkpoint_root())
+            .await
+        {
+            Ok(Some(bootstrap)) => {
+                if let Err(e) = light_client.process_bootstrap(&bootstrap.data) {
+                    warn!(log, "Invalid light client bootstrap"; "error" => ?e);
+                    return;
+                }
+            }
+            Ok(None) => {
+                warn!(
+                    log,
+                    "Beacon node has no bootstrap for the checkpoint root";
+                    "checkpoint_root" => ?light_client.checkpoint_root(),
+                );
+                return;
+            }
+            Err(e) => {
+                warn!(log, "Unable to fetch light client bootstrap"; "error" => %e);
+                return;
+            }
+        }
+    }
+
+    if let Some((start_period, count)) = light_client.required_update_period() {
+        let count = std::cmp::min(count, MAX_REQUEST_LIGHT_CLIENT_UPDATES);
+        match beacon_node
+            .get_beacon_light_client_updates::<E>(start_period, count)
+            .await
+        {
+            Ok(updates) => {
+                for update in updates.into_iter().flatten() {
+                    if let Err(e) = light_client.process_update(update.data) {
+                        debug!(log, "Unable to apply light client update"; "error" => ?e);
+                    }
+                }
+            }
+            Err(e) => warn!(log, "Unable to fetch light client updates"; "error" => %e),
+        }
+    }
+
+    match beacon_node
+        .get_beacon_light_client_finality_update::<E>()
+        .await
+    {
+        Ok(Some(update)) => {
+            if let Err(e) = light_client.process_update(update.data) {
+                debug!(log, "Unable to apply finality update"; "error" => ?e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!(log, "Unable to fetch finality update"; "error" => %e),
+    }
+
+    match beacon_node
+        .get_beacon_light_client_optimistic_update::<E>()
+        .await
+    {
+        Ok(Some(update)) => {
+            if let Err(e) = light_client.process_update(update.data) {
+                debug!(log, "Unable to apply optimistic update"; "error" => ?e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!(log, "Unable to fetch optimistic update"; "error" => %e),
+    }
+}
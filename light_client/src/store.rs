@@ -0,0 +1,858 @@
+//! An implementation of the light client sync protocol.
+//!
+//! See: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md
+//!
+//! The store is initialized from a `LightClientBootstrap` for a trusted block root, and then
+//! follows the chain by verifying light client updates against the sync committees it knows of.
+//! Updates are only applied once they have been signed by a supermajority of the sync committee,
+//! so the client does not implement the spec's "force update" after `UPDATE_TIMEOUT`.
+//!
+//! None of these checks are shared with the beacon node, because it has none to share: the gossip
+//! verification in `beacon_chain`'s `light_client_finality_update_verification` and
+//! `light_client_optimistic_update_verification` only accepts updates which are identical to the
+//! ones the node built from its own chain, without checking branches or sync committee
+//! signatures. A light client has no chain to compare against, so it must verify every update
+//! against the sync committees in its store.
+use bls::PublicKey;
+use merkle_proof::verify_merkle_proof;
+use safe_arith::{ArithError, SafeArith};
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::light_client_update::{
+    CURRENT_SYNC_COMMITTEE_INDEX, CURRENT_SYNC_COMMITTEE_INDEX_ELECTRA, EXECUTION_PAYLOAD_INDEX,
+    FINALIZED_ROOT_INDEX, FINALIZED_ROOT_INDEX_ELECTRA, NEXT_SYNC_COMMITTEE_INDEX,
+    NEXT_SYNC_COMMITTEE_INDEX_ELECTRA,
+};
+use types::{
+    BeaconBlockHeader, ChainSpec, Domain, EthSpec, Hash256, LightClientBootstrap,
+    LightClientFinalityUpdate, LightClientHeader, LightClientOptimisticUpdate, LightClientUpdate,
+    SignedRoot, Slot, SyncAggregate, SyncCommittee,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The bootstrap is not for the trusted block root.
+    UntrustedBootstrap {
+        expected: Hash256,
+        found: Hash256,
+    },
+    /// The execution payload header of a light client header doesn't match its beacon block.
+    InvalidHeader {
+        slot: Slot,
+    },
+    InvalidCurrentSyncCommitteeBranch,
+    InvalidNextSyncCommitteeBranch,
+    InvalidFinalityBranch,
+    /// A finalized header at the genesis slot must be empty.
+    InvalidGenesisFinalizedHeader,
+    InsufficientParticipants {
+        participants: usize,
+    },
+    /// The signature slot is after the current slot.
+    FutureSignatureSlot {
+        signature_slot: Slot,
+        current_slot: Slot,
+    },
+    /// The signature, attested and finalized slots are not in decreasing order.
+    InconsistentSlots {
+        signature_slot: Slot,
+        attested_slot: Slot,
+        finalized_slot: Slot,
+    },
+    /// The update is signed by a sync committee the store doesn't know.
+    UnknownSyncCommittee {
+        signature_period: u64,
+    },
+    /// The update is not newer than the store and doesn't provide a new sync committee.
+    IrrelevantUpdate,
+    /// The update's next sync committee differs from the one already known for that period.
+    ConflictingNextSyncCommittee,
+    InvalidSyncCommitteePublicKey,
+    InvalidSignature,
+    ArithError(ArithError),
+}
+
+impl From<ArithError> for Error {
+    fn from(e: ArithError) -> Self {
+        Error::ArithError(e)
+    }
+}
+
+impl Error {
+    /// Returns `true` if the object the error arose from is invalid, rather than just not useful
+    /// to the store in its current state.
+    pub fn is_invalid(&self) -> bool {
+        match self {
+            Error::UntrustedBootstrap { .. }
+            | Error::InvalidHeader { .. }
+            | Error::InvalidCurrentSyncCommitteeBranch
+            | Error::InvalidNextSyncCommitteeBranch
+            | Error::InvalidFinalityBranch
+            | Error::InvalidGenesisFinalizedHeader
+            | Error::InconsistentSlots { .. }
+            | Error::ConflictingNextSyncCommittee
+            | Error::InvalidSyncCommitteePublicKey
+            | Error::InvalidSignature => true,
+            Error::InsufficientParticipants { .. }
+            | Error::FutureSignatureSlot { .. }
+            | Error::UnknownSyncCommittee { .. }
+            | Error::IrrelevantUpdate
+            | Error::ArithError(_) => false,
+        }
+    }
+}
+
+/// The parts of a light client update used by the sync protocol.
+///
+/// Full, finality and optimistic updates are all converted to this form, with the fields they
+/// don't carry set to `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncProtocolUpdate<E: EthSpec> {
+    pub attested_header: LightClientHeader<E>,
+    pub next_sync_committee: Option<(Arc<SyncCommittee<E>>, Vec<Hash256>)>,
+    pub finalized_header: Option<(LightClientHeader<E>, Vec<Hash256>)>,
+    pub sync_aggregate: SyncAggregate<E>,
+    pub signature_slot: Slot,
+}
+
+impl<E: EthSpec> From<LightClientUpdate<E>> for SyncProtocolUpdate<E> {
+    fn from(update: LightClientUpdate<E>) -> Self {
+        let (attested_header, next_sync_committee, finalized_header) = match &update {
+            LightClientUpdate::Altair(inner) => (
+                LightClientHeader::Altair(inner.attested_header.clone()),
+                (
+                    inner.next_sync_committee.clone(),
+                    inner.next_sync_committee_branch.to_vec(),
+                ),
+                (
+                    LightClientHeader::Altair(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientUpdate::Capella(inner) => (
+                LightClientHeader::Capella(inner.attested_header.clone()),
+                (
+                    inner.next_sync_committee.clone(),
+                    inner.next_sync_committee_branch.to_vec(),
+                ),
+                (
+                    LightClientHeader::Capella(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientUpdate::Deneb(inner) => (
+                LightClientHeader::Deneb(inner.attested_header.clone()),
+                (
+                    inner.next_sync_committee.clone(),
+                    inner.next_sync_committee_branch.to_vec(),
+                ),
+                (
+                    LightClientHeader::Deneb(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientUpdate::Electra(inner) => (
+                LightClientHeader::Electra(inner.attested_header.clone()),
+                (
+                    inner.next_sync_committee.clone(),
+                    inner.next_sync_committee_branch.to_vec(),
+                ),
+                (
+                    LightClientHeader::Electra(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+        };
+
+        // Empty branches indicate that the update doesn't carry the corresponding field.
+        Self {
+            attested_header,
+            next_sync_committee: Some(next_sync_committee)
+                .filter(|(_, branch)| !is_empty_branch(branch)),
+            finalized_header: Some(finalized_header).filter(|(_, branch)| !is_empty_branch(branch)),
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+}
+
+impl<E: EthSpec> From<LightClientFinalityUpdate<E>> for SyncProtocolUpdate<E> {
+    fn from(update: LightClientFinalityUpdate<E>) -> Self {
+        let (attested_header, finalized_header) = match &update {
+            LightClientFinalityUpdate::Altair(inner) => (
+                LightClientHeader::Altair(inner.attested_header.clone()),
+                (
+                    LightClientHeader::Altair(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientFinalityUpdate::Capella(inner) => (
+                LightClientHeader::Capella(inner.attested_header.clone()),
+                (
+                    LightClientHeader::Capella(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientFinalityUpdate::Deneb(inner) => (
+                LightClientHeader::Deneb(inner.attested_header.clone()),
+                (
+                    LightClientHeader::Deneb(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+            LightClientFinalityUpdate::Electra(inner) => (
+                LightClientHeader::Electra(inner.attested_header.clone()),
+                (
+                    LightClientHeader::Electra(inner.finalized_header.clone()),
+                    inner.finality_branch.to_vec(),
+                ),
+            ),
+        };
+
+        Self {
+            attested_header,
+            next_sync_committee: None,
+            finalized_header: Some(finalized_header),
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+}
+
+impl<E: EthSpec> From<LightClientOptimisticUpdate<E>> for SyncProtocolUpdate<E> {
+    fn from(update: LightClientOptimisticUpdate<E>) -> Self {
+        let attested_header = match &update {
+            LightClientOptimisticUpdate::Altair(inner) => {
+                LightClientHeader::Altair(inner.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Capella(inner) => {
+                LightClientHeader::Capella(inner.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Deneb(inner) => {
+                LightClientHeader::Deneb(inner.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Electra(inner) => {
+                LightClientHeader::Electra(inner.attested_header.clone())
+            }
+        };
+
+        Self {
+            attested_header,
+            next_sync_committee: None,
+            finalized_header: None,
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+}
+
+/// The verified state of a light client, as `LightClientStore` in the spec.
+#[derive(Debug, Clone)]
+pub struct LightClientStore<E: EthSpec> {
+    finalized_header: LightClientHeader<E>,
+    current_sync_committee: Arc<SyncCommittee<E>>,
+    next_sync_committee: Option<Arc<SyncCommittee<E>>>,
+    optimistic_header: LightClientHeader<E>,
+    previous_max_active_participants: usize,
+    current_max_active_participants: usize,
+}
+
+impl<E: EthSpec> LightClientStore<E> {
+    /// Initialize the store from a `bootstrap` for the `trusted_block_root`.
+    pub fn from_bootstrap(
+        bootstrap: &LightClientBootstrap<E>,
+        trusted_block_root: Hash256,
+        spec: &ChainSpec,
+    ) -> Result<Self, Error> {
+        let (header, current_sync_committee_branch) = match bootstrap {
+            LightClientBootstrap::Altair(inner) => (
+                LightClientHeader::Altair(inner.header.clone()),
+                inner.current_sync_committee_branch.to_vec(),
+            ),
+            LightClientBootstrap::Capella(inner) => (
+                LightClientHeader::Capella(inner.header.clone()),
+                inner.current_sync_committee_branch.to_vec(),
+            ),
+            LightClientBootstrap::Deneb(inner) => (
+                LightClientHeader::Deneb(inner.header.clone()),
+                inner.current_sync_committee_branch.to_vec(),
+            ),
+            LightClientBootstrap::Electra(inner) => (
+                LightClientHeader::Electra(inner.header.clone()),
+                inner.current_sync_committee_branch.to_vec(),
+            ),
+        };
+        let current_sync_committee = bootstrap.current_sync_committee().clone();
+
+        verify_header(&header, spec)?;
+
+        let block_root = header.beacon().tree_hash_root();
+        if block_root != trusted_block_root {
+            return Err(Error::UntrustedBootstrap {
+                expected: trusted_block_root,
+                found: block_root,
+            });
+        }
+
+        let gindex = if is_electra::<E>(header.beacon().slot, spec) {
+            CURRENT_SYNC_COMMITTEE_INDEX_ELECTRA
+        } else {
+            CURRENT_SYNC_COMMITTEE_INDEX
+        };
+        if !verify_branch(
+            current_sync_committee.tree_hash_root(),
+            &current_sync_committee_branch,
+            gindex,
+            header.beacon().state_root,
+        ) {
+            return Err(Error::InvalidCurrentSyncCommitteeBranch);
+        }
+
+        Ok(Self {
+            finalized_header: header.clone(),
+            current_sync_committee,
+            next_sync_committee: None,
+            optimistic_header: header,
+            previous_max_active_participants: 0,
+            current_max_active_participants: 0,
+        })
+    }
+
+    pub fn finalized_header(&self) -> &LightClientHeader<E> {
+        &self.finalized_header
+    }
+
+    pub fn optimistic_header(&self) -> &LightClientHeader<E> {
+        &self.optimistic_header
+    }
+
+    /// The sync committee period of the finalized header.
+    pub fn finalized_period(&self, spec: &ChainSpec) -> Result<u64, Error> {
+        sync_committee_period::<E>(self.finalized_header.beacon().slot, spec)
+    }
+
+    pub fn is_next_sync_committee_known(&self) -> bool {
+        self.next_sync_committee.is_some()
+    }
+
+    /// Rotate the participation counts at the start of each sync committee period.
+    pub fn process_slot(&mut self, current_slot: Slot, spec: &ChainSpec) -> Result<(), Error> {
+        let update_timeout = spec
+            .epochs_per_sync_committee_period
+            .as_u64()
+            .safe_mul(E::slots_per_epoch())?;
+        if current_slot.as_u64().safe_rem(update_timeout)? == 0 {
+            self.previous_max_active_participants = self.current_max_active_participants;
+            self.current_max_active_participants = 0;
+        }
+        Ok(())
+    }
+
+    /// Verify `update` and apply it to the store, returning `true` if the optimistic or finalized
+    /// header changed.
+    pub fn process_update(
+        &mut self,
+        update: &SyncProtocolUpdate<E>,
+        current_slot: Slot,
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) -> Result<bool, Error> {
+        self.validate_update(update, current_slot, genesis_validators_root, spec)?;
+
+        let participants = update.sync_aggregate.num_set_bits();
+        let attested_slot = update.attested_header.beacon().slot;
+        let mut changed = false;
+
+        self.current_max_active_participants =
+            std::cmp::max(self.current_max_active_participants, participants);
+
+        // The optimistic header only needs the signatures of more than half of the recently
+        // observed participants.
+        let safety_threshold = std::cmp::max(
+            self.previous_max_active_participants,
+            self.current_max_active_participants,
+        ) / 2;
+        if participants > safety_threshold && attested_slot > self.optimistic_header.beacon().slot {
+            self.optimistic_header = update.attested_header.clone();
+            changed = true;
+        }
+
+        // The finalized header and sync committees need a supermajority.
+        let has_supermajority =
+            participants.safe_mul(3)? >= E::sync_committee_size().safe_mul(2)?;
+        let has_finalized_next_sync_committee = match &update.finalized_header {
+            Some((finalized_header, _)) => {
+                self.next_sync_committee.is_none()
+                    && update.next_sync_committee.is_some()
+                    && sync_committee_period::<E>(finalized_header.beacon().slot, spec)?
+                        == sync_committee_period::<E>(attested_slot, spec)?
+            }
+            None => false,
+        };
+        if let Some((finalized_header, _)) = &update.finalized_header {
+            if has_supermajority
+                && (finalized_header.beacon().slot > self.finalized_header.beacon().slot
+                    || has_finalized_next_sync_committee)
+            {
+                self.apply_update(update, finalized_header, spec)?;
+                changed = true;
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// As `validate_light_client_update` in the spec.
+    fn validate_update(
+        &self,
+        update: &SyncProtocolUpdate<E>,
+        current_slot: Slot,
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) -> Result<(), Error> {
+        let sync_aggregate = &update.sync_aggregate;
+        let participants = sync_aggregate.num_set_bits();
+        if (participants as u64) < spec.min_sync_committee_participants {
+            return Err(Error::InsufficientParticipants { participants });
+        }
+
+        verify_header(&update.attested_header, spec)?;
+        let attested_slot = update.attested_header.beacon().slot;
+        let finalized_slot = update
+            .finalized_header
+            .as_ref()
+            .map_or(spec.genesis_slot, |(header, _)| header.beacon().slot);
+        if update.signature_slot > current_slot {
+            return Err(Error::FutureSignatureSlot {
+                signature_slot: update.signature_slot,
+                current_slot,
+            });
+        }
+        if update.signature_slot <= attested_slot || attested_slot < finalized_slot {
+            return Err(Error::InconsistentSlots {
+                signature_slot: update.signature_slot,
+                attested_slot,
+                finalized_slot,
+            });
+        }
+
+        let store_period = self.finalized_period(spec)?;
+        let signature_period = sync_committee_period::<E>(update.signature_slot, spec)?;
+        let sync_committee = if signature_period == store_period {
+            &self.current_sync_committee
+        } else {
+            match &self.next_sync_committee {
+                Some(next_sync_committee) if signature_period == store_period.safe_add(1)? => {
+                    next_sync_committee
+                }
+                _ => return Err(Error::UnknownSyncCommittee { signature_period }),
+            }
+        };
+
+        let attested_period = sync_committee_period::<E>(attested_slot, spec)?;
+        let has_next_sync_committee = self.next_sync_committee.is_none()
+            && update.next_sync_committee.is_some()
+            && attested_period == store_period;
+        if attested_slot <= self.finalized_header.beacon().slot && !has_next_sync_committee {
+            return Err(Error::IrrelevantUpdate);
+        }
+
+        let is_electra = is_electra::<E>(attested_slot, spec);
+        let state_root = update.attested_header.beacon().state_root;
+
+        if let Some((finalized_header, finality_branch)) = &update.finalized_header {
+            let finalized_root = if finalized_header.beacon().slot == spec.genesis_slot {
+                if *finalized_header.beacon() != BeaconBlockHeader::empty() {
+                    return Err(Error::InvalidGenesisFinalizedHeader);
+                }
+                Hash256::zero()
+            } else {
+                verify_header(finalized_header, spec)?;
+                finalized_header.beacon().tree_hash_root()
+            };
+            let gindex = if is_electra {
+                FINALIZED_ROOT_INDEX_ELECTRA
+            } else {
+                FINALIZED_ROOT_INDEX
+            };
+            if !verify_branch(finalized_root, finality_branch, gindex, state_root) {
+                return Err(Error::InvalidFinalityBranch);
+            }
+        }
+
+        if let Some((next_sync_committee, next_sync_committee_branch)) = &update.next_sync_committee
+        {
+            if attested_period == store_period {
+                if let Some(known_next_sync_committee) = &self.next_sync_committee {
+                    if known_next_sync_committee != next_sync_committee {
+                        return Err(Error::ConflictingNextSyncCommittee);
+                    }
+                }
+            }
+            let gindex = if is_electra {
+                NEXT_SYNC_COMMITTEE_INDEX_ELECTRA
+            } else {
+                NEXT_SYNC_COMMITTEE_INDEX
+            };
+            if !verify_branch(
+                next_sync_committee.tree_hash_root(),
+                next_sync_committee_branch,
+                gindex,
+                state_root,
+            ) {
+                return Err(Error::InvalidNextSyncCommitteeBranch);
+            }
+        }
+
+        let participant_pubkeys = sync_committee
+            .pubkeys
+            .iter()
+            .zip(sync_aggregate.sync_committee_bits.iter())
+            .filter(|(_, bit)| *bit)
+            .map(|(pubkey, _)| pubkey.decompress())
+            .collect::<Result<Vec<PublicKey>, _>>()
+            .map_err(|_| Error::InvalidSyncCommitteePublicKey)?;
+
+        // The sync committee signs the attested block in the slot before the signature slot.
+        let fork_version_slot = std::cmp::max(update.signature_slot, Slot::new(1)).safe_sub(1)?;
+        let fork_version =
+            spec.fork_version_for_name(spec.fork_name_at_slot::<E>(fork_version_slot));
+        let domain =
+            spec.compute_domain(Domain::SyncCommittee, fork_version, genesis_validators_root);
+        let signing_root = update.attested_header.beacon().signing_root(domain);
+        if !sync_aggregate
+            .sync_committee_signature
+            .fast_aggregate_verify(
+                signing_root,
+                &participant_pubkeys.iter().collect::<Vec<_>>(),
+            )
+        {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(())
+    }
+
+    /// As `apply_light_client_update` in the spec.
+    fn apply_update(
+        &mut self,
+        update: &SyncProtocolUpdate<E>,
+        finalized_header: &LightClientHeader<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), Error> {
+        let store_period = self.finalized_period(spec)?;
+        let finalized_period = sync_committee_period::<E>(finalized_header.beacon().slot, spec)?;
+        let next_sync_committee = update
+            .next_sync_committee
+            .as_ref()
+            .map(|(committee, _)| committee.clone());
+
+        if self.next_sync_committee.is_none() {
+            if finalized_period != store_period {
+                return Err(Error::IrrelevantUpdate);
+            }
+            self.next_sync_committee = next_sync_committee;
+        } else if finalized_period == store_period.safe_add(1)? {
+            if let Some(current_sync_committee) = self.next_sync_committee.take() {
+                self.current_sync_committee = current_sync_committee;
+            }
+            self.next_sync_committee = next_sync_committee;
+            self.previous_max_active_participants = self.current_max_active_participants;
+            self.current_max_active_participants = 0;
+        }
+
+        if finalized_header.beacon().slot > self.finalized_header.beacon().slot {
+            self.finalized_header = finalized_header.clone();
+            if self.finalized_header.beacon().slot > self.optimistic_header.beacon().slot {
+                self.optimistic_header = self.finalized_header.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// As `is_valid_light_client_header` in the spec: check the execution payload header against the
+/// beacon block body.
+fn verify_header<E: EthSpec>(header: &LightClientHeader<E>, spec: &ChainSpec) -> Result<(), Error> {
+    let (execution_root, execution_branch) = match header {
+        LightClientHeader::Altair(_) => return Ok(()),
+        LightClientHeader::Capella(inner) => (
+            inner.execution.tree_hash_root(),
+            inner.execution_branch.to_vec(),
+        ),
+        LightClientHeader::Deneb(inner) => (
+            inner.execution.tree_hash_root(),
+            inner.execution_branch.to_vec(),
+        ),
+        LightClientHeader::Electra(inner) => (
+            inner.execution.tree_hash_root(),
+            inner.execution_branch.to_vec(),
+        ),
+    };
+
+    let slot = header.beacon().slot;
+    let is_valid = if spec.fork_name_at_slot::<E>(slot).capella_enabled() {
+        verify_branch(
+            execution_root,
+            &execution_branch,
+            EXECUTION_PAYLOAD_INDEX,
+            header.beacon().body_root,
+        )
+    } else {
+        // Blocks from before Capella have no execution header to prove.
+        is_empty_branch(&execution_branch)
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidHeader { slot })
+    }
+}
+
+/// Verify a Merkle `branch` for the `leaf` at the generalized index `gindex` of the tree with
+/// the given `root`.
+fn verify_branch(leaf: Hash256, branch: &[Hash256], gindex: usize, root: Hash256) -> bool {
+    let depth = gindex.ilog2() as usize;
+    let index = gindex ^ (1 << depth);
+    verify_merkle_proof(leaf, branch, depth, index, root)
+}
+
+fn is_empty_branch(branch: &[Hash256]) -> bool {
+    branch.iter().all(Hash256::is_zero)
+}
+
+fn is_electra<E: EthSpec>(slot: Slot, spec: &ChainSpec) -> bool {
+    spec.fork_name_at_slot::<E>(slot).electra_enabled()
+}
+
+pub fn sync_committee_period<E: EthSpec>(slot: Slot, spec: &ChainSpec) -> Result<u64, Error> {
+    Ok(slot
+        .epoch(E::slots_per_epoch())
+        .sync_committee_period(spec)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+        LightClientStrategy, SyncCommitteeStrategy,
+    };
+    use bls::AggregateSignature;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 64;
+
+    async fn get_harness() -> Option<BeaconChainHarness<EphemeralHarnessType<E>>> {
+        let spec = test_spec::<E>();
+        // Light clients are only supported from Altair.
+        if spec.altair_fork_epoch.is_none() {
+            return None;
+        }
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec.into())
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        extend_chain(&harness, 5).await;
+        Some(harness)
+    }
+
+    async fn extend_chain(harness: &BeaconChainHarness<EphemeralHarnessType<E>>, epochs: u64) {
+        harness
+            .extend_chain_with_sync(
+                (epochs * E::slots_per_epoch()) as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+                SyncCommitteeStrategy::AllValidators,
+                LightClientStrategy::Enabled,
+            )
+            .await;
+    }
+
+    fn bootstrap_store(
+        harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    ) -> LightClientStore<E> {
+        let finalized_root = harness
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .root;
+        let (bootstrap, _) = harness
+            .chain
+            .get_light_client_bootstrap(&finalized_root)
+            .unwrap()
+            .unwrap();
+        LightClientStore::from_bootstrap(&bootstrap, finalized_root, &harness.spec).unwrap()
+    }
+
+    fn latest_finality_update(
+        harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    ) -> SyncProtocolUpdate<E> {
+        harness
+            .chain
+            .light_client_server_cache
+            .get_latest_finality_update()
+            .unwrap()
+            .into()
+    }
+
+    #[tokio::test]
+    async fn bootstrap_requires_trusted_root() {
+        let Some(harness) = get_harness().await else {
+            return;
+        };
+        let finalized_root = harness
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .root;
+        let (bootstrap, _) = harness
+            .chain
+            .get_light_client_bootstrap(&finalized_root)
+            .unwrap()
+            .unwrap();
+
+        let store =
+            LightClientStore::from_bootstrap(&bootstrap, finalized_root, &harness.spec).unwrap();
+        assert_eq!(
+            store.finalized_header().beacon().tree_hash_root(),
+            finalized_root
+        );
+
+        let untrusted_root = Hash256::repeat_byte(1);
+        assert_eq!(
+            LightClientStore::from_bootstrap(&bootstrap, untrusted_root, &harness.spec)
+                .unwrap_err(),
+            Error::UntrustedBootstrap {
+                expected: untrusted_root,
+                found: finalized_root,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn finality_update_advances_store() {
+        let Some(harness) = get_harness().await else {
+            return;
+        };
+        let mut store = bootstrap_store(&harness);
+        let bootstrap_slot = store.finalized_header().beacon().slot;
+
+        extend_chain(&harness, 2).await;
+        let update = latest_finality_update(&harness);
+        let (finalized_header, _) = update.finalized_header.clone().unwrap();
+        assert!(finalized_header.beacon().slot > bootstrap_slot);
+
+        assert!(store
+            .process_update(
+                &update,
+                harness.get_current_slot(),
+                harness.chain.genesis_validators_root,
+                &harness.spec,
+            )
+            .unwrap());
+        assert_eq!(store.finalized_header(), &finalized_header);
+        assert_eq!(store.optimistic_header(), &update.attested_header);
+
+        // The same update doesn't change the store again.
+        assert!(!store
+            .process_update(
+                &update,
+                harness.get_current_slot(),
+                harness.chain.genesis_validators_root,
+                &harness.spec,
+            )
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn optimistic_update_only_advances_optimistic_header() {
+        let Some(harness) = get_harness().await else {
+            return;
+        };
+        let mut store = bootstrap_store(&harness);
+        let finalized_header = store.finalized_header().clone();
+
+        let update: SyncProtocolUpdate<E> = harness
+            .chain
+            .light_client_server_cache
+            .get_latest_optimistic_update()
+            .unwrap()
+            .into();
+        assert!(store
+            .process_update(
+                &update,
+                harness.get_current_slot(),
+                harness.chain.genesis_validators_root,
+                &harness.spec,
+            )
+            .unwrap());
+        assert_eq!(store.optimistic_header(), &update.attested_header);
+        assert_eq!(store.finalized_header(), &finalized_header);
+    }
+
+    #[tokio::test]
+    async fn invalid_updates_are_rejected() {
+        let Some(harness) = get_harness().await else {
+            return;
+        };
+        let mut store = bootstrap_store(&harness);
+        extend_chain(&harness, 2).await;
+        let update = latest_finality_update(&harness);
+        let current_slot = harness.get_current_slot();
+        let genesis_validators_root = harness.chain.genesis_validators_root;
+
+        let mut bad_signature = update.clone();
+        bad_signature.sync_aggregate.sync_committee_signature = AggregateSignature::infinity();
+        assert_eq!(
+            store.process_update(
+                &bad_signature,
+                current_slot,
+                genesis_validators_root,
+                &harness.spec
+            ),
+            Err(Error::InvalidSignature)
+        );
+
+        let mut bad_finality_branch = update.clone();
+        if let Some((_, branch)) = bad_finality_branch.finalized_header.as_mut() {
+            branch[0] = Hash256::repeat_byte(1);
+        }
+        assert_eq!(
+            store.process_update(
+                &bad_finality_branch,
+                current_slot,
+                genesis_validators_root,
+                &harness.spec
+            ),
+            Err(Error::InvalidFinalityBranch)
+        );
+
+        let future_slot = update.signature_slot - 1;
+        assert_eq!(
+            store.process_update(&update, future_slot, genesis_validators_root, &harness.spec),
+            Err(Error::FutureSignatureSlot {
+                signature_slot: update.signature_slot,
+                current_slot: future_slot,
+            })
+        );
+
+        // The store is unchanged by the invalid updates.
+        assert!(store
+            .process_update(
+                &update,
+                current_slot,
+                genesis_validators_root,
+                &harness.spec
+            )
+            .unwrap());
+    }
+}
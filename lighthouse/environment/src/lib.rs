@@ -356,6 +356,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
+            sighup_shutdown: true,
         })
     }
 }
@@ -375,6 +376,9 @@ pub struct Environment<E: EthSpec> {
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
+    /// Whether a SIGHUP shuts down the process.
+    #[cfg_attr(not(target_family = "unix"), allow(dead_code))]
+    sighup_shutdown: bool,
 }
 
 impl<E: EthSpec> Environment<E> {
@@ -418,6 +422,12 @@ impl<E: EthSpec> Environment<E> {
         }
     }
 
+    /// Stops a SIGHUP from shutting down the process, for when a service reloads its
+    /// configuration on SIGHUP instead.
+    pub fn disable_sighup_shutdown(&mut self) {
+        self.sighup_shutdown = false;
+    }
+
     /// Block the current thread until a shutdown signal is received.
     ///
     /// This can be either the user Ctrl-C'ing or a task requesting to shutdown.
@@ -454,12 +464,14 @@ impl<E: EthSpec> Environment<E> {
             }
 
             // setup for handling a SIGHUP
-            match signal(SignalKind::hangup()) {
-                Ok(hup_stream) => {
-                    let hup = SignalFuture::new(hup_stream, "Received SIGHUP");
-                    handles.push(hup);
+            if self.sighup_shutdown {
+                match signal(SignalKind::hangup()) {
+                    Ok(hup_stream) => {
+                        let hup = SignalFuture::new(hup_stream, "Received SIGHUP");
+                        handles.push(hup);
+                    }
+                    Err(e) => error!(self.log, "Could not register SIGHUP handler"; "error" => e),
                 }
-                Err(e) => error!(self.log, "Could not register SIGHUP handler"; "error" => e),
             }
 
             future::select(inner_shutdown, future::select_all(handles.into_iter())).await
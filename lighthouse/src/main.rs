@@ -10,7 +10,9 @@ use clap_utils::{
     flags::DISABLE_MALLOC_TUNING_FLAG, get_color_style, get_eth2_network_config, FLAG_HEADER,
 };
 use cli::LighthouseSubcommands;
-use directory::{parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_VALIDATOR_DIR};
+use directory::{
+    parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_LIGHT_CLIENT_DIR, DEFAULT_VALIDATOR_DIR,
+};
 use environment::{EnvironmentBuilder, LoggerConfig};
 use eth2_network_config::{Eth2NetworkConfig, DEFAULT_HARDCODED_NETWORK, HARDCODED_NET_NAMES};
 use ethereum_hashing::have_sha_extensions;
@@ -399,6 +401,7 @@ fn main() {
         .subcommand(beacon_node::cli_app())
         .subcommand(boot_node::cli_app())
         .subcommand(validator_client::cli_app())
+        .subcommand(light_client::cli_app())
         .subcommand(account_manager::cli_app())
         .subcommand(validator_manager::cli_app());
 
@@ -549,6 +552,13 @@ fn run<E: EthSpec>(
                         .with_extension("log"),
                 )
             }
+            Some(("light_client", _)) => Some(
+                parse_path_or_default(matches, "datadir")?
+                    .join(DEFAULT_LIGHT_CLIENT_DIR)
+                    .join("logs")
+                    .join("light_client")
+                    .with_extension("log"),
+            ),
             _ => None,
         };
     }
@@ -691,6 +701,10 @@ fn run<E: EthSpec>(
             let executor = context.executor.clone();
             let mut config = beacon_node::get_config::<E>(matches, &context)?;
             config.logger_config = logger_config;
+            // The rate limits file is reloaded on SIGHUP, so it must not stop the node.
+            if config.network.rate_limits_file.is_some() {
+                environment.disable_sighup_shutdown();
+            }
             // Dump configs if `dump-config` or `dump-chain-config` flags are set
             clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
 
@@ -761,6 +775,27 @@ fn run<E: EthSpec>(
                 "validator_client",
             );
         }
+        Some(("light_client", matches)) => {
+            let context = environment.core_context();
+            let log = context.log().clone();
+            let executor = context.executor.clone();
+            let config = light_client::Config::from_cli(matches, &context)
+                .map_err(|e| format!("Unable to initialize light client config: {}", e))?;
+
+            executor.clone().spawn(
+                async move {
+                    if let Err(e) = light_client::run(context, config).await {
+                        crit!(log, "Failed to start light client"; "reason" => e);
+                        // Ignore the error since it always occurs during normal operation when
+                        // shutting down.
+                        let _ = executor
+                            .shutdown_sender()
+                            .try_send(ShutdownReason::Failure("Failed to start light client"));
+                    }
+                },
+                "light_client",
+            );
+        }
         _ => {
             crit!(log, "No subcommand supplied. See --help .");
             return Err("No subcommand supplied.".into());
@@ -2,11 +2,16 @@ use beacon_node::ClientConfig as Config;
 
 use crate::exec::{CommandLineTestExec, CompletedTest};
 use beacon_node::beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR, DEFAULT_RE_ORG_HEAD_THRESHOLD,
-    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
+    AttestationPacking, DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR,
+    DEFAULT_RE_ORG_HEAD_THRESHOLD, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
 };
+use beacon_node::beacon_chain::fork_choice_journal::ForkChoiceJournalConfig;
 use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
-use beacon_processor::BeaconProcessorConfig;
+use beacon_node::beacon_chain::historical_block_provider::HistoricalBlockSource;
+use beacon_node::beacon_chain::http_backfill::BackfillSource;
+use beacon_node::beacon_chain::store::retention::RetentionPolicy;
+use beacon_processor::{BeaconProcessorConfig, FlowWeights, SchedulerMode, WorkType};
+use db_encryption::KeySource;
 use eth1::Eth1Endpoint;
 use lighthouse_network::PeerId;
 use lighthouse_version;
@@ -232,6 +237,52 @@ fn checkpoint_sync_url_timeout_default() {
         });
 }
 
+#[test]
+fn checkpoint_sync_multiple_urls_genesis_state_url() {
+    CommandLineTest::new()
+        .flag(
+            "checkpoint-sync-url",
+            Some("http://node-a:5052,http://node-b:5052"),
+        )
+        .flag("checkpoint-sync-quorum", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.genesis_state_url.as_deref(),
+                Some("http://node-a:5052/")
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn checkpoint_sync_quorum_exceeds_urls() {
+    CommandLineTest::new()
+        .flag(
+            "checkpoint-sync-url",
+            Some("http://node-a:5052,http://node-b:5052"),
+        )
+        .flag("checkpoint-sync-quorum", Some("3"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn checkpoint_sync_quorum_zero() {
+    CommandLineTest::new()
+        .flag("checkpoint-sync-url", Some("http://node-a:5052"))
+        .flag("checkpoint-sync-quorum", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn checkpoint_sync_quorum_requires_url() {
+    CommandLineTest::new()
+        .flag("checkpoint-sync-quorum", Some("1"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn prepare_payload_lookahead_default() {
     CommandLineTest::new()
@@ -297,6 +348,44 @@ fn paranoid_block_proposal_on() {
         .with_config(|config| assert!(config.chain.paranoid_block_proposal));
 }
 
+#[test]
+fn attestation_packing_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.attestation_packing, AttestationPacking::Greedy)
+        });
+}
+
+#[test]
+fn attestation_packing_unique_attesters() {
+    CommandLineTest::new()
+        .flag("attestation-packing", Some("unique-attesters"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.attestation_packing,
+                AttestationPacking::UniqueAttesters
+            )
+        });
+}
+
+#[test]
+fn attestation_packing_exact() {
+    CommandLineTest::new()
+        .flag("attestation-packing", Some("exact"))
+        .flag("attestation-packing-time-budget", Some("200"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.attestation_packing,
+                AttestationPacking::Exact {
+                    time_budget_ms: 200
+                }
+            )
+        });
+}
+
 #[test]
 fn reset_payload_statuses_default() {
     CommandLineTest::new()
@@ -378,6 +467,146 @@ fn trusted_peers_flag() {
         });
 }
 
+#[test]
+fn historical_blocks_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.historical_block_source, None));
+}
+
+#[test]
+fn historical_blocks_era_dir_flag() {
+    CommandLineTest::new()
+        .flag("historical-blocks-era-dir", Some("/tmp/era"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.historical_block_source,
+                Some(HistoricalBlockSource::EraDir(PathBuf::from("/tmp/era")))
+            )
+        });
+}
+
+#[test]
+fn historical_blocks_datadir_flag() {
+    CommandLineTest::new()
+        .flag("historical-blocks-datadir", Some("/tmp/archive/beacon"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.historical_block_source,
+                Some(HistoricalBlockSource::Datadir(PathBuf::from(
+                    "/tmp/archive/beacon"
+                )))
+            )
+        });
+}
+
+#[test]
+fn fork_choice_journal_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.fork_choice_journal, None));
+}
+
+#[test]
+fn fork_choice_journal_flag() {
+    CommandLineTest::new()
+        .flag("fork-choice-journal", None)
+        .run_with_zero_port()
+        .with_config_and_dir(|config, dir| {
+            assert_eq!(
+                config.chain.fork_choice_journal,
+                Some(ForkChoiceJournalConfig::new(
+                    dir.path().join("beacon").join("fork_choice_journal")
+                ))
+            )
+        });
+}
+
+#[test]
+fn fork_choice_journal_custom() {
+    CommandLineTest::new()
+        .flag("fork-choice-journal", None)
+        .flag("fork-choice-journal-dir", Some("/tmp/journal"))
+        .flag("fork-choice-journal-max-file-size", Some("16"))
+        .flag("fork-choice-journal-max-files", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.fork_choice_journal,
+                Some(ForkChoiceJournalConfig {
+                    dir: PathBuf::from("/tmp/journal"),
+                    max_file_size: 16 * 1024 * 1024,
+                    max_files: 2,
+                })
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn fork_choice_journal_zero_files() {
+    CommandLineTest::new()
+        .flag("fork-choice-journal", None)
+        .flag("fork-choice-journal-max-files", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn backfill_source_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.backfill_source, None));
+}
+
+#[test]
+fn backfill_beacon_node_url_flag() {
+    use sensitive_url::SensitiveUrl;
+    CommandLineTest::new()
+        .flag("backfill-beacon-node-url", Some("http://localhost:5052"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.backfill_source,
+                Some(BackfillSource::BeaconNode(
+                    SensitiveUrl::parse("http://localhost:5052").unwrap()
+                ))
+            )
+        });
+}
+
+#[test]
+fn backfill_era_mirror_url_flag() {
+    use sensitive_url::SensitiveUrl;
+    CommandLineTest::new()
+        .flag(
+            "backfill-era-mirror-url",
+            Some("https://era.example.com/mainnet/"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.backfill_source,
+                Some(BackfillSource::EraMirror(
+                    SensitiveUrl::parse("https://era.example.com/mainnet/").unwrap()
+                ))
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn backfill_source_flags_conflict() {
+    CommandLineTest::new()
+        .flag("backfill-beacon-node-url", Some("http://localhost:5052"))
+        .flag(
+            "backfill-era-mirror-url",
+            Some("https://era.example.com/mainnet/"),
+        )
+        .run_with_zero_port();
+}
+
 #[test]
 fn genesis_backfill_flag() {
     CommandLineTest::new()
@@ -1204,6 +1433,98 @@ fn disable_discovery_flag() {
         .with_config(|config| assert!(config.network.disable_discovery));
 }
 
+#[test]
+fn private_network_flag() {
+    let peers = vec![PeerId::random(), PeerId::random()];
+    CommandLineTest::new()
+        .flag("private-network", None)
+        .flag(
+            "peer-allowlist",
+            Some(format!("{},{}", peers[0], peers[1]).as_str()),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.private_network);
+            let allowlist = config
+                .network
+                .peer_allowlist
+                .iter()
+                .cloned()
+                .map(PeerId::from)
+                .collect::<Vec<_>>();
+            assert_eq!(allowlist, peers);
+        });
+}
+#[test]
+fn private_network_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.network.private_network);
+            assert!(config.network.peer_allowlist.is_empty());
+        });
+}
+#[test]
+#[should_panic]
+fn peer_allowlist_requires_private_network() {
+    CommandLineTest::new()
+        .flag(
+            "peer-allowlist",
+            Some(PeerId::random().to_string().as_str()),
+        )
+        .run_with_zero_port();
+}
+#[test]
+fn private_peer_flag() {
+    let sentry = PeerId::random();
+    CommandLineTest::new()
+        .flag("private-peer", None)
+        .flag("trusted-peers", Some(sentry.to_string().as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.private_peer);
+            assert!(config.network.private_network);
+            assert!(config.network.disable_discovery);
+            assert!(!config.network.upnp_enabled);
+        });
+}
+#[test]
+#[should_panic]
+fn private_peer_requires_trusted_peers() {
+    CommandLineTest::new()
+        .flag("private-peer", None)
+        .run_with_zero_port();
+}
+#[test]
+fn sentry_of_flag() {
+    let private_peers = vec![PeerId::random(), PeerId::random()];
+    CommandLineTest::new()
+        .flag(
+            "sentry-of",
+            Some(format!("{},{}", private_peers[0], private_peers[1]).as_str()),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.network.private_peer);
+            let sentry_of = config
+                .network
+                .sentry_of
+                .iter()
+                .cloned()
+                .map(PeerId::from)
+                .collect::<Vec<_>>();
+            assert_eq!(sentry_of, private_peers);
+            let trusted_peers = config
+                .network
+                .trusted_peers
+                .iter()
+                .cloned()
+                .map(PeerId::from)
+                .collect::<Vec<_>>();
+            assert_eq!(trusted_peers, private_peers);
+        });
+}
+
 #[test]
 fn disable_quic_flag() {
     CommandLineTest::new()
@@ -1629,6 +1950,44 @@ fn disable_inbound_rate_limiter_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
 }
+#[test]
+fn rpc_rate_limits_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("rate_limits.yaml");
+    CommandLineTest::new()
+        .flag("rpc-rate-limits-file", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.rate_limits_file, Some(path.clone())));
+}
+#[test]
+fn rpc_rate_limits_file_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.rate_limits_file, None));
+}
+#[test]
+fn gossip_trace_file_flag() {
+    CommandLineTest::new()
+        .flag("gossip-trace-file", Some("/tmp/gossip_trace.jsonl"))
+        .flag("gossip-trace-max-file-size", Some("10"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.gossip_trace_file,
+                Some(PathBuf::from("/tmp/gossip_trace.jsonl"))
+            );
+            assert_eq!(config.network.gossip_trace_max_file_size, 10 * 1024 * 1024);
+        });
+}
+#[test]
+fn gossip_trace_file_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.gossip_trace_file, None);
+            assert_eq!(config.network.gossip_trace_max_file_size, 100 * 1024 * 1024);
+        });
+}
 
 #[test]
 fn http_allow_origin_flag() {
@@ -1948,6 +2307,19 @@ fn epochs_per_blob_prune_on_startup_five() {
         .with_config(|config| assert!(config.store.epochs_per_blob_prune == 5));
 }
 #[test]
+fn epochs_per_column_stats_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.epochs_per_column_stats, 225));
+}
+#[test]
+fn epochs_per_column_stats_disabled() {
+    CommandLineTest::new()
+        .flag("epochs-per-column-stats", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.epochs_per_column_stats, 0));
+}
+#[test]
 fn blob_prune_margin_epochs_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -1961,6 +2333,104 @@ fn blob_prune_margin_epochs_on_startup_ten() {
         .with_config(|config| assert!(config.store.blob_prune_margin_epochs == 10));
 }
 #[test]
+fn retention_policies_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.blob_retention, None);
+            assert_eq!(config.store.data_column_retention, None);
+            assert_eq!(config.store.payload_retention, None);
+            assert_eq!(config.store.historic_state_retention, None);
+            assert_eq!(
+                config.store.effective_payload_retention(),
+                RetentionPolicy::KeepLastNEpochs(0)
+            );
+        });
+}
+#[test]
+fn retention_policies_custom() {
+    CommandLineTest::new()
+        .flag("blob-retention", Some("keep_all"))
+        .flag("data-column-retention", Some("keep_last_n_epochs:8192"))
+        .flag("payload-retention", Some("keep_finalized_checkpoints_only"))
+        .flag("historic-state-retention", Some("keep_last_n_epochs:256"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.blob_retention, Some(RetentionPolicy::KeepAll));
+            assert_eq!(
+                config.store.data_column_retention,
+                Some(RetentionPolicy::KeepLastNEpochs(8192))
+            );
+            assert_eq!(
+                config.store.payload_retention,
+                Some(RetentionPolicy::KeepFinalizedCheckpointsOnly)
+            );
+            assert_eq!(
+                config.store.historic_state_retention,
+                Some(RetentionPolicy::KeepLastNEpochs(256))
+            );
+        });
+}
+#[test]
+fn payload_retention_overrides_prune_payloads() {
+    CommandLineTest::new()
+        .flag("prune-payloads", Some("true"))
+        .flag("payload-retention", Some("keep_all"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.effective_payload_retention(),
+                RetentionPolicy::KeepAll
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn historic_state_retention_with_reconstruction() {
+    CommandLineTest::new()
+        .flag("historic-state-retention", Some("keep_last_n_epochs:256"))
+        .flag("reconstruct-historic-states", None)
+        .run_with_zero_port();
+}
+#[test]
+fn db_encryption_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.encryption_key_source, None));
+}
+#[test]
+fn db_encryption_key_file_flag() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-file", Some("/tmp/db.key"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.encryption_key_source,
+                Some(KeySource::File(PathBuf::from("/tmp/db.key")))
+            )
+        });
+}
+#[test]
+fn db_encryption_key_env_flag() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-env", Some("LIGHTHOUSE_DB_KEY"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.encryption_key_source,
+                Some(KeySource::Env("LIGHTHOUSE_DB_KEY".into()))
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn db_encryption_key_file_and_env_flags() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-file", Some("/tmp/db.key"))
+        .flag("db-encryption-key-env", Some("LIGHTHOUSE_DB_KEY"))
+        .run_with_zero_port();
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -2521,6 +2991,55 @@ fn light_client_server_enabled() {
         });
 }
 
+#[test]
+fn light_client_historical_updates_default() {
+    CommandLineTest::new()
+        .flag("light-client-server", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.light_client_historical_updates));
+}
+
+#[test]
+fn light_client_historical_updates_enabled() {
+    CommandLineTest::new()
+        .flag("light-client-server", None)
+        .flag("light-client-historical-updates", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.light_client_historical_updates));
+}
+
+#[test]
+#[should_panic]
+fn light_client_historical_updates_requires_light_client_server() {
+    CommandLineTest::new()
+        .flag("light-client-historical-updates", None)
+        .run_with_zero_port();
+}
+
+#[test]
+fn webrtc_port_flag() {
+    CommandLineTest::new()
+        .flag("light-client-server", None)
+        .flag("webrtc-port", Some("9003"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.webrtc_port, Some(9003)));
+}
+
+#[test]
+fn webrtc_port_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.webrtc_port, None));
+}
+
+#[test]
+#[should_panic]
+fn webrtc_port_requires_light_client_server() {
+    CommandLineTest::new()
+        .flag("webrtc-port", Some("9003"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn light_client_http_server_enabled() {
     CommandLineTest::new()
@@ -2618,12 +3137,80 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    enable_backfill_rate_limiting: false,
+                    ..Default::default()
                 }
             )
         });
 }
 
+#[test]
+fn beacon_processor_scheduler_flags() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_processor.scheduler_mode,
+                SchedulerMode::Strict
+            );
+            assert!(config.beacon_processor.work_type_priorities.is_empty());
+            assert_eq!(config.beacon_processor.flow_weights, FlowWeights::default());
+        });
+
+    let peer_id = "16Uiu2HAmBiHWwfAmzvERibMnLVDdL5Sh79w9NcM8N5i9tPxuFpLe";
+    CommandLineTest::new()
+        .flag("beacon-processor-scheduler", Some("fair"))
+        .flag(
+            "beacon-processor-flow-weights",
+            Some(&format!("local=4, {peer_id}=2")),
+        )
+        .flag(
+            "beacon-processor-priorities",
+            Some("blocks_by_range_request=0, chain_segment_backfill=2"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_processor.scheduler_mode,
+                SchedulerMode::FairQueuing
+            );
+            assert_eq!(
+                config.beacon_processor.flow_weights,
+                FlowWeights {
+                    local: 4,
+                    peers: vec![(peer_id.parse().unwrap(), 2)],
+                }
+            );
+            assert_eq!(
+                config.beacon_processor.work_type_priorities,
+                vec![
+                    (WorkType::BlocksByRangeRequest, 0),
+                    (WorkType::ChainSegmentBackfill, 2)
+                ]
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_zero_flow_weight() {
+    CommandLineTest::new()
+        .flag("beacon-processor-scheduler", Some("fair"))
+        .flag("beacon-processor-flow-weights", Some("local=0"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_priorities_unknown_work_type() {
+    CommandLineTest::new()
+        .flag(
+            "beacon-processor-priorities",
+            Some("gossip_attestation_batch=0"),
+        )
+        .run_with_zero_port();
+}
+
 #[test]
 #[should_panic]
 fn beacon_processor_zero_workers() {
@@ -2,6 +2,7 @@ use beacon_node_fallback::{beacon_node_health::BeaconNodeSyncDistanceTiers, ApiT
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
+use db_encryption::KeySource;
 use initialized_validators::DEFAULT_WEB3SIGNER_KEEP_ALIVE;
 use sensitive_url::SensitiveUrl;
 use std::fs::File;
@@ -121,6 +122,48 @@ fn init_slashing_protections_flag() {
         .with_config(|config| assert!(config.init_slashing_protection));
 }
 
+#[test]
+fn db_encryption_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.slashing_protection_key_source, None));
+}
+
+#[test]
+fn db_encryption_key_file_flag() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-file", Some("/tmp/db.key"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_key_source,
+                Some(KeySource::File(PathBuf::from("/tmp/db.key")))
+            )
+        });
+}
+
+#[test]
+fn db_encryption_key_env_flag() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-env", Some("LIGHTHOUSE_DB_KEY"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_key_source,
+                Some(KeySource::Env("LIGHTHOUSE_DB_KEY".into()))
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn db_encryption_key_file_and_env_flags() {
+    CommandLineTest::new()
+        .flag("db-encryption-key-file", Some("/tmp/db.key"))
+        .flag("db-encryption-key-env", Some("LIGHTHOUSE_DB_KEY"))
+        .run();
+}
+
 #[test]
 fn use_long_timeouts_flag() {
     CommandLineTest::new()
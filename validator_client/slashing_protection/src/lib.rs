@@ -16,10 +16,11 @@ pub use crate::slashing_database::{
     InterchangeError, InterchangeImportOutcome, SlashingDatabase,
     SUPPORTED_INTERCHANGE_FORMAT_VERSION,
 };
+use db_encryption::DbCipher;
 use rusqlite::Error as SQLError;
 use std::fmt::Display;
 use std::io::{Error as IOError, ErrorKind};
-use types::{Hash256, PublicKeyBytes};
+use types::{Epoch, Hash256, PublicKeyBytes, Slot};
 
 /// The filename within the `validators` directory that contains the slashing protection DB.
 pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
@@ -38,6 +39,7 @@ pub enum NotSafe {
     SQLError(String),
     SQLPoolError(String),
     ConsistencyError,
+    EncryptionError(String),
 }
 
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
@@ -87,11 +89,50 @@ impl SigningRoot {
     }
 }
 
-/// Safely parse a `SigningRoot` from the given `column` of an SQLite `row`.
-fn signing_root_from_row(column: usize, row: &rusqlite::Row) -> rusqlite::Result<SigningRoot> {
+/// Associated data for an encrypted block signing root.
+///
+/// Binding the ciphertext to the validator and slot of its row means it can't be moved to
+/// another row (or another validator's row) without failing to decrypt.
+fn block_signing_root_aad(pubkey: &PublicKeyBytes, slot: Slot) -> Vec<u8> {
+    let mut aad = b"block_signing_root".to_vec();
+    aad.extend_from_slice(pubkey.as_serialized());
+    aad.extend_from_slice(&slot.as_u64().to_be_bytes());
+    aad
+}
+
+/// Associated data for an encrypted attestation signing root, see `block_signing_root_aad`.
+fn attestation_signing_root_aad(
+    pubkey: &PublicKeyBytes,
+    source_epoch: Epoch,
+    target_epoch: Epoch,
+) -> Vec<u8> {
+    let mut aad = b"attestation_signing_root".to_vec();
+    aad.extend_from_slice(pubkey.as_serialized());
+    aad.extend_from_slice(&source_epoch.as_u64().to_be_bytes());
+    aad.extend_from_slice(&target_epoch.as_u64().to_be_bytes());
+    aad
+}
+
+/// Safely parse a `SigningRoot` from the given `column` of an SQLite `row`, decrypting it with
+/// `cipher` and the row's associated data `aad` if the database is encrypted.
+fn signing_root_from_row(
+    column: usize,
+    row: &rusqlite::Row,
+    cipher: Option<&DbCipher>,
+    aad: &[u8],
+) -> rusqlite::Result<SigningRoot> {
     use rusqlite::{types::Type, Error};
 
-    let bytes: Vec<u8> = row.get(column)?;
+    let mut bytes: Vec<u8> = row.get(column)?;
+    if let Some(cipher) = cipher {
+        bytes = cipher.decrypt(aad, &bytes).map_err(|e| {
+            Error::FromSqlConversionFailure(
+                column,
+                Type::Blob,
+                Box::from(format!("Unable to decrypt signing root: {e:?}")),
+            )
+        })?;
+    }
     if bytes.len() == 32 {
         Ok(SigningRoot::from(Hash256::from_slice(&bytes)))
     } else {
@@ -1,5 +1,6 @@
-use crate::{signing_root_from_row, SigningRoot};
-use types::{AttestationData, Epoch, Hash256, SignedRoot};
+use crate::{attestation_signing_root_aad, signing_root_from_row, SigningRoot};
+use db_encryption::DbCipher;
+use types::{AttestationData, Epoch, Hash256, PublicKeyBytes, SignedRoot};
 
 /// An attestation that has previously been signed.
 #[derive(Clone, Debug, PartialEq)]
@@ -52,11 +53,17 @@ impl SignedAttestation {
         }
     }
 
-    /// Create a `SignedAttestation` from an SQLite row of `(source, target, signing_root)`.
-    pub fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+    /// Create a `SignedAttestation` from an SQLite row of `(source, target, signing_root)`
+    /// belonging to `pubkey`, decrypting it with `cipher` if any.
+    pub fn from_row(
+        row: &rusqlite::Row,
+        pubkey: &PublicKeyBytes,
+        cipher: Option<&DbCipher>,
+    ) -> rusqlite::Result<Self> {
         let source = row.get(0)?;
         let target = row.get(1)?;
-        let signing_root = signing_root_from_row(2, row)?;
+        let aad = attestation_signing_root_aad(pubkey, source, target);
+        let signing_root = signing_root_from_row(2, row, cipher, &aad)?;
         Ok(SignedAttestation::new(source, target, signing_root))
     }
 }
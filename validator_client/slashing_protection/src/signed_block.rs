@@ -1,5 +1,6 @@
-use crate::{signing_root_from_row, SigningRoot};
-use types::{BeaconBlockHeader, Hash256, SignedRoot, Slot};
+use crate::{block_signing_root_aad, signing_root_from_row, SigningRoot};
+use db_encryption::DbCipher;
+use types::{BeaconBlockHeader, Hash256, PublicKeyBytes, SignedRoot, Slot};
 
 /// A block that has previously been signed.
 #[derive(Clone, Debug, PartialEq)]
@@ -27,10 +28,16 @@ impl SignedBlock {
         }
     }
 
-    /// Parse an SQLite row of `(slot, signing_root)`.
-    pub fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+    /// Parse an SQLite row of `(slot, signing_root)` belonging to `pubkey`, decrypting it with
+    /// `cipher` if any.
+    pub fn from_row(
+        row: &rusqlite::Row,
+        pubkey: &PublicKeyBytes,
+        cipher: Option<&DbCipher>,
+    ) -> rusqlite::Result<Self> {
         let slot = row.get(0)?;
-        let signing_root = signing_root_from_row(1, row)?;
+        let aad = block_signing_root_aad(pubkey, slot);
+        let signing_root = signing_root_from_row(1, row, cipher, &aad)?;
         Ok(SignedBlock { slot, signing_root })
     }
 }
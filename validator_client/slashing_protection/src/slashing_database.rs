@@ -4,12 +4,17 @@ use crate::interchange::{
 };
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
-use crate::{signing_root_from_row, NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
+use crate::{
+    attestation_signing_root_aad, block_signing_root_aad, signing_root_from_row, NotSafe, Safe,
+    SignedAttestation, SignedBlock, SigningRoot,
+};
+use db_encryption::DbCipher;
 use filesystem::restrict_file_permissions;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs::File;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
 
@@ -31,18 +36,40 @@ pub const SUPPORTED_INTERCHANGE_FORMAT_VERSION: u64 = 5;
 /// Column ID of the `validators.enabled` column.
 pub const VALIDATORS_ENABLED_CID: i64 = 2;
 
+/// Associated data for encrypted public keys.
+///
+/// Public keys are encrypted deterministically and looked up by ciphertext, so they can't be bound
+/// to their row ID. Instead every encrypted signing root is bound to its validator's public key,
+/// so a public key moved onto another validator's row makes that row's history undecryptable.
+const PUBLIC_KEY_AAD: &[u8] = b"public_key";
+
+/// Plaintext of the marker which records that a database is encrypted.
+const ENCRYPTION_MARKER: &[u8] = b"lighthouse-encrypted-slashing-db";
+
 #[derive(Debug, Clone)]
 pub struct SlashingDatabase {
     conn_pool: Pool,
+    /// Cipher for encrypting public keys and signing roots at rest, if enabled.
+    ///
+    /// Slots and epochs remain in plaintext so that slashing conditions can be checked in SQL.
+    cipher: Option<Arc<DbCipher>>,
 }
 
 impl SlashingDatabase {
     /// Open an existing database at the given `path`, or create one if none exists.
     pub fn open_or_create(path: &Path) -> Result<Self, NotSafe> {
+        Self::open_or_create_with_cipher(path, None)
+    }
+
+    /// Open an existing database encrypted with `cipher` (if any), or create one if none exists.
+    pub fn open_or_create_with_cipher(
+        path: &Path,
+        cipher: Option<Arc<DbCipher>>,
+    ) -> Result<Self, NotSafe> {
         if path.exists() {
-            Self::open(path)
+            Self::open_with_cipher(path, cipher)
         } else {
-            Self::create(path)
+            Self::create_with_cipher(path, cipher)
         }
     }
 
@@ -50,6 +77,13 @@ impl SlashingDatabase {
     ///
     /// Error if a database (or any file) already exists at `path`.
     pub fn create(path: &Path) -> Result<Self, NotSafe> {
+        Self::create_with_cipher(path, None)
+    }
+
+    /// Create a slashing database at the given path, encrypted with `cipher` if any.
+    ///
+    /// Error if a database (or any file) already exists at `path`.
+    pub fn create_with_cipher(path: &Path, cipher: Option<Arc<DbCipher>>) -> Result<Self, NotSafe> {
         let _file = File::options()
             .write(true)
             .read(true)
@@ -93,23 +127,148 @@ impl SlashingDatabase {
 
         // The tables created above are for the v0 schema. We immediately update them
         // to the latest schema without dropping the connection.
+        let db = Self { conn_pool, cipher };
         let txn = conn.transaction()?;
         Self::apply_schema_migrations(&txn)?;
+        db.check_encryption_marker(&txn)?;
         txn.commit()?;
 
-        Ok(Self { conn_pool })
+        Ok(db)
     }
 
     /// Open an existing `SlashingDatabase` from disk.
     ///
     /// This will automatically check for and apply the latest schema migrations.
     pub fn open(path: &Path) -> Result<Self, NotSafe> {
+        Self::open_with_cipher(path, None)
+    }
+
+    /// Open an existing `SlashingDatabase` from disk, encrypted with `cipher` if any.
+    ///
+    /// Error if the database is encrypted but no cipher is provided, or vice versa. A database
+    /// with registered validators can't be encrypted in place.
+    pub fn open_with_cipher(path: &Path, cipher: Option<Arc<DbCipher>>) -> Result<Self, NotSafe> {
         let conn_pool = Self::open_conn_pool(path)?;
-        let db = Self { conn_pool };
-        db.with_transaction(Self::apply_schema_migrations)?;
+        let db = Self { conn_pool, cipher };
+        db.with_transaction(|txn| {
+            Self::apply_schema_migrations(txn)?;
+            db.check_encryption_marker(txn)
+        })?;
         Ok(db)
     }
 
+    /// Check that the presence of the encryption marker matches the cipher, writing the marker
+    /// to a new encrypted database.
+    fn check_encryption_marker(&self, txn: &Transaction) -> Result<(), NotSafe> {
+        let marker_exists = txn
+            .query_row(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'encryption'",
+                params![],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        let marker: Option<Vec<u8>> = if marker_exists {
+            txn.query_row("SELECT marker FROM encryption", params![], |row| row.get(0))
+                .optional()?
+        } else {
+            None
+        };
+
+        match (marker, &self.cipher) {
+            (Some(marker), Some(cipher)) => {
+                if cipher
+                    .decrypt(ENCRYPTION_MARKER, &marker)
+                    .is_ok_and(|plaintext| plaintext == ENCRYPTION_MARKER)
+                {
+                    Ok(())
+                } else {
+                    Err(NotSafe::EncryptionError(
+                        "database is encrypted with a different key".into(),
+                    ))
+                }
+            }
+            (Some(_), None) => Err(NotSafe::EncryptionError(
+                "database is encrypted but no key was provided".into(),
+            )),
+            (None, Some(cipher)) => {
+                let num_validators: u32 =
+                    txn.query_row("SELECT COUNT(*) FROM validators", params![], |row| {
+                        row.get(0)
+                    })?;
+                if num_validators > 0 {
+                    return Err(NotSafe::EncryptionError(
+                        "database is not encrypted and already contains validators".into(),
+                    ));
+                }
+                let marker = cipher
+                    .encrypt(ENCRYPTION_MARKER, ENCRYPTION_MARKER)
+                    .map_err(|e| NotSafe::EncryptionError(format!("{e:?}")))?;
+                txn.execute(
+                    "CREATE TABLE IF NOT EXISTS encryption (marker BLOB NOT NULL)",
+                    params![],
+                )?;
+                txn.execute(
+                    "INSERT INTO encryption (marker) VALUES (?1)",
+                    params![marker],
+                )?;
+                Ok(())
+            }
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Return `true` if public keys and signing roots in this database are encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Encode a public key for storage in the `validators` table.
+    ///
+    /// Encrypted public keys are encrypted deterministically, so that they can still be looked
+    /// up by equality.
+    fn encode_pubkey(&self, public_key: &PublicKeyBytes) -> Result<String, NotSafe> {
+        let hex_pubkey = public_key.as_hex_string();
+        match &self.cipher {
+            Some(cipher) => cipher
+                .encrypt_deterministic(PUBLIC_KEY_AAD, hex_pubkey.as_bytes())
+                .map(hex::encode)
+                .map_err(|e| NotSafe::EncryptionError(format!("{e:?}"))),
+            None => Ok(hex_pubkey),
+        }
+    }
+
+    /// Decode a public key read from the `validators` table.
+    fn decode_pubkey(&self, encoded: String) -> Result<PublicKeyBytes, InterchangeError> {
+        let hex_pubkey = match &self.cipher {
+            Some(cipher) => hex::decode(&encoded)
+                .ok()
+                .and_then(|ciphertext| cipher.decrypt(PUBLIC_KEY_AAD, &ciphertext).ok())
+                .and_then(|plaintext| String::from_utf8(plaintext).ok())
+                .ok_or_else(|| {
+                    InterchangeError::InvalidPubkey(format!("Unable to decrypt {encoded}"))
+                })?,
+            None => encoded,
+        };
+        hex_pubkey.parse().map_err(InterchangeError::InvalidPubkey)
+    }
+
+    /// Encode a signing root for storage, encrypting it with the row's associated data `aad` if
+    /// enabled.
+    fn encode_signing_root(
+        &self,
+        signing_root: SigningRoot,
+        aad: &[u8],
+    ) -> Result<Vec<u8>, NotSafe> {
+        let bytes = signing_root.to_hash256_raw();
+        match &self.cipher {
+            Some(cipher) => cipher
+                .encrypt(aad, bytes.as_slice())
+                .map_err(|e| NotSafe::EncryptionError(format!("{e:?}"))),
+            None => Ok(bytes.as_slice().to_vec()),
+        }
+    }
+
     fn apply_schema_migrations(txn: &Transaction) -> Result<(), NotSafe> {
         // Add the `enabled` column to the `validators` table if it does not already exist.
         let enabled_col_exists = txn
@@ -216,7 +375,7 @@ impl SlashingDatabase {
         for pubkey in public_keys {
             match self.get_validator_id_with_status(txn, pubkey)? {
                 None => {
-                    stmt.execute([pubkey.as_hex_string()])?;
+                    stmt.execute([self.encode_pubkey(pubkey)?])?;
                 }
                 Some((validator_id, false)) => {
                     self.update_validator_status(txn, validator_id, true)?;
@@ -261,10 +420,7 @@ impl SlashingDatabase {
         txn.prepare("SELECT id, public_key FROM validators ORDER BY id ASC")?
             .query_and_then(params![], |row| {
                 let validator_id = row.get(0)?;
-                let pubkey_str: String = row.get(1)?;
-                let pubkey = pubkey_str
-                    .parse()
-                    .map_err(InterchangeError::InvalidPubkey)?;
+                let pubkey = self.decode_pubkey(row.get(1)?)?;
                 Ok((validator_id, pubkey))
             })?
             .collect()
@@ -315,7 +471,7 @@ impl SlashingDatabase {
         Ok(txn
             .query_row(
                 "SELECT id, enabled FROM validators WHERE public_key = ?1",
-                params![&public_key.as_hex_string()],
+                params![&self.encode_pubkey(public_key)?],
                 |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .optional()?)
@@ -337,7 +493,9 @@ impl SlashingDatabase {
                  FROM signed_blocks
                  WHERE validator_id = ?1 AND slot = ?2",
             )?
-            .query_row(params![validator_id, slot], SignedBlock::from_row)
+            .query_row(params![validator_id, slot], |row| {
+                SignedBlock::from_row(row, validator_pubkey, self.cipher.as_deref())
+            })
             .optional()?;
 
         if let Some(existing_block) = existing_block {
@@ -397,10 +555,9 @@ impl SlashingDatabase {
                  FROM signed_attestations
                  WHERE validator_id = ?1 AND target_epoch = ?2",
             )?
-            .query_row(
-                params![validator_id, att_target_epoch],
-                SignedAttestation::from_row,
-            )
+            .query_row(params![validator_id, att_target_epoch], |row| {
+                SignedAttestation::from_row(row, validator_pubkey, self.cipher.as_deref())
+            })
             .optional()?;
 
         if let Some(existing_attestation) = same_target_att {
@@ -428,7 +585,7 @@ impl SlashingDatabase {
             )?
             .query_row(
                 params![validator_id, att_source_epoch, att_target_epoch],
-                SignedAttestation::from_row,
+                |row| SignedAttestation::from_row(row, validator_pubkey, self.cipher.as_deref()),
             )
             .optional()?;
 
@@ -450,7 +607,7 @@ impl SlashingDatabase {
             )?
             .query_row(
                 params![validator_id, att_source_epoch, att_target_epoch],
-                SignedAttestation::from_row,
+                |row| SignedAttestation::from_row(row, validator_pubkey, self.cipher.as_deref()),
             )
             .optional()?;
 
@@ -513,7 +670,14 @@ impl SlashingDatabase {
         txn.execute(
             "INSERT INTO signed_blocks (validator_id, slot, signing_root)
              VALUES (?1, ?2, ?3)",
-            params![validator_id, slot, signing_root.to_hash256_raw().as_slice()],
+            params![
+                validator_id,
+                slot,
+                self.encode_signing_root(
+                    signing_root,
+                    &block_signing_root_aad(validator_pubkey, slot)
+                )?
+            ],
         )?;
         Ok(())
     }
@@ -539,7 +703,14 @@ impl SlashingDatabase {
                 validator_id,
                 att_source_epoch,
                 att_target_epoch,
-                att_signing_root.to_hash256_raw().as_slice()
+                self.encode_signing_root(
+                    att_signing_root,
+                    &attestation_signing_root_aad(
+                        validator_pubkey,
+                        att_source_epoch,
+                        att_target_epoch
+                    )
+                )?
             ],
         )?;
         Ok(())
@@ -832,9 +1003,9 @@ impl SlashingDatabase {
             .into_iter()
             .map(|(validator_id, pubkey)| {
                 let signed_blocks =
-                    self.export_interchange_blocks_for_validator(validator_id, txn)?;
+                    self.export_interchange_blocks_for_validator(validator_id, &pubkey, txn)?;
                 let signed_attestations =
-                    self.export_interchange_attestations_for_validator(validator_id, txn)?;
+                    self.export_interchange_attestations_for_validator(validator_id, &pubkey, txn)?;
                 Ok(InterchangeData {
                     pubkey,
                     signed_blocks,
@@ -854,6 +1025,7 @@ impl SlashingDatabase {
     fn export_interchange_blocks_for_validator(
         &self,
         validator_id: i64,
+        pubkey: &PublicKeyBytes,
         txn: &Transaction,
     ) -> Result<Vec<InterchangeBlock>, InterchangeError> {
         txn.prepare(
@@ -864,7 +1036,9 @@ impl SlashingDatabase {
         )?
         .query_and_then(params![validator_id], |row| {
             let slot = row.get(0)?;
-            let signing_root = signing_root_from_row(1, row)?.to_hash256();
+            let aad = block_signing_root_aad(pubkey, slot);
+            let signing_root =
+                signing_root_from_row(1, row, self.cipher.as_deref(), &aad)?.to_hash256();
             Ok(InterchangeBlock { slot, signing_root })
         })?
         .collect()
@@ -873,6 +1047,7 @@ impl SlashingDatabase {
     fn export_interchange_attestations_for_validator(
         &self,
         validator_id: i64,
+        pubkey: &PublicKeyBytes,
         txn: &Transaction,
     ) -> Result<Vec<InterchangeAttestation>, InterchangeError> {
         txn.prepare(
@@ -884,7 +1059,9 @@ impl SlashingDatabase {
         .query_and_then(params![validator_id], |row| {
             let source_epoch = row.get(0)?;
             let target_epoch = row.get(1)?;
-            let signing_root = signing_root_from_row(2, row)?.to_hash256();
+            let aad = attestation_signing_root_aad(pubkey, source_epoch, target_epoch);
+            let signing_root =
+                signing_root_from_row(2, row, self.cipher.as_deref(), &aad)?.to_hash256();
             let signed_attestation = InterchangeAttestation {
                 source_epoch,
                 target_epoch,
@@ -1181,8 +1358,15 @@ impl From<serde_json::Error> for InterchangeError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::pubkey;
     use tempfile::tempdir;
 
+    fn cipher(byte: u8) -> Option<Arc<DbCipher>> {
+        Some(Arc::new(
+            DbCipher::new(&[byte; db_encryption::KEY_LEN]).unwrap(),
+        ))
+    }
+
     #[test]
     fn open_non_existent_error() {
         let dir = tempdir().unwrap();
@@ -1238,6 +1422,147 @@ mod tests {
         check(&db2);
     }
 
+    #[test]
+    fn encrypted_database() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let pk = pubkey(0);
+        let signing_root = Hash256::repeat_byte(1);
+        let genesis_validators_root = Hash256::repeat_byte(2);
+
+        let db = SlashingDatabase::create_with_cipher(&file, cipher(1)).unwrap();
+        db.register_validator(pk).unwrap();
+        db.check_and_insert_block_signing_root(&pk, Slot::new(1), signing_root.into())
+            .unwrap();
+
+        // Public keys and signing roots are not stored in plaintext.
+        let stored_pubkey: String = db
+            .conn_pool
+            .get()
+            .unwrap()
+            .query_row("SELECT public_key FROM validators", params![], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_ne!(stored_pubkey, pk.as_hex_string());
+        drop(db);
+
+        assert!(matches!(
+            SlashingDatabase::open(&file),
+            Err(NotSafe::EncryptionError(_))
+        ));
+        assert!(matches!(
+            SlashingDatabase::open_with_cipher(&file, cipher(2)),
+            Err(NotSafe::EncryptionError(_))
+        ));
+
+        let db = SlashingDatabase::open_with_cipher(&file, cipher(1)).unwrap();
+        assert_eq!(
+            db.check_and_insert_block_signing_root(&pk, Slot::new(1), signing_root.into()),
+            Ok(Safe::SameData)
+        );
+        assert!(matches!(
+            db.check_and_insert_block_signing_root(
+                &pk,
+                Slot::new(1),
+                Hash256::repeat_byte(3).into()
+            ),
+            Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_)))
+        ));
+
+        let interchange = db
+            .export_all_interchange_info(genesis_validators_root)
+            .unwrap();
+        assert_eq!(interchange.data.len(), 1);
+        assert_eq!(interchange.data[0].pubkey, pk);
+        assert_eq!(
+            interchange.data[0].signed_blocks[0].signing_root,
+            Some(signing_root)
+        );
+    }
+
+    #[test]
+    fn encrypted_signing_roots_bound_to_rows() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let (pk0, pk1) = (pubkey(0), pubkey(1));
+
+        let db = SlashingDatabase::create_with_cipher(&file, cipher(1)).unwrap();
+        db.register_validators([&pk0, &pk1].into_iter()).unwrap();
+        for (pk, slot, root) in [(&pk0, 1, 1), (&pk0, 2, 2), (&pk1, 1, 3)] {
+            db.check_and_insert_block_signing_root(
+                pk,
+                Slot::new(slot),
+                Hash256::repeat_byte(root).into(),
+            )
+            .unwrap();
+        }
+
+        let move_signing_root = |from: (i64, i64), to: (i64, i64)| {
+            let conn = db.conn_pool.get().unwrap();
+            let ciphertext: Vec<u8> = conn
+                .query_row(
+                    "SELECT signing_root FROM signed_blocks WHERE validator_id = ?1 AND slot = ?2",
+                    params![from.0, from.1],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            conn.execute(
+                "UPDATE signed_blocks SET signing_root = ?1 WHERE validator_id = ?2 AND slot = ?3",
+                params![ciphertext, to.0, to.1],
+            )
+            .unwrap();
+        };
+        let id0 = db.get_validator_id(&pk0).unwrap();
+        let id1 = db.get_validator_id(&pk1).unwrap();
+
+        // A signing root copied to another slot of the same validator fails to decrypt.
+        move_signing_root((id0, 2), (id0, 1));
+        assert!(matches!(
+            db.check_and_insert_block_signing_root(
+                &pk0,
+                Slot::new(1),
+                Hash256::repeat_byte(2).into()
+            ),
+            Err(NotSafe::SQLError(_))
+        ));
+
+        // As does a signing root copied to another validator's row.
+        move_signing_root((id0, 2), (id1, 1));
+        assert!(matches!(
+            db.check_and_insert_block_signing_root(
+                &pk1,
+                Slot::new(1),
+                Hash256::repeat_byte(2).into()
+            ),
+            Err(NotSafe::SQLError(_))
+        ));
+        assert!(db
+            .export_all_interchange_info(Hash256::repeat_byte(4))
+            .is_err());
+    }
+
+    #[test]
+    fn unencrypted_database_with_validators_rejects_key() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+
+        let db = SlashingDatabase::create(&file).unwrap();
+        drop(db);
+        // An empty database can be encrypted.
+        drop(SlashingDatabase::open_with_cipher(&file, cipher(1)).unwrap());
+        assert!(SlashingDatabase::open(&file).is_err());
+
+        let file = dir.path().join("db2.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.register_validator(pubkey(0)).unwrap();
+        drop(db);
+        assert!(matches!(
+            SlashingDatabase::open_with_cipher(&file, cipher(1)),
+            Err(NotSafe::EncryptionError(_))
+        ));
+    }
+
     #[test]
     fn test_transaction_failure() {
         let dir = tempdir().unwrap();
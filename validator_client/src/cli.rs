@@ -93,6 +93,31 @@ pub fn cli_app() -> Command {
                 )
                 .display_order(0)
         )
+        .arg(
+            Arg::new("db-encryption-key-file")
+                .long("db-encryption-key-file")
+                .value_name("PATH")
+                .help(
+                    "Encrypt public keys and signing roots in the slashing protection database \
+                     using the hex-encoded 32-byte key in this file. Only a new database, or one \
+                     without any validators, can be encrypted."
+                )
+                .action(ArgAction::Set)
+                .conflicts_with("db-encryption-key-env")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("db-encryption-key-env")
+                .long("db-encryption-key-env")
+                .value_name("VAR")
+                .help(
+                    "Encrypt public keys and signing roots in the slashing protection database \
+                     using the hex-encoded 32-byte key in this environment variable. Only a new \
+                     database, or one without any validators, can be encrypted."
+                )
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-auto-discover")
             .long("disable-auto-discover")
@@ -1,6 +1,7 @@
 use beacon_node_fallback::{beacon_node_health::BeaconNodeSyncDistanceTiers, ApiTopic};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
+use db_encryption::KeySource;
 use directory::{
     get_network_dir, DEFAULT_HARDCODED_NETWORK, DEFAULT_ROOT_DIR, DEFAULT_SECRET_DIR,
     DEFAULT_VALIDATOR_DIR,
@@ -46,6 +47,8 @@ pub struct Config {
     pub disable_auto_discover: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// Where to load the key for encrypting the slashing protection database. Disabled if `None`.
+    pub slashing_protection_key_source: Option<KeySource>,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
     /// Graffiti to be inserted everytime we create a block.
@@ -109,6 +112,7 @@ impl Default for Config {
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
             init_slashing_protection: false,
+            slashing_protection_key_source: None,
             use_long_timeouts: false,
             graffiti: None,
             graffiti_file: None,
@@ -186,6 +190,12 @@ impl Config {
 
         config.disable_auto_discover = cli_args.get_flag("disable-auto-discover");
         config.init_slashing_protection = cli_args.get_flag("init-slashing-protection");
+
+        if let Some(path) = parse_optional(cli_args, "db-encryption-key-file")? {
+            config.slashing_protection_key_source = Some(KeySource::File(path));
+        } else if let Some(name) = parse_optional(cli_args, "db-encryption-key-env")? {
+            config.slashing_protection_key_source = Some(KeySource::Env(name));
+        }
         config.use_long_timeouts = cli_args.get_flag("use-long-timeouts");
 
         if let Some(graffiti_file_path) = cli_args.get_one::<String>("graffiti-file") {
@@ -238,15 +238,27 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         // `init_slashing_protection` is not supplied. There is no risk in creating a slashing
         // database without any validators in it.
         let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
-        let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
-            SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
+        let slashing_db_cipher = config
+            .slashing_protection_key_source
+            .as_ref()
+            .map(|source| source.load_cipher().map(Arc::new))
+            .transpose()
+            .map_err(|e| {
                 format!(
-                    "Failed to open or create slashing protection database: {:?}",
+                    "Failed to load slashing protection database encryption key: {:?}",
                     e
                 )
-            })
+            })?;
+        let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
+            SlashingDatabase::open_or_create_with_cipher(&slashing_db_path, slashing_db_cipher)
+                .map_err(|e| {
+                    format!(
+                        "Failed to open or create slashing protection database: {:?}",
+                        e
+                    )
+                })
         } else {
-            SlashingDatabase::open(&slashing_db_path).map_err(|e| {
+            SlashingDatabase::open_with_cipher(&slashing_db_path, slashing_db_cipher).map_err(|e| {
                 format!(
                     "Failed to open slashing protection database: {:?}.\n\
                      Ensure that `slashing_protection.sqlite` is in {:?} folder",